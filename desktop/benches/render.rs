@@ -0,0 +1,120 @@
+//! Rendering benchmarks over large documents
+//!
+//! Run with `cargo bench --bench render`. The fixtures are generated, so they stay the same
+//! across runs: a long changelog (many sections of short lists) and API documentation (code
+//! blocks, tables and diagrams).
+
+use arto::{render_document, render_document_with_cache, BlockCache, RenderOptions};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::fmt::Write;
+use std::hint::black_box;
+use std::path::Path;
+
+fn changelog(releases: usize) -> String {
+    let mut markdown = String::from("# Changelog\n\n");
+    for release in (0..releases).rev() {
+        let _ = writeln!(markdown, "## v{}.{}.0\n", release / 10, release % 10);
+        for kind in ["Added", "Changed", "Fixed"] {
+            let _ = writeln!(markdown, "### {kind}\n");
+            for item in 0..5 {
+                let _ = writeln!(
+                    markdown,
+                    "- {kind} item {item} of release {release} in `module_{item}` \
+                     ([#{}](https://example.com/pull/{})) :tada:",
+                    release * 10 + item,
+                    release * 10 + item,
+                );
+            }
+            markdown.push('\n');
+        }
+    }
+    markdown
+}
+
+fn api_docs(functions: usize) -> String {
+    let mut markdown = String::from("# API Reference\n\n");
+    for function in 0..functions {
+        let _ = writeln!(
+            markdown,
+            "## `fn_{function}`\n\n\
+             Computes **something** for *function {function}*, returning $x^{function}$.\n\n\
+             ```rust\n\
+             pub fn fn_{function}(input: &str) -> Result<Vec<u8>> {{\n    \
+                 Ok(input.bytes().map(|b| b.wrapping_add({function} as u8)).collect())\n\
+             }}\n\
+             ```\n\n\
+             | Parameter | Type | Description |\n\
+             | --- | --- | --- |\n\
+             | `input` | `&str` | Text to encode |\n\
+             | `returns` | `Vec<u8>` | Encoded bytes |\n\n\
+             > [!NOTE]\n\
+             > Allocates a new buffer on every call.\n"
+        );
+        if function % 10 == 0 {
+            markdown.push_str("```mermaid\ngraph TD\n  A --> B\n  B --> C\n```\n\n");
+        }
+    }
+    markdown
+}
+
+fn fixtures() -> Vec<(&'static str, String)> {
+    vec![("changelog", changelog(500)), ("api_docs", api_docs(300))]
+}
+
+fn cold_render(c: &mut Criterion) {
+    let options = RenderOptions::default();
+    let mut group = c.benchmark_group("cold_render");
+    for (name, markdown) in fixtures() {
+        group.throughput(Throughput::Bytes(markdown.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(name),
+            &markdown,
+            |b, markdown| {
+                b.iter(|| render_document(black_box(markdown), Path::new("doc.md"), &options));
+            },
+        );
+    }
+    group.finish();
+}
+
+fn incremental_render(c: &mut Criterion) {
+    let options = RenderOptions::default();
+    let mut group = c.benchmark_group("incremental_render");
+    for (name, markdown) in fixtures() {
+        // Edits alternate, so each iteration has a changed block to process
+        let edited = markdown.replacen(" item 0 ", " item zero ", 1);
+        let edited = edited.replacen("**something**", "**anything**", 1);
+        let versions = [markdown, edited];
+        let mut cache = BlockCache::default();
+        render_document_with_cache(&versions[0], Path::new("doc.md"), &options, &mut cache)
+            .unwrap();
+
+        group.throughput(Throughput::Bytes(versions[0].len() as u64));
+        group.bench_function(BenchmarkId::new("unchanged", name), |b| {
+            b.iter(|| {
+                render_document_with_cache(
+                    black_box(&versions[0]),
+                    Path::new("doc.md"),
+                    &options,
+                    &mut cache,
+                )
+            });
+        });
+        let mut version = 0;
+        group.bench_function(BenchmarkId::new("one_block_edited", name), |b| {
+            b.iter(|| {
+                version = 1 - version;
+                render_document_with_cache(
+                    black_box(&versions[version]),
+                    Path::new("doc.md"),
+                    &options,
+                    &mut cache,
+                )
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, cold_render, incremental_render);
+criterion_main!(benches);
@@ -0,0 +1,484 @@
+//! `arto-asset://` protocol serving local images and media to the webview
+//!
+//! Documents reference local files through [asset URLs](asset_url) instead of inlining them
+//! as data URLs, so a document with dozens of screenshots stays a small HTML string and the
+//! webview loads (and caches) images lazily. A webview is only served the files the documents
+//! it shows were [granted](AssetGrant), usually their directories, for as long as it shows
+//! them; a document can't read arbitrary files.
+
+use dioxus::desktop::wry::http::{header, Method, Request, Response, StatusCode, Uri};
+use dioxus::desktop::wry::{RequestAsyncResponder, WebViewId};
+use parking_lot::RwLock;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::LazyLock;
+use std::time::UNIX_EPOCH;
+
+/// Scheme of the protocol, registered on every main window
+pub const ASSET_SCHEME: &str = "arto-asset";
+
+/// Origin custom protocols are reachable at; WebView2 only routes them as `http://<scheme>.localhost`
+#[cfg(not(windows))]
+const ASSET_ORIGIN: &str = "arto-asset://localhost";
+#[cfg(windows)]
+const ASSET_ORIGIN: &str = "http://arto-asset.localhost";
+
+/// Characters kept as is in the path of an asset URL
+const PATH_CHARACTERS: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'/')
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// Largest chunk served for a single request; media elements request the rest by range
+const MAX_CHUNK_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Bytes read to sniff the type of a file
+const SNIFF_BYTES: usize = 512;
+
+/// Paths served to webviews, by grant
+static GRANTS: LazyLock<RwLock<HashMap<u64, Grant>>> = LazyLock::new(Default::default);
+
+/// Identifier of the next grant
+static NEXT_GRANT: AtomicU64 = AtomicU64::new(0);
+
+/// Canonicalized paths a webview may load files from
+#[derive(Debug)]
+struct Grant {
+    webview: String,
+    /// Directories whose files, at any depth, are served
+    directories: Vec<PathBuf>,
+    /// Single files served
+    files: Vec<PathBuf>,
+}
+
+/// Permission for a webview to load the local files of the document it shows, revoked when
+/// dropped (when the document is closed or replaced)
+#[derive(Debug)]
+pub struct AssetGrant {
+    id: u64,
+}
+
+impl AssetGrant {
+    /// Serve the files in `directories` and below to `webview`
+    pub fn new(webview: &str, directories: impl IntoIterator<Item = impl AsRef<Path>>) -> Self {
+        let id = NEXT_GRANT.fetch_add(1, Ordering::Relaxed);
+        let grant = Grant {
+            webview: webview.to_string(),
+            directories: directories.into_iter().map(canonical).collect(),
+            files: Vec::new(),
+        };
+        GRANTS.write().insert(id, grant);
+        Self { id }
+    }
+
+    /// Serve the files in `directories` and below to the webview of the current window
+    pub fn for_window(directories: impl IntoIterator<Item = impl AsRef<Path>>) -> Self {
+        Self::new(dioxus::desktop::window().webview.id(), directories)
+    }
+
+    /// Also serve `files`, e.g. images of files included from other directories
+    pub fn allow_files(&self, files: impl IntoIterator<Item = impl AsRef<Path>>) {
+        if let Some(grant) = GRANTS.write().get_mut(&self.id) {
+            grant.files.extend(files.into_iter().map(canonical));
+        }
+    }
+}
+
+impl Drop for AssetGrant {
+    fn drop(&mut self) {
+        GRANTS.write().remove(&self.id);
+    }
+}
+
+fn canonical(path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Whether `webview` was granted the canonicalized `path`
+fn is_allowed(webview: &str, path: &Path) -> bool {
+    GRANTS.read().values().any(|grant| {
+        grant.webview == webview
+            && (grant.directories.iter().any(|dir| path.starts_with(dir))
+                || grant.files.iter().any(|file| path == file))
+    })
+}
+
+/// Local files the asset URLs in `html` lead to
+pub fn asset_paths(html: &str) -> Vec<PathBuf> {
+    html.match_indices(ASSET_ORIGIN)
+        .filter_map(|(start, _)| {
+            let url = &html[start..];
+            let end = url
+                .find(|c: char| c == '"' || c == '\'' || c == ',' || c.is_whitespace())
+                .unwrap_or(url.len());
+            path_from_uri(&url[..end].parse().ok()?)
+        })
+        .collect()
+}
+
+/// URL the webview loads the local file at `path` from
+pub fn asset_url(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let separator = if path.starts_with('/') { "" } else { "/" };
+    format!(
+        "{ASSET_ORIGIN}{separator}{}",
+        utf8_percent_encode(&path, PATH_CHARACTERS)
+    )
+}
+
+/// Whether `url` is an asset URL
+pub fn is_asset_url(url: &str) -> bool {
+    url.trim_start().starts_with(ASSET_ORIGIN)
+}
+
+fn path_from_uri(uri: &Uri) -> Option<PathBuf> {
+    let path = percent_decode_str(uri.path()).decode_utf8().ok()?;
+    // `/C:/Users/…` on Windows
+    let path = if cfg!(windows) {
+        path.trim_start_matches('/')
+    } else {
+        &path
+    };
+    Some(PathBuf::from(path))
+}
+
+/// MIME type of a file from its leading bytes, falling back to its extension
+pub fn sniff_mime_type(path: &Path, head: &[u8]) -> &'static str {
+    // SVG first, as it would be detected as generic XML
+    let text = String::from_utf8_lossy(head);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    if text.starts_with("<svg") || (text.starts_with("<?xml") && text.contains("<svg")) {
+        return "image/svg+xml";
+    }
+    if let Some(kind) = infer::get(head) {
+        return kind.mime_type();
+    }
+    match path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("svg") => "image/svg+xml",
+        Some("vtt") => "text/vtt",
+        Some("ico") => "image/x-icon",
+        _ => "application/octet-stream",
+    }
+}
+
+/// MIME type of the file at `path`; see [`sniff_mime_type`]
+pub fn file_mime_type(path: &Path) -> io::Result<&'static str> {
+    let mut head = Vec::with_capacity(SNIFF_BYTES);
+    File::open(path)?
+        .take(SNIFF_BYTES as u64)
+        .read_to_end(&mut head)?;
+    Ok(sniff_mime_type(path, &head))
+}
+
+/// Handle a request of the webview on the blocking pool
+pub fn handle_request(
+    webview: WebViewId,
+    request: Request<Vec<u8>>,
+    responder: RequestAsyncResponder,
+) {
+    let webview = webview.to_string();
+    tokio::task::spawn_blocking(move || responder.respond(respond(&webview, &request)));
+}
+
+/// Respond to a request of `webview` for an asset, honoring `Range` and `If-None-Match`
+fn respond(webview: &str, request: &Request<Vec<u8>>) -> Response<Cow<'static, [u8]>> {
+    if request.method() != Method::GET && request.method() != Method::HEAD {
+        return status(StatusCode::METHOD_NOT_ALLOWED);
+    }
+    let Some(path) = path_from_uri(request.uri()) else {
+        return status(StatusCode::BAD_REQUEST);
+    };
+    let Ok(path) = path.canonicalize() else {
+        return status(StatusCode::NOT_FOUND);
+    };
+    if !is_allowed(webview, &path) {
+        tracing::warn!(
+            "Refusing to serve asset outside the documents of {}: {:?}",
+            webview,
+            path
+        );
+        return status(StatusCode::FORBIDDEN);
+    }
+
+    match read_asset(request, &path) {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::warn!("Failed to serve asset {:?}: {}", path, e);
+            status(StatusCode::NOT_FOUND)
+        }
+    }
+}
+
+fn read_asset(request: &Request<Vec<u8>>, path: &Path) -> io::Result<Response<Cow<'static, [u8]>>> {
+    let mut file = File::open(path)?;
+    let metadata = file.metadata()?;
+    if !metadata.is_file() {
+        return Ok(status(StatusCode::NOT_FOUND));
+    }
+    let size = metadata.len();
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default();
+    // Changes whenever the file is rewritten, so reloaded documents show edited images
+    let etag = format!("\"{size:x}-{modified:x}\"");
+    let builder = Response::builder()
+        .header(header::ETAG, &etag)
+        .header(header::CACHE_CONTROL, "no-cache")
+        .header(header::ACCEPT_RANGES, "bytes");
+
+    let if_none_match = request.headers().get(header::IF_NONE_MATCH);
+    if if_none_match.is_some_and(|value| value.as_bytes() == etag.as_bytes()) {
+        return Ok(builder
+            .status(StatusCode::NOT_MODIFIED)
+            .body(Cow::Borrowed(&[][..]))
+            .unwrap());
+    }
+
+    let mut head = Vec::with_capacity(SNIFF_BYTES);
+    (&mut file)
+        .take(SNIFF_BYTES as u64)
+        .read_to_end(&mut head)?;
+    let builder = builder.header(header::CONTENT_TYPE, sniff_mime_type(path, &head));
+
+    let range = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok());
+    let (status, start, end) = match range.map(|range| parse_range(range, size)) {
+        None => (StatusCode::OK, 0, size),
+        Some(Some((start, end))) => (StatusCode::PARTIAL_CONTENT, start, end),
+        Some(None) => {
+            return Ok(builder
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(header::CONTENT_RANGE, format!("bytes */{size}"))
+                .body(Cow::Borrowed(&[][..]))
+                .unwrap());
+        }
+    };
+    let builder = if status == StatusCode::PARTIAL_CONTENT {
+        builder.header(
+            header::CONTENT_RANGE,
+            format!("bytes {start}-{}/{size}", end - 1),
+        )
+    } else {
+        builder
+    };
+
+    let mut body = Vec::new();
+    if request.method() == Method::GET {
+        file.seek(SeekFrom::Start(start))?;
+        file.take(end - start).read_to_end(&mut body)?;
+    }
+    Ok(builder
+        .status(status)
+        .header(header::CONTENT_LENGTH, end - start)
+        .body(Cow::Owned(body))
+        .unwrap())
+}
+
+/// Byte range `start..end` requested by a `Range: bytes=…` header, capped to
+/// [`MAX_CHUNK_BYTES`]; `None` when it can't be satisfied. Only the first range is served.
+fn parse_range(range: &str, size: u64) -> Option<(u64, u64)> {
+    let spec = range
+        .trim()
+        .strip_prefix("bytes=")?
+        .split(',')
+        .next()?
+        .trim();
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        // Suffix range: the last `n` bytes
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().ok()?;
+            (size.saturating_sub(suffix), size)
+        }
+        (start, "") => (start.parse().ok()?, size),
+        (start, end) => {
+            let end: u64 = end.parse().ok()?;
+            (start.parse().ok()?, end.saturating_add(1).min(size))
+        }
+    };
+    (start < end).then(|| (start, end.min(start + MAX_CHUNK_BYTES)))
+}
+
+fn status(status: StatusCode) -> Response<Cow<'static, [u8]>> {
+    Response::builder()
+        .status(status)
+        .body(Cow::Borrowed(&[][..]))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const PNG: &[u8] = &[
+        0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48, 0x44,
+        0x52,
+    ];
+
+    fn get_from(
+        webview: &str,
+        path: &Path,
+        headers: &[(&str, &str)],
+    ) -> Response<Cow<'static, [u8]>> {
+        let mut request = Request::builder().uri(asset_url(path));
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        respond(webview, &request.body(Vec::new()).unwrap())
+    }
+
+    fn get(path: &Path, headers: &[(&str, &str)]) -> Response<Cow<'static, [u8]>> {
+        get_from("test", path, headers)
+    }
+
+    #[test]
+    fn test_asset_url_roundtrip() {
+        let path = Path::new("/docs/my notes/图 #1.png");
+        let url = asset_url(path);
+
+        assert!(is_asset_url(&url));
+        assert!(!url.contains(' ') && !url.contains('#'), "{url}");
+        let uri: Uri = url.parse().unwrap();
+        assert_eq!(path_from_uri(&uri).unwrap(), path);
+    }
+
+    #[test]
+    fn test_sniff_mime_type() {
+        // The content wins over a misleading extension
+        assert_eq!(sniff_mime_type(Path::new("shot.jpg"), PNG), "image/png");
+        assert_eq!(
+            sniff_mime_type(Path::new("diagram"), b"<?xml version=\"1.0\"?>\n<svg>"),
+            "image/svg+xml"
+        );
+        assert_eq!(
+            sniff_mime_type(Path::new("clip.webm"), &[0x1A, 0x45, 0xDF, 0xA3]),
+            "video/webm"
+        );
+        assert_eq!(
+            sniff_mime_type(Path::new("unknown.png"), b"plain text"),
+            "application/octet-stream"
+        );
+    }
+
+    #[test]
+    fn test_serves_only_allowed_directories() {
+        let temp = TempDir::new().unwrap();
+        let docs = temp.path().join("docs");
+        std::fs::create_dir_all(docs.join("images")).unwrap();
+        std::fs::write(docs.join("images/shot.png"), PNG).unwrap();
+        std::fs::write(temp.path().join("secret.png"), PNG).unwrap();
+        let _grant = AssetGrant::new("test", [&docs]);
+
+        let response = get(&docs.join("images/shot.png"), &[]);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/png");
+        assert_eq!(response.body().as_ref(), PNG);
+
+        // Paths escaping the directory are resolved before checking
+        let escaping = docs.join("images/../../secret.png");
+        assert_eq!(get(&escaping, &[]).status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            get(&docs.join("missing.png"), &[]).status(),
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[test]
+    fn test_grants_are_scoped_and_revoked() {
+        let temp = TempDir::new().unwrap();
+        let docs = temp.path().join("docs");
+        std::fs::create_dir_all(&docs).unwrap();
+        let shot = docs.join("shot.png");
+        let included = temp.path().join("included.png");
+        std::fs::write(&shot, PNG).unwrap();
+        std::fs::write(&included, PNG).unwrap();
+        std::fs::write(temp.path().join("other.png"), PNG).unwrap();
+
+        let grant = AssetGrant::new("main", [&docs]);
+        grant.allow_files([&included]);
+        assert_eq!(get_from("main", &shot, &[]).status(), StatusCode::OK);
+        assert_eq!(get_from("main", &included, &[]).status(), StatusCode::OK);
+        // Single files don't open up their directory
+        assert_eq!(
+            get_from("main", &temp.path().join("other.png"), &[]).status(),
+            StatusCode::FORBIDDEN
+        );
+        // Nor does a grant to one webview serve another
+        assert_eq!(
+            get_from("other", &shot, &[]).status(),
+            StatusCode::FORBIDDEN
+        );
+
+        drop(grant);
+        assert_eq!(get_from("main", &shot, &[]).status(), StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn test_asset_paths() {
+        let html = format!(
+            r#"<img src="{}" srcset="{} 2x, https://example.com/c.png 1x">"#,
+            asset_url(Path::new("/docs/a b.png")),
+            asset_url(Path::new("/docs/b.png")),
+        );
+        assert_eq!(
+            asset_paths(&html),
+            vec![PathBuf::from("/docs/a b.png"), PathBuf::from("/docs/b.png")]
+        );
+    }
+
+    #[test]
+    fn test_range_and_revalidation() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("shot.png");
+        std::fs::write(&path, PNG).unwrap();
+        let _grant = AssetGrant::new("test", [temp.path()]);
+
+        let response = get(&path, &[("Range", "bytes=4-7")]);
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes 4-7/16");
+        assert_eq!(response.body().as_ref(), &PNG[4..8]);
+
+        let response = get(&path, &[("Range", "bytes=99-")]);
+        assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+
+        let etag = get(&path, &[]).headers()[header::ETAG]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let response = get(&path, &[("If-None-Match", &etag)]);
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert!(response.body().is_empty());
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-", 100), Some((0, 100)));
+        assert_eq!(parse_range("bytes=10-19", 100), Some((10, 20)));
+        assert_eq!(parse_range("bytes=-30", 100), Some((70, 100)));
+        assert_eq!(parse_range("bytes=90-200", 100), Some((90, 100)));
+        assert_eq!(parse_range("bytes=100-", 100), None);
+        assert_eq!(
+            parse_range("bytes=0-", 100 * MAX_CHUNK_BYTES),
+            Some((0, MAX_CHUNK_BYTES))
+        );
+    }
+}
@@ -1,9 +1,14 @@
 pub mod app;
+pub mod config_error_banner;
 pub mod content;
+pub mod encoding_selector;
+pub mod git_indicator;
 pub mod header;
 pub mod icon;
 pub mod main_app;
 pub mod mermaid_window;
+pub mod presentation;
+pub mod presenter_window;
 pub mod sidebar;
 pub mod tab_bar;
 pub mod tab_context_menu;
@@ -4,20 +4,26 @@ use dioxus::desktop::{use_muda_event_handler, use_wry_event_handler, window};
 use dioxus::document;
 use dioxus::html::HasFileData;
 use dioxus::prelude::*;
-use dioxus_core::use_drop;
+use dioxus_core::{use_drop, Task};
 use std::path::PathBuf;
 use std::time::Duration;
 
+use super::config_error_banner::ConfigErrorBanner;
 use super::content::Content;
 use super::header::Header;
 use super::icon::{Icon, IconName};
+use super::presentation::PresentationView;
 use super::sidebar::Sidebar;
 use super::tab_bar::TabBar;
 use crate::assets::MAIN_SCRIPT;
-use crate::events::{DIRECTORY_OPEN_BROADCAST, FILE_OPEN_BROADCAST};
+use crate::config::{ConfigLoadError, Project, CONFIG, CONFIG_LOAD_ERROR};
+use crate::events::{
+    CONFIG_CHANGED_BROADCAST, DIRECTORY_OPEN_BROADCAST, FILE_OPEN_BROADCAST, FILE_RENAMED_BROADCAST,
+};
 use crate::menu;
 use crate::state::{AppState, PersistedState, Tab, LAST_FOCUSED_STATE};
-use crate::theme::Theme;
+use crate::theme::{custom_style_paths, CustomStyles, Theme};
+use crate::watcher::FILE_WATCHER;
 
 const WINDOW_METRICS_DEBOUNCE_MS: u64 = 200;
 
@@ -54,6 +60,7 @@ pub fn App(
     sidebar_open: bool,
     sidebar_width: f64,
     sidebar_show_all_files: bool,
+    sidebar_show_hidden_files: bool,
 ) -> Element {
     // Initialize application state with the provided tab
     let mut state = use_context_provider(|| {
@@ -76,11 +83,14 @@ pub fn App(
             sidebar.open = sidebar_open;
             sidebar.width = sidebar_width;
             sidebar.show_all_files = sidebar_show_all_files;
+            sidebar.show_hidden_files = sidebar_show_hidden_files;
+            sidebar.sort_mode = CONFIG.read().sidebar.sort_mode;
             // Update last focused state for "Last Focused" behavior
             let mut state = LAST_FOCUSED_STATE.write();
             state.sidebar_open = sidebar_open;
             state.sidebar_width = sidebar_width;
             state.sidebar_show_all_files = sidebar_show_all_files;
+            state.sidebar_show_hidden_files = sidebar_show_hidden_files;
         }
         let metrics = crate::window::metrics::capture_window_metrics(&window().window);
         *app_state.position.write() = LogicalPosition::new(metrics.position.x, metrics.position.y);
@@ -100,13 +110,14 @@ pub fn App(
 
     // Initialize JavaScript main module (theme listeners, etc.)
     use_hook(|| {
+        let vim_navigation = CONFIG.read().keybindings.vim_navigation;
         spawn(async move {
             let _ = document::eval(&format!(
                 r#"
                 (async () => {{
                     try {{
                         const {{ init }} = await import("{MAIN_SCRIPT}");
-                        init();
+                        init({{ vimNavigation: {vim_navigation} }});
                     }} catch (error) {{
                         console.error("Failed to load main module:", error);
                     }}
@@ -164,6 +175,18 @@ pub fn App(
     // Listen for directory open broadcasts from background process
     setup_directory_open_listener(state);
 
+    // Follow files renamed from the file explorer of any window
+    setup_file_renamed_listener(state);
+
+    // Keep the project configuration (.arto.toml) in sync with the current directory
+    setup_project_discovery(state);
+
+    // Layer custom theme, project and user stylesheets over the built-in styles
+    setup_custom_styles(state);
+
+    // Apply configuration changes and surface config.json errors
+    let config_error = setup_config_change_listener(state);
+
     // Update window title when active tab changes
     use_effect(move || {
         let active_index = *state.active_tab.read();
@@ -276,6 +299,7 @@ pub fn App(
 
             div {
                 class: "main-area",
+                ConfigErrorBanner { error: config_error },
                 Header {},
                 TabBar {},
                 Content {},
@@ -285,6 +309,11 @@ pub fn App(
             if is_dragging() {
                 DragDropOverlay {}
             }
+
+            // Slides shown full screen over the window
+            if let Some(presentation) = state.presentation.read().clone() {
+                PresentationView { presentation }
+            }
         }
     }
 }
@@ -320,7 +349,7 @@ async fn handle_dropped_files(evt: Event<DragData>, mut state: AppState) {
         if resolved_path.is_dir() {
             // If it's a directory, set it as root and show the sidebar
             tracing::info!("Setting dropped directory as root: {:?}", resolved_path);
-            state.set_root_directory(resolved_path);
+            state.open_directory(resolved_path);
             // Show the sidebar if it's hidden so users can see the directory tree
             if !state.sidebar.read().open {
                 state.toggle_sidebar();
@@ -367,6 +396,18 @@ fn setup_file_open_listener(mut state: AppState) {
     });
 }
 
+/// Setup listener for files renamed from the file explorer (in any window)
+fn setup_file_renamed_listener(mut state: AppState) {
+    use_future(move || async move {
+        let mut rx = FILE_RENAMED_BROADCAST.subscribe();
+
+        while let Ok((from, to)) = rx.recv().await {
+            tracing::debug!("Updating tabs after rename: {:?} -> {:?}", from, to);
+            state.rename_in_tabs(&from, &to);
+        }
+    });
+}
+
 /// Setup listener for directory open broadcasts from the background process
 fn setup_directory_open_listener(mut state: AppState) {
     use_future(move || async move {
@@ -376,7 +417,7 @@ fn setup_directory_open_listener(mut state: AppState) {
             // Only handle in the focused window
             if window().is_focused() {
                 tracing::info!("Opening directory from broadcast: {:?}", dir);
-                state.set_root_directory(dir.clone());
+                state.open_directory(dir.clone());
                 // Optionally show the sidebar if it's hidden
                 if !state.sidebar.read().open {
                     state.toggle_sidebar();
@@ -386,6 +427,167 @@ fn setup_directory_open_listener(mut state: AppState) {
     });
 }
 
+/// Setup listener for configuration changes, returning the current config.json error (if any)
+fn setup_config_change_listener(mut state: AppState) -> Signal<Option<ConfigLoadError>> {
+    let mut config_error = use_signal(|| CONFIG_LOAD_ERROR.read().clone());
+
+    use_future(move || async move {
+        let mut rx = CONFIG_CHANGED_BROADCAST.subscribe();
+
+        while let Ok(result) = rx.recv().await {
+            match result {
+                Ok(config) => {
+                    config_error.set(None);
+                    if state.sidebar.peek().sort_mode != config.sidebar.sort_mode {
+                        state.sidebar.write().sort_mode = config.sidebar.sort_mode;
+                    }
+                    let _ = document::eval(&format!(
+                        "document.dispatchEvent(new CustomEvent('arto:vim-navigation-changed', {{ detail: {} }}))",
+                        config.keybindings.vim_navigation
+                    ));
+                }
+                Err(e) => config_error.set(Some(e)),
+            }
+        }
+    });
+
+    config_error
+}
+
+/// Discover the project configuration for the current directory and pass its
+/// rendering options to JavaScript
+fn setup_project_discovery(mut state: AppState) {
+    use_effect(move || {
+        let directory = state.directory.read().clone();
+        let project = directory.as_deref().and_then(Project::discover);
+        if *state.project.peek() != project {
+            state.project.set(project);
+        }
+    });
+
+    use_effect(move || {
+        let (mermaid, math) = state
+            .project
+            .read()
+            .as_ref()
+            .map(|project| (project.config.mermaid.clone(), project.config.math.clone()))
+            .unwrap_or_default();
+        let render_config = serde_json::json!({ "mermaid": mermaid, "math": math });
+        spawn(async move {
+            let _ = document::eval(&indoc::formatdoc! {r#"
+                const {{ setRenderConfig }} = await import("{MAIN_SCRIPT}");
+                setRenderConfig({render_config});
+            "#})
+            .await;
+        });
+    });
+}
+
+/// Apply the selected custom theme, project stylesheet and user stylesheet, re-applying
+/// when any of the files or the remote image policy change
+fn setup_custom_styles(state: AppState) {
+    let mut custom_theme = use_signal(|| CONFIG.read().theme.custom_theme.clone());
+    let mut remote_images = use_signal(|| CONFIG.read().remote_content.images);
+    let mut watch_tasks = use_signal(Vec::<(PathBuf, Task)>::new);
+
+    // Follow custom theme selection made in any window or in config.json
+    use_future(move || async move {
+        let mut rx = CONFIG_CHANGED_BROADCAST.subscribe();
+
+        while let Ok(result) = rx.recv().await {
+            if let Ok(config) = result {
+                if *custom_theme.peek() != config.theme.custom_theme {
+                    custom_theme.set(config.theme.custom_theme);
+                }
+                if *remote_images.peek() != config.remote_content.images {
+                    remote_images.set(config.remote_content.images);
+                }
+            }
+        }
+    });
+
+    use_effect(move || {
+        let theme_id = custom_theme();
+        let remote_images = remote_images();
+        let project_stylesheet = state
+            .project
+            .read()
+            .as_ref()
+            .and_then(|project| project.stylesheet());
+
+        // Stop watching the files of the previous selection
+        for (path, task) in watch_tasks.write().drain(..) {
+            task.cancel();
+            spawn(async move {
+                if let Err(e) = FILE_WATCHER.unwatch(path.clone()).await {
+                    tracing::error!("Failed to unregister file watcher for {:?}: {:?}", path, e);
+                }
+            });
+        }
+
+        let paths = custom_style_paths(theme_id.as_deref(), project_stylesheet.as_deref());
+        let load_styles = move || {
+            CustomStyles::load(
+                theme_id.as_deref(),
+                project_stylesheet.as_deref(),
+                remote_images,
+            )
+        };
+        spawn(apply_custom_styles(load_styles()));
+
+        let tasks = paths
+            .into_iter()
+            .map(|path| {
+                let load_styles = load_styles.clone();
+                let task = spawn({
+                    let path = path.clone();
+                    async move {
+                        let mut watcher = match FILE_WATCHER.watch(path.clone()).await {
+                            Ok(watcher) => watcher,
+                            Err(e) => {
+                                tracing::error!(
+                                    "Failed to register file watcher for {:?}: {:?}",
+                                    path,
+                                    e
+                                );
+                                return;
+                            }
+                        };
+
+                        while watcher.recv().await.is_some() {
+                            tracing::info!("Stylesheet changed, reloading: {:?}", path);
+                            apply_custom_styles(load_styles()).await;
+                        }
+                    }
+                });
+                (path, task)
+            })
+            .collect();
+        watch_tasks.set(tasks);
+    });
+}
+
+/// Replace the contents of the custom `<style>` elements in the document head
+async fn apply_custom_styles(styles: CustomStyles) {
+    let styles = serde_json::json!([
+        ["arto-custom-theme", styles.theme_css],
+        ["arto-project-stylesheet", styles.project_css],
+        ["arto-user-stylesheet", styles.user_css],
+    ]);
+    let _ = document::eval(&indoc::formatdoc! {r#"
+        for (const [id, css] of {styles}) {{
+            let style = document.getElementById(id);
+            if (!style) {{
+                style = document.createElement("style");
+                style.id = id;
+                document.head.appendChild(style);
+            }}
+            style.textContent = css;
+        }}
+    "#})
+    .await;
+}
+
 #[component]
 fn DragDropOverlay() -> Element {
     rsx! {
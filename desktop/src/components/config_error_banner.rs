@@ -0,0 +1,42 @@
+use dioxus::prelude::*;
+
+use super::icon::{Icon, IconName};
+use crate::config::{Config, ConfigLoadError};
+
+/// Non-blocking banner shown while config.json cannot be loaded
+#[component]
+pub fn ConfigErrorBanner(error: Signal<Option<ConfigLoadError>>) -> Element {
+    let Some(current_error) = error() else {
+        return rsx! {};
+    };
+    let message = current_error.to_string();
+
+    rsx! {
+        div {
+            class: "config-error-banner",
+            role: "alert",
+            Icon { name: IconName::AlertTriangle, size: 16 }
+            div {
+                class: "config-error-banner-text",
+                strong { "Configuration not applied. " }
+                span { "Arto keeps using the previous settings until config.json is fixed." }
+                code { class: "config-error-banner-detail", "{message}" }
+            }
+            button {
+                class: "config-error-banner-button",
+                onclick: move |_| {
+                    if let Err(e) = open::that(Config::path()) {
+                        tracing::error!("Failed to open config.json: {:?}", e);
+                    }
+                },
+                "Open config.json"
+            }
+            button {
+                class: "config-error-banner-close",
+                title: "Dismiss",
+                onclick: move |_| error.set(None),
+                Icon { name: IconName::Close, size: 14 }
+            }
+        }
+    }
+}
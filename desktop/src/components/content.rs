@@ -1,8 +1,10 @@
+mod change_navigator;
 mod file_error_view;
 mod file_viewer;
 mod inline_viewer;
 mod no_file_view;
 mod preferences_view;
+mod sanitization_notice;
 
 use dioxus::prelude::*;
 
@@ -14,7 +16,10 @@ use no_file_view::NoFileView;
 use preferences_view::PreferencesView;
 
 // Re-export for menu system
+pub use change_navigator::go_to_change;
 pub use preferences_view::set_preferences_tab_to_about;
+// Re-export for headless PDF export
+pub(crate) use file_viewer::{allow_included_assets, patch_document, render_page};
 
 #[component]
 pub fn Content() -> Element {
@@ -22,6 +27,8 @@ pub fn Content() -> Element {
     let zoom_level = state.zoom_level;
 
     let current_tab = state.current_tab();
+    let anchor = current_tab.as_ref().and_then(|tab| tab.anchor.clone());
+    let encoding = current_tab.as_ref().and_then(|tab| tab.encoding_override);
     let content = current_tab.map(|tab| tab.content);
 
     // Use CSS zoom property for vector-based scaling (not transform: scale)
@@ -35,18 +42,13 @@ pub fn Content() -> Element {
 
             match content {
                 Some(TabContent::File(file)) => {
-                    rsx! { FileViewer { file } }
+                    rsx! { FileViewer { file, anchor, encoding } }
                 },
                 Some(TabContent::Inline(markdown)) => {
                     rsx! { InlineViewer { markdown } }
                 },
                 Some(TabContent::FileError(file, error)) => {
-                    let filename = file
-                        .file_name()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or("Unknown file")
-                        .to_string();
-                    rsx! { FileErrorView { filename, error_message: error } }
+                    rsx! { FileErrorView { file, error_message: error } }
                 },
                 Some(TabContent::Preferences) => {
                     rsx! { PreferencesView {} }
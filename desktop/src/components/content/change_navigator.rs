@@ -0,0 +1,65 @@
+use dioxus::document;
+use dioxus::prelude::*;
+
+use crate::assets::MAIN_SCRIPT;
+use crate::components::icon::{Icon, IconName};
+use crate::state::AppState;
+
+/// Scroll to the next (or previous) highlighted change of the displayed document
+pub fn go_to_change(forward: bool) {
+    let direction = if forward { 1 } else { -1 };
+    spawn(async move {
+        let _ = document::eval(&indoc::formatdoc! {r#"
+            const {{ goToChange }} = await import("{MAIN_SCRIPT}");
+            goToChange({direction});
+        "#})
+        .await;
+    });
+}
+
+/// Summary of the highlighted changes, with buttons to step through them
+#[component]
+pub fn ChangeNavigator(
+    /// Number of places where the document changed
+    hunks: usize,
+    /// Revision the document is compared with
+    base: String,
+) -> Element {
+    let mut state = use_context::<AppState>();
+
+    let summary = match hunks {
+        0 => format!("No changes since {base}"),
+        1 => format!("1 change since {base}"),
+        n => format!("{n} changes since {base}"),
+    };
+
+    rsx! {
+        div {
+            class: "change-navigator",
+            role: "toolbar",
+
+            span { class: "change-navigator-summary", "{summary}" }
+
+            button {
+                class: "change-navigator-button",
+                title: "Previous Change",
+                disabled: hunks == 0,
+                onclick: move |_| go_to_change(false),
+                Icon { name: IconName::ArrowUp, size: 16 }
+            }
+            button {
+                class: "change-navigator-button",
+                title: "Next Change",
+                disabled: hunks == 0,
+                onclick: move |_| go_to_change(true),
+                Icon { name: IconName::ArrowDown, size: 16 }
+            }
+            button {
+                class: "change-navigator-button",
+                title: "Hide Changes",
+                onclick: move |_| state.change_base.set(None),
+                Icon { name: IconName::Close, size: 16 }
+            }
+        }
+    }
+}
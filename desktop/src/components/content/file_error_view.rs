@@ -1,9 +1,42 @@
 use dioxus::prelude::*;
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
 
+use crate::asset_protocol::AssetGrant;
 use crate::components::icon::{Icon, IconName};
+use crate::file_preview::{explain_error, preview_file, FilePreview, HEX_DUMP_BYTES};
+use crate::state::{AppState, TabContent};
+use crate::utils::file_operations::reveal_in_file_manager;
 
 #[component]
-pub fn FileErrorView(filename: String, error_message: String) -> Element {
+pub fn FileErrorView(file: PathBuf, error_message: String) -> Element {
+    let filename = file
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("Unknown file")
+        .to_string();
+    // The previewed image is served to the window while it's shown
+    let asset_grant = use_hook(|| Rc::new(RefCell::new(None::<AssetGrant>)));
+    let preview = use_memo(use_reactive!(|file| {
+        let preview = preview_file(&file);
+        let grant = matches!(preview, FilePreview::Image { .. }).then(|| {
+            let grant = AssetGrant::for_window(None::<PathBuf>);
+            grant.allow_files([&file]);
+            grant
+        });
+        asset_grant.replace(grant);
+        preview
+    }));
+
+    let (icon, title) = match &*preview.read() {
+        FilePreview::Image { .. } => (IconName::Photo, "Image"),
+        FilePreview::Pdf => (IconName::FileTypePdf, "PDF Document"),
+        FilePreview::Binary { .. } => (IconName::Binary, "Binary File"),
+        FilePreview::Unreadable(_) => (IconName::AlertTriangle, "Cannot Open File"),
+    };
+    let can_open = !matches!(*preview.read(), FilePreview::Unreadable(_));
+
     rsx! {
         div {
             class: "no-file file-error",
@@ -11,40 +44,149 @@ pub fn FileErrorView(filename: String, error_message: String) -> Element {
                 class: "no-file-container",
                 div {
                     class: "no-file-icon file-error-icon",
-                    Icon { name: IconName::AlertTriangle, size: 64 }
+                    Icon { name: icon, size: 64 }
                 }
                 h2 {
                     class: "no-file-title file-error-title",
-                    "Cannot Open File"
+                    "{title}"
                 }
                 p {
                     class: "no-file-description file-error-filename",
                     "{filename}"
                 }
-                div {
-                    class: "no-file-hints",
-                    div {
-                        class: "no-file-hint",
-                        span {
-                            class: "no-file-hint-icon",
-                            Icon { name: IconName::AlertCircle, size: 20 }
+
+                match preview() {
+                    FilePreview::Image { url, mime_type } => rsx! {
+                        img {
+                            class: "file-error-image",
+                            src: "{url}",
+                            alt: "{filename}",
+                            title: "{mime_type}",
                         }
-                        span {
-                            class: "no-file-hint-text",
-                            "This file cannot be opened. It may be a binary file or an unsupported format."
+                    },
+                    FilePreview::Pdf => rsx! {
+                        FileErrorHint {
+                            "PDF documents open in your default PDF viewer."
                         }
-                    }
-                    div {
-                        class: "no-file-hint",
-                        span {
-                            class: "no-file-hint-icon",
-                            Icon { name: IconName::AlertCircle, size: 20 }
+                    },
+                    FilePreview::Binary { mime_type, dump, size } => rsx! {
+                        FileErrorHint {
+                            match mime_type {
+                                Some(mime_type) => rsx! { "This file isn't text ({mime_type}, {size} bytes)." },
+                                None => rsx! { "This file isn't text ({size} bytes)." },
+                            }
+                            " If it is, choose its encoding in the header to open it anyway."
                         }
-                        span {
-                            class: "no-file-hint-text",
-                            "Error: {error_message}"
+                        pre {
+                            class: "file-error-hexdump",
+                            title: "First {HEX_DUMP_BYTES} bytes",
+                            "{dump}"
                         }
+                    },
+                    FilePreview::Unreadable(kind) => rsx! {
+                        div {
+                            class: "no-file-hints",
+                            div {
+                                class: "no-file-hint",
+                                span {
+                                    class: "no-file-hint-icon",
+                                    Icon { name: IconName::AlertCircle, size: 20 }
+                                }
+                                span {
+                                    class: "no-file-hint-text",
+                                    "{explain_error(kind)}"
+                                }
+                            }
+                            div {
+                                class: "no-file-hint",
+                                span {
+                                    class: "no-file-hint-icon",
+                                    Icon { name: IconName::AlertCircle, size: 20 }
+                                }
+                                span {
+                                    class: "no-file-hint-text",
+                                    "Error: {error_message}"
+                                }
+                            }
+                        }
+                    },
+                }
+
+                FileErrorActions { file, can_open }
+            }
+        }
+    }
+}
+
+#[component]
+fn FileErrorHint(children: Element) -> Element {
+    rsx! {
+        div {
+            class: "no-file-hints",
+            div {
+                class: "no-file-hint",
+                span {
+                    class: "no-file-hint-icon",
+                    Icon { name: IconName::InfoCircle, size: 20 }
+                }
+                span {
+                    class: "no-file-hint-text",
+                    {children}
+                }
+            }
+        }
+    }
+}
+
+/// Retry, Open with Default App and Reveal in File Manager
+#[component]
+fn FileErrorActions(file: PathBuf, can_open: bool) -> Element {
+    let mut state = use_context::<AppState>();
+    let can_reveal = file.exists() || file.parent().is_some_and(|dir| dir.exists());
+
+    rsx! {
+        div {
+            class: "file-error-actions",
+            button {
+                class: "file-error-action",
+                onclick: {
+                    let file = file.clone();
+                    move |_| {
+                        let file = file.clone();
+                        state.update_current_tab(|tab| tab.content = TabContent::File(file));
                     }
+                },
+                Icon { name: IconName::Refresh, size: 16 }
+                "Retry"
+            }
+            if can_open {
+                button {
+                    class: "file-error-action",
+                    onclick: {
+                        let file = file.clone();
+                        move |_| {
+                            if let Err(e) = open::that_detached(&file) {
+                                tracing::error!("Failed to open {:?}: {}", file, e);
+                            }
+                        }
+                    },
+                    Icon { name: IconName::ExternalLink, size: 16 }
+                    "Open with Default App"
+                }
+            }
+            if can_reveal {
+                button {
+                    class: "file-error-action",
+                    onclick: {
+                        let file = file.clone();
+                        move |_| {
+                            if let Err(e) = reveal_in_file_manager(&file) {
+                                tracing::error!("Failed to reveal {:?}: {}", file, e);
+                            }
+                        }
+                    },
+                    Icon { name: IconName::FolderOpen, size: 16 }
+                    "Reveal in File Manager"
                 }
             }
         }
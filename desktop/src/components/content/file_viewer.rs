@@ -1,10 +1,37 @@
+use dioxus::core::{spawn_forever, Task};
+use dioxus::document;
 use dioxus::prelude::*;
+use encoding_rs::Encoding;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
 
-use crate::markdown::render_to_html;
+use super::change_navigator::ChangeNavigator;
+use super::sanitization_notice::SanitizationNotice;
+use crate::asset_protocol::{asset_paths, AssetGrant};
+use crate::assets::MAIN_SCRIPT;
+use crate::config::CONFIG;
+use crate::csv_table::render_table_file_to_html;
+use crate::git::{self, diff_blocks, BlockChanges};
+use crate::markdown::{
+    block_keys, render_document_with_cache, resolve_local_path, Block, BlockCache, Heading,
+    RenderOptions, RenderedDocument,
+};
+use crate::markup::{render_asciidoc, render_rst};
+use crate::notebook::render_notebook;
+use crate::remote_images::{
+    fetch_image, fetch_into_cache, rewrite_remote_images, ImageCache, RemoteImageOptions,
+};
+use crate::source_view::render_source_to_html;
 use crate::state::{AppState, TabContent};
-use crate::utils::file::is_markdown_file;
+use crate::utils::encoding::decode_text;
+use crate::utils::file::{
+    is_asciidoc_file, is_markdown_file, is_notebook_file, is_rst_file, is_table_file,
+};
 use crate::watcher::FILE_WATCHER;
 
 /// Data structure for markdown link clicks from JavaScript
@@ -14,102 +41,479 @@ struct LinkClickData {
     button: u32,
 }
 
+/// Block of the displayed document, sent to the renderer to patch it (`document-patch.ts`)
+#[derive(Serialize)]
+struct DocumentBlock<'a> {
+    key: Option<String>,
+    html: &'a str,
+}
+
+/// Processed blocks of the last render of the viewed file
+type SharedBlockCache = Arc<Mutex<(PathBuf, BlockCache)>>;
+
 /// Mouse button constants
 const LEFT_CLICK: u32 = 0;
 const MIDDLE_CLICK: u32 = 1;
 
 #[component]
-pub fn FileViewer(file: PathBuf) -> Element {
+pub fn FileViewer(
+    file: PathBuf,
+    anchor: Option<String>,
+    /// Encoding chosen by the reader, instead of detecting it
+    encoding: Option<&'static Encoding>,
+) -> Element {
     let state = use_context::<AppState>();
-    let html = use_signal(String::new);
+    let rendered_file = use_signal(|| None::<PathBuf>);
+    let includes = use_signal(Vec::<PathBuf>::new);
+    let stripped = use_signal(Vec::<String>::new);
+    let blocks = use_signal(Vec::<Block>::new);
+    let changes = use_signal(|| None::<BlockChanges>);
     let reload_trigger = use_signal(|| 0usize);
 
     // Setup component hooks
-    use_file_loader(file.clone(), html, reload_trigger, state);
+    use_file_loader(
+        file.clone(),
+        encoding,
+        includes,
+        stripped,
+        blocks,
+        rendered_file,
+        reload_trigger,
+        state,
+    );
+    use_anchor_reveal(file.clone(), anchor, rendered_file, state);
+    use_repository_status(rendered_file, state);
+    use_change_highlights(file.clone(), blocks, rendered_file, changes, state);
     use_file_watcher(file.clone(), reload_trigger);
+    use_include_watcher(includes, reload_trigger);
+    use_section_fold_handler(file.clone(), state);
     use_link_click_handler(file, state);
+    use_remote_image_handler();
     use_mermaid_window_handler();
 
     rsx! {
         div {
             class: "markdown-viewer",
+            SanitizationNotice { stripped: stripped() }
+            if let (Some(changes), Some(base)) = (changes(), state.change_base.read().clone()) {
+                ChangeNavigator { hunks: changes.hunks, base }
+            }
+            // Filled in block by block by the renderer
             article {
                 class: "markdown-body",
-                dangerous_inner_html: "{html}"
+                "data-document": "true",
             }
         }
     }
 }
 
 /// Hook to load and render file content
+#[allow(clippy::too_many_arguments)]
 fn use_file_loader(
     file: PathBuf,
-    html: Signal<String>,
+    encoding: Option<&'static Encoding>,
+    includes: Signal<Vec<PathBuf>>,
+    stripped: Signal<Vec<String>>,
+    blocks: Signal<Vec<Block>>,
+    rendered_file: Signal<Option<PathBuf>>,
     reload_trigger: Signal<usize>,
     mut state: AppState,
 ) {
-    use_effect(use_reactive!(|file, reload_trigger| {
-        let mut html = html;
+    let block_cache: SharedBlockCache = use_hook(Default::default);
+    // Render in progress, superseded by the next reload
+    let render_task = use_hook(|| Rc::new(Cell::new(None::<Task>)));
+    // Local files the webview may load for the document, revoked when it's closed or replaced
+    let asset_grant = use_hook(|| Rc::new(RefCell::new(None::<AssetGrant>)));
+
+    use_effect(use_reactive!(|file, encoding, reload_trigger| {
+        let mut includes = includes;
+        let mut stripped = stripped;
+        let mut blocks = blocks;
+        let mut rendered_file = rendered_file;
         let _ = reload_trigger();
         let file = file.clone();
+        let block_cache = block_cache.clone();
+        let render_options = render_options_for(&file, state);
+        let image_options = CONFIG.read().remote_content.image_options();
+        // Serve the images and media of the document, which may also come from link roots
+        let directories = render_options.asset_directories(&file);
+        asset_grant.replace(Some(AssetGrant::for_window(directories)));
+        let asset_grant = asset_grant.clone();
 
-        spawn(async move {
+        if let Some(task) = render_task.take() {
+            task.cancel();
+        }
+        let task = spawn(async move {
             tracing::info!("Loading and rendering file: {:?}", &file);
 
-            // Try to read as string (UTF-8 text file)
-            match tokio::fs::read_to_string(file.as_path()).await {
-                Ok(content) => {
-                    // Check if file has markdown extension
-                    if is_markdown_file(&file) {
-                        // Render as markdown
-                        match render_to_html(&content, &file) {
-                            Ok(rendered) => {
-                                html.set(rendered);
-                                tracing::trace!("Rendered as Markdown: {:?}", &file);
-                            }
-                            Err(e) => {
-                                // Markdown parsing failed, render as plain text
-                                tracing::warn!(
-                                    "Markdown parsing failed for {:?}, rendering as plain text: {}",
-                                    &file,
-                                    e
-                                );
-                                let escaped_content = html_escape::encode_text(&content);
-                                let plain_html = format!(
-                                    r#"<pre class="plain-text-viewer">{}</pre>"#,
-                                    escaped_content
-                                );
-                                html.set(plain_html);
-                            }
+            let content = match tokio::fs::read(file.as_path()).await {
+                Ok(bytes) => decode_text(&bytes, encoding).ok_or_else(|| {
+                    "The file appears to be binary and can't be shown as text.".to_string()
+                }),
+                Err(e) => Err(e.to_string()),
+            };
+            match content {
+                Ok(decoded) => {
+                    if state.current_tab().and_then(|tab| tab.encoding) != Some(decoded.encoding) {
+                        state.update_current_tab(|tab| tab.encoding = Some(decoded.encoding));
+                    }
+                    let content = decoded.text;
+                    // Rendering large documents takes a while; keep the UI responsive meanwhile
+                    let task_file = file.clone();
+                    let task_options = render_options.clone();
+                    let rendered = tokio::task::spawn_blocking(move || {
+                        render_page(
+                            &task_file,
+                            &content,
+                            &task_options,
+                            &image_options,
+                            &block_cache,
+                        )
+                    })
+                    .await;
+                    let rendered = match rendered {
+                        Ok(rendered) => rendered,
+                        Err(e) => {
+                            tracing::error!("Rendering {:?} failed: {}", file, e);
+                            return;
                         }
-                    } else {
-                        // Non-markdown file, render as plain text directly
-                        tracing::info!("Rendering non-markdown file as plain text: {:?}", &file);
-                        let escaped_content = html_escape::encode_text(&content);
-                        let plain_html = format!(
-                            r#"<pre class="plain-text-viewer">{}</pre>"#,
-                            escaped_content
-                        );
-                        html.set(plain_html);
+                    };
+                    forget_removed_sections(&file, &rendered, &render_options, state);
+                    if let Some(grant) = asset_grant.borrow().as_ref() {
+                        allow_included_assets(grant, &rendered);
+                    }
+                    patch_document(&rendered.blocks).await;
+                    stripped.set(rendered.stripped);
+                    blocks.set(rendered.blocks);
+                    // Only re-register watchers when the set of included files changes
+                    if *includes.peek() != rendered.includes {
+                        includes.set(rendered.includes);
                     }
+                    rendered_file.set(Some(file));
                 }
-                Err(e) => {
-                    // Failed to read as UTF-8 text (likely binary file)
-                    tracing::error!("Failed to read file {:?} as text: {}", file, e);
-                    let error_msg = format!("{:?}", e);
+                Err(error_msg) => {
+                    // Unreadable or binary file
+                    tracing::error!("Failed to read file {:?} as text: {}", file, error_msg);
 
                     // Update tab content to FileError
                     let file_clone = file.clone();
                     state.update_current_tab(move |tab| {
                         tab.content = TabContent::FileError(file_clone, error_msg);
+                        tab.encoding = None;
                     });
-                    html.set(String::new());
+                    includes.set(Vec::new());
+                    stripped.set(Vec::new());
+                }
+            }
+        });
+        render_task.set(Some(task));
+    }));
+}
+
+/// Serve the images and media of files included into `rendered` from other directories, but
+/// not their neighbors
+pub(crate) fn allow_included_assets(grant: &AssetGrant, rendered: &RenderedDocument) {
+    let include_dirs: Vec<_> = rendered
+        .includes
+        .iter()
+        .filter_map(|path| path.parent()?.canonicalize().ok())
+        .collect();
+    grant.allow_files(
+        rendered
+            .blocks
+            .iter()
+            .flat_map(|block| asset_paths(block.html()))
+            .filter_map(|path| path.canonicalize().ok())
+            .filter(|path| include_dirs.iter().any(|dir| path.starts_with(dir))),
+    );
+}
+
+/// Options to render `file` with in the window of `state`
+fn render_options_for(file: &Path, state: AppState) -> RenderOptions {
+    RenderOptions {
+        folded_sections: state.folded_sections_of(file),
+        ..state.render_options_for(file)
+    }
+}
+
+/// Render a file and apply the remote image policy to its blocks
+pub(crate) fn render_page(
+    file: &Path,
+    content: &str,
+    render_options: &RenderOptions,
+    image_options: &RemoteImageOptions,
+    block_cache: &Mutex<(PathBuf, BlockCache)>,
+) -> RenderedDocument {
+    let mut rendered = {
+        let mut cache = block_cache.lock();
+        if cache.0 != file {
+            *cache = (file.to_path_buf(), BlockCache::default());
+        }
+        render_content(file, content, render_options, &mut cache.1)
+    };
+
+    let mut uncached = Vec::new();
+    for block in &mut rendered.blocks {
+        if let Block::Content(html) = block {
+            let (page, urls) = rewrite_remote_images(html, image_options);
+            *html = page;
+            uncached.extend(urls);
+        }
+    }
+    if let Some(cache) = image_options.cache.clone() {
+        prefetch_remote_images(uncached, cache);
+    }
+    rendered
+}
+
+/// Render file content by type: Markdown, notebook, table, or highlighted source.
+/// Falls back to plain text when the content can't be parsed as its type.
+fn render_content(
+    file: &Path,
+    content: &str,
+    render_options: &RenderOptions,
+    block_cache: &mut BlockCache,
+) -> RenderedDocument {
+    let rendered = if is_markdown_file(file) {
+        render_document_with_cache(content, file, render_options, block_cache)
+    } else if is_asciidoc_file(file) {
+        render_asciidoc(content, file, render_options)
+    } else if is_rst_file(file) {
+        render_rst(content, file, render_options)
+    } else if is_notebook_file(file) {
+        render_notebook(content, file, render_options)
+    } else if is_table_file(file) {
+        render_table_file_to_html(content, file).map(RenderedDocument::from)
+    } else {
+        Ok(render_source_to_html(content, file).into())
+    };
+
+    rendered.unwrap_or_else(|e| {
+        tracing::warn!(
+            "Rendering failed for {:?}, rendering as plain text: {}",
+            file,
+            e
+        );
+        format!(
+            r#"<pre class="plain-text-viewer">{}</pre>"#,
+            html_escape::encode_text(content)
+        )
+        .into()
+    })
+}
+
+/// Cache the remote images the webview loads itself, so they also render offline later
+fn prefetch_remote_images(urls: Vec<String>, cache: ImageCache) {
+    if urls.is_empty() {
+        return;
+    }
+    tokio::task::spawn_blocking(move || {
+        for url in urls {
+            if let Err(e) = fetch_into_cache(&url, &cache) {
+                tracing::warn!("Failed to cache remote image: {:#}", e);
+            }
+        }
+    });
+}
+
+/// Replace the displayed document with `blocks`, keeping the blocks that didn't change
+pub(crate) async fn patch_document(blocks: &[Block]) {
+    let blocks: Vec<DocumentBlock> = block_keys(blocks)
+        .into_iter()
+        .zip(blocks)
+        .map(|(key, block)| DocumentBlock {
+            key,
+            html: block.html(),
+        })
+        .collect();
+    let eval = document::eval(&indoc::formatdoc! {r#"
+        const {{ patchDocument }} = await import("{MAIN_SCRIPT}");
+        patchDocument(await dioxus.recv());
+    "#});
+    if let Err(e) = eval.send(&blocks) {
+        tracing::error!("Failed to send the document to the renderer: {:?}", e);
+        return;
+    }
+    let _ = eval.await;
+}
+
+/// Highlight the blocks of the displayed document that changed, or remove the highlights
+async fn show_changes(changes: Option<&BlockChanges>) {
+    let eval = document::eval(&indoc::formatdoc! {r#"
+        const {{ showChanges }} = await import("{MAIN_SCRIPT}");
+        showChanges(await dioxus.recv());
+    "#});
+    if let Err(e) = eval.send(changes) {
+        tracing::error!("Failed to send the changes to the renderer: {:?}", e);
+        return;
+    }
+    let _ = eval.await;
+}
+
+/// Drop folds of sections whose heading was removed or renamed
+fn forget_removed_sections(
+    file: &Path,
+    rendered: &RenderedDocument,
+    render_options: &RenderOptions,
+    mut state: AppState,
+) {
+    // Only Markdown documents report their outline
+    if rendered.outline.is_empty() {
+        return;
+    }
+    let headings: HashSet<&str> = rendered.outline.iter().flat_map(Heading::ids).collect();
+    let folded = &render_options.folded_sections;
+    if folded.iter().all(|id| headings.contains(id.as_str())) {
+        return;
+    }
+    let folded = folded
+        .iter()
+        .filter(|id| headings.contains(id.as_str()))
+        .cloned()
+        .collect();
+    state.set_folded_sections(file.to_path_buf(), folded);
+}
+
+/// Hook to scroll to (and highlight) the tab's anchor once its file has been rendered
+fn use_anchor_reveal(
+    file: PathBuf,
+    anchor: Option<String>,
+    rendered_file: Signal<Option<PathBuf>>,
+    mut state: AppState,
+) {
+    use_effect(use_reactive!(|file, anchor| {
+        let Some(anchor) = anchor.clone() else {
+            return;
+        };
+        // Wait for this file's content; the DOM may still show the previous file
+        if rendered_file.read().as_ref() != Some(&file) {
+            return;
+        }
+        // Reveal once, so live reloads keep the reader's scroll position
+        state.update_current_tab(|tab| tab.anchor = None);
+        let anchor = serde_json::to_string(&anchor).unwrap_or_default();
+        spawn(async move {
+            let _ = document::eval(&indoc::formatdoc! {r#"
+                const {{ revealAnchor }} = await import("{MAIN_SCRIPT}");
+                revealAnchor({anchor});
+            "#})
+            .await;
+        });
+    }));
+}
+
+/// Hook to refresh the status of the repository of the file after every render
+fn use_repository_status(rendered_file: Signal<Option<PathBuf>>, mut state: AppState) {
+    use_effect(move || {
+        let Some(file) = rendered_file() else {
+            return;
+        };
+        spawn(async move {
+            let status = tokio::task::spawn_blocking(move || git::status(&file))
+                .await
+                .ok()
+                .flatten();
+            if *state.repository.peek() != status {
+                state.repository.set(status);
+            }
+        });
+    });
+}
+
+/// Hook to highlight the blocks of Markdown documents changed since the revision chosen in
+/// `state.change_base`, after every render
+fn use_change_highlights(
+    file: PathBuf,
+    blocks: Signal<Vec<Block>>,
+    rendered_file: Signal<Option<PathBuf>>,
+    changes: Signal<Option<BlockChanges>>,
+    state: AppState,
+) {
+    // Processed blocks of the base version, which seldom changes
+    let base_cache: SharedBlockCache = use_hook(Default::default);
+    let highlight_task = use_hook(|| Rc::new(Cell::new(None::<Task>)));
+
+    use_effect(use_reactive!(|file| {
+        let mut changes = changes;
+        let base = state.change_base.read().clone();
+        let blocks = blocks.read().clone();
+        if let Some(task) = highlight_task.take() {
+            task.cancel();
+        }
+        // Wait for this file's content; the blocks may still be the previous file's
+        if rendered_file.read().as_ref() != Some(&file) {
+            return;
+        }
+        let Some(base) = base.filter(|_| is_markdown_file(&file)) else {
+            if changes.peek().is_some() {
+                changes.set(None);
+                spawn(show_changes(None));
+            }
+            return;
+        };
+
+        let file = file.clone();
+        let base_cache = base_cache.clone();
+        let render_options = render_options_for(&file, state);
+        let image_options = CONFIG.read().remote_content.image_options();
+        let task = spawn(async move {
+            let task_file = file.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                changes_since(
+                    &task_file,
+                    &base,
+                    &blocks,
+                    &render_options,
+                    &image_options,
+                    &base_cache,
+                )
+            })
+            .await;
+            match result {
+                Ok(Ok(found)) => {
+                    show_changes(Some(&found)).await;
+                    changes.set(Some(found));
                 }
+                Ok(Err(e)) => {
+                    tracing::warn!("Failed to find the changes of {:?}: {:#}", file, e);
+                    show_changes(None).await;
+                    changes.set(None);
+                }
+                Err(e) => tracing::error!("Finding the changes of {:?} failed: {}", file, e),
             }
         });
+        highlight_task.set(Some(task));
     }));
 }
 
+/// Compare `blocks`, rendered from `file`, with the rendered version of `file` at `base`
+fn changes_since(
+    file: &Path,
+    base: &str,
+    blocks: &[Block],
+    render_options: &RenderOptions,
+    image_options: &RemoteImageOptions,
+    base_cache: &Mutex<(PathBuf, BlockCache)>,
+) -> anyhow::Result<BlockChanges> {
+    let base_blocks = match git::read_file_at(file, base)? {
+        Some(bytes) => {
+            let content = decode_text(&bytes, None)
+                .ok_or_else(|| anyhow::anyhow!("The file at {base} appears to be binary"))?;
+            render_page(
+                file,
+                &content.text,
+                render_options,
+                image_options,
+                base_cache,
+            )
+            .blocks
+        }
+        // New to the repository since
+        None => Vec::new(),
+    };
+    Ok(diff_blocks(&base_blocks, blocks))
+}
+
 /// Hook to watch file for changes and trigger reload
 fn use_file_watcher(file: PathBuf, reload_trigger: Signal<usize>) {
     use_effect(use_reactive!(|file| {
@@ -146,6 +550,95 @@ fn use_file_watcher(file: PathBuf, reload_trigger: Signal<usize>) {
     }));
 }
 
+/// Hook to reload the document when a file included into it changes
+fn use_include_watcher(includes: Signal<Vec<PathBuf>>, reload_trigger: Signal<usize>) {
+    // Watch tasks by included file; not a signal, so the effect only tracks `includes`
+    let watched = use_hook(|| Rc::new(RefCell::new(Vec::<(PathBuf, Task)>::new())));
+
+    use_effect({
+        let watched = watched.clone();
+        move || {
+            let includes = includes();
+            let mut watched = watched.borrow_mut();
+
+            // Stop watching files that are no longer included
+            watched.retain(|(path, task)| {
+                if includes.contains(path) {
+                    return true;
+                }
+                task.cancel();
+                let path = path.clone();
+                spawn(async move {
+                    if let Err(e) = FILE_WATCHER.unwatch(path.clone()).await {
+                        tracing::error!(
+                            "Failed to unregister file watcher for {:?}: {:?}",
+                            path,
+                            e
+                        );
+                    }
+                });
+                false
+            });
+
+            for path in includes {
+                if watched
+                    .iter()
+                    .any(|(watched_path, _)| *watched_path == path)
+                {
+                    continue;
+                }
+                let task = spawn(watch_include(path.clone(), reload_trigger));
+                watched.push((path, task));
+            }
+        }
+    });
+
+    use_drop(move || {
+        for (path, task) in watched.borrow_mut().drain(..) {
+            task.cancel();
+            // The component's scope is going away, so its own tasks can't be used
+            spawn_forever(async move {
+                let _ = FILE_WATCHER.unwatch(path).await;
+            });
+        }
+    });
+}
+
+async fn watch_include(path: PathBuf, mut reload_trigger: Signal<usize>) {
+    let mut watcher = match FILE_WATCHER.watch(path.clone()).await {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            tracing::error!("Failed to register file watcher for {:?}: {:?}", path, e);
+            return;
+        }
+    };
+
+    while watcher.recv().await.is_some() {
+        tracing::info!("Included file changed, reloading: {:?}", path);
+        reload_trigger.set(reload_trigger() + 1);
+    }
+}
+
+/// Hook to remember which sections the reader folded, so reloads keep them folded
+fn use_section_fold_handler(file: PathBuf, state: AppState) {
+    use_effect(use_reactive!(|file| {
+        let file = file.clone();
+        let mut eval_provider = document::eval(indoc::indoc! {r#"
+            window.handleSectionFoldChange = (folded) => {
+                dioxus.send(folded);
+            };
+        "#});
+
+        let mut state = state;
+
+        spawn(async move {
+            while let Ok(folded) = eval_provider.recv::<HashSet<String>>().await {
+                state.set_folded_sections(file.clone(), folded);
+            }
+        });
+    }));
+}
+
 /// Hook to setup JavaScript handler for markdown link clicks
 fn use_link_click_handler(file: PathBuf, state: AppState) {
     use_effect(use_reactive!(|file| {
@@ -177,8 +670,15 @@ fn handle_link_click(click_data: LinkClickData, base_dir: &Path, state: &mut App
 
     tracing::info!("Markdown link clicked: {} (button: {})", path, button);
 
-    // Resolve and normalize the path
-    let target_path = base_dir.join(&path);
+    // Split off a fragment such as `#L10-L20` or `#section`, revealed after opening
+    let (path, anchor) = match path.split_once('#') {
+        Some((path, fragment)) if !fragment.is_empty() => (path, Some(fragment.to_string())),
+        Some((path, _)) => (path, None),
+        None => (path.as_str(), None),
+    };
+
+    // Resolve and normalize the path; links may point into the site root or any workspace root
+    let target_path = resolve_local_path(path, base_dir, &state.link_roots());
     let Ok(canonical_path) = target_path.canonicalize() else {
         tracing::error!("Failed to resolve path: {:?}", target_path);
         return;
@@ -197,8 +697,65 @@ fn handle_link_click(click_data: LinkClickData, base_dir: &Path, state: &mut App
         }
         _ => {
             tracing::debug!("Ignoring click with button: {}", button);
+            return;
         }
     }
+
+    if anchor.is_some() {
+        state.update_current_tab(|tab| tab.anchor = anchor);
+    }
+}
+
+/// Hook to load remote images whose placeholder the reader clicked
+fn use_remote_image_handler() {
+    use_effect(|| {
+        let mut eval_provider = document::eval(indoc::indoc! {r#"
+            window.handleRemoteImageLoad = (url) => {
+                dioxus.send(url);
+            };
+        "#});
+
+        spawn(async move {
+            while let Ok(url) = eval_provider.recv::<String>().await {
+                tracing::info!("Loading remote image: {}", url);
+                let cache = CONFIG.read().remote_content.image_options().cache;
+                let task_url = url.clone();
+                let result = tokio::task::spawn_blocking(move || {
+                    let image = fetch_image(&task_url)?;
+                    if let Some(cache) = cache {
+                        if let Err(e) = cache.insert(&task_url, &image) {
+                            tracing::warn!("Failed to cache remote image {}: {}", task_url, e);
+                        }
+                    }
+                    anyhow::Ok(image)
+                })
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result);
+
+                let url_json = serde_json::to_string(&url).unwrap_or_default();
+                let script = match result {
+                    Ok(image) => {
+                        let data_url =
+                            serde_json::to_string(&image.to_data_url()).unwrap_or_default();
+                        indoc::formatdoc! {r#"
+                            const {{ loadRemoteImage }} = await import("{MAIN_SCRIPT}");
+                            loadRemoteImage({url_json}, {data_url});
+                        "#}
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to load remote image: {:#}", e);
+                        let message = serde_json::to_string(&format!("{e:#}")).unwrap_or_default();
+                        indoc::formatdoc! {r#"
+                            const {{ failRemoteImage }} = await import("{MAIN_SCRIPT}");
+                            failRemoteImage({url_json}, {message});
+                        "#}
+                    }
+                };
+                let _ = document::eval(&script).await;
+            }
+        });
+    });
 }
 
 /// Hook to setup Mermaid window open handler
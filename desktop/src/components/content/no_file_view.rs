@@ -53,7 +53,13 @@ pub fn NoFileView() -> Element {
                             class: "no-file-hint-icon",
                             Icon { name: IconName::Click, size: 20 }
                         }
-                        span { class: "no-file-hint-text", "Right-click in Finder and choose \"Open with Arto\"" }
+                        span { class: "no-file-hint-text",
+                            if cfg!(target_os = "macos") {
+                                "Right-click in Finder and choose \"Open with Arto\""
+                            } else {
+                                "Right-click in your file manager and choose \"Open With Arto\""
+                            }
+                        }
                     }
                 }
             }
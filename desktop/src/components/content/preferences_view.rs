@@ -3,4 +3,6 @@ mod main_view;
 mod tabs;
 
 // Re-export only public API
-pub use main_view::{set_preferences_tab_to_about, PreferencesView};
+pub use main_view::{
+    set_preferences_tab_to_about, set_preferences_tab_to_security, PreferencesView,
+};
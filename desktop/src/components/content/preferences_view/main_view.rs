@@ -1,9 +1,11 @@
 use super::tabs::{
-    about_tab::AboutTab, directory_tab::DirectoryTab, sidebar_tab::SidebarTab, theme_tab::ThemeTab,
+    about_tab::AboutTab, directory_tab::DirectoryTab, keybindings_tab::KeybindingsTab,
+    security_tab::SecurityTab, sidebar_tab::SidebarTab, theme_tab::ThemeTab,
     window_position_tab::WindowPositionTab, window_size_tab::WindowSizeTab,
 };
 use crate::components::icon::{Icon, IconName};
 use crate::config::{Config, CONFIG};
+use crate::events::CONFIG_CHANGED_BROADCAST;
 use crate::state::AppState;
 use dioxus::prelude::*;
 use parking_lot::RwLock;
@@ -17,6 +19,8 @@ pub enum PreferencesTab {
     WindowSize,
     Sidebar,
     Directory,
+    Keybindings,
+    Security,
     About,
 }
 
@@ -29,6 +33,11 @@ pub fn set_preferences_tab_to_about() {
     *LAST_PREFERENCES_TAB.write() = PreferencesTab::About;
 }
 
+/// Set the preferences tab to Security (called from the sanitization notice)
+pub fn set_preferences_tab_to_security() {
+    *LAST_PREFERENCES_TAB.write() = PreferencesTab::Security;
+}
+
 /// Save status for the preferences page
 #[derive(Clone, Copy, PartialEq, Eq, Default)]
 enum SaveStatus {
@@ -53,6 +62,19 @@ pub fn PreferencesView() -> Element {
         has_changes.set(false);
     });
 
+    // Pick up changes made outside this view (config.json edits, theme selector)
+    use_future(move || async move {
+        let mut rx = CONFIG_CHANGED_BROADCAST.subscribe();
+
+        while let Ok(result) = rx.recv().await {
+            if let Ok(new_config) = result {
+                if !has_changes() {
+                    config.set(new_config);
+                }
+            }
+        }
+    });
+
     let handle_save = move |_| {
         let cfg = config().clone();
         save_status.set(SaveStatus::Saving);
@@ -63,6 +85,7 @@ pub fn PreferencesView() -> Element {
             } else {
                 *CONFIG.write() = cfg.clone();
                 has_changes.set(false);
+                let _ = CONFIG_CHANGED_BROADCAST.send(Ok(cfg));
                 save_status.set(SaveStatus::Saved);
                 // Reset to idle after showing success
                 tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
@@ -130,6 +153,24 @@ pub fn PreferencesView() -> Element {
                         Icon { name: IconName::Folder, size: 18 }
                         span { "Directory" }
                     }
+                    button {
+                        class: if current_tab == PreferencesTab::Keybindings { "nav-tab active" } else { "nav-tab" },
+                        onclick: move |_| {
+                            active_tab.set(PreferencesTab::Keybindings);
+                            *LAST_PREFERENCES_TAB.write() = PreferencesTab::Keybindings;
+                        },
+                        Icon { name: IconName::Keyboard, size: 18 }
+                        span { "Keyboard" }
+                    }
+                    button {
+                        class: if current_tab == PreferencesTab::Security { "nav-tab active" } else { "nav-tab" },
+                        onclick: move |_| {
+                            active_tab.set(PreferencesTab::Security);
+                            *LAST_PREFERENCES_TAB.write() = PreferencesTab::Security;
+                        },
+                        Icon { name: IconName::Shield, size: 18 }
+                        span { "Security" }
+                    }
 
                     // Spacer to push About to bottom
                     div { class: "nav-spacer" }
@@ -207,6 +248,18 @@ pub fn PreferencesView() -> Element {
                                 current_directory: state.directory.read().clone(),
                             }
                         },
+                        PreferencesTab::Keybindings => rsx! {
+                            KeybindingsTab {
+                                config,
+                                has_changes,
+                            }
+                        },
+                        PreferencesTab::Security => rsx! {
+                            SecurityTab {
+                                config,
+                                has_changes,
+                            }
+                        },
                         PreferencesTab::About => rsx! {
                             AboutTab {}
                         },
@@ -1,5 +1,7 @@
 pub mod about_tab;
 pub mod directory_tab;
+pub mod keybindings_tab;
+pub mod security_tab;
 pub mod sidebar_tab;
 pub mod theme_tab;
 pub mod window_position_tab;
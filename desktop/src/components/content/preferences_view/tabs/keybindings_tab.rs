@@ -0,0 +1,101 @@
+use super::super::form_controls::{OptionCardItem, OptionCards};
+use crate::config::Config;
+use crate::menu::{keybinding_entries, keybinding_issues};
+use dioxus::prelude::*;
+
+#[component]
+pub fn KeybindingsTab(config: Signal<Config>, has_changes: Signal<bool>) -> Element {
+    // Extract values upfront to avoid holding read guard across closures
+    let vim_navigation = config.read().keybindings.vim_navigation;
+    let entries = keybinding_entries();
+    let issues = keybinding_issues();
+
+    rsx! {
+        div {
+            class: "preferences-pane",
+
+            h3 { class: "preference-section-title", "Document Navigation" }
+
+            div {
+                class: "preference-item",
+                div {
+                    class: "preference-item-header",
+                    label { "Vim-style Keys" }
+                    p {
+                        class: "preference-description",
+                        "Use j/k to scroll, gg/G to jump to top/bottom, n/N to repeat search, and [/] to move between headings."
+                    }
+                }
+                OptionCards {
+                    name: "keybindings-vim-navigation".to_string(),
+                    options: vec![
+                        OptionCardItem {
+                            icon: None,
+                            value: false,
+                            title: "Disabled".to_string(),
+                            description: Some("Keys are not intercepted".to_string()),
+                        },
+                        OptionCardItem {
+                            icon: None,
+                            value: true,
+                            title: "Enabled".to_string(),
+                            description: Some("Navigate with vim-style keys".to_string()),
+                        },
+                    ],
+                    selected: vim_navigation,
+                    on_change: move |new_state| {
+                        config.write().keybindings.vim_navigation = new_state;
+                        has_changes.set(true);
+                    },
+                }
+            }
+
+            h3 { class: "preference-section-title", "Menu Shortcuts" }
+
+            div {
+                class: "preference-item",
+                div {
+                    class: "preference-item-header",
+                    label { "Current Shortcuts" }
+                    p {
+                        class: "preference-description",
+                        "Override shortcuts in the \"keybindings.menu\" section of config.json (e.g. \"view.toggle_sidebar\": \"CmdOrCtrl+Shift+B\"). Changes apply after restarting Arto."
+                    }
+                }
+
+                if !issues.is_empty() {
+                    ul {
+                        class: "keybinding-issues",
+                        for issue in issues {
+                            li { "{issue}" }
+                        }
+                    }
+                }
+
+                table {
+                    class: "keybinding-table",
+                    tbody {
+                        for entry in entries {
+                            tr {
+                                key: "{entry.id}",
+                                td { class: "keybinding-label", "{entry.label}" }
+                                td { class: "keybinding-id", code { "{entry.id}" } }
+                                td {
+                                    class: "keybinding-shortcut",
+                                    if let Some(shortcut) = entry.shortcut {
+                                        kbd { "{shortcut}" }
+                                    } else {
+                                        span { class: "keybinding-none", "—" }
+                                    }
+                                    if entry.customized {
+                                        span { class: "keybinding-custom", "Custom" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
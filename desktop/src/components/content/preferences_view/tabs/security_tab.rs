@@ -0,0 +1,140 @@
+use super::super::form_controls::{OptionCardItem, OptionCards, SliderInput};
+use crate::config::{Config, SanitizationMode};
+use crate::remote_images::RemoteImagePolicy;
+use dioxus::prelude::*;
+
+#[component]
+pub fn SecurityTab(config: Signal<Config>, has_changes: Signal<bool>) -> Element {
+    // Extract values upfront to avoid holding read guard across closures
+    let sanitization = config.read().sanitization.clone();
+    let remote_content = config.read().remote_content.clone();
+
+    rsx! {
+        div {
+            class: "preferences-pane",
+
+            h3 { class: "preference-section-title", "Raw HTML" }
+
+            div {
+                class: "preference-item",
+                div {
+                    class: "preference-item-header",
+                    label { "Sanitization" }
+                    p {
+                        class: "preference-description",
+                        "Which HTML written in documents is kept. Scripts, event handlers and embedded frames are always removed unless the document is trusted. Applies to documents opened or reloaded afterwards."
+                    }
+                }
+                OptionCards {
+                    name: "sanitization-mode".to_string(),
+                    options: SanitizationMode::ALL
+                        .into_iter()
+                        .map(|mode| OptionCardItem {
+                            icon: None,
+                            value: mode,
+                            title: mode.label().to_string(),
+                            description: Some(
+                                match mode {
+                                    SanitizationMode::Strict => "Only what Markdown itself produces",
+                                    SanitizationMode::Github => "The HTML GitHub allows",
+                                    SanitizationMode::TrustedDirectories => "No sanitization in trusted directories",
+                                }
+                                .to_string(),
+                            ),
+                        })
+                        .collect(),
+                    selected: sanitization.mode,
+                    on_change: move |mode| {
+                        config.write().sanitization.mode = mode;
+                        has_changes.set(true);
+                    },
+                }
+            }
+
+            div {
+                class: "preference-item",
+                div {
+                    class: "preference-item-header",
+                    label { "Trusted Directories" }
+                    p {
+                        class: "preference-description",
+                        "Documents in these directories are rendered as is in Trusted Directories mode. Directories are listed in "
+                        code { "sanitization.trustedDirectories" }
+                        " in config.json."
+                    }
+                }
+                if sanitization.trusted_directories.is_empty() {
+                    p { class: "preference-description", "None" }
+                } else {
+                    ul {
+                        for dir in sanitization.trusted_directories.iter() {
+                            li { code { "{dir.display()}" } }
+                        }
+                    }
+                }
+            }
+
+            h3 { class: "preference-section-title", "Remote Content" }
+
+            div {
+                class: "preference-item",
+                div {
+                    class: "preference-item-header",
+                    label { "Remote Images" }
+                    p {
+                        class: "preference-description",
+                        "Whether images hosted elsewhere are loaded. Loading them reveals your IP address and that you opened the document to their host. Cached images are always shown."
+                    }
+                }
+                OptionCards {
+                    name: "remote-images".to_string(),
+                    options: RemoteImagePolicy::ALL
+                        .into_iter()
+                        .map(|policy| OptionCardItem {
+                            icon: None,
+                            value: policy,
+                            title: policy.label().to_string(),
+                            description: Some(
+                                match policy {
+                                    RemoteImagePolicy::Block => "Never contact image hosts",
+                                    RemoteImagePolicy::ClickToLoad => "Show a placeholder to click",
+                                    RemoteImagePolicy::Always => "Load images like a browser",
+                                }
+                                .to_string(),
+                            ),
+                        })
+                        .collect(),
+                    selected: remote_content.images,
+                    on_change: move |policy| {
+                        config.write().remote_content.images = policy;
+                        has_changes.set(true);
+                    },
+                }
+            }
+
+            div {
+                class: "preference-item",
+                div {
+                    class: "preference-item-header",
+                    label { "Image Cache Size" }
+                    p {
+                        class: "preference-description",
+                        "Loaded images are kept on disk so documents render offline. The least recently used images are removed beyond this size; 0 disables the cache."
+                    }
+                }
+                SliderInput {
+                    value: remote_content.image_cache_size_mb as f64,
+                    min: 0.0,
+                    max: 1000.0,
+                    step: 10.0,
+                    unit: "MB".to_string(),
+                    on_change: move |size: f64| {
+                        config.write().remote_content.image_cache_size_mb = size.round() as u64;
+                        has_changes.set(true);
+                    },
+                    current_value: None,
+                }
+            }
+        }
+    }
+}
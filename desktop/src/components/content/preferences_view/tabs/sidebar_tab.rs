@@ -1,5 +1,5 @@
 use super::super::form_controls::{OptionCardItem, OptionCards, SliderInput};
-use crate::config::{Config, NewWindowBehavior, StartupBehavior};
+use crate::config::{Config, NewWindowBehavior, SortMode, StartupBehavior};
 use dioxus::prelude::*;
 
 #[component]
@@ -74,7 +74,12 @@ pub fn SidebarTab(
                 div {
                     class: "preference-item-header",
                     label { "Show All Files" }
-                    p { class: "preference-description", "Whether to show non-markdown files in the file explorer." }
+                    p {
+                        class: "preference-description",
+                        "Whether to show files other than documents in the file explorer. Document extensions are listed in "
+                        code { "sidebar.documentExtensions" }
+                        " in config.json."
+                    }
                 }
                 OptionCards {
                     name: "sidebar-show-all-files".to_string(),
@@ -100,6 +105,116 @@ pub fn SidebarTab(
                 }
             }
 
+            div {
+                class: "preference-item",
+                div {
+                    class: "preference-item-header",
+                    label { "Show Hidden Files" }
+                    p { class: "preference-description", "Whether to show dotfiles and dot-directories in the file explorer." }
+                }
+                OptionCards {
+                    name: "sidebar-show-hidden-files".to_string(),
+                    options: vec![
+                        OptionCardItem {
+                            icon: None,
+                            value: false,
+                            title: "Hide".to_string(),
+                            description: Some("Hide dotfiles".to_string()),
+                        },
+                        OptionCardItem {
+                            icon: None,
+                            value: true,
+                            title: "Show".to_string(),
+                            description: Some("Show dotfiles (except .git)".to_string()),
+                        },
+                    ],
+                    selected: sidebar.default_show_hidden_files,
+                    on_change: move |new_state| {
+                        config.write().sidebar.default_show_hidden_files = new_state;
+                        has_changes.set(true);
+                    },
+                }
+            }
+
+            div {
+                class: "preference-item",
+                div {
+                    class: "preference-item-header",
+                    label { "Ignore Files" }
+                    p {
+                        class: "preference-description",
+                        "Whether to hide files matched by .gitignore and .ignore. Additional patterns can be listed in "
+                        code { "sidebar.ignoreGlobs" }
+                        " in config.json."
+                    }
+                }
+                OptionCards {
+                    name: "sidebar-respect-ignore-files".to_string(),
+                    options: vec![
+                        OptionCardItem {
+                            icon: None,
+                            value: true,
+                            title: "Respect".to_string(),
+                            description: Some("Hide ignored files".to_string()),
+                        },
+                        OptionCardItem {
+                            icon: None,
+                            value: false,
+                            title: "Show All".to_string(),
+                            description: Some("Show ignored files".to_string()),
+                        },
+                    ],
+                    selected: sidebar.respect_ignore_files,
+                    on_change: move |new_state| {
+                        config.write().sidebar.respect_ignore_files = new_state;
+                        has_changes.set(true);
+                    },
+                }
+            }
+
+            div {
+                class: "preference-item",
+                div {
+                    class: "preference-item-header",
+                    label { "Sort Order" }
+                    p { class: "preference-description", "How entries in the file explorer are ordered. Directories are always listed first." }
+                }
+                OptionCards {
+                    name: "sidebar-sort-mode".to_string(),
+                    options: vec![
+                        OptionCardItem {
+                            icon: None,
+                            value: SortMode::Name,
+                            title: SortMode::Name.label().to_string(),
+                            description: Some("Natural order (2 before 10)".to_string()),
+                        },
+                        OptionCardItem {
+                            icon: None,
+                            value: SortMode::Modified,
+                            title: SortMode::Modified.label().to_string(),
+                            description: Some("Recently modified first".to_string()),
+                        },
+                        OptionCardItem {
+                            icon: None,
+                            value: SortMode::Size,
+                            title: SortMode::Size.label().to_string(),
+                            description: Some("Largest files first".to_string()),
+                        },
+                        OptionCardItem {
+                            icon: None,
+                            value: SortMode::FrontMatter,
+                            title: SortMode::FrontMatter.label().to_string(),
+                            description: Some("By order or weight field".to_string()),
+                        },
+                    ],
+                    selected: sidebar.sort_mode,
+                    on_change: move |new_mode| {
+                        config.write().sidebar.sort_mode = new_mode;
+                        has_changes.set(true);
+                    },
+                }
+            }
+
             h3 { class: "preference-section-title", "Behavior" }
 
             div {
@@ -1,12 +1,34 @@
 use super::super::form_controls::{OptionCardItem, OptionCards};
 use crate::components::icon::IconName;
 use crate::config::{Config, NewWindowBehavior, StartupBehavior};
-use crate::theme::Theme;
+use crate::theme::{list_custom_themes, themes_dir, user_stylesheet_path, Theme};
 use dioxus::prelude::*;
 
 #[component]
 pub fn ThemeTab(config: Signal<Config>, has_changes: Signal<bool>) -> Element {
     let theme = config.read().theme.clone();
+    let custom_themes = use_hook(list_custom_themes);
+    let custom_theme_description = format!(
+        "CSS files in {} layered over the built-in styles.",
+        themes_dir().display()
+    );
+    let user_stylesheet_description = format!(
+        "{} is always applied last; use it for tweaks such as font family, font size or line width.",
+        user_stylesheet_path().display()
+    );
+
+    let mut custom_theme_options = vec![OptionCardItem {
+        value: None,
+        icon: None,
+        title: "None".to_string(),
+        description: Some("Built-in styles only".to_string()),
+    }];
+    custom_theme_options.extend(custom_themes.iter().map(|custom| OptionCardItem {
+        value: Some(custom.id.clone()),
+        icon: None,
+        title: custom.name.clone(),
+        description: custom.description.clone(),
+    }));
 
     rsx! {
         div {
@@ -51,6 +73,33 @@ pub fn ThemeTab(config: Signal<Config>, has_changes: Signal<bool>) -> Element {
                 }
             }
 
+            div {
+                class: "preference-item",
+                div {
+                    class: "preference-item-header",
+                    label { "Custom Theme" }
+                    p { class: "preference-description", "{custom_theme_description}" }
+                    p { class: "preference-description", "{user_stylesheet_description}" }
+                }
+                OptionCards {
+                    name: "theme-custom".to_string(),
+                    options: custom_theme_options,
+                    selected: theme.custom_theme,
+                    on_change: move |new_theme: Option<String>| {
+                        // Switch to the light/dark variant the custom theme is designed for
+                        let base = new_theme.as_deref().and_then(|id| {
+                            custom_themes.iter().find(|custom| custom.id == id)?.base
+                        });
+                        let mut config = config.write();
+                        if let Some(base) = base {
+                            config.theme.default_theme = base;
+                        }
+                        config.theme.custom_theme = new_theme;
+                        has_changes.set(true);
+                    },
+                }
+            }
+
             h3 { class: "preference-section-title", "Behavior" }
 
             div {
@@ -0,0 +1,37 @@
+use dioxus::prelude::*;
+
+use super::preferences_view::set_preferences_tab_to_security;
+use crate::components::icon::{Icon, IconName};
+use crate::state::AppState;
+
+/// Notice shown above a document whose raw HTML was partly removed by the sanitizer
+#[component]
+pub fn SanitizationNotice(stripped: Vec<String>) -> Element {
+    let mut state = use_context::<AppState>();
+    if stripped.is_empty() {
+        return rsx! {};
+    }
+    let details = stripped.join(", ");
+
+    rsx! {
+        div {
+            class: "sanitization-notice",
+            role: "status",
+            Icon { name: IconName::Shield, size: 16 }
+            div {
+                class: "sanitization-notice-text",
+                strong { "Some HTML was removed. " }
+                span { "Parts of this document could run code or load content Arto doesn't allow." }
+                code { class: "sanitization-notice-detail", "{details}" }
+            }
+            button {
+                class: "sanitization-notice-button",
+                onclick: move |_| {
+                    set_preferences_tab_to_security();
+                    state.open_preferences();
+                },
+                "Security Settings"
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use dioxus::document;
+use dioxus::prelude::*;
+use encoding_rs::Encoding;
+
+use crate::state::AppState;
+use crate::utils::encoding::ENCODINGS;
+
+/// Encoding of the displayed file, with a menu to reopen it with another one
+#[component]
+pub fn EncodingSelector(
+    /// Encoding the file was decoded with (`None` while loading or when it couldn't be opened)
+    encoding: Option<&'static Encoding>,
+    /// Encoding the reader chose, if not detected
+    chosen: Option<&'static Encoding>,
+) -> Element {
+    let mut state = use_context::<AppState>();
+    let mut is_expanded = use_signal(|| false);
+
+    // Listen for clicks outside the encoding selector
+    use_hook(|| {
+        spawn(async move {
+            loop {
+                let _ = document::eval(
+                    r#"
+                    await new Promise((resolve) => {
+                        const handler = (e) => {
+                            if (!e.target.closest('.encoding-selector')) {
+                                resolve();
+                            } else {
+                                document.addEventListener('mousedown', handler, { once: true });
+                            }
+                        };
+                        document.addEventListener('mousedown', handler, { once: true });
+                    })
+                    "#,
+                )
+                .await;
+
+                if is_expanded() {
+                    is_expanded.set(false);
+                }
+            }
+        });
+    });
+
+    let mut reopen = move |encoding: Option<&'static Encoding>| {
+        state.update_current_tab(|tab| tab.reopen_with_encoding(encoding));
+        is_expanded.set(false);
+    };
+
+    let label = encoding.map_or("Encoding", Encoding::name);
+
+    rsx! {
+        div {
+            class: "encoding-selector",
+
+            button {
+                class: "encoding-selector-main",
+                class: if chosen.is_some() { "encoding-selector-main--chosen" },
+                "aria-expanded": if is_expanded() { "true" } else { "false" },
+                "aria-haspopup": "menu",
+                title: "Reopen with Encoding…",
+                onclick: move |evt| {
+                    evt.stop_propagation();
+                    is_expanded.set(!is_expanded());
+                },
+                "{label}"
+            }
+
+            div {
+                class: "encoding-selector-dropdown",
+                class: if is_expanded() { "encoding-selector-dropdown--expanded" },
+                role: "menu",
+
+                div { class: "encoding-selector-heading", "Reopen with Encoding" }
+
+                button {
+                    class: "encoding-option",
+                    class: if chosen.is_none() { "encoding-option--active" },
+                    role: "menuitemradio",
+                    onclick: move |evt| {
+                        evt.stop_propagation();
+                        reopen(None);
+                    },
+                    "Auto-detect"
+                }
+
+                div { class: "encoding-selector-separator" }
+
+                for &option in ENCODINGS {
+                    button {
+                        key: "{option.name()}",
+                        class: "encoding-option",
+                        class: if chosen == Some(option) { "encoding-option--active" },
+                        role: "menuitemradio",
+                        onclick: move |evt| {
+                            evt.stop_propagation();
+                            reopen(Some(option));
+                        },
+                        "{option.name()}"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use dioxus::document;
+use dioxus::prelude::*;
+use std::path::PathBuf;
+
+use crate::components::icon::{Icon, IconName};
+use crate::git::{self, RepositoryStatus, DEFAULT_BASE};
+use crate::state::AppState;
+
+/// Branch and dirty state of the displayed file's repository, with a menu to highlight the
+/// changes of the document since a revision
+#[component]
+pub fn GitIndicator(status: RepositoryStatus, file: PathBuf) -> Element {
+    let mut state = use_context::<AppState>();
+    let mut is_expanded = use_signal(|| false);
+    let mut refs = use_signal(Vec::<String>::new);
+    let mut revision = use_signal(String::new);
+
+    // Listen for clicks outside the indicator
+    use_hook(|| {
+        spawn(async move {
+            loop {
+                let _ = document::eval(
+                    r#"
+                    await new Promise((resolve) => {
+                        const handler = (e) => {
+                            if (!e.target.closest('.git-indicator')) {
+                                resolve();
+                            } else {
+                                document.addEventListener('mousedown', handler, { once: true });
+                            }
+                        };
+                        document.addEventListener('mousedown', handler, { once: true });
+                    })
+                    "#,
+                )
+                .await;
+
+                if is_expanded() {
+                    is_expanded.set(false);
+                }
+            }
+        });
+    });
+
+    let change_base = state.change_base.read().clone();
+    let mut compare_with = move |base: Option<String>| {
+        state.change_base.set(base);
+        is_expanded.set(false);
+    };
+    let toggle = move |evt: MouseEvent| {
+        evt.stop_propagation();
+        let expand = !is_expanded();
+        is_expanded.set(expand);
+        if expand {
+            // Branches and tags may have changed since the menu was last opened
+            let file = file.clone();
+            spawn(async move {
+                let found = tokio::task::spawn_blocking(move || git::list_refs(&file))
+                    .await
+                    .unwrap_or_default();
+                refs.set(found);
+            });
+        }
+    };
+
+    let title = match (status.detached, status.dirty) {
+        (false, false) => format!("On branch {}", status.head),
+        (false, true) => format!("On branch {}, with uncommitted changes", status.head),
+        (true, false) => format!("HEAD detached at {}", status.head),
+        (true, true) => format!("HEAD detached at {}, with uncommitted changes", status.head),
+    };
+
+    rsx! {
+        div {
+            class: "git-indicator",
+
+            button {
+                class: "git-indicator-main",
+                class: if change_base.is_some() { "git-indicator-main--comparing" },
+                "aria-expanded": if is_expanded() { "true" } else { "false" },
+                "aria-haspopup": "menu",
+                title: "{title}",
+                onclick: toggle,
+                Icon { name: IconName::GitBranch, size: 16 }
+                span { class: "git-indicator-head", "{status.head}" }
+                if status.dirty {
+                    span { class: "git-indicator-dirty", "aria-label": "Uncommitted changes" }
+                }
+            }
+
+            div {
+                class: "git-indicator-dropdown",
+                class: if is_expanded() { "git-indicator-dropdown--expanded" },
+                role: "menu",
+
+                div { class: "git-indicator-heading", "Show Changes Since" }
+
+                button {
+                    class: "git-option",
+                    class: if change_base.is_none() { "git-option--active" },
+                    role: "menuitemradio",
+                    onclick: move |evt| {
+                        evt.stop_propagation();
+                        compare_with(None);
+                    },
+                    "Don't Show Changes"
+                }
+                button {
+                    class: "git-option",
+                    class: if change_base.as_deref() == Some(DEFAULT_BASE) { "git-option--active" },
+                    role: "menuitemradio",
+                    onclick: move |evt| {
+                        evt.stop_propagation();
+                        compare_with(Some(DEFAULT_BASE.to_string()));
+                    },
+                    "Last Commit (HEAD)"
+                }
+
+                if !refs.read().is_empty() {
+                    div { class: "git-indicator-separator" }
+                }
+
+                for name in refs() {
+                    button {
+                        key: "{name}",
+                        class: "git-option",
+                        class: if change_base.as_deref() == Some(name.as_str()) { "git-option--active" },
+                        role: "menuitemradio",
+                        onclick: {
+                            let name = name.clone();
+                            move |evt: MouseEvent| {
+                                evt.stop_propagation();
+                                compare_with(Some(name.clone()));
+                            }
+                        },
+                        "{name}"
+                    }
+                }
+
+                div { class: "git-indicator-separator" }
+
+                // Any revision git understands: `HEAD~3`, `v1.0^`, a commit id, …
+                input {
+                    class: "git-revision-input",
+                    r#type: "text",
+                    placeholder: "Other revision…",
+                    spellcheck: "false",
+                    value: "{revision}",
+                    oninput: move |evt| revision.set(evt.value()),
+                    onkeydown: move |evt: KeyboardEvent| {
+                        if evt.key() != Key::Enter {
+                            return;
+                        }
+                        let value = revision().trim().to_string();
+                        if !value.is_empty() {
+                            compare_with(Some(value));
+                            revision.set(String::new());
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
@@ -1,9 +1,14 @@
 use dioxus::document;
 use dioxus::prelude::*;
+use std::path::Path;
 
+use crate::components::encoding_selector::EncodingSelector;
+use crate::components::git_indicator::GitIndicator;
 use crate::components::icon::{Icon, IconName};
 use crate::components::theme_selector::ThemeSelector;
+use crate::presentation::start_presentation;
 use crate::state::AppState;
+use crate::utils::file::is_markdown_file;
 
 #[component]
 pub fn Header() -> Element {
@@ -69,6 +74,15 @@ pub fn Header() -> Element {
 
     // Check if there's a file to reload/copy
     let can_reload = file_path.is_some();
+    let can_present = file_path.as_ref().is_some_and(is_markdown_file);
+    let encoding = current_tab.as_ref().and_then(|tab| tab.encoding);
+    let chosen_encoding = current_tab.as_ref().and_then(|tab| tab.encoding_override);
+    // Repository of the displayed file, once it's been read
+    let repository = state.repository.read().clone().filter(|status| {
+        file_path
+            .as_ref()
+            .is_some_and(|f| f.starts_with(&status.workdir))
+    });
 
     // Copy feedback state
     let mut is_copied = use_signal(|| false);
@@ -148,6 +162,26 @@ pub fn Header() -> Element {
             div {
                 class: "header-right",
 
+                // Branch of the displayed file, and its changes
+                if let (Some(status), Some(file)) = (repository, file_path.map(Path::to_path_buf)) {
+                    GitIndicator { status, file }
+                }
+
+                // Encoding of the displayed file
+                if can_reload {
+                    EncodingSelector { encoding, chosen: chosen_encoding }
+                }
+
+                // Present the document as slides
+                if can_present {
+                    button {
+                        class: "nav-button",
+                        onclick: move |_| start_presentation(state),
+                        title: "Start Presentation",
+                        Icon { name: IconName::Presentation }
+                    }
+                }
+
                 // Reload button
                 button {
                     class: "nav-button reload-button",
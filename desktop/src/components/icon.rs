@@ -14,6 +14,8 @@ pub enum IconName {
     File,
     Folder,
     FolderOpen,
+    FolderPlus,
+    Folders,
     Command,
     Click,
     FileUpload,
@@ -22,6 +24,9 @@ pub enum IconName {
     Sidebar,
     Eye,
     EyeOff,
+    FileDots,
+    Search,
+    SortAscending,
     AlertTriangle,
     AlertCircle,
     ArrowsDiagonal,
@@ -35,6 +40,19 @@ pub enum IconName {
     InfoCircle,
     BrandGithub,
     Bug,
+    Keyboard,
+    Palette,
+    PaletteOff,
+    Shield,
+    Photo,
+    FileTypePdf,
+    Binary,
+    ExternalLink,
+    Presentation,
+    DeviceDesktop,
+    GitBranch,
+    ArrowUp,
+    ArrowDown,
 }
 
 impl fmt::Display for IconName {
@@ -49,6 +67,8 @@ impl fmt::Display for IconName {
             IconName::File => "file",
             IconName::Folder => "folder",
             IconName::FolderOpen => "folder-open",
+            IconName::FolderPlus => "folder-plus",
+            IconName::Folders => "folders",
             IconName::Command => "command",
             IconName::Click => "click",
             IconName::FileUpload => "file-upload",
@@ -57,6 +77,9 @@ impl fmt::Display for IconName {
             IconName::Sidebar => "layout-sidebar",
             IconName::Eye => "eye",
             IconName::EyeOff => "eye-off",
+            IconName::FileDots => "file-dots",
+            IconName::Search => "search",
+            IconName::SortAscending => "sort-ascending",
             IconName::AlertTriangle => "alert-triangle",
             IconName::AlertCircle => "alert-circle",
             IconName::ArrowsDiagonal => "arrows-diagonal",
@@ -70,6 +93,19 @@ impl fmt::Display for IconName {
             IconName::InfoCircle => "info-circle",
             IconName::BrandGithub => "brand-github",
             IconName::Bug => "bug",
+            IconName::Keyboard => "keyboard",
+            IconName::Palette => "palette",
+            IconName::PaletteOff => "palette-off",
+            IconName::Shield => "shield",
+            IconName::Photo => "photo",
+            IconName::FileTypePdf => "file-type-pdf",
+            IconName::Binary => "binary",
+            IconName::ExternalLink => "external-link",
+            IconName::Presentation => "presentation",
+            IconName::DeviceDesktop => "device-desktop",
+            IconName::GitBranch => "git-branch",
+            IconName::ArrowUp => "arrow-up",
+            IconName::ArrowDown => "arrow-down",
         };
         write!(f, "{}", name)
     }
@@ -23,7 +23,8 @@ pub enum OpenEvent {
     File(PathBuf),
     /// Directory opened from Finder/CLI (should set sidebar root)
     Directory(PathBuf),
-    /// App icon clicked (reopen event)
+    /// App icon clicked (reopen event, only sent on macOS)
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
     Reopen,
 }
 
@@ -84,16 +85,20 @@ fn handle_open_event(event: OpenEvent) {
 // ============================================================================
 
 /// MainApp - Component dedicated to the first window
-/// Configures system event handling and WindowHides behavior
+/// Configures system event handling and, on macOS, WindowHides behavior
 ///
 /// NOTE: This component should only be used for the first window launched from main.rs.
 /// Additional windows should use the App component directly.
 #[component]
 pub fn MainApp() -> Element {
-    // Configure WindowCloseBehaviour::WindowHides for first window
     use_hook(|| {
-        tracing::debug!("Configuring main window with WindowHides behavior");
-        window().set_close_behavior(WindowCloseBehaviour::WindowHides);
+        // Configure WindowCloseBehaviour::WindowHides for first window so the app keeps running
+        // in the Dock; elsewhere there is no way to bring a hidden window back, so closing the
+        // last window quits
+        if cfg!(target_os = "macos") {
+            tracing::debug!("Configuring main window with WindowHides behavior");
+            window().set_close_behavior(WindowCloseBehaviour::WindowHides);
+        }
 
         // Register the first window in MAIN_WINDOWS list
         // This is critical for has_any_main_windows() to work correctly
@@ -157,6 +162,11 @@ pub fn MainApp() -> Element {
         });
     });
 
+    // Reload config.json when it is edited outside the app
+    use_hook(|| {
+        spawn_forever(crate::config::watch_config_file());
+    });
+
     // Render App component with initial state
     rsx! {
         crate::components::app::App {
@@ -166,6 +176,7 @@ pub fn MainApp() -> Element {
             sidebar_open: sidebar_pref.open,
             sidebar_width: sidebar_pref.width,
             sidebar_show_all_files: sidebar_pref.show_all_files,
+            sidebar_show_hidden_files: sidebar_pref.show_hidden_files,
         }
     }
 }
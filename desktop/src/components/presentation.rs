@@ -0,0 +1,146 @@
+use dioxus::desktop::window;
+use dioxus::prelude::*;
+use dioxus_core::use_drop;
+use std::rc::Rc;
+
+use crate::asset_protocol::AssetGrant;
+use crate::components::icon::{Icon, IconName};
+use crate::presentation::{Presentation, SlideCommand};
+use crate::state::AppState;
+use crate::window::{close_presenter_window, open_or_focus_presenter_window};
+
+/// Follow the slide shown in `presentation`, wherever it is navigated from
+///
+/// Also serves the images and media of the slides to the window while it shows them.
+pub fn use_current_slide(presentation: &Presentation) -> Signal<usize> {
+    let directories = presentation.asset_directories.clone();
+    use_hook(|| Rc::new(AssetGrant::for_window(directories.iter())));
+    let mut current = use_signal(|| presentation.current());
+    let presentation = presentation.clone();
+    use_future(move || {
+        let mut rx = presentation.subscribe();
+        async move {
+            while rx.changed().await.is_ok() {
+                let index = *rx.borrow_and_update();
+                current.set(index);
+            }
+        }
+    });
+    current
+}
+
+/// Slide scaled to fit its container, keeping a 16:9 aspect ratio
+#[component]
+pub fn SlideFrame(html: String) -> Element {
+    rsx! {
+        div {
+            class: "slide-stage",
+            div {
+                class: "slide-frame",
+                div {
+                    class: "markdown-body slide-content",
+                    dangerous_inner_html: "{html}",
+                }
+            }
+        }
+    }
+}
+
+/// Full-screen presentation over the window
+#[component]
+pub fn PresentationView(presentation: Presentation) -> Element {
+    let mut state = use_context::<AppState>();
+    let current = use_current_slide(&presentation);
+
+    use_hook(|| window().set_fullscreen(true));
+    use_drop(|| window().set_fullscreen(false));
+
+    let mut end = move || {
+        close_presenter_window();
+        state.presentation.set(None);
+    };
+    let open_presenter_view = {
+        let presentation = presentation.clone();
+        move || open_or_focus_presenter_window(presentation.clone(), *state.current_theme.read())
+    };
+
+    let index = current();
+    let total = presentation.slides.len();
+    let html = presentation.slides[index].html.clone();
+
+    rsx! {
+        div {
+            class: "presentation",
+            tabindex: 0,
+            onmounted: move |evt| async move {
+                let _ = evt.set_focus(true).await;
+            },
+            onkeydown: {
+                let presentation = presentation.clone();
+                let open_presenter_view = open_presenter_view.clone();
+                move |evt: KeyboardEvent| {
+                    let Some(command) = SlideCommand::from_key(&evt.key()) else {
+                        return;
+                    };
+                    evt.prevent_default();
+                    match command {
+                        SlideCommand::Exit => end(),
+                        SlideCommand::PresenterView => open_presenter_view(),
+                        command => {
+                            presentation.navigate(command);
+                        }
+                    }
+                }
+            },
+
+            SlideFrame { html }
+
+            div {
+                class: "presentation-controls",
+                button {
+                    class: "presentation-control",
+                    title: "Previous Slide",
+                    disabled: index == 0,
+                    onclick: {
+                        let presentation = presentation.clone();
+                        move |_| {
+                            presentation.navigate(SlideCommand::Previous);
+                        }
+                    },
+                    Icon { name: IconName::ChevronLeft, size: 18 }
+                }
+                span {
+                    class: "presentation-counter",
+                    "{index + 1} / {total}"
+                }
+                button {
+                    class: "presentation-control",
+                    title: "Next Slide",
+                    disabled: index + 1 == total,
+                    onclick: {
+                        let presentation = presentation.clone();
+                        move |_| {
+                            presentation.navigate(SlideCommand::Next);
+                        }
+                    },
+                    Icon { name: IconName::ChevronRight, size: 18 }
+                }
+                button {
+                    class: "presentation-control",
+                    title: "Presenter View (S)",
+                    onclick: {
+                        let open_presenter_view = open_presenter_view.clone();
+                        move |_| open_presenter_view()
+                    },
+                    Icon { name: IconName::DeviceDesktop, size: 18 }
+                }
+                button {
+                    class: "presentation-control",
+                    title: "End Presentation (Esc)",
+                    onclick: move |_| end(),
+                    Icon { name: IconName::Close, size: 18 }
+                }
+            }
+        }
+    }
+}
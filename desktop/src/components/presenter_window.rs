@@ -0,0 +1,150 @@
+use dioxus::desktop::window;
+use dioxus::prelude::*;
+use std::time::{Duration, Instant};
+
+use crate::assets::MAIN_SCRIPT;
+use crate::components::icon::{Icon, IconName};
+use crate::components::presentation::{use_current_slide, SlideFrame};
+use crate::presentation::{Presentation, SlideCommand};
+use crate::theme::Theme;
+
+/// Props for PresenterWindow component
+#[derive(Props, Clone, PartialEq)]
+pub struct PresenterWindowProps {
+    pub presentation: Presentation,
+    /// Initial theme
+    pub theme: Theme,
+}
+
+/// Presenter view: the current and next slides, speaker notes and elapsed time
+#[component]
+pub fn PresenterWindow(props: PresenterWindowProps) -> Element {
+    let presentation = props.presentation;
+    let current = use_current_slide(&presentation);
+    let elapsed = use_elapsed_time();
+
+    // Diagrams, formulas and highlighting are rendered by the renderer module
+    use_hook(|| {
+        spawn(async move {
+            let _ = document::eval(&indoc::formatdoc! {r#"
+                const {{ init }} = await import("{MAIN_SCRIPT}");
+                init();
+            "#})
+            .await;
+        });
+    });
+
+    let index = current();
+    let total = presentation.slides.len();
+    let slide = &presentation.slides[index];
+    let next = presentation.slides.get(index + 1);
+    let file_name = presentation
+        .file
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let (minutes, seconds) = (elapsed().as_secs() / 60, elapsed().as_secs() % 60);
+
+    rsx! {
+        div {
+            class: "presenter",
+            tabindex: 0,
+            onmounted: move |evt| async move {
+                let _ = evt.set_focus(true).await;
+            },
+            onkeydown: {
+                let presentation = presentation.clone();
+                move |evt: KeyboardEvent| {
+                    let Some(command) = SlideCommand::from_key(&evt.key()) else {
+                        return;
+                    };
+                    evt.prevent_default();
+                    if command == SlideCommand::Exit {
+                        window().close();
+                    } else {
+                        presentation.navigate(command);
+                    }
+                }
+            },
+
+            div {
+                class: "presenter-header",
+                span { class: "presenter-title", "{file_name}" }
+                span { class: "presenter-counter", "Slide {index + 1} of {total}" }
+                span { class: "presenter-timer", "{minutes:02}:{seconds:02}" }
+                div {
+                    class: "presenter-controls",
+                    button {
+                        class: "presentation-control",
+                        title: "Previous Slide",
+                        disabled: index == 0,
+                        onclick: {
+                            let presentation = presentation.clone();
+                            move |_| {
+                                presentation.navigate(SlideCommand::Previous);
+                            }
+                        },
+                        Icon { name: IconName::ChevronLeft, size: 18 }
+                    }
+                    button {
+                        class: "presentation-control",
+                        title: "Next Slide",
+                        disabled: next.is_none(),
+                        onclick: {
+                            let presentation = presentation.clone();
+                            move |_| {
+                                presentation.navigate(SlideCommand::Next);
+                            }
+                        },
+                        Icon { name: IconName::ChevronRight, size: 18 }
+                    }
+                }
+            }
+
+            div {
+                class: "presenter-body",
+                div {
+                    class: "presenter-current",
+                    SlideFrame { html: slide.html.clone() }
+                }
+                div {
+                    class: "presenter-side",
+                    div {
+                        class: "presenter-next",
+                        h3 { class: "presenter-label", "Next" }
+                        if let Some(next) = next {
+                            SlideFrame { html: next.html.clone() }
+                        } else {
+                            p { class: "presenter-empty", "End of presentation" }
+                        }
+                    }
+                    div {
+                        class: "presenter-notes",
+                        h3 { class: "presenter-label", "Notes" }
+                        if slide.notes.is_empty() {
+                            p { class: "presenter-empty", "No notes for this slide" }
+                        } else {
+                            div {
+                                class: "markdown-body presenter-notes-content",
+                                dangerous_inner_html: "{slide.notes}",
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Time since the presenter view was opened, updated every second
+fn use_elapsed_time() -> Signal<Duration> {
+    let mut elapsed = use_signal(Duration::default);
+    use_future(move || async move {
+        let start = Instant::now();
+        loop {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            elapsed.set(start.elapsed());
+        }
+    });
+    elapsed
+}
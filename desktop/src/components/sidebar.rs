@@ -1,3 +1,4 @@
+pub mod file_context_menu;
 pub mod file_explorer;
 
 use dioxus::document;
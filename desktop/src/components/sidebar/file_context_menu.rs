@@ -0,0 +1,299 @@
+use dioxus::document;
+use dioxus::prelude::*;
+use std::path::{Path, PathBuf};
+
+use crate::events::FILE_RENAMED_BROADCAST;
+use crate::state::{AppState, Tab};
+use crate::utils::file_operations::{
+    create_markdown_file, move_to_trash, rename_entry, reveal_in_file_manager,
+};
+use crate::window::main::{create_new_main_window, CreateMainWindowConfigParams};
+
+/// Which part of the context menu is shown
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MenuMode {
+    Menu,
+    NewFile,
+    Rename,
+    ConfirmTrash,
+}
+
+/// Context menu for file explorer entries
+#[component]
+pub fn FileContextMenu(
+    path: PathBuf,
+    position: (i32, i32),
+    refresh_counter: Signal<u32>,
+    on_close: EventHandler<()>,
+) -> Element {
+    let mut state = use_context::<AppState>();
+    let mut mode = use_signal(|| MenuMode::Menu);
+    let mut value = use_signal(String::new);
+    let mut error = use_signal(|| None::<String>);
+
+    let is_dir = path.is_dir();
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let relative_path = state
+        .root_for(&path)
+        .and_then(|root| path.strip_prefix(root).ok().map(Path::to_path_buf))
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string_lossy().into_owned());
+
+    // Refresh the tree and close the menu after a successful operation
+    let mut finish = move || {
+        refresh_counter.set(refresh_counter() + 1);
+        on_close.call(());
+    };
+
+    if *mode.read() != MenuMode::Menu {
+        let current_mode = *mode.read();
+        let (title, confirm_label) = match current_mode {
+            MenuMode::NewFile => ("New Markdown File", "Create"),
+            MenuMode::Rename => ("Rename", "Rename"),
+            _ => ("Move to Trash", "Move to Trash"),
+        };
+
+        let path_for_confirm = path.clone();
+        let mut confirm = move || {
+            let path = path_for_confirm.clone();
+            let result = match current_mode {
+                MenuMode::NewFile => {
+                    let dir = if path.is_dir() {
+                        path.clone()
+                    } else {
+                        path.parent().map(Path::to_path_buf).unwrap_or_default()
+                    };
+                    create_markdown_file(&dir, &value.read()).map(|created| {
+                        state.sidebar.write().expanded_dirs.insert(dir);
+                        state.open_file(&created);
+                    })
+                }
+                MenuMode::Rename => rename_entry(&path, &value.read()).map(|renamed| {
+                    {
+                        let mut sidebar = state.sidebar.write();
+                        if sidebar.expanded_dirs.remove(&path) {
+                            sidebar.expanded_dirs.insert(renamed.clone());
+                        }
+                    }
+                    let _ = FILE_RENAMED_BROADCAST.send((path.clone(), renamed));
+                }),
+                _ => move_to_trash(&path),
+            };
+            match result {
+                Ok(()) => finish(),
+                Err(e) => {
+                    tracing::error!("File operation failed for {:?}: {}", path, e);
+                    error.set(Some(e.to_string()));
+                }
+            }
+        };
+
+        return rsx! {
+            div {
+                class: "file-dialog-backdrop",
+                onclick: move |_| on_close.call(()),
+            }
+            div {
+                class: "file-dialog",
+                role: "dialog",
+                onclick: move |evt| evt.stop_propagation(),
+
+                h3 { class: "file-dialog-title", "{title}" }
+
+                if current_mode == MenuMode::ConfirmTrash {
+                    p {
+                        class: "file-dialog-message",
+                        "Move \"{name}\" to the trash?"
+                        if is_dir { " The folder and everything in it will be moved." }
+                    }
+                } else {
+                    input {
+                        class: "file-dialog-input",
+                        r#type: "text",
+                        value: "{value}",
+                        spellcheck: false,
+                        autofocus: true,
+                        oninput: move |evt| {
+                            value.set(evt.value());
+                            error.set(None);
+                        },
+                        onkeydown: {
+                            let mut confirm = confirm.clone();
+                            move |evt: KeyboardEvent| match evt.key() {
+                                Key::Enter => confirm(),
+                                Key::Escape => on_close.call(()),
+                                _ => {}
+                            }
+                        },
+                    }
+                }
+
+                if let Some(message) = error() {
+                    p { class: "file-dialog-error", "{message}" }
+                }
+
+                div {
+                    class: "file-dialog-actions",
+                    button {
+                        class: "file-dialog-button",
+                        onclick: move |_| on_close.call(()),
+                        "Cancel"
+                    }
+                    button {
+                        class: "file-dialog-button primary",
+                        class: if current_mode == MenuMode::ConfirmTrash { "danger" },
+                        onclick: move |_| confirm(),
+                        "{confirm_label}"
+                    }
+                }
+            }
+        };
+    }
+
+    let open_in_new_window = {
+        let path = path.clone();
+        move |_| {
+            let (tab, directory) = if is_dir {
+                (Tab::default(), Some(path.clone()))
+            } else {
+                (Tab::new(path.clone()), state.directory.read().clone())
+            };
+            spawn(async move {
+                let params = CreateMainWindowConfigParams {
+                    directory,
+                    ..Default::default()
+                };
+                create_new_main_window(tab, params).await;
+            });
+            on_close.call(());
+        }
+    };
+
+    let copy_to_clipboard = move |text: String| {
+        let text = serde_json::to_string(&text).unwrap_or_default();
+        spawn(async move {
+            let _ = document::eval(&format!("navigator.clipboard.writeText({text})")).await;
+        });
+        on_close.call(());
+    };
+
+    rsx! {
+        // Backdrop to close menu on outside click
+        div {
+            class: "context-menu-backdrop",
+            onclick: move |_| on_close.call(()),
+            oncontextmenu: move |evt| {
+                evt.prevent_default();
+                on_close.call(());
+            },
+        }
+
+        div {
+            class: "context-menu",
+            style: "left: {position.0}px; top: {position.1}px;",
+            onclick: move |evt| evt.stop_propagation(),
+
+            if !is_dir {
+                div {
+                    class: "context-menu-item",
+                    onclick: {
+                        let path = path.clone();
+                        move |_| {
+                            state.add_file_tab(path.clone(), true);
+                            on_close.call(());
+                        }
+                    },
+                    "Open in New Tab"
+                }
+            }
+            div {
+                class: "context-menu-item",
+                onclick: open_in_new_window,
+                "Open in New Window"
+            }
+            div {
+                class: "context-menu-item",
+                onclick: {
+                    let path = path.clone();
+                    move |_| {
+                        if let Err(e) = reveal_in_file_manager(&path) {
+                            tracing::error!("Failed to reveal {:?}: {}", path, e);
+                        }
+                        on_close.call(());
+                    }
+                },
+                if cfg!(target_os = "macos") { "Reveal in Finder" } else { "Reveal in File Manager" }
+            }
+
+            div { class: "context-menu-separator" }
+
+            div {
+                class: "context-menu-item",
+                onclick: {
+                    let path = path.to_string_lossy().into_owned();
+                    move |_| copy_to_clipboard(path.clone())
+                },
+                "Copy Absolute Path"
+            }
+            div {
+                class: "context-menu-item",
+                onclick: move |_| copy_to_clipboard(relative_path.clone()),
+                "Copy Relative Path"
+            }
+
+            div { class: "context-menu-separator" }
+
+            if is_dir {
+                div {
+                    class: "context-menu-item",
+                    onclick: {
+                        let path = path.clone();
+                        move |_| {
+                            state.set_root_directory(&path);
+                            on_close.call(());
+                        }
+                    },
+                    "Set as Root"
+                }
+                div {
+                    class: "context-menu-item",
+                    onclick: {
+                        let path = path.clone();
+                        move |_| {
+                            state.add_workspace_folder(path.clone());
+                            on_close.call(());
+                        }
+                    },
+                    "Add to Workspace"
+                }
+            }
+            div {
+                class: "context-menu-item",
+                onclick: move |_| {
+                    value.set("untitled.md".to_string());
+                    mode.set(MenuMode::NewFile);
+                },
+                "New Markdown File…"
+            }
+            div {
+                class: "context-menu-item",
+                onclick: {
+                    let name = name.clone();
+                    move |_| {
+                        value.set(name.clone());
+                        mode.set(MenuMode::Rename);
+                    }
+                },
+                "Rename…"
+            }
+            div {
+                class: "context-menu-item danger",
+                onclick: move |_| mode.set(MenuMode::ConfirmTrash),
+                "Move to Trash…"
+            }
+        }
+    }
+}
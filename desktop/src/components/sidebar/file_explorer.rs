@@ -1,58 +1,231 @@
+use dioxus::core::Task;
 use dioxus::document;
 use dioxus::prelude::*;
-use std::cmp::Ordering;
-use std::fs;
-use std::path::PathBuf;
-
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::file_context_menu::FileContextMenu;
 use crate::components::icon::{Icon, IconName};
+use crate::config::{SortMode, CONFIG};
+use crate::menu::pick_directory;
 use crate::state::AppState;
-use crate::utils::file::is_markdown_file;
-
-// Sort entries: directories first, then files, both alphabetically
-fn sort_entries(items: &mut [PathBuf]) {
-    items.sort_by(|a, b| {
-        let a_is_dir = a.is_dir();
-        let b_is_dir = b.is_dir();
-
-        match (a_is_dir, b_is_dir) {
-            (true, false) => Ordering::Less,
-            (false, true) => Ordering::Greater,
-            _ => a.file_name().cmp(&b.file_name()),
-        }
-    });
+use crate::utils::file::has_extension;
+use crate::utils::file_filter::FileFilter;
+use crate::utils::file_sort::sort_entries;
+use crate::workspace::Workspace;
+
+/// Maximum number of matches collected for the filename filter
+const MAX_FILTER_MATCHES: usize = 500;
+
+/// Maximum number of entries walked looking for matches of the filename filter
+const MAX_FILTER_SCANNED: usize = 50_000;
+
+/// Pause in typing after which the filename filter looks for matches
+const FILTER_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Paths shown while the filename filter is in use
+#[derive(Debug, Clone, Default, PartialEq)]
+struct FilterMatches {
+    /// Matching entries and their ancestors
+    visible: HashSet<PathBuf>,
+    /// Ancestors of matching entries, shown expanded
+    expanded: HashSet<PathBuf>,
+    /// Whether the scan or match limit was reached, so there may be more matches
+    truncated: bool,
 }
 
-// Read and sort directory entries
-fn read_sorted_entries(path: &PathBuf) -> Vec<PathBuf> {
-    match fs::read_dir(path) {
-        Ok(entries) => {
-            let mut items: Vec<_> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
-            sort_entries(&mut items);
-            items
-        }
-        Err(err) => {
-            tracing::error!("Failed to read directory {:?}: {}", path, err);
-            vec![]
+fn file_filter(state: &AppState) -> FileFilter {
+    FileFilter::new(
+        &CONFIG.read().sidebar,
+        state.sidebar.read().show_hidden_files,
+    )
+    .with_project(state.project.read().as_ref())
+}
+
+// Read and sort directory entries, skipping ignored and (unless enabled) hidden files
+fn read_sorted_entries(state: &AppState, root: &Path, path: &Path) -> Vec<PathBuf> {
+    let mut items = file_filter(state).read_dir(root, path);
+    sort_entries(&mut items, state.sidebar.read().sort_mode);
+    items
+}
+
+// Collect entries below the roots whose name contains the query, with their ancestors, up to
+// the scan and match limits; `None` when `cancelled` is set before the walk is done
+fn find_filter_matches(
+    filter: &FileFilter,
+    roots: &[PathBuf],
+    query: &str,
+    show_all_files: bool,
+    extensions: &[String],
+    cancelled: &AtomicBool,
+) -> Option<FilterMatches> {
+    let mut matches = FilterMatches::default();
+    let mut scanned = 0;
+    let mut found_count = 0;
+    let found = roots
+        .iter()
+        .flat_map(|root| filter.walk(root).map(move |path| (root, path)))
+        .take(MAX_FILTER_SCANNED)
+        .take_while(|_| !cancelled.load(Ordering::Relaxed))
+        .inspect(|_| scanned += 1)
+        .filter(|(_, path)| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().to_lowercase().contains(query))
+        })
+        .filter(|(_, path)| show_all_files || path.is_dir() || has_extension(path, extensions))
+        .take(MAX_FILTER_MATCHES)
+        .inspect(|_| found_count += 1);
+    for (root, path) in found {
+        for ancestor in path.ancestors().skip(1) {
+            if ancestor == root || !matches.expanded.insert(ancestor.to_path_buf()) {
+                break;
+            }
         }
+        matches.visible.insert(path);
+    }
+    if cancelled.load(Ordering::Relaxed) {
+        return None;
     }
+    matches.visible.extend(matches.expanded.iter().cloned());
+    matches.truncated = scanned == MAX_FILTER_SCANNED || found_count == MAX_FILTER_MATCHES;
+    Some(matches)
 }
 
 #[component]
 pub fn FileExplorer() -> Element {
     let state = use_context::<AppState>();
-    let root_directory = state.directory.read().clone();
+    let roots = state.roots();
+    let workspace_name = state.workspace.read().as_ref().map(Workspace::display_name);
 
     // Refresh counter to force DirectoryTree re-render
     let refresh_counter = use_signal(|| 0u32);
 
+    // Filename filter; matches are looked for off the UI thread once typing pauses
+    let mut filter_query = use_signal(String::new);
+    let mut filter_matches = use_signal(|| None::<FilterMatches>);
+    // Walk looking for matches of the last query, and the flag stopping it
+    let filter_walk = use_hook(|| Rc::new(RefCell::new(None::<(Task, Arc<AtomicBool>)>)));
+    let cancel_filter_walk = {
+        let filter_walk = filter_walk.clone();
+        move || {
+            if let Some((task, cancelled)) = filter_walk.borrow_mut().take() {
+                task.cancel();
+                cancelled.store(true, Ordering::Relaxed);
+            }
+        }
+    };
+    use_effect({
+        let cancel_filter_walk = cancel_filter_walk.clone();
+        let filter_walk = filter_walk.clone();
+        move || {
+            let _ = refresh_counter();
+            let query = filter_query().trim().to_lowercase();
+            let roots = state.roots();
+            let filter = file_filter(&state);
+            let show_all_files = state.sidebar.read().show_all_files;
+            let extensions = CONFIG.read().sidebar.document_extensions.clone();
+
+            cancel_filter_walk();
+            if query.is_empty() || roots.is_empty() {
+                filter_matches.set(None);
+                return;
+            }
+            let cancelled = Arc::new(AtomicBool::new(false));
+            let task = spawn({
+                let cancelled = cancelled.clone();
+                async move {
+                    tokio::time::sleep(FILTER_DEBOUNCE).await;
+                    let walk = tokio::task::spawn_blocking(move || {
+                        find_filter_matches(
+                            &filter,
+                            &roots,
+                            &query,
+                            show_all_files,
+                            &extensions,
+                            &cancelled,
+                        )
+                    });
+                    if let Ok(Some(matches)) = walk.await {
+                        filter_matches.set(Some(matches));
+                    }
+                }
+            });
+            filter_walk.borrow_mut().replace((task, cancelled));
+        }
+    });
+    // Stop a walk still running in the background
+    use_drop(cancel_filter_walk);
+
     rsx! {
         div {
             class: "file-explorer",
             key: "{refresh_counter}",
 
-            if let Some(root) = root_directory {
-                ParentNavigation { current_dir: root.clone(), refresh_counter }
-                DirectoryTree { path: root, refresh_counter }
+            if let Some(first_root) = roots.first().cloned() {
+                if let Some(name) = workspace_name {
+                    WorkspaceHeader { name, refresh_counter }
+                } else {
+                    ParentNavigation { current_dir: first_root.clone(), refresh_counter }
+                }
+                div {
+                    class: "file-explorer-filter",
+                    Icon {
+                        name: IconName::Search,
+                        size: 14,
+                        class: "file-explorer-filter-icon",
+                    }
+                    input {
+                        class: "file-explorer-filter-input",
+                        r#type: "text",
+                        placeholder: "Filter files",
+                        spellcheck: false,
+                        value: "{filter_query}",
+                        oninput: move |evt| filter_query.set(evt.value()),
+                        onkeydown: move |evt| {
+                            if evt.key() == Key::Escape {
+                                filter_query.set(String::new());
+                            }
+                        },
+                    }
+                    if !filter_query.read().is_empty() {
+                        button {
+                            class: "file-explorer-filter-clear",
+                            title: "Clear filter",
+                            onclick: move |_| filter_query.set(String::new()),
+                            Icon { name: IconName::Close, size: 12 }
+                        }
+                    }
+                }
+                if filter_matches.read().as_ref().is_some_and(|m| m.visible.is_empty()) {
+                    div {
+                        class: "file-explorer-filter-empty",
+                        "No matching files"
+                    }
+                }
+                if filter_matches.read().as_ref().is_some_and(|m| m.truncated) {
+                    div {
+                        class: "file-explorer-filter-truncated",
+                        title: "Too many files to search them all; refine the filter",
+                        "Showing partial results"
+                    }
+                }
+                if state.workspace.read().is_some() {
+                    for root in roots {
+                        WorkspaceRoot {
+                            key: "{root.display()}",
+                            root: root.clone(),
+                            refresh_counter,
+                            filter_matches,
+                        }
+                    }
+                } else {
+                    DirectoryTree { path: first_root, refresh_counter, filter_matches }
+                }
             } else {
                 div {
                     class: "file-explorer-empty",
@@ -64,10 +237,8 @@ pub fn FileExplorer() -> Element {
 }
 
 #[component]
-fn ParentNavigation(current_dir: PathBuf, mut refresh_counter: Signal<u32>) -> Element {
+fn ParentNavigation(current_dir: PathBuf, refresh_counter: Signal<u32>) -> Element {
     let mut state = use_context::<AppState>();
-    let show_all_files = state.sidebar.read().show_all_files;
-
     let has_parent = current_dir.parent().is_some();
 
     // Get current directory name
@@ -77,24 +248,6 @@ fn ParentNavigation(current_dir: PathBuf, mut refresh_counter: Signal<u32>) -> E
         .unwrap_or("..")
         .to_string();
 
-    // Reload state for animation
-    let is_reloading = use_signal(|| false);
-    let mut is_reloading_write = is_reloading;
-
-    let on_reload = move |_| {
-        // Set reloading state for animation
-        is_reloading_write.set(true);
-
-        // Increment counter to force DirectoryTree re-render
-        refresh_counter.set(refresh_counter() + 1);
-
-        // Reset reloading state after animation
-        spawn(async move {
-            tokio::time::sleep(tokio::time::Duration::from_millis(600)).await;
-            is_reloading_write.set(false);
-        });
-    };
-
     rsx! {
         div {
             class: "parent-nav-container",
@@ -142,60 +295,243 @@ fn ParentNavigation(current_dir: PathBuf, mut refresh_counter: Signal<u32>) -> E
                 }
             }
 
-            // Toolbar buttons container
+            ExplorerToolbar { refresh_counter, show_add_folder: false }
+        }
+    }
+}
+
+/// Header shown instead of parent navigation when several roots are open
+#[component]
+fn WorkspaceHeader(name: String, refresh_counter: Signal<u32>) -> Element {
+    let file = use_context::<AppState>()
+        .workspace
+        .read()
+        .as_ref()
+        .and_then(|workspace| workspace.file.clone());
+    let title = file
+        .map(|file| file.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "Unsaved workspace".to_string());
+
+    rsx! {
+        div {
+            class: "parent-nav-container",
+
             div {
-                class: "file-explorer-toolbar",
+                class: "file-tree-node parent-nav root-indicator workspace-indicator",
+                title: "{title}",
 
-                // Reload button
-                button {
-                    class: "file-explorer-toolbar-button",
-                    class: if *is_reloading.read() { "reloading" },
-                    title: "Reload file explorer",
-                    onclick: on_reload,
+                div {
+                    class: "file-tree-node-content",
                     Icon {
-                        name: IconName::Refresh,
-                        size: 20,
+                        name: IconName::Folders,
+                        size: 16,
+                        class: "file-tree-icon",
+                    }
+                    span {
+                        class: "file-tree-label",
+                        "{name}"
                     }
                 }
+            }
 
-                // File visibility toggle button
+            ExplorerToolbar { refresh_counter, show_add_folder: true }
+        }
+    }
+}
+
+#[component]
+fn ExplorerToolbar(mut refresh_counter: Signal<u32>, show_add_folder: bool) -> Element {
+    let mut state = use_context::<AppState>();
+    let show_all_files = state.sidebar.read().show_all_files;
+    let show_hidden_files = state.sidebar.read().show_hidden_files;
+    let sort_mode = state.sidebar.read().sort_mode;
+    let next_sort_mode = SortMode::ALL
+        .into_iter()
+        .cycle()
+        .skip_while(|mode| *mode != sort_mode)
+        .nth(1)
+        .unwrap_or_default();
+
+    // Reload state for animation
+    let is_reloading = use_signal(|| false);
+    let mut is_reloading_write = is_reloading;
+
+    let on_reload = move |_| {
+        // Set reloading state for animation
+        is_reloading_write.set(true);
+
+        // Increment counter to force DirectoryTree re-render
+        refresh_counter.set(refresh_counter() + 1);
+
+        // Reset reloading state after animation
+        spawn(async move {
+            tokio::time::sleep(tokio::time::Duration::from_millis(600)).await;
+            is_reloading_write.set(false);
+        });
+    };
+
+    rsx! {
+        // Toolbar buttons container
+        div {
+            class: "file-explorer-toolbar",
+
+            // Add workspace folder button
+            if show_add_folder {
                 button {
                     class: "file-explorer-toolbar-button",
-                    title: if show_all_files { "Hide non-markdown files" } else { "Show all files" },
+                    title: "Add folder to workspace",
                     onclick: move |_| {
-                        state.sidebar.write().show_all_files = !show_all_files;
+                        if let Some(dir) = pick_directory() {
+                            state.add_workspace_folder(dir);
+                        }
                     },
                     Icon {
-                        name: if show_all_files { IconName::Eye } else { IconName::EyeOff },
+                        name: IconName::FolderPlus,
                         size: 20,
                     }
                 }
             }
+
+            // Reload button
+            button {
+                class: "file-explorer-toolbar-button",
+                class: if *is_reloading.read() { "reloading" },
+                title: "Reload file explorer",
+                onclick: on_reload,
+                Icon {
+                    name: IconName::Refresh,
+                    size: 20,
+                }
+            }
+
+            // Sort mode button (cycles through the modes)
+            button {
+                class: "file-explorer-toolbar-button",
+                title: "Sort by {sort_mode.label()} (click for {next_sort_mode.label()})",
+                onclick: move |_| state.set_sort_mode(next_sort_mode),
+                Icon {
+                    name: IconName::SortAscending,
+                    size: 20,
+                }
+            }
+
+            // File visibility toggle button
+            button {
+                class: "file-explorer-toolbar-button",
+                title: if show_all_files { "Hide non-markdown files" } else { "Show all files" },
+                onclick: move |_| {
+                    state.sidebar.write().show_all_files = !show_all_files;
+                },
+                Icon {
+                    name: if show_all_files { IconName::Eye } else { IconName::EyeOff },
+                    size: 20,
+                }
+            }
+
+            // Hidden files toggle button
+            button {
+                class: "file-explorer-toolbar-button",
+                class: if show_hidden_files { "active" },
+                title: if show_hidden_files { "Hide hidden files" } else { "Show hidden files" },
+                onclick: move |_| {
+                    state.sidebar.write().show_hidden_files = !show_hidden_files;
+                },
+                Icon {
+                    name: IconName::FileDots,
+                    size: 20,
+                }
+            }
+        }
+    }
+}
+
+/// Collapsible top-level section for one workspace root
+#[component]
+fn WorkspaceRoot(
+    root: PathBuf,
+    refresh_counter: Signal<u32>,
+    filter_matches: Signal<Option<FilterMatches>>,
+) -> Element {
+    let mut state = use_context::<AppState>();
+    let is_filtering = filter_matches.read().is_some();
+    // Sections always show their matches while filtering
+    let is_collapsed = !is_filtering && state.sidebar.read().collapsed_roots.contains(&root);
+
+    let name = root
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| root.to_string_lossy().into_owned());
+    let title = root.to_string_lossy().into_owned();
+
+    rsx! {
+        div {
+            class: "workspace-root",
+
+            div {
+                class: "workspace-root-header",
+                title: "{title}",
+                onclick: {
+                    let root = root.clone();
+                    move |_| state.sidebar.write().toggle_root_collapsed(&root)
+                },
+
+                Icon {
+                    name: if is_collapsed { IconName::ChevronRight } else { IconName::ChevronDown },
+                    size: 16,
+                    class: "file-tree-chevron",
+                }
+                span { class: "workspace-root-label", "{name}" }
+                button {
+                    class: "workspace-root-remove",
+                    title: "Remove folder from workspace",
+                    onclick: {
+                        let root = root.clone();
+                        move |evt| {
+                            evt.stop_propagation();
+                            state.remove_workspace_folder(&root);
+                        }
+                    },
+                    Icon { name: IconName::Close, size: 12 }
+                }
+            }
+
+            if !is_collapsed {
+                DirectoryTree { path: root.clone(), refresh_counter, filter_matches }
+            }
         }
     }
 }
 
 #[component]
-fn DirectoryTree(path: PathBuf, refresh_counter: Signal<u32>) -> Element {
-    let entries = read_sorted_entries(&path);
+fn DirectoryTree(
+    path: PathBuf,
+    refresh_counter: Signal<u32>,
+    filter_matches: Signal<Option<FilterMatches>>,
+) -> Element {
+    let state = use_context::<AppState>();
+    let entries = read_sorted_entries(&state, &path, &path);
 
     rsx! {
         div {
             class: "directory-tree",
             key: "{refresh_counter}",
             for entry in entries {
-                FileTreeNode { path: entry, depth: 0, refresh_counter }
+                FileTreeNode { path: entry, depth: 0, refresh_counter, filter_matches }
             }
         }
     }
 }
 
 #[component]
-fn FileTreeNode(path: PathBuf, depth: usize, refresh_counter: Signal<u32>) -> Element {
+fn FileTreeNode(
+    path: PathBuf,
+    depth: usize,
+    refresh_counter: Signal<u32>,
+    filter_matches: Signal<Option<FilterMatches>>,
+) -> Element {
     let mut state = use_context::<AppState>();
 
     let is_dir = path.is_dir();
-    let is_expanded = state.sidebar.read().expanded_dirs.contains(&path);
     let show_all_files = state.sidebar.read().show_all_files;
 
     let name = path
@@ -204,13 +540,22 @@ fn FileTreeNode(path: PathBuf, depth: usize, refresh_counter: Signal<u32>) -> El
         .unwrap_or("Unknown")
         .to_string();
 
-    let is_markdown = !is_dir && is_markdown_file(&path);
+    let is_document = !is_dir && has_extension(&path, &CONFIG.read().sidebar.document_extensions);
 
-    // Hide non-markdown files if show_all_files is disabled
-    if !show_all_files && !is_dir && !is_markdown {
+    // Hide non-document files if show_all_files is disabled
+    if !show_all_files && !is_dir && !is_document {
         return rsx! {};
     }
 
+    // While filtering, show only matches and their (expanded) ancestors
+    let is_expanded = match filter_matches.read().as_ref() {
+        Some(matches) if !matches.visible.contains(&path) => return rsx! {},
+        Some(matches) => {
+            matches.expanded.contains(&path) || state.sidebar.read().expanded_dirs.contains(&path)
+        }
+        None => state.sidebar.read().expanded_dirs.contains(&path),
+    };
+
     let current_tab = state.current_tab();
     let is_active = current_tab
         .and_then(|tab| tab.file().map(|f| f == path))
@@ -221,6 +566,10 @@ fn FileTreeNode(path: PathBuf, depth: usize, refresh_counter: Signal<u32>) -> El
     // Copy feedback state
     let mut is_copied = use_signal(|| false);
 
+    // Context menu state
+    let mut show_context_menu = use_signal(|| false);
+    let mut context_menu_position = use_signal(|| (0, 0));
+
     rsx! {
         div {
             class: "file-tree-node",
@@ -228,7 +577,14 @@ fn FileTreeNode(path: PathBuf, depth: usize, refresh_counter: Signal<u32>) -> El
 
             div {
                 class: "file-tree-node-content",
+                class: if show_context_menu() { "context-menu-open" },
                 style: "{indent_style}",
+                oncontextmenu: move |evt| {
+                    evt.prevent_default();
+                    let coordinates = evt.data().client_coordinates();
+                    context_menu_position.set((coordinates.x as i32, coordinates.y as i32));
+                    show_context_menu.set(true);
+                },
                 onclick: {
                     let path = path.clone();
                     move |_| {
@@ -266,7 +622,7 @@ fn FileTreeNode(path: PathBuf, depth: usize, refresh_counter: Signal<u32>) -> El
                 // Label
                 span {
                     class: "file-tree-label",
-                    class: if !is_markdown && !is_dir { "disabled" },
+                    class: if !is_document && !is_dir { "disabled" },
                     "{name}"
                 }
 
@@ -317,15 +673,25 @@ fn FileTreeNode(path: PathBuf, depth: usize, refresh_counter: Signal<u32>) -> El
                 }
             }
 
+            if show_context_menu() {
+                FileContextMenu {
+                    path: path.clone(),
+                    position: context_menu_position(),
+                    refresh_counter,
+                    on_close: move |_| show_context_menu.set(false),
+                }
+            }
+
             // Expanded directory children
             if is_dir && is_expanded {
                 {
-                    let children = read_sorted_entries(&path);
+                    let root = state.root_for(&path).unwrap_or_else(|| path.clone());
+                    let children = read_sorted_entries(&state, &root, &path);
                     rsx! {
                         div {
                             key: "{refresh_counter}",
                             for child in children {
-                                FileTreeNode { path: child, depth: depth + 1, refresh_counter }
+                                FileTreeNode { path: child, depth: depth + 1, refresh_counter, filter_matches }
                             }
                         }
                     }
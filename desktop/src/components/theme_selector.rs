@@ -3,8 +3,9 @@ use dioxus::prelude::*;
 use dioxus_sdk_window::theme::use_system_theme;
 
 use crate::components::icon::{Icon, IconName};
+use crate::config::CONFIG;
 use crate::state::LAST_FOCUSED_STATE;
-use crate::theme::{DioxusTheme, Theme};
+use crate::theme::{list_custom_themes, select_custom_theme, CustomTheme, DioxusTheme, Theme};
 
 #[component]
 pub fn ThemeSelector(current_theme: Signal<Theme>) -> Element {
@@ -42,6 +43,8 @@ pub fn ThemeSelector(current_theme: Signal<Theme>) -> Element {
 
     // Expansion state for dropdown menu
     let mut is_expanded = use_signal(|| false);
+    // Custom themes found in the themes directory (refreshed when the dropdown opens)
+    let mut custom_themes = use_signal(Vec::<CustomTheme>::new);
 
     // Listen for clicks outside the theme selector
     use_hook(|| {
@@ -105,6 +108,8 @@ pub fn ThemeSelector(current_theme: Signal<Theme>) -> Element {
         ],
     };
 
+    let active_custom_theme = CONFIG.read().theme.custom_theme.clone();
+
     rsx! {
         div {
             class: "theme-selector",
@@ -120,6 +125,9 @@ pub fn ThemeSelector(current_theme: Signal<Theme>) -> Element {
                 },
                 onclick: move |evt| {
                     evt.stop_propagation();
+                    if !is_expanded() {
+                        custom_themes.set(list_custom_themes());
+                    }
                     is_expanded.set(!is_expanded());
                 },
                 Icon { name: current_icon, size: 18 }
@@ -151,6 +159,50 @@ pub fn ThemeSelector(current_theme: Signal<Theme>) -> Element {
                         Icon { name: icon, size: 18 }
                     }
                 }
+
+                if !custom_themes.read().is_empty() {
+                    div { class: "theme-selector-separator" }
+
+                    for custom in custom_themes() {
+                        button {
+                            key: "{custom.id}",
+                            class: "theme-option",
+                            class: if active_custom_theme.as_deref() == Some(custom.id.as_str()) { "theme-option--active" },
+                            role: "menuitem",
+                            title: "{custom.name}",
+                            onmousedown: move |evt| {
+                                evt.stop_propagation();
+                            },
+                            onclick: move |evt| {
+                                evt.stop_propagation();
+                                if let Some(base) = custom.base {
+                                    let mut current_theme = current_theme;
+                                    current_theme.set(base);
+                                }
+                                select_custom_theme(Some(custom.id.clone()));
+                                is_expanded.set(false);
+                            },
+                            Icon { name: IconName::Palette, size: 18 }
+                        }
+                    }
+
+                    if active_custom_theme.is_some() {
+                        button {
+                            class: "theme-option",
+                            role: "menuitem",
+                            title: "Built-in styles only",
+                            onmousedown: move |evt| {
+                                evt.stop_propagation();
+                            },
+                            onclick: move |evt| {
+                                evt.stop_propagation();
+                                select_custom_theme(None);
+                                is_expanded.set(false);
+                            },
+                            Icon { name: IconName::PaletteOff, size: 18 }
+                        }
+                    }
+                }
             }
         }
     }
@@ -4,4 +4,12 @@ pub use app_config::*;
 
 // Configuration persistence (load/save methods + CONFIG global)
 mod persistence;
-pub use persistence::CONFIG;
+pub use persistence::{ConfigLoadError, CONFIG, CONFIG_LOAD_ERROR};
+
+// Per-project configuration (.arto.toml)
+mod project_config;
+pub use project_config::Project;
+
+// Reload config.json when it changes on disk
+mod hot_reload;
+pub use hot_reload::watch_config_file;
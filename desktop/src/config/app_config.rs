@@ -2,6 +2,10 @@ use serde::{Deserialize, Serialize};
 
 mod behavior;
 mod directory_config;
+mod keybindings_config;
+mod print_config;
+mod remote_content_config;
+mod sanitization_config;
 mod sidebar_config;
 mod theme_config;
 mod window_dimension;
@@ -10,7 +14,11 @@ mod window_size_config;
 
 pub use behavior::{NewWindowBehavior, StartupBehavior};
 pub use directory_config::DirectoryConfig;
-pub use sidebar_config::SidebarConfig;
+pub use keybindings_config::KeybindingsConfig;
+pub use print_config::PrintConfig;
+pub use remote_content_config::RemoteContentConfig;
+pub use sanitization_config::{SanitizationConfig, SanitizationMode};
+pub use sidebar_config::{SidebarConfig, SortMode};
 pub use theme_config::ThemeConfig;
 pub use window_dimension::{WindowDimension, WindowDimensionUnit};
 pub use window_position_config::{
@@ -27,12 +35,17 @@ pub struct Config {
     pub sidebar: SidebarConfig,
     pub window_position: WindowPositionConfig,
     pub window_size: WindowSizeConfig,
+    pub keybindings: KeybindingsConfig,
+    pub sanitization: SanitizationConfig,
+    pub remote_content: RemoteContentConfig,
+    pub print: PrintConfig,
 }
 
 #[cfg(test)]
 mod tests {
     use super::window_position_config::WindowPositionOffset;
     use super::*;
+    use crate::remote_images::RemoteImagePolicy;
     use crate::theme::Theme;
     use std::path::PathBuf;
 
@@ -44,6 +57,7 @@ mod tests {
         assert_eq!(config.theme.default_theme, Theme::Auto);
         assert_eq!(config.theme.on_startup, StartupBehavior::Default);
         assert_eq!(config.theme.on_new_window, NewWindowBehavior::Default);
+        assert_eq!(config.theme.custom_theme, None);
 
         // Directory defaults
         assert_eq!(config.directory.default_directory, None);
@@ -54,6 +68,14 @@ mod tests {
         assert!(!config.sidebar.default_open); // Default is false
         assert_eq!(config.sidebar.default_width, 280.0);
         assert!(!config.sidebar.default_show_all_files);
+        assert!(!config.sidebar.default_show_hidden_files);
+        assert!(config.sidebar.respect_ignore_files);
+        assert!(config.sidebar.ignore_globs.is_empty());
+        assert_eq!(config.sidebar.sort_mode, SortMode::Name);
+        assert_eq!(
+            config.sidebar.document_extensions,
+            vec!["md", "markdown", "adoc", "asciidoc", "rst", "ipynb"]
+        );
         assert_eq!(config.sidebar.on_startup, StartupBehavior::Default);
         assert_eq!(config.sidebar.on_new_window, NewWindowBehavior::Default);
 
@@ -71,6 +93,23 @@ mod tests {
         assert_eq!(config.window_size.on_startup, StartupBehavior::Default);
         assert_eq!(config.window_size.on_new_window, NewWindowBehavior::Default);
 
+        // Keybinding defaults
+        assert!(config.keybindings.menu.is_empty());
+        assert!(!config.keybindings.vim_navigation);
+
+        // Sanitization defaults
+        assert_eq!(config.sanitization.mode, SanitizationMode::Github);
+        assert!(config.sanitization.trusted_directories.is_empty());
+
+        // Remote content defaults
+        assert_eq!(config.remote_content.images, RemoteImagePolicy::ClickToLoad);
+        assert_eq!(config.remote_content.image_cache_size_mb, 100);
+
+        // Print defaults
+        assert!(!config.print.table_of_contents);
+        assert!(config.print.page_header);
+        assert!(config.print.link_footnotes);
+
         // Window position defaults
         assert_eq!(
             config.window_position.default_position_mode,
@@ -102,6 +141,7 @@ mod tests {
                 default_theme: Theme::Dark,
                 on_startup: StartupBehavior::LastClosed,
                 on_new_window: NewWindowBehavior::LastFocused,
+                custom_theme: Some("solarized".to_string()),
             },
             directory: DirectoryConfig {
                 default_directory: Some(PathBuf::from("/home/user")),
@@ -112,6 +152,11 @@ mod tests {
                 default_open: false,
                 default_width: 320.0,
                 default_show_all_files: true,
+                default_show_hidden_files: true,
+                respect_ignore_files: false,
+                ignore_globs: vec!["node_modules".to_string(), "*.log".to_string()],
+                sort_mode: SortMode::FrontMatter,
+                document_extensions: vec!["md".to_string(), "mdx".to_string()],
                 on_startup: StartupBehavior::LastClosed,
                 on_new_window: NewWindowBehavior::LastFocused,
             },
@@ -145,13 +190,38 @@ mod tests {
                 on_startup: StartupBehavior::LastClosed,
                 on_new_window: NewWindowBehavior::LastFocused,
             },
+            keybindings: KeybindingsConfig {
+                menu: [(
+                    "view.toggle_sidebar".to_string(),
+                    "CmdOrCtrl+Shift+B".to_string(),
+                )]
+                .into_iter()
+                .collect(),
+                vim_navigation: true,
+            },
+            sanitization: SanitizationConfig {
+                mode: SanitizationMode::TrustedDirectories,
+                trusted_directories: vec![PathBuf::from("~/notes")],
+            },
+            remote_content: RemoteContentConfig {
+                images: RemoteImagePolicy::Always,
+                image_cache_size_mb: 0,
+            },
+            print: PrintConfig {
+                table_of_contents: true,
+                page_header: false,
+                link_footnotes: true,
+            },
         };
 
         let json = serde_json::to_string_pretty(&config).unwrap();
         let parsed: Config = serde_json::from_str(&json).unwrap();
+        assert!(json.contains(r#""mode": "trusted_directories""#));
+        assert!(json.contains(r#""imageCacheSizeMb": 0"#));
 
         assert_eq!(parsed.theme.default_theme, Theme::Dark);
         assert_eq!(parsed.theme.on_startup, StartupBehavior::LastClosed);
+        assert_eq!(parsed.theme.custom_theme, Some("solarized".to_string()));
         assert_eq!(
             parsed.directory.default_directory,
             Some(PathBuf::from("/home/user"))
@@ -173,5 +243,26 @@ mod tests {
             parsed.window_size.default_size.width.unit,
             WindowDimensionUnit::Pixels
         );
+        assert_eq!(
+            parsed.keybindings.menu.get("view.toggle_sidebar"),
+            Some(&"CmdOrCtrl+Shift+B".to_string())
+        );
+        assert!(parsed.keybindings.vim_navigation);
+        assert_eq!(parsed.remote_content.images, RemoteImagePolicy::Always);
+        assert_eq!(parsed.remote_content.image_cache_size_mb, 0);
+        assert!(parsed.print.table_of_contents);
+        assert!(!parsed.print.page_header);
+    }
+
+    #[test]
+    fn test_config_without_keybindings_section() {
+        let parsed: Config = serde_json::from_str(
+            r#"{"theme":{"defaultTheme":"dark","onStartup":"default","onNewWindow":"default"}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(parsed.theme.default_theme, Theme::Dark);
+        assert_eq!(parsed.theme.custom_theme, None);
+        assert_eq!(parsed.keybindings, KeybindingsConfig::default());
     }
 }
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Configuration for keyboard shortcuts
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct KeybindingsConfig {
+    /// Menu accelerator overrides keyed by menu ID (e.g. "view.toggle_sidebar": "CmdOrCtrl+Shift+B").
+    /// An empty string removes the shortcut from the menu item.
+    pub menu: BTreeMap<String, String>,
+    /// Whether vim-style keys (j/k, gg/G, n/N, [/]) navigate the document
+    pub vim_navigation: bool,
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration for printing and PDF export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PrintConfig {
+    /// Whether a table of contents is printed before the document
+    pub table_of_contents: bool,
+    /// Whether the file name is printed at the top of every page
+    pub page_header: bool,
+    /// Whether link URLs are listed as footnotes at the end of the document
+    pub link_footnotes: bool,
+}
+
+impl Default for PrintConfig {
+    fn default() -> Self {
+        Self {
+            table_of_contents: false,
+            page_header: true,
+            link_footnotes: true,
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use crate::remote_images::{ImageCache, RemoteImageOptions, RemoteImagePolicy};
+
+pub const DEFAULT_IMAGE_CACHE_SIZE_MB: u64 = 100;
+
+fn default_image_cache_size_mb() -> u64 {
+    DEFAULT_IMAGE_CACHE_SIZE_MB
+}
+
+/// Configuration for content documents loaded from remote hosts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RemoteContentConfig {
+    /// What to do with remote images that aren't cached
+    pub images: RemoteImagePolicy,
+    /// Size limit of the offline image cache in megabytes (0 disables the cache)
+    #[serde(default = "default_image_cache_size_mb")]
+    pub image_cache_size_mb: u64,
+}
+
+impl Default for RemoteContentConfig {
+    fn default() -> Self {
+        Self {
+            images: RemoteImagePolicy::default(),
+            image_cache_size_mb: default_image_cache_size_mb(),
+        }
+    }
+}
+
+impl RemoteContentConfig {
+    /// How remote images are handled when rendering documents
+    pub fn image_options(&self) -> RemoteImageOptions {
+        RemoteImageOptions {
+            policy: self.images,
+            cache: (self.image_cache_size_mb > 0).then(|| {
+                ImageCache::new(
+                    ImageCache::default_dir(),
+                    self.image_cache_size_mb * 1024 * 1024,
+                )
+            }),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::markdown::HtmlPolicy;
+
+/// How raw HTML in Markdown documents is sanitized
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SanitizationMode {
+    /// Keep only the elements Markdown itself produces
+    Strict,
+    /// Keep the elements and attributes GitHub allows
+    #[default]
+    Github,
+    /// Render documents in the trusted directories as is, and others like `Github`
+    TrustedDirectories,
+}
+
+impl SanitizationMode {
+    pub const ALL: [SanitizationMode; 3] = [
+        SanitizationMode::Strict,
+        SanitizationMode::Github,
+        SanitizationMode::TrustedDirectories,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SanitizationMode::Strict => "Strict",
+            SanitizationMode::Github => "GitHub",
+            SanitizationMode::TrustedDirectories => "Trusted Directories",
+        }
+    }
+}
+
+/// Configuration for sanitizing raw HTML in documents
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SanitizationConfig {
+    pub mode: SanitizationMode,
+    /// Directories whose documents are not sanitized in `trusted_directories` mode
+    /// (`~` expands to the home directory)
+    pub trusted_directories: Vec<PathBuf>,
+}
+
+impl SanitizationConfig {
+    /// The policy applied to the document at `file`
+    pub fn policy_for(&self, file: &Path) -> HtmlPolicy {
+        match self.mode {
+            SanitizationMode::Strict => HtmlPolicy::Strict,
+            SanitizationMode::Github => HtmlPolicy::Github,
+            SanitizationMode::TrustedDirectories if self.is_trusted(file) => HtmlPolicy::Trusted,
+            SanitizationMode::TrustedDirectories => HtmlPolicy::Github,
+        }
+    }
+
+    fn is_trusted(&self, file: &Path) -> bool {
+        let file = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+        self.trusted_directories.iter().any(|dir| {
+            let dir = match (dir.strip_prefix("~"), dirs::home_dir()) {
+                (Ok(rest), Some(home)) => home.join(rest),
+                _ => dir.clone(),
+            };
+            let dir = dir.canonicalize().unwrap_or(dir);
+            file.starts_with(dir)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_policy_for() {
+        let temp = TempDir::new().unwrap();
+        let trusted = temp.path().join("notes");
+        std::fs::create_dir(&trusted).unwrap();
+        std::fs::write(trusted.join("todo.md"), "").unwrap();
+        let config = SanitizationConfig {
+            mode: SanitizationMode::TrustedDirectories,
+            trusted_directories: vec![trusted.clone()],
+        };
+
+        assert_eq!(
+            config.policy_for(&trusted.join("todo.md")),
+            HtmlPolicy::Trusted
+        );
+        assert_eq!(
+            config.policy_for(&temp.path().join("notes-other/todo.md")),
+            HtmlPolicy::Github
+        );
+
+        let config = SanitizationConfig {
+            mode: SanitizationMode::Strict,
+            ..config
+        };
+        assert_eq!(
+            config.policy_for(&trusted.join("todo.md")),
+            HtmlPolicy::Strict
+        );
+    }
+}
@@ -8,6 +8,54 @@ fn default_sidebar_width() -> f64 {
     DEFAULT_SIDEBAR_WIDTH
 }
 
+fn default_respect_ignore_files() -> bool {
+    true
+}
+
+fn default_document_extensions() -> Vec<String> {
+    vec![
+        "md".to_string(),
+        "markdown".to_string(),
+        "adoc".to_string(),
+        "asciidoc".to_string(),
+        "rst".to_string(),
+        "ipynb".to_string(),
+    ]
+}
+
+/// Order of entries in the file explorer (directories are always listed first)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortMode {
+    /// File name in natural order (`2.md` before `10.md`)
+    #[default]
+    Name,
+    /// Most recently modified first
+    Modified,
+    /// Largest first
+    Size,
+    /// `order` or `weight` from the Markdown front matter, then by name
+    FrontMatter,
+}
+
+impl SortMode {
+    pub const ALL: [SortMode; 4] = [
+        SortMode::Name,
+        SortMode::Modified,
+        SortMode::Size,
+        SortMode::FrontMatter,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::Name => "Name",
+            SortMode::Modified => "Modified",
+            SortMode::Size => "Size",
+            SortMode::FrontMatter => "Front Matter",
+        }
+    }
+}
+
 /// Configuration for sidebar-related settings
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -19,6 +67,21 @@ pub struct SidebarConfig {
     pub default_width: f64,
     /// Whether to show all files (including non-markdown) by default
     pub default_show_all_files: bool,
+    /// Whether to show hidden files (dotfiles) by default
+    #[serde(default)]
+    pub default_show_hidden_files: bool,
+    /// Whether to hide files matched by `.gitignore` and `.ignore` files
+    #[serde(default = "default_respect_ignore_files")]
+    pub respect_ignore_files: bool,
+    /// Additional gitignore-style patterns hidden from the file explorer
+    #[serde(default)]
+    pub ignore_globs: Vec<String>,
+    /// Order of entries in the file explorer
+    #[serde(default)]
+    pub sort_mode: SortMode,
+    /// File extensions listed when not showing all files
+    #[serde(default = "default_document_extensions")]
+    pub document_extensions: Vec<String>,
     /// Behavior on app startup: "default" or "last_closed"
     pub on_startup: StartupBehavior,
     /// Behavior when opening a new window: "default" or "last_focused"
@@ -31,6 +94,11 @@ impl Default for SidebarConfig {
             default_open: false,
             default_width: default_sidebar_width(),
             default_show_all_files: false,
+            default_show_hidden_files: false,
+            respect_ignore_files: default_respect_ignore_files(),
+            ignore_globs: Vec::new(),
+            sort_mode: SortMode::default(),
+            document_extensions: default_document_extensions(),
             on_startup: StartupBehavior::Default,
             on_new_window: NewWindowBehavior::Default,
         }
@@ -12,4 +12,7 @@ pub struct ThemeConfig {
     pub on_startup: StartupBehavior,
     /// Behavior when opening a new window: "default" or "last_focused"
     pub on_new_window: NewWindowBehavior,
+    /// Custom theme (file stem in the themes directory) layered over the built-in styles
+    #[serde(default)]
+    pub custom_theme: Option<String>,
 }
@@ -0,0 +1,64 @@
+use super::app_config::Config;
+use super::persistence::{CONFIG, CONFIG_LOAD_ERROR};
+use crate::events::CONFIG_CHANGED_BROADCAST;
+use crate::watcher::FILE_WATCHER;
+use std::time::Duration;
+
+/// How often to check whether the directory of config.json has been created
+const MISSING_DIRECTORY_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Watch config.json and broadcast the re-parsed configuration to all windows.
+///
+/// Runs for the lifetime of the application. A malformed file keeps the current configuration
+/// active and broadcasts the parse error instead.
+pub async fn watch_config_file() {
+    let path = Config::path();
+
+    // The watcher needs the directory; the file itself may be created later
+    while path.parent().is_some_and(|dir| !dir.is_dir()) {
+        tokio::time::sleep(MISSING_DIRECTORY_POLL_INTERVAL).await;
+    }
+
+    // A single watch for the lifetime of the application: the watcher follows the directory,
+    // so editors that save by replacing the file are still noticed
+    let mut watcher = match FILE_WATCHER.watch(path.clone()).await {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            tracing::error!("Failed to register config watcher: {:?}", e);
+            return;
+        }
+    };
+
+    while watcher.recv().await.is_some() {
+        if !path.exists() {
+            // Removed, or about to be replaced
+            continue;
+        }
+        tracing::info!("Configuration file changed, reloading: {:?}", path);
+        reload_config();
+    }
+}
+
+/// Re-read config.json, update `CONFIG` and notify windows about the outcome
+fn reload_config() {
+    match Config::load() {
+        Ok(config) => {
+            let had_error = CONFIG_LOAD_ERROR.write().take().is_some();
+            let changed = {
+                let mut current = CONFIG.write();
+                let changed = *current != config;
+                *current = config.clone();
+                changed
+            };
+            // Also notify when only the error cleared so windows can hide the banner
+            if changed || had_error {
+                let _ = CONFIG_CHANGED_BROADCAST.send(Ok(config));
+            }
+        }
+        Err(e) => {
+            tracing::warn!("Failed to reload configuration: {e}");
+            *CONFIG_LOAD_ERROR.write() = Some(e.clone());
+            let _ = CONFIG_CHANGED_BROADCAST.send(Err(e));
+        }
+    }
+}
@@ -4,9 +4,41 @@ use parking_lot::RwLock;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::LazyLock;
+use thiserror::Error;
+
+/// Error raised when config.json exists but cannot be used
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ConfigLoadError {
+    #[error("Failed to read config.json: {0}")]
+    Read(String),
+    #[error("config.json:{line}:{column}: {message}")]
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+}
+
+impl From<serde_json::Error> for ConfigLoadError {
+    fn from(e: serde_json::Error) -> Self {
+        // serde_json appends the position to the message; report it separately instead
+        let (line, column) = (e.line(), e.column());
+        let message = e.to_string();
+        let message = message
+            .strip_suffix(&format!(" at line {line} column {column}"))
+            .unwrap_or(&message)
+            .to_string();
+        Self::Parse {
+            line,
+            column,
+            message,
+        }
+    }
+}
 
 impl Config {
-    /// Get the configuration file path based on the platform
+    /// Get the configuration file path based on the platform (`$XDG_CONFIG_HOME` on Linux,
+    /// `~/Library/Application Support` on macOS)
     pub fn path() -> PathBuf {
         const FILENAME: &str = "config.json";
         if let Some(mut path) = dirs::config_dir() {
@@ -26,21 +58,27 @@ impl Config {
     }
 
     /// Load configuration from file or return default configuration
-    pub fn load() -> Result<Self> {
+    pub fn load() -> Result<Self, ConfigLoadError> {
         let path = Self::path();
 
         if !path.exists() {
             return Ok(Config::default());
         }
 
-        let content = fs::read_to_string(&path)?;
-        let config: Config = serde_json::from_str(&content)?;
+        let content =
+            fs::read_to_string(&path).map_err(|e| ConfigLoadError::Read(e.to_string()))?;
+        let config = Self::parse(&content)?;
 
         tracing::debug!(path = %path.display(), "Configuration loaded");
 
         Ok(config)
     }
 
+    /// Parse configuration from JSON text
+    pub fn parse(content: &str) -> Result<Self, ConfigLoadError> {
+        Ok(serde_json::from_str(content)?)
+    }
+
     /// Save configuration to file
     pub fn save(&self) -> Result<()> {
         let path = Self::path();
@@ -61,6 +99,44 @@ impl Config {
 
 /// Global configuration instance
 pub static CONFIG: LazyLock<RwLock<Config>> = LazyLock::new(|| {
-    let config = Config::load().unwrap_or_default();
+    let config = Config::load().unwrap_or_else(|e| {
+        tracing::error!("Failed to load configuration, using defaults: {e}");
+        *CONFIG_LOAD_ERROR.write() = Some(e);
+        Config::default()
+    });
     RwLock::new(config)
 });
+
+/// Error from the most recent attempt to load config.json (`None` if it loaded cleanly)
+pub static CONFIG_LOAD_ERROR: LazyLock<RwLock<Option<ConfigLoadError>>> =
+    LazyLock::new(|| RwLock::new(None));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_reports_position() {
+        let err = Config::parse("{\n  \"theme\": {\n    \"defaultTheme\": \"purple\"\n  }\n}")
+            .unwrap_err();
+        match err {
+            ConfigLoadError::Parse {
+                line,
+                column,
+                message,
+            } => {
+                assert_eq!(line, 3);
+                assert!(column > 0);
+                assert!(message.contains("purple"), "{message}");
+                assert!(!message.contains(" at line "), "{message}");
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_error_display() {
+        let err = Config::parse("{ not json").unwrap_err();
+        assert!(err.to_string().starts_with("config.json:1:"), "{err}");
+    }
+}
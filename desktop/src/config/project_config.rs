@@ -0,0 +1,347 @@
+use super::app_config::Config;
+use crate::theme::Theme;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// File name of the per-project configuration, looked up from the opened directory upwards
+pub const PROJECT_CONFIG_FILENAME: &str = ".arto.toml";
+
+/// Mermaid options a project may set; the rest (`securityLevel`, `themeCSS`, …) could get
+/// around the HTML sanitizer of an untrusted repository
+const MERMAID_OPTIONS: &[&str] = &[
+    "theme",
+    "themeVariables",
+    "fontFamily",
+    "flowchart",
+    "sequence",
+    "gantt",
+    "class",
+    "state",
+    "er",
+    "pie",
+    "journey",
+];
+
+/// Options of the Mermaid diagram tables above a project may not set
+const MERMAID_DIAGRAM_DENIED: &[&str] = &["htmlLabels"];
+
+/// KaTeX options a project may set; `trust` in particular is left out
+const MATH_OPTIONS: &[&str] = &["macros", "throwOnError", "errorColor", "fleqn", "leqno"];
+
+/// Per-project reading setup loaded from `.arto.toml`
+///
+/// Relative paths are resolved against the directory containing the file; paths leading out of
+/// it are ignored.
+///
+/// ```toml
+/// theme = "dark"
+/// root = "docs"
+/// start_page = "README.md"
+/// site_root = "docs"
+/// stylesheet = "docs/arto.css"
+/// ignore = ["target", "*.tmp", "drafts/**"]
+///
+/// [mermaid]
+/// theme = "forest"
+///
+/// [math]
+/// macros = { "\\RR" = "\\mathbb{R}" }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ProjectConfig {
+    /// Theme applied when the project directory is opened
+    pub theme: Option<Theme>,
+    /// Directory shown as the sidebar root when the project directory is opened
+    pub root: Option<PathBuf>,
+    /// Gitignore-style patterns hidden from the file explorer, anchored at the project
+    /// directory (see [`crate::utils::file_filter::FileFilter`])
+    pub ignore: Vec<String>,
+    /// Extra stylesheet layered over the built-in styles
+    pub stylesheet: Option<PathBuf>,
+    /// Directory that absolute links (e.g. `/guide/intro.md`) are resolved against
+    pub site_root: Option<PathBuf>,
+    /// File opened when the project directory is opened and no file is shown
+    pub start_page: Option<PathBuf>,
+    /// Options passed to `mermaid.initialize`, limited to [`MERMAID_OPTIONS`]
+    pub mermaid: serde_json::Map<String, serde_json::Value>,
+    /// Options passed to KaTeX, limited to [`MATH_OPTIONS`]
+    pub math: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ProjectConfig {
+    /// Parse configuration from TOML text, dropping the renderer options a project may not set
+    pub fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        let mut config: Self = toml::from_str(content)?;
+        retain_options(&mut config.mermaid, "mermaid", MERMAID_OPTIONS);
+        for diagram in config.mermaid.values_mut() {
+            if let serde_json::Value::Object(options) = diagram {
+                options.retain(|key, _| !MERMAID_DIAGRAM_DENIED.contains(&key.as_str()));
+            }
+        }
+        retain_options(&mut config.math, "math", MATH_OPTIONS);
+        Ok(config)
+    }
+
+    /// Apply the project settings that have a global counterpart over `config`
+    pub fn merge_into(&self, config: &Config, project_dir: &Path) -> Config {
+        let mut merged = config.clone();
+        if let Some(theme) = self.theme {
+            merged.theme.default_theme = theme;
+        }
+        if let Some(root) = &self.root {
+            merged.directory.default_directory = resolve_within(project_dir, "root", root);
+        }
+        merged
+    }
+}
+
+/// A project configuration together with the directory it was found in
+#[derive(Debug, Clone, PartialEq)]
+pub struct Project {
+    pub dir: PathBuf,
+    pub config: ProjectConfig,
+}
+
+impl Project {
+    /// Find the nearest `.arto.toml` in `start` or any of its ancestors
+    pub fn discover(start: &Path) -> Option<Self> {
+        start.ancestors().find_map(Self::load)
+    }
+
+    /// Load `.arto.toml` from `dir`; invalid files are logged and skipped
+    fn load(dir: &Path) -> Option<Self> {
+        let path = dir.join(PROJECT_CONFIG_FILENAME);
+        let content = fs::read_to_string(&path).ok()?;
+        match ProjectConfig::parse(&content) {
+            Ok(config) => {
+                tracing::debug!(path = %path.display(), "Project configuration loaded");
+                Some(Self {
+                    dir: dir.to_path_buf(),
+                    config,
+                })
+            }
+            Err(e) => {
+                tracing::warn!(path = %path.display(), "Invalid project configuration: {e}");
+                None
+            }
+        }
+    }
+
+    /// Global configuration with this project's settings merged over it
+    pub fn merged_config(&self, config: &Config) -> Config {
+        self.config.merge_into(config, &self.dir)
+    }
+
+    /// Start page, if configured and present
+    pub fn start_page(&self) -> Option<PathBuf> {
+        self.config
+            .start_page
+            .as_ref()
+            .and_then(|page| resolve_within(&self.dir, "start_page", page))
+            .filter(|page| page.is_file())
+    }
+
+    /// Project stylesheet, if configured, whether it exists yet or not
+    pub fn stylesheet(&self) -> Option<PathBuf> {
+        self.config
+            .stylesheet
+            .as_ref()
+            .and_then(|stylesheet| resolve_within(&self.dir, "stylesheet", stylesheet))
+    }
+
+    /// Site root, if configured
+    pub fn site_root(&self) -> Option<PathBuf> {
+        self.config
+            .site_root
+            .as_ref()
+            .and_then(|site_root| resolve_within(&self.dir, "site_root", site_root))
+    }
+}
+
+/// `path` of the `key` setting resolved against the project directory `dir`, or `None` with a
+/// warning when it leads out of it (absolute, `..` or through a symlink)
+fn resolve_within(dir: &Path, key: &str, path: &Path) -> Option<PathBuf> {
+    let resolved = dir.join(path);
+    let relative = path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    // Files that don't exist yet are checked through their nearest existing ancestor
+    let contained = relative
+        && dir.canonicalize().map_or(true, |dir| {
+            resolved
+                .ancestors()
+                .find_map(|ancestor| ancestor.canonicalize().ok())
+                .is_some_and(|ancestor| ancestor.starts_with(dir))
+        });
+    if !contained {
+        tracing::warn!(
+            "Ignoring {key} outside of the project in {PROJECT_CONFIG_FILENAME}: {}",
+            path.display()
+        );
+        return None;
+    }
+    Some(resolved)
+}
+
+/// Drop the options of the `table` table that aren't `allowed`, with a warning
+fn retain_options(
+    options: &mut serde_json::Map<String, serde_json::Value>,
+    table: &str,
+    allowed: &[&str],
+) {
+    options.retain(|key, _| {
+        let keep = allowed.contains(&key.as_str());
+        if !keep {
+            tracing::warn!(
+                "Ignoring unsupported option {table}.{key} in {PROJECT_CONFIG_FILENAME}"
+            );
+        }
+        keep
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_project_config() {
+        let config = ProjectConfig::parse(indoc::indoc! {r#"
+            theme = "dark"
+            root = "docs"
+            start_page = "README.md"
+            site_root = "docs"
+            stylesheet = "docs/arto.css"
+            ignore = ["target", "*.tmp"]
+
+            [mermaid]
+            theme = "forest"
+
+            [math]
+            macros = { "\\RR" = "\\mathbb{R}" }
+        "#})
+        .unwrap();
+
+        assert_eq!(config.theme, Some(Theme::Dark));
+        assert_eq!(config.root, Some(PathBuf::from("docs")));
+        assert_eq!(config.start_page, Some(PathBuf::from("README.md")));
+        assert_eq!(config.ignore, vec!["target", "*.tmp"]);
+        assert_eq!(config.mermaid["theme"], "forest");
+        assert_eq!(config.math["macros"]["\\RR"], "\\mathbb{R}");
+    }
+
+    #[test]
+    fn test_parse_drops_unsafe_render_options() {
+        let config = ProjectConfig::parse(indoc::indoc! {r#"
+            [mermaid]
+            theme = "forest"
+            securityLevel = "loose"
+            themeCSS = "* { background: url(https://example.com) }"
+            flowchart = { htmlLabels = true, curve = "basis" }
+
+            [math]
+            trust = true
+            macros = { "\\RR" = "\\mathbb{R}" }
+        "#})
+        .unwrap();
+
+        assert_eq!(
+            config.mermaid.keys().collect::<Vec<_>>(),
+            vec!["flowchart", "theme"]
+        );
+        assert_eq!(
+            config.mermaid["flowchart"],
+            serde_json::json!({ "curve": "basis" })
+        );
+        assert_eq!(config.math.keys().collect::<Vec<_>>(), vec!["macros"]);
+    }
+
+    #[test]
+    fn test_parse_empty_project_config() {
+        assert_eq!(ProjectConfig::parse("").unwrap(), ProjectConfig::default());
+    }
+
+    #[test]
+    fn test_merge_into_global_config() {
+        let project = ProjectConfig {
+            theme: Some(Theme::Light),
+            root: Some(PathBuf::from("docs")),
+            ..Default::default()
+        };
+        let merged = project.merge_into(&Config::default(), Path::new("/repo"));
+
+        assert_eq!(merged.theme.default_theme, Theme::Light);
+        assert_eq!(
+            merged.directory.default_directory,
+            Some(PathBuf::from("/repo/docs"))
+        );
+        assert_eq!(merged.sidebar, Config::default().sidebar);
+
+        let merged = ProjectConfig::default().merge_into(&Config::default(), Path::new("/repo"));
+        assert_eq!(merged, Config::default());
+    }
+
+    #[test]
+    fn test_paths_outside_the_project_are_ignored() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join("repo");
+        fs::create_dir_all(dir.join("docs")).unwrap();
+        let project = |config: &str| Project {
+            dir: dir.clone(),
+            config: ProjectConfig::parse(config).unwrap(),
+        };
+
+        let inside = project("site_root = \"docs\"\nstylesheet = \"docs/new.css\"");
+        assert_eq!(inside.site_root(), Some(dir.join("docs")));
+        assert_eq!(inside.stylesheet(), Some(dir.join("docs/new.css")));
+
+        let outside = project("site_root = \"/\"\nstylesheet = \"../other.css\"");
+        assert_eq!(outside.site_root(), None);
+        assert_eq!(outside.stylesheet(), None);
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(temp.path(), dir.join("escape")).unwrap();
+            assert_eq!(project("site_root = \"escape\"").site_root(), None);
+            assert_eq!(
+                project("stylesheet = \"escape/new.css\"").stylesheet(),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn test_discover_walks_up() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join(PROJECT_CONFIG_FILENAME),
+            "theme = \"dark\"",
+        )
+        .unwrap();
+        let nested = temp.path().join("a/b");
+        fs::create_dir_all(&nested).unwrap();
+
+        let project = Project::discover(&nested).unwrap();
+        assert_eq!(project.dir, temp.path());
+        assert_eq!(project.config.theme, Some(Theme::Dark));
+    }
+
+    #[test]
+    fn test_discover_skips_invalid_file() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join(PROJECT_CONFIG_FILENAME),
+            "theme = \"dark\"",
+        )
+        .unwrap();
+        let nested = temp.path().join("sub");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join(PROJECT_CONFIG_FILENAME), "theme = [").unwrap();
+
+        let project = Project::discover(&nested).unwrap();
+        assert_eq!(project.dir, temp.path());
+    }
+}
@@ -0,0 +1,171 @@
+use anyhow::{Context, Result};
+use serde_json::json;
+use std::path::Path;
+
+/// Delimiters considered when sniffing CSV-like content
+const CANDIDATE_DELIMITERS: [u8; 4] = [b',', b'\t', b';', b'|'];
+
+/// Number of lines inspected to detect the delimiter
+const SNIFF_LINES: usize = 20;
+
+/// Maximum number of rows handed to the table renderer; larger files are truncated
+const MAX_ROWS: usize = 100_000;
+
+/// Render a `.csv` or `.tsv` file as a table; CSV delimiters are detected since
+/// semicolon-separated files are common
+pub fn render_table_file_to_html(content: &str, path: impl AsRef<Path>) -> Result<String> {
+    let is_tsv = path
+        .as_ref()
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("tsv"));
+    render_csv_to_html(content, is_tsv.then_some(b'\t'))
+}
+
+/// Render delimited text (CSV, TSV, …) as a table placeholder
+///
+/// The rows are embedded as JSON and turned into a sortable, paginated table by the
+/// renderer (`csv-table.ts`), so large files don't produce huge DOM trees. When `delimiter`
+/// is `None`, it is detected from the content.
+pub fn render_csv_to_html(content: &str, delimiter: Option<u8>) -> Result<String> {
+    let delimiter = delimiter.unwrap_or_else(|| detect_delimiter(content));
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let mut rows = Vec::new();
+    let mut total_rows = 0;
+    for record in reader.records() {
+        let record = record.context("Failed to parse delimited text")?;
+        total_rows += 1;
+        if rows.len() <= MAX_ROWS {
+            rows.push(record.iter().map(str::to_string).collect::<Vec<_>>());
+        }
+    }
+
+    let header = if detect_header(&rows) {
+        total_rows -= 1;
+        Some(rows.remove(0))
+    } else {
+        None
+    };
+    rows.truncate(MAX_ROWS);
+
+    let data = json!({
+        "header": header,
+        "rows": rows,
+        "totalRows": total_rows,
+    });
+    // Plain text rather than a <script>, so the placeholder survives HTML sanitization
+    let data = html_escape::encode_text(&data.to_string()).into_owned();
+
+    Ok(format!(
+        r#"<div class="csv-table"><pre class="csv-table-data" hidden>{data}</pre></div>"#
+    ))
+}
+
+/// Pick the candidate delimiter that splits the first lines into the most consistent columns
+fn detect_delimiter(content: &str) -> u8 {
+    let lines: Vec<&str> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .take(SNIFF_LINES)
+        .collect();
+
+    CANDIDATE_DELIMITERS
+        .into_iter()
+        .filter_map(|delimiter| {
+            let counts: Vec<usize> = lines
+                .iter()
+                .map(|line| line.bytes().filter(|b| *b == delimiter).count())
+                .collect();
+            let first = *counts.first()?;
+            let consistent = counts.iter().filter(|count| **count == first).count();
+            (first > 0).then_some((delimiter, consistent, first))
+        })
+        // Prefer delimiters producing the same column count on most lines, then more columns
+        .max_by_key(|(_, consistent, columns)| (*consistent, *columns))
+        .map(|(delimiter, _, _)| delimiter)
+        .unwrap_or(b',')
+}
+
+/// Treat the first row as a header when its cells look like labels: all present,
+/// distinct and non-numeric, while data rows follow
+fn detect_header(rows: &[Vec<String>]) -> bool {
+    let Some(first) = rows.first() else {
+        return false;
+    };
+    let mut seen = std::collections::HashSet::new();
+    rows.len() > 1
+        && first.iter().all(|cell| {
+            let cell = cell.trim();
+            !cell.is_empty() && cell.parse::<f64>().is_err() && seen.insert(cell)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn table_data(html: &str) -> Value {
+        let start = html.find('{').unwrap();
+        let end = html.rfind('}').unwrap();
+        serde_json::from_str(&html_escape::decode_html_entities(&html[start..=end])).unwrap()
+    }
+
+    #[test]
+    fn test_detect_delimiter() {
+        assert_eq!(detect_delimiter("a,b,c\n1,2,3\n"), b',');
+        assert_eq!(detect_delimiter("a\tb\tc\n1\t2,5\t3\n"), b'\t');
+        assert_eq!(detect_delimiter("name;price\nfoo;1,5\nbar;2,25\n"), b';');
+        assert_eq!(detect_delimiter("single column\nvalue\n"), b',');
+    }
+
+    #[test]
+    fn test_detect_header() {
+        let rows = |data: &[&[&str]]| -> Vec<Vec<String>> {
+            data.iter()
+                .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+                .collect()
+        };
+
+        assert!(detect_header(&rows(&[&["name", "age"], &["Alice", "30"]])));
+        assert!(!detect_header(&rows(&[&["1", "2"], &["3", "4"]])));
+        assert!(!detect_header(&rows(&[&["a", "a"], &["b", "c"]])));
+        assert!(!detect_header(&rows(&[&["name", ""], &["Alice", "30"]])));
+        assert!(!detect_header(&rows(&[&["name", "age"]])));
+    }
+
+    #[test]
+    fn test_render_csv_with_header() {
+        let html = render_csv_to_html("name,age\nAlice,30\n\"Bob, Jr.\",4\n", None).unwrap();
+        let data = table_data(&html);
+
+        assert!(html.starts_with(r#"<div class="csv-table">"#));
+        assert_eq!(data["header"], json!(["name", "age"]));
+        assert_eq!(data["rows"], json!([["Alice", "30"], ["Bob, Jr.", "4"]]));
+        assert_eq!(data["totalRows"], 2);
+    }
+
+    #[test]
+    fn test_render_tsv_without_header() {
+        let html = render_csv_to_html("1\t2\n3\t4\t5\n", Some(b'\t')).unwrap();
+        let data = table_data(&html);
+
+        assert_eq!(data["header"], Value::Null);
+        assert_eq!(data["rows"], json!([["1", "2"], ["3", "4", "5"]]));
+        assert_eq!(data["totalRows"], 2);
+    }
+
+    #[test]
+    fn test_render_csv_escapes_markup() {
+        let html = render_csv_to_html("note,value\n</pre><b>x</b>,1\n", None).unwrap();
+        let data = table_data(&html);
+
+        assert_eq!(html.matches("</pre>").count(), 1);
+        assert!(!html.contains("<b>"));
+        assert_eq!(data["rows"], json!([["</pre><b>x</b>", "1"]]));
+    }
+}
@@ -14,7 +14,8 @@
 //! │       │                                                             │
 //! │       ├──→ Event::Opened { urls } ──→ OpenEvent::File(path)        │
 //! │       ├──→ Event::Opened { urls } ──→ OpenEvent::Directory(path)   │
-//! │       └──→ Event::Reopen          ──→ OpenEvent::Reopen            │
+//! │       ├──→ Event::Reopen          ──→ OpenEvent::Reopen            │
+//! │       └──→ argv (non-macOS)       ──→ OpenEvent::File/Directory    │
 //! │                │                                                    │
 //! │                v                                                    │
 //! │       OPEN_EVENT_RECEIVER (mpsc::Receiver)                         │
@@ -77,6 +78,7 @@
 //!
 //! The Entrypoint layer provides this coordination logic before broadcasting to App components.
 
+use crate::config::{Config, ConfigLoadError};
 use crate::state::Tab;
 use dioxus::desktop::tao::window::WindowId;
 use std::path::PathBuf;
@@ -98,6 +100,22 @@ pub static FILE_OPEN_BROADCAST: std::sync::LazyLock<broadcast::Sender<PathBuf>>
 pub static DIRECTORY_OPEN_BROADCAST: std::sync::LazyLock<broadcast::Sender<PathBuf>> =
     std::sync::LazyLock::new(|| broadcast::channel(100).0);
 
+/// Global broadcast sender for configuration changes.
+///
+/// Sent when config.json is re-parsed after an external edit, or when settings are changed
+/// from within the app (Preferences, theme selector). `Err` carries the error of a malformed
+/// file while the previous configuration stays active. Every window handles the event.
+pub static CONFIG_CHANGED_BROADCAST: std::sync::LazyLock<
+    broadcast::Sender<Result<Config, ConfigLoadError>>,
+> = std::sync::LazyLock::new(|| broadcast::channel(10).0);
+
+/// Global broadcast sender for renamed files and directories, as `(from, to)`.
+///
+/// Sent after a rename from the file explorer so every window can update tabs that point
+/// at the old path. Every window handles the event.
+pub static FILE_RENAMED_BROADCAST: std::sync::LazyLock<broadcast::Sender<(PathBuf, PathBuf)>> =
+    std::sync::LazyLock::new(|| broadcast::channel(10).0);
+
 // ============================================================================
 // Tab Transfer Events (Two-Phase Commit Pattern)
 // ============================================================================
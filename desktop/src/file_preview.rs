@@ -0,0 +1,193 @@
+//! Previews of files that can't be shown as documents
+//!
+//! Files that aren't text, or can't be read at all, get a tab explaining why (`FileErrorView`).
+//! Images are still shown, PDFs are left to the default app, other binary files get a hex dump
+//! of their first bytes, and read errors are explained in plain language.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use crate::asset_protocol::{asset_url, sniff_mime_type};
+
+/// Bytes of a binary file shown in its hex dump
+pub const HEX_DUMP_BYTES: usize = 4096;
+
+/// Bytes shown per line of a hex dump
+const BYTES_PER_LINE: usize = 16;
+
+/// How a file that couldn't be opened as a document is previewed
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilePreview {
+    /// Image shown inline, served through the asset protocol
+    Image {
+        url: String,
+        mime_type: &'static str,
+    },
+    /// PDF document, left to the default app
+    Pdf,
+    /// Hex dump of the first bytes of another binary file
+    Binary {
+        /// MIME type when recognized from the content
+        mime_type: Option<&'static str>,
+        dump: String,
+        /// Size of the whole file
+        size: u64,
+    },
+    /// File that couldn't be read
+    Unreadable(io::ErrorKind),
+}
+
+/// Preview the file at `path`
+///
+/// Images are served from the asset protocol, so `path` must be
+/// [granted](crate::asset_protocol::AssetGrant) to the webview for them to load.
+pub fn preview_file(path: &Path) -> FilePreview {
+    match read_head(path) {
+        Ok((head, size)) => {
+            let mime_type = sniff_mime_type(path, &head);
+            if mime_type.starts_with("image/") {
+                FilePreview::Image {
+                    url: asset_url(path),
+                    mime_type,
+                }
+            } else if mime_type == "application/pdf" {
+                FilePreview::Pdf
+            } else {
+                FilePreview::Binary {
+                    mime_type: (mime_type != "application/octet-stream").then_some(mime_type),
+                    dump: hex_dump(&head),
+                    size,
+                }
+            }
+        }
+        Err(e) => FilePreview::Unreadable(e.kind()),
+    }
+}
+
+/// First bytes of a file, along with its size
+fn read_head(path: &Path) -> io::Result<(Vec<u8>, u64)> {
+    let file = File::open(path)?;
+    let metadata = file.metadata()?;
+    if metadata.is_dir() {
+        return Err(io::ErrorKind::IsADirectory.into());
+    }
+    let mut head = Vec::with_capacity(HEX_DUMP_BYTES);
+    file.take(HEX_DUMP_BYTES as u64).read_to_end(&mut head)?;
+    Ok((head, metadata.len()))
+}
+
+/// Hex dump of `bytes` with offsets and the printable ASCII characters, like `hexdump -C`
+pub fn hex_dump(bytes: &[u8]) -> String {
+    let mut dump = String::new();
+    for (line, chunk) in bytes.chunks(BYTES_PER_LINE).enumerate() {
+        dump.push_str(&format!("{:08x} ", line * BYTES_PER_LINE));
+        for i in 0..BYTES_PER_LINE {
+            if i % 8 == 0 {
+                dump.push(' ');
+            }
+            match chunk.get(i) {
+                Some(byte) => dump.push_str(&format!("{byte:02x} ")),
+                None => dump.push_str("   "),
+            }
+        }
+        dump.push_str(" |");
+        dump.extend(chunk.iter().map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            }
+        }));
+        dump.push_str("|\n");
+    }
+    dump
+}
+
+/// Plain-language explanation of why a file couldn't be read
+pub fn explain_error(kind: io::ErrorKind) -> &'static str {
+    match kind {
+        io::ErrorKind::NotFound => {
+            "The file doesn't exist anymore. It may have been moved, renamed or deleted."
+        }
+        io::ErrorKind::PermissionDenied => {
+            "You don't have permission to read this file. Check its permissions, or ask its owner for access."
+        }
+        io::ErrorKind::IsADirectory => "This is a folder, not a file.",
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => {
+            "Reading the file took too long. It may be on a slow or disconnected drive."
+        }
+        io::ErrorKind::StaleNetworkFileHandle | io::ErrorKind::NotConnected => {
+            "The file is on a network drive that is no longer available."
+        }
+        io::ErrorKind::OutOfMemory | io::ErrorKind::FileTooLarge => {
+            "The file is too large to open."
+        }
+        io::ErrorKind::ResourceBusy => "The file is in use by another program. Try again later.",
+        _ => "The file couldn't be read.",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_hex_dump() {
+        let dump = hex_dump(b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR\x01\x02");
+
+        assert_eq!(
+            dump,
+            indoc! {"
+                00000000  89 50 4e 47 0d 0a 1a 0a  00 00 00 0d 49 48 44 52  |.PNG........IHDR|
+                00000010  01 02                                             |..|
+            "}
+        );
+    }
+
+    #[test]
+    fn test_preview_file() {
+        let temp = TempDir::new().unwrap();
+        let write = |name: &str, content: &[u8]| {
+            let path = temp.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            path
+        };
+
+        let image = write("logo.bin", b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR");
+        assert_eq!(
+            preview_file(&image),
+            FilePreview::Image {
+                url: asset_url(&image),
+                mime_type: "image/png"
+            }
+        );
+
+        let pdf = write("paper.pdf", b"%PDF-1.7\n%\xe2\xe3\xcf\xd3\n");
+        assert_eq!(preview_file(&pdf), FilePreview::Pdf);
+
+        let data = write("data.bin", &[0u8; HEX_DUMP_BYTES + 10]);
+        let FilePreview::Binary {
+            mime_type,
+            dump,
+            size,
+        } = preview_file(&data)
+        else {
+            panic!("expected a hex dump");
+        };
+        assert_eq!(mime_type, None);
+        assert_eq!(dump.lines().count(), HEX_DUMP_BYTES / BYTES_PER_LINE);
+        assert_eq!(size, HEX_DUMP_BYTES as u64 + 10);
+
+        assert_eq!(
+            preview_file(&temp.path().join("missing.md")),
+            FilePreview::Unreadable(io::ErrorKind::NotFound)
+        );
+        assert_eq!(
+            preview_file(temp.path()),
+            FilePreview::Unreadable(io::ErrorKind::IsADirectory)
+        );
+    }
+}
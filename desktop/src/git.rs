@@ -0,0 +1,180 @@
+//! Reading the git repository a document belongs to
+//!
+//! The local repository is read with gitoxide, a Rust implementation of git, so git needn't
+//! be installed and nothing is fetched. It tells the branch and dirty state shown in the
+//! header, and gives the version of a document at a revision, which the file viewer renders
+//! to highlight the blocks that changed since (see [`changes`]).
+
+mod changes;
+
+pub use changes::{diff_blocks, BlockChanges};
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Revision changes are highlighted against unless another one is chosen
+pub const DEFAULT_BASE: &str = "HEAD";
+
+/// Most branches and tags offered to compare with
+const MAX_REFS: usize = 50;
+
+/// Branch and state of the working tree of a repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepositoryStatus {
+    /// Root of the working tree
+    pub workdir: PathBuf,
+    /// Checked out branch, or the abbreviated commit id when the HEAD is detached
+    pub head: String,
+    pub detached: bool,
+    /// Whether tracked files have uncommitted changes (untracked files don't count)
+    pub dirty: bool,
+}
+
+/// Status of the repository whose working tree contains `file`, if any
+pub fn status(file: &Path) -> Option<RepositoryStatus> {
+    let repo = discover(file)?;
+    let workdir = repo.workdir()?.to_path_buf();
+    let (head, detached) = match repo.head_name() {
+        Ok(Some(name)) => (name.shorten().to_string(), false),
+        // Detached, or in a repository without commits
+        _ => match repo.head_id() {
+            Ok(id) => (id.shorten_or_id().to_string(), true),
+            Err(_) => (String::new(), false),
+        },
+    };
+    let dirty = repo.is_dirty().unwrap_or_else(|e| {
+        tracing::warn!("Failed to read the status of {:?}: {}", workdir, e);
+        false
+    });
+    Some(RepositoryStatus {
+        workdir,
+        head,
+        detached,
+        dirty,
+    })
+}
+
+/// Content of `file` at `revision` (`HEAD`, a branch, a tag, `HEAD~2`, a commit id, …), or
+/// `None` when the file didn't exist then
+pub fn read_file_at(file: &Path, revision: &str) -> Result<Option<Vec<u8>>> {
+    let repo = discover(file).context("Not in a git working tree")?;
+    let workdir = repo
+        .workdir()
+        .context("The repository has no working tree")?;
+    let path = relative_path(file, workdir)?;
+
+    let tree = repo
+        .rev_parse_single(revision)
+        .with_context(|| format!("Unknown revision {revision:?}"))?
+        .object()?
+        .peel_to_tree()?;
+    let Some(entry) = tree.lookup_entry_by_path(&path)? else {
+        return Ok(None);
+    };
+    if !entry.mode().is_blob() {
+        return Ok(None);
+    }
+    let blob = entry.object()?.detach();
+    Ok(Some(blob.data))
+}
+
+/// Local branches and tags of the repository of `file`, to compare with
+pub fn list_refs(file: &Path) -> Vec<String> {
+    let Some(repo) = discover(file) else {
+        return Vec::new();
+    };
+    let Ok(references) = repo.references() else {
+        return Vec::new();
+    };
+    let names = |refs: Result<gix::reference::iter::Iter<'_, '_>, _>| -> Vec<String> {
+        refs.into_iter()
+            .flatten()
+            .flatten()
+            .map(|reference| reference.name().shorten().to_string())
+            .collect()
+    };
+    let mut refs = names(references.local_branches());
+    refs.extend(names(references.tags()).into_iter().rev());
+    refs.truncate(MAX_REFS);
+    refs
+}
+
+/// Repository whose working tree contains `file`
+fn discover(file: &Path) -> Option<gix::Repository> {
+    gix::discover(file.parent()?).ok()
+}
+
+/// Path of `file` relative to `workdir`, resolving symbolic links on either side
+fn relative_path(file: &Path, workdir: &Path) -> Result<PathBuf> {
+    let file = file.canonicalize()?;
+    let workdir = workdir.canonicalize()?;
+    Ok(file
+        .strip_prefix(&workdir)
+        .with_context(|| format!("{file:?} is outside of {workdir:?}"))?
+        .to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    /// Run git in `dir`, returning whether it's available
+    fn git(dir: &Path, args: &[&str]) -> bool {
+        Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .is_ok_and(|output| output.status.success())
+    }
+
+    #[test]
+    fn test_read_file_at_and_status() {
+        let dir = tempfile::tempdir().unwrap();
+        if !git(dir.path(), &["init", "-q", "-b", "main"]) {
+            // Tests run without git too; there's nothing to read then
+            return;
+        }
+        let file = dir.path().join("docs").join("guide.md");
+        std::fs::create_dir(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, "# One\n").unwrap();
+        assert!(git(dir.path(), &["add", "."]));
+        assert!(git(dir.path(), &["commit", "-q", "-m", "one"]));
+        std::fs::write(&file, "# Two\n").unwrap();
+        assert!(git(dir.path(), &["commit", "-q", "-am", "two"]));
+
+        assert_eq!(
+            read_file_at(&file, "HEAD").unwrap().as_deref(),
+            Some(&b"# Two\n"[..])
+        );
+        assert_eq!(
+            read_file_at(&file, "HEAD~1").unwrap().as_deref(),
+            Some(&b"# One\n"[..])
+        );
+        assert!(read_file_at(&file, "no-such-branch").is_err());
+        // Untracked files didn't exist at any revision
+        let new_file = dir.path().join("new.md");
+        std::fs::write(&new_file, "# New\n").unwrap();
+        assert_eq!(read_file_at(&new_file, "HEAD").unwrap(), None);
+
+        let clean = status(&file).unwrap();
+        assert_eq!(clean.head, "main");
+        assert!(!clean.detached);
+        assert!(!clean.dirty);
+
+        std::fs::write(&file, "# Three\n").unwrap();
+        assert!(status(&file).unwrap().dirty);
+        assert_eq!(list_refs(&file), vec!["main".to_string()]);
+    }
+
+    #[test]
+    fn test_status_outside_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        // The temporary directory may itself be inside a repository
+        if gix::discover(dir.path()).is_ok() {
+            return;
+        }
+        assert_eq!(status(&dir.path().join("README.md")), None);
+    }
+}
@@ -0,0 +1,203 @@
+//! Blocks of a document changed since another version of it
+//!
+//! Both versions are rendered, and their content blocks (see [`crate::markdown::Block`]) are
+//! compared by their HTML, so changes are shown as the reader sees them: editing a paragraph
+//! marks that paragraph, while reflowing its source or changing a link definition marks the
+//! blocks it changes the rendering of.
+
+use serde::Serialize;
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+
+use crate::markdown::{block_keys, Block};
+
+/// How a block of the document differs from the base version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+    Added,
+    Modified,
+}
+
+/// Block of the document that isn't in the base version as it is
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChangedBlock {
+    /// Key of the block in the document (see [`block_keys`])
+    pub key: String,
+    pub kind: ChangeKind,
+}
+
+/// Block of the base version that isn't in the document anymore
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RemovedBlock {
+    /// Key of the block of the document it stood before, `None` at the end of the document
+    pub before: Option<String>,
+    pub html: String,
+}
+
+/// Differences between the blocks of a document and those of its base version, sent to the
+/// renderer to highlight them (`git-changes.ts`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BlockChanges {
+    pub changed: Vec<ChangedBlock>,
+    pub removed: Vec<RemovedBlock>,
+    /// Number of places where the document changed; adjacent changed blocks count once
+    pub hunks: usize,
+}
+
+/// Compare the blocks of a document with those of its `base` version
+pub fn diff_blocks(base: &[Block], blocks: &[Block]) -> BlockChanges {
+    let base: Vec<&str> = content_blocks(base).collect();
+    let keys: Vec<String> = block_keys(blocks).into_iter().flatten().collect();
+    let current: Vec<&str> = content_blocks(blocks).collect();
+
+    let mut changes = BlockChanges::default();
+    let mut mark = |new_index: usize, len: usize, kind: ChangeKind| {
+        for key in &keys[new_index..new_index + len] {
+            changes.changed.push(ChangedBlock {
+                key: key.clone(),
+                kind,
+            });
+        }
+    };
+    let mut removed = Vec::new();
+    let mut remove = |old: std::ops::Range<usize>, new_index: usize| {
+        removed.extend(base[old].iter().map(|html| RemovedBlock {
+            before: keys.get(new_index).cloned(),
+            html: html.to_string(),
+        }));
+    };
+    let mut hunks = 0;
+    for op in capture_diff_slices(Algorithm::Myers, &base, &current) {
+        match op {
+            DiffOp::Equal { .. } => continue,
+            DiffOp::Insert {
+                new_index, new_len, ..
+            } => mark(new_index, new_len, ChangeKind::Added),
+            DiffOp::Replace {
+                old_index,
+                old_len,
+                new_index,
+                new_len,
+            } => {
+                mark(new_index, new_len, ChangeKind::Modified);
+                // Blocks replaced by fewer ones: the rest is gone
+                if old_len > new_len {
+                    remove(
+                        old_index + new_len..old_index + old_len,
+                        new_index + new_len,
+                    );
+                }
+            }
+            DiffOp::Delete {
+                old_index,
+                old_len,
+                new_index,
+            } => remove(old_index..old_index + old_len, new_index),
+        }
+        hunks += 1;
+    }
+    changes.removed = removed;
+    changes.hunks = hunks;
+    changes
+}
+
+/// HTML of the content blocks among `blocks`
+fn content_blocks(blocks: &[Block]) -> impl Iterator<Item = &str> {
+    blocks.iter().filter_map(|block| match block {
+        Block::Content(html) => Some(html.as_str()),
+        Block::Markup(_) => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn content(html: &str) -> Block {
+        Block::Content(html.to_string())
+    }
+
+    #[test]
+    fn test_diff_blocks() {
+        let base = [
+            Block::Markup("<section>".to_string()),
+            content("<h1>Title</h1>"),
+            content("<p>Kept</p>"),
+            content("<p>Old wording</p>"),
+            content("<p>Dropped</p>"),
+            content("<p>Last</p>"),
+            Block::Markup("</section>".to_string()),
+        ];
+        let blocks = [
+            Block::Markup("<section>".to_string()),
+            content("<h1>Title</h1>"),
+            content("<p>New</p>"),
+            content("<p>Kept</p>"),
+            content("<p>New wording</p>"),
+            content("<p>Last</p>"),
+            Block::Markup("</section>".to_string()),
+        ];
+        let keys: Vec<String> = block_keys(&blocks).into_iter().flatten().collect();
+
+        let changes = diff_blocks(&base, &blocks);
+
+        assert_eq!(
+            changes.changed,
+            vec![
+                ChangedBlock {
+                    key: keys[1].clone(),
+                    kind: ChangeKind::Added,
+                },
+                ChangedBlock {
+                    key: keys[3].clone(),
+                    kind: ChangeKind::Modified,
+                },
+            ]
+        );
+        // Two blocks replaced by one: the first is modified, the second removed
+        assert_eq!(
+            changes.removed,
+            vec![RemovedBlock {
+                before: Some(keys[4].clone()),
+                html: "<p>Dropped</p>".to_string(),
+            }]
+        );
+        assert_eq!(changes.hunks, 2);
+    }
+
+    #[test]
+    fn test_diff_blocks_removed() {
+        let base = [
+            content("<p>One</p>"),
+            content("<p>Two</p>"),
+            content("<p>Three</p>"),
+        ];
+        let blocks = [content("<p>One</p>")];
+
+        let changes = diff_blocks(&base, &blocks);
+
+        assert!(changes.changed.is_empty());
+        assert_eq!(
+            changes.removed,
+            vec![
+                RemovedBlock {
+                    before: None,
+                    html: "<p>Two</p>".to_string(),
+                },
+                RemovedBlock {
+                    before: None,
+                    html: "<p>Three</p>".to_string(),
+                },
+            ]
+        );
+        assert_eq!(changes.hunks, 1);
+
+        // A document new to the repository is all added
+        let changes = diff_blocks(&[], &base);
+        assert_eq!(changes.changed.len(), 3);
+        assert!(changes
+            .changed
+            .iter()
+            .all(|block| block.kind == ChangeKind::Added));
+    }
+}
@@ -1,5 +1,7 @@
 use std::path::{Path, PathBuf};
 
+use crate::utils::file::rebase_path;
+
 /// Manages navigation history for markdown files
 #[derive(Debug, Clone, PartialEq)]
 pub struct HistoryManager {
@@ -82,6 +84,15 @@ impl HistoryManager {
         None
     }
 
+    /// Update entries after `from` (a file or directory) was renamed to `to`
+    pub fn rename(&mut self, from: &Path, to: &Path) {
+        for path in &mut self.history {
+            if let Some(renamed) = rebase_path(path, from, to) {
+                *path = renamed;
+            }
+        }
+    }
+
     /// Get the history length
     #[cfg(test)]
     pub fn len(&self) -> usize {
@@ -200,4 +211,25 @@ mod tests {
 
         assert_eq!(manager.len(), 1);
     }
+
+    #[test]
+    fn test_rename() {
+        let mut manager = HistoryManager::new();
+        manager.push("/docs/guide/intro.md");
+        manager.push("/docs/guide/setup.md");
+        manager.push("/docs/other.md");
+
+        manager.rename(Path::new("/docs/guide"), Path::new("/docs/handbook"));
+        manager.rename(Path::new("/docs/other.md"), Path::new("/docs/faq.md"));
+
+        assert_eq!(manager.current(), Some(Path::new("/docs/faq.md")));
+        assert_eq!(
+            manager.go_back(),
+            Some(Path::new("/docs/handbook/setup.md"))
+        );
+        assert_eq!(
+            manager.go_back(),
+            Some(Path::new("/docs/handbook/intro.md"))
+        );
+    }
 }
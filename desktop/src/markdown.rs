@@ -1,14 +1,188 @@
 use anyhow::Result;
-use base64::{engine::general_purpose, Engine as _};
+use lol_html::html_content::ContentType;
 use lol_html::{element, HtmlRewriter, Settings};
-use pulldown_cmark::{html, CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
+mod autolink;
+mod blocks;
+mod include;
+mod outline;
+mod sanitize;
+
+pub use autolink::Repository;
+pub use blocks::{block_keys, Block, BlockCache};
+use include::Includes;
+pub use outline::Heading;
+pub use sanitize::{sanitize, HtmlPolicy};
+
+use crate::asset_protocol::{asset_url, file_mime_type, is_asset_url};
+use crate::config::{Project, CONFIG};
+use crate::csv_table::render_csv_to_html;
+use crate::source_view::language_for_path;
+use crate::utils::file::{is_asciidoc_file, is_notebook_file, is_rst_file, is_table_file};
+
+/// Options affecting how the rendered document resolves local paths
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+    /// Directories local paths are also resolved against, in order (project site root,
+    /// workspace roots); see [`resolve_local_path`]
+    pub link_roots: Vec<PathBuf>,
+    /// Repository `#123`, `owner/repo#123`, `@user` and commit SHA references are linked to;
+    /// see [`Repository::discover`]
+    pub repository: Option<Repository>,
+    /// Ids of the heading sections rendered folded; see [`Heading::id`]
+    pub folded_sections: HashSet<String>,
+    /// How raw HTML in the document is sanitized
+    pub html_policy: HtmlPolicy,
+}
+
+impl RenderOptions {
+    /// Options to render `file` with when it belongs to `project`, its links also resolving
+    /// against the `workspace_roots`; no section is folded
+    pub fn for_file(file: &Path, project: Option<&Project>, workspace_roots: &[PathBuf]) -> Self {
+        Self {
+            link_roots: project
+                .and_then(Project::site_root)
+                .into_iter()
+                .chain(workspace_roots.iter().cloned())
+                .collect(),
+            repository: file.parent().and_then(Repository::discover),
+            folded_sections: HashSet::new(),
+            html_policy: CONFIG.read().sanitization.policy_for(file),
+        }
+    }
+
+    /// Directories the images and media of `file` are served from: its own and the link roots
+    pub fn asset_directories<'a>(&'a self, file: &'a Path) -> impl Iterator<Item = &'a Path> {
+        file.parent()
+            .into_iter()
+            .chain(self.link_roots.iter().map(PathBuf::as_path))
+    }
+}
+
 /// Render Markdown to HTML
 pub fn render_to_html(markdown: impl AsRef<str>, base_path: impl AsRef<Path>) -> Result<String> {
-    let markdown = markdown.as_ref();
+    render_to_html_with_options(markdown, base_path, &RenderOptions::default())
+}
+
+/// Render Markdown to HTML with the given options
+pub fn render_to_html_with_options(
+    markdown: impl AsRef<str>,
+    base_path: impl AsRef<Path>,
+    render_options: &RenderOptions,
+) -> Result<String> {
+    render_document(markdown, base_path, render_options).map(|document| document.html)
+}
+
+/// Rendered HTML of a document along with the files included into it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RenderedDocument {
+    pub html: String,
+    /// Top-level blocks making up `html`, to patch the document with
+    pub blocks: Vec<Block>,
+    /// Files transcluded into the document (see [`include`]), to be watched for changes
+    pub includes: Vec<PathBuf>,
+    /// Top-level headings of the document, which also delimit its collapsible sections
+    pub outline: Vec<Heading>,
+    /// Raw HTML removed by the sanitizer (`<script>`, `onerror attribute`, …)
+    pub stripped: Vec<String>,
+}
+
+impl From<String> for RenderedDocument {
+    fn from(html: String) -> Self {
+        Self {
+            blocks: vec![Block::Content(html.clone())],
+            html,
+            ..Self::default()
+        }
+    }
+}
+
+/// Render Markdown to sanitized HTML, expanding includes relative to `base_path`
+pub fn render_document(
+    markdown: impl AsRef<str>,
+    base_path: impl AsRef<Path>,
+    render_options: &RenderOptions,
+) -> Result<RenderedDocument> {
+    render_document_with_cache(
+        markdown,
+        base_path,
+        render_options,
+        &mut BlockCache::default(),
+    )
+}
+
+/// Render Markdown like [`render_document`], only processing the blocks that changed since
+/// the previous render with `cache`
+pub fn render_document_with_cache(
+    markdown: impl AsRef<str>,
+    base_path: impl AsRef<Path>,
+    render_options: &RenderOptions,
+    cache: &mut BlockCache,
+) -> Result<RenderedDocument> {
     let base_path = base_path.as_ref();
+    let mut includes = Includes::new(base_path);
+    let ((blocks, stripped), outline) = process_markdown(
+        markdown.as_ref(),
+        base_path,
+        render_options,
+        &mut includes,
+        |events, base_dir| {
+            let roots = &render_options.link_roots;
+            let policy = render_options.html_policy;
+            let fingerprint = blocks::hash(&(base_dir, roots, policy));
+            cache.render(events, fingerprint, |html| {
+                let html = post_process_html_tags(html, base_dir, roots);
+                sanitize::sanitize(&html, policy)
+            })
+        },
+    );
+
+    Ok(RenderedDocument {
+        html: blocks.iter().map(Block::html).collect(),
+        blocks,
+        includes: includes.into_files(),
+        outline,
+        stripped,
+    })
+}
 
+/// Render a document or an included Markdown file, returning the HTML and heading tree
+fn render_markdown(
+    markdown: &str,
+    base_path: &Path,
+    render_options: &RenderOptions,
+    includes: &mut Includes,
+) -> (String, Vec<Heading>) {
+    process_markdown(
+        markdown,
+        base_path,
+        render_options,
+        includes,
+        |events, base_dir| {
+            // Convert to HTML
+            let mut html_output = String::new();
+            html::push_html(&mut html_output, events.into_iter());
+
+            // Post-process HTML to handle all img and anchor tags (both from Markdown syntax and
+            // HTML tags)
+            post_process_html_tags(&html_output, base_dir, &render_options.link_roots)
+        },
+    )
+}
+
+/// Parse and process a document or an included Markdown file, converting the resulting events
+/// with `render` (given the directory relative paths are resolved against), and return the
+/// result with the heading tree
+fn process_markdown<T>(
+    markdown: &str,
+    base_path: &Path,
+    render_options: &RenderOptions,
+    includes: &mut Includes,
+    render: impl FnOnce(Vec<Event>, &Path) -> T,
+) -> (T, Vec<Heading>) {
     // Enable GitHub Flavored Markdown options
     let options = Options::all();
 
@@ -23,18 +197,21 @@ pub fn render_to_html(markdown: impl AsRef<str>, base_path: impl AsRef<Path>) ->
 
     // Parse Markdown and process blocks
     let parser = Parser::new_ext(&processed_markdown, options);
-    let parser = process_code_blocks(parser, "mermaid");
+    let events = includes.expand(parser, &base_dir, render_options);
+    // Sections of included files would clash with the including document's
+    let (events, outline) = if includes.is_fragment() {
+        (events, Vec::new())
+    } else {
+        outline::process_sections(events, &render_options.folded_sections)
+    };
+    let parser = process_code_blocks(events.into_iter(), "mermaid");
     let parser = process_code_blocks(parser, "math");
+    let parser = process_csv_blocks(parser);
     let parser = process_math_expressions(parser);
+    let parser = process_emoji_shortcodes(parser);
+    let parser = process_references(parser, render_options.repository.as_ref());
 
-    // Convert to HTML
-    let mut html_output = String::new();
-    html::push_html(&mut html_output, parser);
-
-    // Post-process HTML to handle all img and anchor tags (both from Markdown syntax and HTML tags)
-    let html_output = post_process_html_tags(&html_output, base_dir.as_path());
-
-    Ok(html_output)
+    (render(parser.collect(), &base_dir), outline)
 }
 
 /// Get SVG icon placeholder for alert type (actual SVG injected by JavaScript)
@@ -175,6 +352,46 @@ fn process_code_blocks<'a>(
     })
 }
 
+/// Render fenced ```csv and ```tsv blocks as tables
+fn process_csv_blocks<'a>(
+    parser: impl Iterator<Item = Event<'a>>,
+) -> impl Iterator<Item = Event<'a>> {
+    let mut in_block = false;
+    let mut delimiter = None;
+    let mut content = String::new();
+
+    parser.flat_map(move |event| match event {
+        Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(ref lang)))
+            if matches!(lang.as_ref(), "csv" | "tsv") =>
+        {
+            in_block = true;
+            // CSV delimiters are detected, so `;`-separated data works too
+            delimiter = (lang.as_ref() == "tsv").then_some(b'\t');
+            content.clear();
+            vec![]
+        }
+        Event::End(TagEnd::CodeBlock) if in_block => {
+            in_block = false;
+            match render_csv_to_html(&content, delimiter) {
+                Ok(html) => vec![Event::Html(html.into())],
+                // Show malformed data as a regular code block
+                Err(_) => vec![Event::Html(
+                    format!(
+                        r#"<pre><code class="language-csv">{}</code></pre>"#,
+                        html_escape::encode_text(&content)
+                    )
+                    .into(),
+                )],
+            }
+        }
+        Event::Text(text) if in_block => {
+            content.push_str(&text);
+            vec![]
+        }
+        _ => vec![event],
+    })
+}
+
 /// Process math expressions (inline and display)
 fn process_math_expressions<'a>(
     parser: impl Iterator<Item = Event<'a>>,
@@ -202,46 +419,195 @@ fn process_math_expressions<'a>(
     })
 }
 
-/// Infer MIME type from file extension
-fn get_mime_type(path: &Path) -> &'static str {
-    match path.extension().and_then(|e| e.to_str()) {
-        Some("png") => "image/png",
-        Some("jpg") | Some("jpeg") => "image/jpeg",
-        Some("gif") => "image/gif",
-        Some("svg") => "image/svg+xml",
-        Some("webp") => "image/webp",
-        Some("bmp") => "image/bmp",
-        Some("ico") => "image/x-icon",
-        _ => "image/png", // Default
+/// Replace emoji shortcodes (`:tada:`) with the emoji, like GitHub
+fn process_emoji_shortcodes<'a>(
+    parser: impl Iterator<Item = Event<'a>>,
+) -> impl Iterator<Item = Event<'a>> {
+    let mut in_code_block = false;
+
+    parser.map(move |event| match event {
+        Event::Start(Tag::CodeBlock(_)) => {
+            in_code_block = true;
+            event
+        }
+        Event::End(TagEnd::CodeBlock) => {
+            in_code_block = false;
+            event
+        }
+        Event::Text(text) if !in_code_block => Event::Text(replace_emoji_shortcodes(text)),
+        _ => event,
+    })
+}
+
+fn replace_emoji_shortcodes(text: CowStr<'_>) -> CowStr<'_> {
+    if !text.contains(':') {
+        return text;
+    }
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text.as_ref();
+    while let Some(start) = rest.find(':') {
+        let after = &rest[start + 1..];
+        let emoji = after.find(':').and_then(|end| {
+            let name = &after[..end];
+            let is_shortcode = !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-'));
+            is_shortcode
+                .then(|| emojis::get_by_shortcode(name))
+                .flatten()
+                .map(|emoji| (name, emoji))
+        });
+        match emoji {
+            Some((name, emoji)) => {
+                output.push_str(&rest[..start]);
+                output.push_str(emoji.as_str());
+                rest = &after[name.len() + 1..];
+            }
+            None => {
+                output.push_str(&rest[..=start]);
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+    output.into()
+}
+
+/// Link issue/PR references, mentions and commit SHAs in text to the document's repository
+fn process_references<'a>(
+    parser: impl Iterator<Item = Event<'a>>,
+    repository: Option<&'a Repository>,
+) -> impl Iterator<Item = Event<'a>> {
+    // Nesting of links, images, code blocks and front matter, whose text is left alone
+    let mut skip_depth = 0usize;
+
+    parser.map(move |event| {
+        let Some(repository) = repository else {
+            return event;
+        };
+        match event {
+            Event::Start(
+                Tag::Link { .. } | Tag::Image { .. } | Tag::CodeBlock(_) | Tag::MetadataBlock(_),
+            ) => {
+                skip_depth += 1;
+                event
+            }
+            Event::End(
+                TagEnd::Link | TagEnd::Image | TagEnd::CodeBlock | TagEnd::MetadataBlock(_),
+            ) => {
+                skip_depth = skip_depth.saturating_sub(1);
+                event
+            }
+            Event::Text(ref text) if skip_depth == 0 => {
+                match autolink::link_references(text, repository) {
+                    Some(html) => Event::InlineHtml(html.into()),
+                    None => event,
+                }
+            }
+            _ => event,
+        }
+    })
+}
+
+/// Existing local file referenced by an image or media `src`, resolved like links
+fn local_asset(src: Option<String>, base_dir: &Path, roots: &[PathBuf]) -> Option<PathBuf> {
+    let src = src?;
+    let is_url = src
+        .split_once(':')
+        .is_some_and(|(scheme, _)| scheme.len() > 1 && !scheme.contains(['/', '.']));
+    if src.is_empty() || is_url || src.starts_with("//") || is_asset_url(&src) {
+        return None;
     }
+    // Query strings and fragments (`clip.mp4#t=10`) aren't part of the file name
+    let path = src.split(['?', '#']).next().unwrap_or_default();
+    let path = percent_encoding::percent_decode_str(path)
+        .decode_utf8()
+        .ok()?;
+    resolve_local_path(&path, base_dir, roots)
+        .canonicalize()
+        .ok()
+        .filter(|path| path.is_file())
+}
+
+/// Resolve a local link or image path against the document directory and `roots`
+///
+/// Root-relative paths (`/api/index.md`) are looked up in each root, then on the filesystem;
+/// relative paths next to the document, then in each root. The first existing candidate wins;
+/// if none exists, the preferred candidate is returned.
+pub fn resolve_local_path(path: &str, base_dir: &Path, roots: &[PathBuf]) -> PathBuf {
+    let candidates: Vec<PathBuf> = match path.strip_prefix('/') {
+        Some(relative) => roots
+            .iter()
+            .map(|root| root.join(relative))
+            .chain([base_dir.join(path)])
+            .collect(),
+        None => std::iter::once(base_dir.join(path))
+            .chain(roots.iter().map(|root| root.join(path)))
+            .collect(),
+    };
+    candidates
+        .iter()
+        .find(|candidate| candidate.exists())
+        .unwrap_or(&candidates[0])
+        .clone()
+}
+
+/// Whether the file viewer renders a linked file (other document formats, notebooks,
+/// tables, source code)
+fn is_viewable_file(path: &str) -> bool {
+    is_asciidoc_file(path)
+        || is_rst_file(path)
+        || is_notebook_file(path)
+        || is_table_file(path)
+        || language_for_path(path).is_some()
 }
 
 /// Post-process HTML to handle img and anchor tags using lol_html
-fn post_process_html_tags(html_str: &str, base_dir: &Path) -> String {
-    let base_dir = base_dir.to_path_buf();
+fn post_process_html_tags(html_str: &str, base_dir: &Path, roots: &[PathBuf]) -> String {
     let mut output = Vec::new();
 
     let mut rewriter = HtmlRewriter::new(
         Settings {
             element_content_handlers: vec![
-                // Process img tags: convert relative paths to data URLs
-                element!("img[src]", move |el| {
-                    if let Some(src) = el.get_attribute("src") {
-                        if !src.starts_with("http://")
-                            && !src.starts_with("https://")
-                            && !src.starts_with("data:")
-                        {
-                            let absolute_path = base_dir.join(&src);
-                            if let Ok(canonical_path) = absolute_path.canonicalize() {
-                                if let Ok(image_data) = std::fs::read(&canonical_path) {
-                                    let mime_type = get_mime_type(&canonical_path);
-                                    let base64_data = general_purpose::STANDARD.encode(&image_data);
-                                    let data_url =
-                                        format!("data:{};base64,{}", mime_type, base64_data);
-                                    el.set_attribute("src", &data_url)?;
-                                }
-                            }
-                        }
+                // Process img tags: serve local images through the asset protocol, and turn
+                // local video and audio embedded with image syntax into players
+                element!("img[src]", |el| {
+                    let Some(path) = local_asset(el.get_attribute("src"), base_dir, roots) else {
+                        return Ok(());
+                    };
+                    let url = asset_url(&path);
+                    let mime_type = file_mime_type(&path).unwrap_or_default();
+                    let player = ["video", "audio"]
+                        .into_iter()
+                        .find(|kind| mime_type.starts_with(&format!("{kind}/")));
+                    if let Some(player) = player {
+                        let title = el.get_attribute("alt").unwrap_or_default();
+                        el.replace(
+                            &format!(
+                                r#"<{player} src="{}" title="{}" controls preload="metadata"></{player}>"#,
+                                html_escape::encode_double_quoted_attribute(&url),
+                                html_escape::encode_double_quoted_attribute(&title),
+                            ),
+                            ContentType::Html,
+                        );
+                    } else {
+                        el.set_attribute("src", &url)?;
+                        el.set_attribute("loading", "lazy")?;
+                        el.set_attribute("decoding", "async")?;
+                    }
+                    Ok(())
+                }),
+                // Process media sources written in raw HTML
+                element!("video[src], audio[src], source[src], track[src]", |el| {
+                    if let Some(path) = local_asset(el.get_attribute("src"), base_dir, roots) {
+                        el.set_attribute("src", &asset_url(&path))?;
+                    }
+                    Ok(())
+                }),
+                element!("video[poster]", |el| {
+                    if let Some(path) = local_asset(el.get_attribute("poster"), base_dir, roots) {
+                        el.set_attribute("poster", &asset_url(&path))?;
                     }
                     Ok(())
                 }),
@@ -249,26 +615,22 @@ fn post_process_html_tags(html_str: &str, base_dir: &Path) -> String {
                 element!("a[href]", |el| {
                     if let Some(href) = el.get_attribute("href") {
                         if !href.starts_with("http://") && !href.starts_with("https://") {
-                            if let Some(ext) = std::path::Path::new(&href)
+                            // Fragments (`#L10-L20`, `#section`) are handled after opening
+                            let target = href.split('#').next().unwrap_or_default();
+                            if let Some(ext) = std::path::Path::new(target)
                                 .extension()
                                 .and_then(|e| e.to_str())
                             {
-                                // Replace with span element
-                                let escaped_href = href.replace('\'', "\\'");
-                                let onclick = indoc::formatdoc! {r#"
-                                        if (event.button === 0 || event.button === 1) {{
-                                            event.preventDefault();
-                                            window.handleMarkdownLinkClick('{escaped_href}', event.button); 
-                                        }}"#
-                                };
+                                // Replace with span element; clicks are handled by the
+                                // renderer (`markdown-links.ts`)
                                 el.set_tag_name("span")?;
                                 el.remove_attribute("href");
-                                if ext != "md" && ext != "markdown" {
+                                if ext != "md" && ext != "markdown" && !is_viewable_file(target) {
                                     el.set_attribute("class", "md-link md-link-invalid")?;
                                 } else {
                                     el.set_attribute("class", "md-link")?;
                                 }
-                                el.set_attribute("onmousedown", &onclick)?;
+                                el.set_attribute("data-href", &href)?;
                             }
                         }
                     }
@@ -294,19 +656,6 @@ mod tests {
     use std::fs;
     use tempfile::TempDir;
 
-    #[test]
-    fn test_get_mime_type() {
-        assert_eq!(get_mime_type(Path::new("test.png")), "image/png");
-        assert_eq!(get_mime_type(Path::new("test.jpg")), "image/jpeg");
-        assert_eq!(get_mime_type(Path::new("test.jpeg")), "image/jpeg");
-        assert_eq!(get_mime_type(Path::new("test.gif")), "image/gif");
-        assert_eq!(get_mime_type(Path::new("test.svg")), "image/svg+xml");
-        assert_eq!(get_mime_type(Path::new("test.webp")), "image/webp");
-        assert_eq!(get_mime_type(Path::new("test.bmp")), "image/bmp");
-        assert_eq!(get_mime_type(Path::new("test.ico")), "image/x-icon");
-        assert_eq!(get_mime_type(Path::new("test.unknown")), "image/png");
-    }
-
     #[test]
     fn test_get_alert_icon_placeholder() {
         let result = get_alert_icon_placeholder("note");
@@ -443,39 +792,159 @@ mod tests {
         fs::write(&image_path, png_data).unwrap();
 
         let html = r#"<p><img src="test.png" alt="test" /></p>"#;
-        let result = post_process_html_tags(html, temp_dir.path());
+        let result = post_process_html_tags(html, temp_dir.path(), &[]);
 
+        let url = asset_url(&image_path.canonicalize().unwrap());
         assert!(
-            result.contains("data:image/png;base64,"),
-            "Should convert img src to data URL"
+            result.contains(&format!(r#"src="{url}""#)),
+            "Should serve img src through the asset protocol: {result}"
         );
+        assert!(result.contains(r#"loading="lazy""#));
         assert!(
             !result.contains(r#"src="test.png""#),
             "Should not contain original path"
         );
     }
 
+    #[test]
+    fn test_post_process_html_tags_img_site_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let site_root = temp_dir.path().join("site");
+        fs::create_dir_all(site_root.join("images")).unwrap();
+        fs::write(site_root.join("images/logo.png"), [0x89, 0x50, 0x4E, 0x47]).unwrap();
+
+        let html = r#"<p><img src="/images/logo.png" alt="logo" /></p>"#;
+        let result =
+            post_process_html_tags(html, temp_dir.path(), std::slice::from_ref(&site_root));
+
+        let url = asset_url(&site_root.join("images/logo.png").canonicalize().unwrap());
+        assert!(
+            result.contains(&format!(r#"src="{url}""#)),
+            "Should resolve root-relative src against the site root"
+        );
+    }
+
+    #[test]
+    fn test_post_process_html_tags_media() {
+        let temp_dir = TempDir::new().unwrap();
+        // WebM (EBML) header, whatever the extension says
+        fs::write(
+            temp_dir.path().join("demo clip.png"),
+            [0x1A, 0x45, 0xDF, 0xA3],
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("poster.png"), [0x89, 0x50, 0x4E, 0x47]).unwrap();
+        let url = |name: &str| asset_url(&temp_dir.path().join(name).canonicalize().unwrap());
+
+        let html = r#"<p><img src="demo%20clip.png" alt="Demo" /></p>"#;
+        let result = post_process_html_tags(html, temp_dir.path(), &[]);
+        assert_eq!(
+            result,
+            format!(
+                r#"<p><video src="{}" title="Demo" controls preload="metadata"></video></p>"#,
+                url("demo clip.png")
+            )
+        );
+
+        let html = r#"<video poster="poster.png"><source src="demo clip.png#t=5"></video>"#;
+        let result = post_process_html_tags(html, temp_dir.path(), &[]);
+        assert_eq!(
+            result,
+            format!(
+                r#"<video poster="{}"><source src="{}"></video>"#,
+                url("poster.png"),
+                url("demo clip.png")
+            )
+        );
+
+        // Missing files and URLs are left alone
+        let html = r#"<img src="missing.png"><img src="https://example.com/a.png">"#;
+        assert_eq!(post_process_html_tags(html, temp_dir.path(), &[]), html);
+    }
+
+    #[test]
+    fn test_resolve_local_path() {
+        let base_dir = Path::new("/repo/docs/guide");
+        let site_root = PathBuf::from("/repo/docs");
+
+        assert_eq!(
+            resolve_local_path("intro.md", base_dir, std::slice::from_ref(&site_root)),
+            PathBuf::from("/repo/docs/guide/intro.md")
+        );
+        assert_eq!(
+            resolve_local_path("/api/index.md", base_dir, &[site_root]),
+            PathBuf::from("/repo/docs/api/index.md")
+        );
+        assert_eq!(
+            resolve_local_path("/api/index.md", base_dir, &[]),
+            PathBuf::from("/api/index.md")
+        );
+    }
+
+    #[test]
+    fn test_resolve_local_path_across_roots() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.path().join("repo");
+        let handbook = temp_dir.path().join("handbook");
+        fs::create_dir_all(repo.join("guide")).unwrap();
+        fs::create_dir_all(handbook.join("style")).unwrap();
+        fs::write(repo.join("guide/intro.md"), "").unwrap();
+        fs::write(handbook.join("style/naming.md"), "").unwrap();
+        let roots = [repo.clone(), handbook.clone()];
+        let base_dir = repo.join("guide");
+
+        // Existing files next to the document win
+        assert_eq!(
+            resolve_local_path("intro.md", &base_dir, &roots),
+            repo.join("guide/intro.md")
+        );
+        // Otherwise relative and root-relative paths are looked up in every root
+        assert_eq!(
+            resolve_local_path("style/naming.md", &base_dir, &roots),
+            handbook.join("style/naming.md")
+        );
+        assert_eq!(
+            resolve_local_path("/style/naming.md", &base_dir, &roots),
+            handbook.join("style/naming.md")
+        );
+        // Missing files resolve to the preferred candidate
+        assert_eq!(
+            resolve_local_path("missing.md", &base_dir, &roots),
+            base_dir.join("missing.md")
+        );
+    }
+
     #[test]
     fn test_post_process_html_tags_anchor() {
         let html = r#"<a href="doc.md">Link</a>"#;
-        let result = post_process_html_tags(html, Path::new("."));
+        let result = post_process_html_tags(html, Path::new("."), &[]);
 
         assert!(
             result.contains(r#"<span class="md-link""#),
             "Should convert to span"
         );
         assert!(
-            result.contains("handleMarkdownLinkClick"),
-            "Should add click handler"
+            result.contains(r#"data-href="doc.md""#),
+            "Should keep the link target for the click handler"
         );
         assert!(!result.contains("<a "), "Should not contain anchor tag");
     }
 
+    #[test]
+    fn test_post_process_html_tags_source_file_with_line_range() {
+        let html = r#"<a href="src/main.rs#L10-L20">main</a><a href="guide.md#setup">Setup</a>"#;
+        let result = post_process_html_tags(html, Path::new("."), &[]);
+
+        assert!(!result.contains("md-link-invalid"));
+        assert!(result.contains(r#"data-href="src/main.rs#L10-L20""#));
+        assert!(result.contains(r#"data-href="guide.md#setup""#));
+    }
+
     #[test]
     fn test_post_process_html_tags_http_urls() {
         let html =
             r#"<img src="https://example.com/image.png" /><a href="https://example.com">Link</a>"#;
-        let result = post_process_html_tags(html, Path::new("."));
+        let result = post_process_html_tags(html, Path::new("."), &[]);
 
         assert!(
             result.contains(r#"src="https://example.com/image.png""#),
@@ -490,15 +959,15 @@ mod tests {
     #[test]
     fn test_post_process_html_tags_non_md_local_file() {
         let html = r#"<a href="file.txt">Text File</a>"#;
-        let result = post_process_html_tags(html, Path::new("."));
+        let result = post_process_html_tags(html, Path::new("."), &[]);
 
         assert!(
             result.contains(r#"<span class="md-link md-link-invalid""#),
             "Should convert to span with md-link and md-link-invalid class"
         );
         assert!(
-            result.contains("handleMarkdownLinkClick"),
-            "Should add click handler for local files"
+            result.contains("data-href="),
+            "Should keep the link target for local files"
         );
         assert!(!result.contains("<a "), "Should not contain anchor tag");
     }
@@ -506,7 +975,7 @@ mod tests {
     #[test]
     fn test_post_process_html_tags_md_vs_other_files() {
         let html = r#"<a href="doc.md">MD</a><a href="file.txt">TXT</a>"#;
-        let result = post_process_html_tags(html, Path::new("."));
+        let result = post_process_html_tags(html, Path::new("."), &[]);
 
         // MD file should have only md-link class
         assert!(
@@ -521,7 +990,7 @@ mod tests {
         );
 
         // Both should have click handlers
-        let click_handler_count = result.matches("handleMarkdownLinkClick").count();
+        let click_handler_count = result.matches("data-href=").count();
         assert_eq!(
             click_handler_count, 2,
             "Should have click handlers for both links"
@@ -731,6 +1200,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_render_to_html_with_emoji_shortcodes() {
+        let markdown = "Done :white_check_mark: :+1: at 10:30:00 :not_an_emoji:\n\n`:tada:`\n";
+        let html = render_to_html(markdown, Path::new("test.md")).unwrap();
+
+        assert!(html.contains("Done ✅ 👍 at 10:30:00 :not_an_emoji:"));
+        assert!(html.contains("<code>:tada:</code>"));
+    }
+
+    #[test]
+    fn test_render_to_html_with_references() {
+        let markdown = indoc! {"
+            Fixed in #42 by @octocat, see [#7](https://example.com) and `#8`.
+
+            ```
+            #9
+            ```
+        "};
+        let options = RenderOptions {
+            repository: Repository::from_remote_url("https://github.com/owner/repo.git"),
+            ..RenderOptions::default()
+        };
+
+        let html = render_to_html_with_options(markdown, Path::new("test.md"), &options).unwrap();
+
+        assert!(html.contains(
+            r#"<a href="https://github.com/owner/repo/issues/42" class="issue-link">#42</a>"#
+        ));
+        assert!(html.contains(r#"<a href="https://github.com/octocat" class="user-mention">"#));
+        assert!(html.contains(r#"<a href="https://example.com">#7</a>"#));
+        assert!(html.contains("<code>#8</code>"));
+        assert!(html.contains("<pre><code>#9\n</code></pre>"));
+
+        // Without a repository, references stay text
+        let html = render_to_html(markdown, Path::new("test.md")).unwrap();
+        assert!(!html.contains("issue-link"));
+    }
+
+    #[test]
+    fn test_render_to_html_with_csv_block() {
+        let markdown = indoc! {"
+            ```csv
+            name;qty
+            apple;3
+            ```
+
+            ```rust
+            let a = 1;
+            ```
+        "};
+        let result = render_to_html(markdown, Path::new("test.md")).unwrap();
+
+        assert!(result.contains(r#"<div class="csv-table">"#));
+        assert!(result.contains(r#""header":["name","qty"]"#));
+        assert!(result.contains(r#"<code class="language-rust">"#));
+    }
+
     #[test]
     fn test_render_to_html_with_math() {
         let markdown = indoc! {"
@@ -800,8 +1326,10 @@ mod tests {
             "Should render alert"
         );
         assert!(
-            result.contains("data:image/png"),
-            "Should convert image to data URL"
+            result.contains(&asset_url(
+                &temp_dir.path().join("image.png").canonicalize().unwrap()
+            )),
+            "Should serve image through the asset protocol"
         );
         assert!(
             result.contains(r#"class="md-link""#),
@@ -0,0 +1,379 @@
+//! Offline autolinking of GitHub-style references
+//!
+//! Like GitHub, `#123`, `owner/repo#123`, `@user` and commit SHAs in text are linked to the
+//! web pages of the repository the document lives in. The repository is taken from the
+//! `origin` remote (or the first remote) in the nearest `.git/config`; nothing is fetched, so
+//! references are linked whether or not the issue, user or commit exists.
+
+use std::path::{Path, PathBuf};
+
+/// Web location of the repository a document belongs to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repository {
+    /// Scheme and host, e.g. `https://github.com`
+    host_url: String,
+    /// Path of the repository on the host, e.g. `owner/repo`
+    slug: String,
+}
+
+impl Repository {
+    /// Find the repository containing `dir` and read its remote from `.git/config`
+    pub fn discover(dir: &Path) -> Option<Self> {
+        let git_dir = dir.ancestors().find_map(git_dir_of)?;
+        let config = std::fs::read_to_string(git_dir.join("config")).ok()?;
+        Self::from_remote_url(&remote_url(&config)?)
+    }
+
+    /// Parse a remote URL: `git@host:owner/repo.git`, `ssh://git@host:22/owner/repo.git`,
+    /// `https://host/owner/repo` and the like
+    pub fn from_remote_url(url: &str) -> Option<Self> {
+        let (host, path) = match url.split_once("://") {
+            Some((_, rest)) => rest.split_once('/')?,
+            // scp-like syntax; anything else without a scheme is a local path
+            None => url
+                .split_once(':')
+                .filter(|(host, _)| !host.contains('/'))?,
+        };
+        // Drop the user and port
+        let host = host.rsplit('@').next()?;
+        let host = host.split(':').next()?;
+        let slug = path.trim_matches('/');
+        let slug = slug.strip_suffix(".git").unwrap_or(slug);
+        if host.is_empty() || !slug.contains('/') {
+            return None;
+        }
+        Some(Self {
+            host_url: format!("https://{host}"),
+            slug: slug.to_string(),
+        })
+    }
+
+    /// GitLab puts repository pages below `/-/`
+    fn page_prefix(&self) -> &'static str {
+        if self.host_url.contains("gitlab") {
+            "/-"
+        } else {
+            ""
+        }
+    }
+
+    fn issue_url(&self, slug: &str, number: &str) -> String {
+        format!(
+            "{}/{slug}{}/issues/{number}",
+            self.host_url,
+            self.page_prefix()
+        )
+    }
+
+    fn commit_url(&self, sha: &str) -> String {
+        format!(
+            "{}/{}{}/commit/{sha}",
+            self.host_url,
+            self.slug,
+            self.page_prefix()
+        )
+    }
+
+    fn user_url(&self, user: &str) -> String {
+        format!("{}/{user}", self.host_url)
+    }
+}
+
+/// The git directory of a work tree root: `.git`, or where a `.git` file points to
+/// (worktrees and submodules)
+fn git_dir_of(dir: &Path) -> Option<PathBuf> {
+    let dot_git = dir.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+    let pointer = std::fs::read_to_string(&dot_git).ok()?;
+    let git_dir = dir.join(pointer.trim().strip_prefix("gitdir:")?.trim());
+    // Linked worktrees share the main repository's config
+    match std::fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common_dir) => Some(git_dir.join(common_dir.trim())),
+        Err(_) => Some(git_dir),
+    }
+}
+
+/// URL of the `origin` remote, or of the first remote with a URL
+fn remote_url(config: &str) -> Option<String> {
+    let mut remote = None;
+    let mut urls = Vec::new();
+    for line in config.lines().map(str::trim) {
+        if let Some(section) = line.strip_prefix('[') {
+            remote = section
+                .strip_prefix("remote")
+                .and_then(|name| name.trim().strip_suffix(']'))
+                .map(|name| name.trim().trim_matches('"').to_string());
+        } else if let (Some(name), Some((key, value))) = (&remote, line.split_once('=')) {
+            if key.trim() == "url" {
+                urls.push((name.clone(), value.trim().to_string()));
+            }
+        }
+    }
+    let origin = urls.iter().position(|(name, _)| name == "origin");
+    let (_, url) = urls.into_iter().nth(origin.unwrap_or(0))?;
+    Some(url)
+}
+
+/// Characters that can't directly precede or follow a reference
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Whether a reference may start after `previous`; excludes the middle of words, paths,
+/// URLs and email addresses
+fn is_boundary(previous: Option<char>) -> bool {
+    previous.is_none_or(|c| !is_word_char(c) && !matches!(c, '/' | '.' | '-' | '@' | ':' | '#'))
+}
+
+/// A reference found at the start of some text
+struct Reference {
+    len: usize,
+    url: String,
+    class: &'static str,
+    label: String,
+}
+
+/// Render `text` as HTML with references linked, or `None` if it has none
+pub(super) fn link_references(text: &str, repository: &Repository) -> Option<String> {
+    let mut html = String::new();
+    let mut copied = 0;
+    let mut previous = None;
+    let mut index = 0;
+
+    while let Some(c) = text[index..].chars().next() {
+        if is_boundary(previous) {
+            if let Some(reference) = match_reference(&text[index..], repository) {
+                html.push_str(&html_escape::encode_text(&text[copied..index]));
+                html.push_str(&format!(
+                    r#"<a href="{}" class="{}">{}</a>"#,
+                    html_escape::encode_double_quoted_attribute(&reference.url),
+                    reference.class,
+                    reference.label
+                ));
+                index += reference.len;
+                copied = index;
+                previous = text[..index].chars().next_back();
+                continue;
+            }
+        }
+        previous = Some(c);
+        index += c.len_utf8();
+    }
+
+    if copied == 0 {
+        return None;
+    }
+    html.push_str(&html_escape::encode_text(&text[copied..]));
+    Some(html)
+}
+
+fn match_reference(text: &str, repository: &Repository) -> Option<Reference> {
+    let reference = if let Some(rest) = text.strip_prefix('@') {
+        let len = user_len(rest)?;
+        let user = &rest[..len];
+        Reference {
+            len: len + 1,
+            url: repository.user_url(user),
+            class: "user-mention",
+            label: format!("@{user}"),
+        }
+    } else if let Some(rest) = text.strip_prefix('#') {
+        let len = digits_len(rest)?;
+        let number = &rest[..len];
+        Reference {
+            len: len + 1,
+            url: repository.issue_url(&repository.slug, number),
+            class: "issue-link",
+            label: format!("#{number}"),
+        }
+    } else if let Some(reference) = match_cross_repository_issue(text, repository) {
+        reference
+    } else {
+        let len = sha_len(text)?;
+        let sha = &text[..len];
+        Reference {
+            len,
+            url: repository.commit_url(sha),
+            class: "commit-link",
+            label: format!("<code>{}</code>", &sha[..7]),
+        }
+    };
+
+    // References must end at a word boundary; `@user/team` mentions aren't supported
+    let next = text[reference.len..].chars().next();
+    next.is_none_or(|c| !is_word_char(c) && c != '/')
+        .then_some(reference)
+}
+
+/// `owner/repo#123`
+fn match_cross_repository_issue(text: &str, repository: &Repository) -> Option<Reference> {
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.');
+    let owner_len = text.find(|c| !is_name_char(c))?;
+    let rest = text[owner_len..].strip_prefix('/')?;
+    let name_len = rest.find(|c| !is_name_char(c))?;
+    let number = rest[name_len..].strip_prefix('#')?;
+    let number = &number[..digits_len(number)?];
+    if owner_len == 0 || name_len == 0 {
+        return None;
+    }
+    let slug = &text[..owner_len + 1 + name_len];
+    Some(Reference {
+        len: slug.len() + 1 + number.len(),
+        url: repository.issue_url(slug, number),
+        class: "issue-link",
+        label: format!("{}#{number}", html_escape::encode_text(slug)),
+    })
+}
+
+/// Length of a user name: alphanumerics and single inner hyphens, at most 39 characters
+fn user_len(text: &str) -> Option<usize> {
+    let len = text
+        .find(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+        .unwrap_or(text.len());
+    let user = &text[..len];
+    let valid = (1..=39).contains(&len)
+        && !user.starts_with('-')
+        && !user.ends_with('-')
+        && !user.contains("--");
+    valid.then_some(len)
+}
+
+fn digits_len(text: &str) -> Option<usize> {
+    let len = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    (len > 0).then_some(len)
+}
+
+/// Length of an abbreviated or full commit SHA: 7 to 40 lowercase hex digits, with at least
+/// one digit and one letter so plain numbers and words like `deadbeef` aren't linked
+fn sha_len(text: &str) -> Option<usize> {
+    let len = text
+        .find(|c: char| !matches!(c, '0'..='9' | 'a'..='f'))
+        .unwrap_or(text.len());
+    let sha = &text[..len];
+    let valid = (7..=40).contains(&len)
+        && sha.contains(|c: char| c.is_ascii_digit())
+        && sha.contains(|c: char| c.is_ascii_alphabetic());
+    valid.then_some(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn repository() -> Repository {
+        Repository::from_remote_url("git@github.com:owner/repo.git").unwrap()
+    }
+
+    #[test]
+    fn test_from_remote_url() {
+        let expected = Some(Repository {
+            host_url: "https://github.com".to_string(),
+            slug: "owner/repo".to_string(),
+        });
+        assert_eq!(
+            Repository::from_remote_url("git@github.com:owner/repo.git"),
+            expected
+        );
+        assert_eq!(
+            Repository::from_remote_url("https://github.com/owner/repo"),
+            expected
+        );
+        assert_eq!(
+            Repository::from_remote_url("ssh://git@github.com:22/owner/repo.git/"),
+            expected
+        );
+        assert_eq!(
+            Repository::from_remote_url("https://token@gitlab.com/group/sub/project.git"),
+            Some(Repository {
+                host_url: "https://gitlab.com".to_string(),
+                slug: "group/sub/project".to_string(),
+            })
+        );
+        assert_eq!(Repository::from_remote_url("/srv/git/repo.git"), None);
+        assert_eq!(Repository::from_remote_url("../repo"), None);
+        assert_eq!(
+            Repository::from_remote_url("https://github.com/owner"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_discover() {
+        let temp_dir = TempDir::new().unwrap();
+        let git_dir = temp_dir.path().join(".git");
+        fs::create_dir(&git_dir).unwrap();
+        fs::write(
+            git_dir.join("config"),
+            indoc::indoc! {r#"
+                [core]
+                    bare = false
+                [remote "upstream"]
+                    url = https://github.com/upstream/repo.git
+                [remote "origin"]
+                    url = git@github.com:owner/repo.git
+                    fetch = +refs/heads/*:refs/remotes/origin/*
+            "#},
+        )
+        .unwrap();
+        let docs = temp_dir.path().join("docs");
+        fs::create_dir(&docs).unwrap();
+
+        assert_eq!(Repository::discover(&docs), Some(repository()));
+    }
+
+    #[test]
+    fn test_link_references() {
+        let repository = repository();
+
+        assert_eq!(
+            link_references("Fixes #12 and other/lib#3.", &repository).unwrap(),
+            concat!(
+                r#"Fixes <a href="https://github.com/owner/repo/issues/12" class="issue-link">#12</a>"#,
+                r#" and <a href="https://github.com/other/lib/issues/3" class="issue-link">other/lib#3</a>."#,
+            )
+        );
+        assert_eq!(
+            link_references("Thanks @octo-cat!", &repository).unwrap(),
+            r#"Thanks <a href="https://github.com/octo-cat" class="user-mention">@octo-cat</a>!"#
+        );
+        assert_eq!(
+            link_references("Reverts a1b2c3d4e5f6 <oops>", &repository).unwrap(),
+            concat!(
+                r#"Reverts <a href="https://github.com/owner/repo/commit/a1b2c3d4e5f6" class="commit-link">"#,
+                r#"<code>a1b2c3d</code></a> &lt;oops&gt;"#,
+            )
+        );
+    }
+
+    #[test]
+    fn test_link_references_ignores_lookalikes() {
+        let repository = repository();
+
+        for text in [
+            "mail me@example.com",
+            "C#7 and issue#12",
+            "see https://example.com/a1b2c3d4",
+            "#12abc @-user @user/team",
+            "deadbeef 1234567 a1b2c3",
+            "no references here",
+        ] {
+            assert_eq!(link_references(text, &repository), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_gitlab_urls() {
+        let repository = Repository::from_remote_url("git@gitlab.com:group/project.git").unwrap();
+
+        let html = link_references("#4 a1b2c3d", &repository).unwrap();
+
+        assert!(html.contains("https://gitlab.com/group/project/-/issues/4"));
+        assert!(html.contains("https://gitlab.com/group/project/-/commit/a1b2c3d"));
+    }
+}
@@ -0,0 +1,316 @@
+//! Top-level blocks of a rendered document, reprocessed only when they change
+//!
+//! The event stream of a document is split into its top-level blocks (paragraphs, lists, code
+//! blocks, diagrams, tables, …) and the markup between them (the section wrappers of
+//! [`super::outline`]). Each block is post-processed and sanitized on its own, and
+//! [`BlockCache`] keeps the results, so reloading a large document after an edit only
+//! processes the blocks that changed. The renderer patches the DOM block by block using the
+//! [keys](block_keys) of the blocks (`document-patch.ts`).
+//!
+//! Raw HTML opening an element in one block and closing it in a later one (`<details>` around
+//! Markdown) is kept in a single block so the sanitizer sees both tags. Documents with
+//! footnotes are a single block, as footnotes are numbered across the whole document.
+
+use pulldown_cmark::{html, Event, Tag};
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// Elements without a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// A part of a rendered document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Block {
+    /// Markup opening or closing elements around blocks (section wrappers)
+    Markup(String),
+    /// Processed HTML of a top-level block
+    Content(String),
+}
+
+impl Block {
+    pub fn html(&self) -> &str {
+        match self {
+            Block::Markup(html) | Block::Content(html) => html,
+        }
+    }
+}
+
+/// Processed blocks of the last render of a document, by hash of their raw HTML
+#[derive(Debug, Default)]
+pub struct BlockCache {
+    /// Hash of the options blocks were processed with
+    fingerprint: u64,
+    blocks: HashMap<u64, ProcessedBlock>,
+}
+
+/// HTML of a block after post-processing and sanitization, with the markup removed from it
+pub(super) type ProcessedBlock = (String, Vec<String>);
+
+impl BlockCache {
+    /// Render `events` block by block, processing the raw HTML of blocks with `process` unless
+    /// the previous render with the same `fingerprint` already did. Returns the blocks along
+    /// with the markup the processing removed from any block.
+    pub(super) fn render(
+        &mut self,
+        events: Vec<Event>,
+        fingerprint: u64,
+        process: impl Fn(&str) -> ProcessedBlock,
+    ) -> (Vec<Block>, Vec<String>) {
+        let mut previous = std::mem::take(&mut self.blocks);
+        if fingerprint != self.fingerprint {
+            previous.clear();
+            self.fingerprint = fingerprint;
+        }
+
+        let mut blocks = Vec::new();
+        let mut stripped = BTreeSet::new();
+        for unit in split_blocks(events) {
+            let events = match unit {
+                Unit::Markup(html) => {
+                    blocks.push(Block::Markup(html));
+                    continue;
+                }
+                Unit::Block(events) => events,
+            };
+            let mut raw = String::new();
+            html::push_html(&mut raw, events.into_iter());
+            let key = hash(&raw);
+            // Repeated blocks (`---`, identical paragraphs) share an entry
+            let processed = match previous.remove(&key) {
+                Some(processed) => processed,
+                None => match self.blocks.get(&key) {
+                    Some(processed) => processed.clone(),
+                    None => process(&raw),
+                },
+            };
+            stripped.extend(processed.1.iter().cloned());
+            blocks.push(Block::Content(processed.0.clone()));
+            self.blocks.insert(key, processed);
+        }
+        (blocks, stripped.into_iter().collect())
+    }
+}
+
+/// Keys identifying content blocks across renders (`None` for markup), unique within the
+/// document
+pub fn block_keys(blocks: &[Block]) -> Vec<Option<String>> {
+    let mut occurrences: HashMap<u64, usize> = HashMap::new();
+    blocks
+        .iter()
+        .map(|block| match block {
+            Block::Markup(_) => None,
+            Block::Content(html) => {
+                let hash = hash(html);
+                let occurrence = occurrences.entry(hash).or_default();
+                *occurrence += 1;
+                Some(format!("{hash:016x}-{occurrence}"))
+            }
+        })
+        .collect()
+}
+
+enum Unit<'a> {
+    Markup(String),
+    Block(Vec<Event<'a>>),
+}
+
+/// Split events into top-level blocks and the markup between them
+fn split_blocks(events: Vec<Event>) -> Vec<Unit> {
+    let has_footnotes = events.iter().any(|event| {
+        matches!(
+            event,
+            Event::FootnoteReference(_) | Event::Start(Tag::FootnoteDefinition(_))
+        )
+    });
+    if has_footnotes {
+        return vec![Unit::Block(events)];
+    }
+
+    let mut units = Vec::new();
+    let mut block = Vec::new();
+    let mut depth = 0usize;
+    // Elements opened by raw HTML in the current block and not closed yet
+    let mut open_elements = 0i32;
+
+    for event in events {
+        let at_boundary = depth == 0 && open_elements == 0;
+        if let Event::Html(html) = &event {
+            if at_boundary && tag_balance(html) != 0 {
+                units.push(Unit::Markup(html.to_string()));
+                continue;
+            }
+        }
+        match &event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth = depth.saturating_sub(1),
+            Event::Html(html) | Event::InlineHtml(html) => open_elements += tag_balance(html),
+            _ => {}
+        }
+        block.push(event);
+        if depth == 0 && open_elements <= 0 {
+            open_elements = 0;
+            units.push(Unit::Block(std::mem::take(&mut block)));
+        }
+    }
+    if !block.is_empty() {
+        units.push(Unit::Block(block));
+    }
+    units
+}
+
+/// Number of elements opened minus closed by raw HTML
+fn tag_balance(html: &str) -> i32 {
+    let mut balance = 0;
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let (closing, tag) = match rest.strip_prefix('/') {
+            Some(tag) => (true, tag),
+            None => (false, rest),
+        };
+        let name_len = tag
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+            .unwrap_or(tag.len());
+        let name = tag[..name_len].to_ascii_lowercase();
+        // Comments, doctypes and stray `<`
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic())
+            || VOID_ELEMENTS.contains(&name.as_str())
+        {
+            continue;
+        }
+        let self_closing = tag.find('>').is_some_and(|end| tag[..end].ends_with('/'));
+        if closing {
+            balance -= 1;
+        } else if !self_closing {
+            balance += 1;
+        }
+    }
+    balance
+}
+
+pub(super) fn hash(value: &(impl Hash + ?Sized)) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown::{render_document_with_cache, RenderOptions};
+    use indoc::indoc;
+    use std::path::Path;
+
+    fn contents(blocks: &[Block]) -> Vec<&str> {
+        blocks
+            .iter()
+            .filter_map(|block| match block {
+                Block::Content(html) => Some(html.as_str()),
+                Block::Markup(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_tag_balance() {
+        assert_eq!(tag_balance("<details>\n<summary>More</summary>"), 1);
+        assert_eq!(tag_balance("</details>"), -1);
+        assert_eq!(tag_balance(r#"<img src="a.png"><br/><x-icon />"#), 0);
+        assert_eq!(tag_balance("<!-- note --> 1 < 2"), 0);
+    }
+
+    #[test]
+    fn test_blocks() {
+        let markdown = indoc! {"
+            Intro
+
+            <details>
+            <summary>More</summary>
+
+            Hidden *text*
+
+            </details>
+
+            ## Usage
+
+            ```mermaid
+            graph TD
+            ```
+
+            ---
+
+            ---
+        "};
+        let mut cache = BlockCache::default();
+
+        let rendered = render_document_with_cache(
+            markdown,
+            Path::new("doc.md"),
+            &RenderOptions::default(),
+            &mut cache,
+        )
+        .unwrap();
+
+        let blocks = contents(&rendered.blocks);
+        assert_eq!(blocks.len(), 6, "{blocks:#?}");
+        assert_eq!(blocks[0], "<p>Intro</p>\n");
+        assert!(blocks[1].starts_with("<details>") && blocks[1].ends_with("</details>\n"));
+        assert!(blocks[1].contains("<em>text</em>"));
+        assert_eq!(blocks[2], "<h2>Usage</h2>\n");
+        assert!(blocks[3].starts_with(r#"<pre class="preprocessed-mermaid""#));
+        assert_eq!(blocks[4], "<hr>\n");
+        assert_eq!(
+            rendered.blocks.iter().map(Block::html).collect::<String>(),
+            rendered.html
+        );
+
+        let keys = block_keys(&rendered.blocks);
+        assert_eq!(keys.iter().flatten().count(), 6);
+        assert_ne!(
+            keys.last(),
+            keys.get(keys.len() - 2),
+            "repeated blocks get distinct keys"
+        );
+    }
+
+    #[test]
+    fn test_cache_reprocesses_changed_blocks() {
+        let options = RenderOptions::default();
+        let mut cache = BlockCache::default();
+        let render = |markdown: &str, cache: &mut BlockCache| {
+            render_document_with_cache(markdown, Path::new("doc.md"), &options, cache).unwrap()
+        };
+
+        let first = render("One\n\nTwo <script>x()</script>\n", &mut cache);
+        assert_eq!(first.stripped, vec!["<script>"]);
+        let keys: Vec<u64> = cache.blocks.keys().copied().collect();
+
+        let second = render("One\n\nTwo <script>x()</script>\n\nThree\n", &mut cache);
+        assert_eq!(contents(&second.blocks)[..2], contents(&first.blocks)[..]);
+        // Unchanged blocks keep reporting what was removed from them
+        assert_eq!(second.stripped, vec!["<script>"]);
+        assert!(keys.iter().all(|key| cache.blocks.contains_key(key)));
+
+        // Blocks no longer in the document are dropped
+        render("Three\n", &mut cache);
+        assert_eq!(cache.blocks.len(), 1);
+    }
+
+    #[test]
+    fn test_footnotes_render_as_one_block() {
+        let markdown = "See[^1].\n\nMore[^1].\n\n[^1]: Note\n";
+
+        let rendered = render_document_with_cache(
+            markdown,
+            Path::new("doc.md"),
+            &RenderOptions::default(),
+            &mut BlockCache::default(),
+        )
+        .unwrap();
+
+        assert_eq!(contents(&rendered.blocks).len(), 1);
+    }
+}
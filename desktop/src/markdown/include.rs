@@ -0,0 +1,543 @@
+//! Transclusion of other files into a Markdown document
+//!
+//! Two forms are supported:
+//!
+//! - `<!-- include: ../shared/footer.md -->` on a line of its own includes a whole file.
+//!   Markdown is rendered in place, CSV/TSV files as tables and anything else as a code block.
+//! - A fenced code block with a `file=` attribute (```` ```rust file=src/lib.rs lines=10-40 ````)
+//!   is filled with the file's content, or a 1-based inclusive range of its lines. The language
+//!   defaults to the one detected from the file name.
+//!
+//! Paths are resolved like links, relative to the including file. Failures (missing files,
+//! cycles, nesting deeper than [`MAX_INCLUDE_DEPTH`], bad line ranges) render as a visible
+//! error block in place of the included content.
+
+use anyhow::{bail, Context as _, Result};
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, Tag, TagEnd};
+use std::path::{Path, PathBuf};
+
+use super::{get_alert_icon_placeholder, render_markdown, resolve_local_path, RenderOptions};
+use crate::csv_table::render_table_file_to_html;
+use crate::source_view::{detect_language, language_for_path};
+use crate::utils::encoding::decode_text;
+use crate::utils::file::{is_markdown_file, is_table_file};
+
+/// Maximum nesting of included Markdown files
+pub const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// Tracks the files being rendered (for cycle detection) and every file included so far
+pub(super) struct Includes {
+    /// The document followed by the Markdown files currently being included into it
+    stack: Vec<PathBuf>,
+    /// Canonical paths of all included files, in order of first inclusion
+    files: Vec<PathBuf>,
+}
+
+impl Includes {
+    pub(super) fn new(document: &Path) -> Self {
+        Self {
+            stack: vec![canonical(document)],
+            files: Vec::new(),
+        }
+    }
+
+    /// Whether an included file (rather than the document itself) is being rendered
+    pub(super) fn is_fragment(&self) -> bool {
+        self.stack.len() > 1
+    }
+
+    pub(super) fn into_files(self) -> Vec<PathBuf> {
+        self.files
+    }
+
+    /// Replace include directives and `file=` code blocks in a parsed document
+    pub(super) fn expand<'a>(
+        &mut self,
+        parser: impl Iterator<Item = Event<'a>>,
+        base_dir: &Path,
+        render_options: &RenderOptions,
+    ) -> Vec<Event<'a>> {
+        // Links in an included file are relative to it, not to the including document
+        let rebase_links = self.is_fragment();
+        let mut events = Vec::new();
+        let mut in_snippet = false;
+
+        for event in parser {
+            if in_snippet {
+                // The body of a `file=` block is replaced by the file's content
+                in_snippet = !matches!(event, Event::End(TagEnd::CodeBlock));
+                continue;
+            }
+            match event {
+                Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(ref info))) => {
+                    match Snippet::parse(info) {
+                        Some(snippet) => {
+                            in_snippet = true;
+                            events.extend(self.include_snippet(
+                                &snippet,
+                                base_dir,
+                                &render_options.link_roots,
+                            ));
+                        }
+                        None => events.push(event),
+                    }
+                }
+                Event::Html(ref html) => match parse_include_directive(html) {
+                    Some(path) => events.push(Event::Html(
+                        self.include_file(path, base_dir, render_options).into(),
+                    )),
+                    None => events.push(event),
+                },
+                Event::Start(Tag::Link {
+                    link_type,
+                    dest_url,
+                    title,
+                    id,
+                }) if rebase_links => events.push(Event::Start(Tag::Link {
+                    link_type,
+                    dest_url: rebase_link(dest_url, base_dir, &render_options.link_roots),
+                    title,
+                    id,
+                })),
+                _ => events.push(event),
+            }
+        }
+        events
+    }
+
+    /// Render a file included with `<!-- include: … -->`, or an error block
+    fn include_file(&mut self, path: &str, base_dir: &Path, options: &RenderOptions) -> String {
+        self.render_file(path, base_dir, options)
+            .unwrap_or_else(|e| error_block(path, &e))
+    }
+
+    fn render_file(
+        &mut self,
+        path: &str,
+        base_dir: &Path,
+        options: &RenderOptions,
+    ) -> Result<String> {
+        let file = resolve_include(path, base_dir, &options.link_roots)?;
+        if self.stack.contains(&file) {
+            bail!("Circular include of {}", file.display());
+        }
+        if self.stack.len() > MAX_INCLUDE_DEPTH {
+            bail!("Includes are nested more than {MAX_INCLUDE_DEPTH} levels deep");
+        }
+        let content = self.read(&file)?;
+
+        if is_markdown_file(&file) {
+            self.stack.push(file.clone());
+            let (html, _) = render_markdown(&content, &file, options, self);
+            self.stack.pop();
+            Ok(format!(r#"<div class="markdown-include">{html}</div>"#))
+        } else if is_table_file(&file) {
+            render_table_file_to_html(&content, &file)
+        } else {
+            let language = detect_language(&file, &content).unwrap_or_default();
+            Ok(code_block(language, &content))
+        }
+    }
+
+    /// Code block events for a ```` ```lang file=… ```` block, or an error block
+    fn include_snippet<'a>(
+        &mut self,
+        snippet: &Snippet,
+        base_dir: &Path,
+        roots: &[PathBuf],
+    ) -> Vec<Event<'a>> {
+        match self.read_snippet(snippet, base_dir, roots) {
+            // Emit a regular fenced block so ```mermaid, ```math and ```csv snippets are
+            // processed like inline ones
+            Ok((language, code)) => vec![
+                Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(language.into()))),
+                Event::Text(code.into()),
+                Event::End(TagEnd::CodeBlock),
+            ],
+            Err(e) => vec![Event::Html(error_block(&snippet.path, &e).into())],
+        }
+    }
+
+    fn read_snippet(
+        &mut self,
+        snippet: &Snippet,
+        base_dir: &Path,
+        roots: &[PathBuf],
+    ) -> Result<(String, String)> {
+        let file = resolve_include(&snippet.path, base_dir, roots)?;
+        let content = self.read(&file)?;
+        let mut code = match &snippet.lines {
+            Some(range) => select_lines(&content, range)?,
+            None => content,
+        };
+        if !code.is_empty() && !code.ends_with('\n') {
+            code.push('\n');
+        }
+        let language = snippet
+            .language
+            .clone()
+            .or_else(|| language_for_path(&file).map(str::to_string))
+            .unwrap_or_default();
+        Ok((language, code))
+    }
+
+    /// Read an included file and remember it, so it can be watched for changes
+    fn read(&mut self, file: &Path) -> Result<String> {
+        let bytes =
+            std::fs::read(file).with_context(|| format!("Failed to read {}", file.display()))?;
+        let Some(content) = decode_text(&bytes, None) else {
+            bail!("{} is not a text file", file.display());
+        };
+        if !self.files.iter().any(|included| included == file) {
+            self.files.push(file.to_path_buf());
+        }
+        Ok(content.text)
+    }
+}
+
+/// Attributes of a ```` ```lang file=path lines=a-b ```` code block
+#[derive(Debug, PartialEq)]
+struct Snippet {
+    language: Option<String>,
+    path: String,
+    lines: Option<String>,
+}
+
+impl Snippet {
+    /// Parse a code block info string; `None` unless it has a `file=` attribute
+    fn parse(info: &str) -> Option<Self> {
+        let mut language = None;
+        let mut path = None;
+        let mut lines = None;
+        for (index, word) in info.split_whitespace().enumerate() {
+            match word.split_once('=') {
+                Some(("file", value)) => path = Some(unquote(value).to_string()),
+                Some(("lines", value)) => lines = Some(unquote(value).to_string()),
+                Some(_) => {}
+                None if index == 0 => language = Some(word.to_string()),
+                None => {}
+            }
+        }
+        Some(Self {
+            language,
+            path: path.filter(|path| !path.is_empty())?,
+            lines,
+        })
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Path of an `<!-- include: path -->` directive
+fn parse_include_directive(html: &str) -> Option<&str> {
+    let path = html
+        .trim()
+        .strip_prefix("<!--")?
+        .strip_suffix("-->")?
+        .trim()
+        .strip_prefix("include:")?
+        .trim();
+    (!path.is_empty()).then_some(path)
+}
+
+/// Resolve an include path to an existing file
+fn resolve_include(path: &str, base_dir: &Path, roots: &[PathBuf]) -> Result<PathBuf> {
+    let file = resolve_local_path(path, base_dir, roots);
+    let file = file
+        .canonicalize()
+        .with_context(|| format!("File not found: {}", file.display()))?;
+    if !file.is_file() {
+        bail!("Not a file: {}", file.display());
+    }
+    Ok(file)
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Lines `start..=end` (1-based) of `content`, for a range such as `10-40`, `10-` or `10`
+fn select_lines(content: &str, range: &str) -> Result<String> {
+    let invalid = || anyhow::anyhow!("Invalid line range: {range}");
+    let parse = |number: &str| number.trim().parse::<usize>().map_err(|_| invalid());
+    let (start, end) = match range.split_once('-') {
+        Some((start, "")) => (parse(start)?, usize::MAX),
+        Some((start, end)) => (parse(start)?, parse(end)?),
+        None => {
+            let line = parse(range)?;
+            (line, line)
+        }
+    };
+    if start == 0 || end < start {
+        return Err(invalid());
+    }
+
+    let line_count = content.lines().count();
+    if start > line_count {
+        bail!("Line {start} is past the end of the file ({line_count} lines)");
+    }
+    Ok(content.lines().skip(start - 1).take(end - start + 1).fold(
+        String::new(),
+        |mut code, line| {
+            code.push_str(line);
+            code.push('\n');
+            code
+        },
+    ))
+}
+
+/// Make a relative link in an included file point at the same target from any document
+fn rebase_link<'a>(dest_url: CowStr<'a>, base_dir: &Path, roots: &[PathBuf]) -> CowStr<'a> {
+    if dest_url.is_empty() || dest_url.starts_with(['#', '/']) || dest_url.contains(':') {
+        return dest_url;
+    }
+    let (target, fragment) = match dest_url.split_once('#') {
+        Some((target, fragment)) => (target, Some(fragment)),
+        None => (dest_url.as_ref(), None),
+    };
+    let mut rebased = resolve_local_path(target, base_dir, roots)
+        .to_string_lossy()
+        .into_owned();
+    if let Some(fragment) = fragment {
+        rebased.push('#');
+        rebased.push_str(fragment);
+    }
+    rebased.into()
+}
+
+fn code_block(language: &str, code: &str) -> String {
+    let class = if language.is_empty() {
+        String::new()
+    } else {
+        format!(r#" class="language-{language}""#)
+    };
+    format!(
+        "<pre><code{class}>{}</code></pre>\n",
+        html_escape::encode_text(code)
+    )
+}
+
+/// Visible replacement for an include that failed
+fn error_block(path: &str, error: &anyhow::Error) -> String {
+    format!(
+        concat!(
+            r#"<div class="markdown-alert markdown-alert-caution markdown-include-error" dir="auto">"#,
+            r#"<p class="markdown-alert-title" dir="auto">{icon}Include failed</p>"#,
+            r#"<p><code>{path}</code>: {error}</p></div>"#,
+            "\n",
+        ),
+        icon = get_alert_icon_placeholder("caution"),
+        path = html_escape::encode_text(path),
+        error = html_escape::encode_text(&format!("{error:#}")),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown::render_document;
+    use indoc::indoc;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_include_directive() {
+        assert_eq!(
+            parse_include_directive("<!-- include: ../shared/footer.md -->\n"),
+            Some("../shared/footer.md")
+        );
+        assert_eq!(parse_include_directive("<!--include:a.md-->"), Some("a.md"));
+        assert_eq!(parse_include_directive("<!-- a comment -->"), None);
+        assert_eq!(parse_include_directive("<!-- include: -->"), None);
+        assert_eq!(parse_include_directive("<div>include: a.md</div>"), None);
+    }
+
+    #[test]
+    fn test_parse_snippet() {
+        assert_eq!(
+            Snippet::parse("rust file=src/lib.rs lines=10-40"),
+            Some(Snippet {
+                language: Some("rust".to_string()),
+                path: "src/lib.rs".to_string(),
+                lines: Some("10-40".to_string()),
+            })
+        );
+        assert_eq!(
+            Snippet::parse(r#"file="main.py""#),
+            Some(Snippet {
+                language: None,
+                path: "main.py".to_string(),
+                lines: None,
+            })
+        );
+        assert_eq!(Snippet::parse("rust"), None);
+        assert_eq!(Snippet::parse("rust lines=1-2"), None);
+    }
+
+    #[test]
+    fn test_select_lines() {
+        let content = "one\ntwo\nthree\nfour\n";
+        assert_eq!(select_lines(content, "2-3").unwrap(), "two\nthree\n");
+        assert_eq!(select_lines(content, "3-").unwrap(), "three\nfour\n");
+        assert_eq!(select_lines(content, "1").unwrap(), "one\n");
+        assert_eq!(select_lines(content, "4-99").unwrap(), "four\n");
+        assert!(select_lines(content, "5-6").is_err());
+        assert!(select_lines(content, "0-2").is_err());
+        assert!(select_lines(content, "3-2").is_err());
+        assert!(select_lines(content, "a-b").is_err());
+    }
+
+    #[test]
+    fn test_include_markdown() {
+        let temp_dir = TempDir::new().unwrap();
+        let shared = temp_dir.path().join("shared");
+        fs::create_dir(&shared).unwrap();
+        fs::write(
+            shared.join("footer.md"),
+            "Footer with [a link](other.md#top) and <!-- a comment -->\n",
+        )
+        .unwrap();
+        fs::write(shared.join("other.md"), "").unwrap();
+        let docs = temp_dir.path().join("docs");
+        fs::create_dir(&docs).unwrap();
+        let document = docs.join("index.md");
+
+        let rendered = render_document(
+            "# Title\n\n<!-- include: ../shared/footer.md -->\n",
+            &document,
+            &RenderOptions::default(),
+        )
+        .unwrap();
+
+        assert!(rendered
+            .html
+            .contains(r#"<div class="markdown-include"><p>Footer with "#));
+        // The link keeps pointing next to the fragment
+        let other = shared.canonicalize().unwrap().join("other.md#top");
+        assert!(rendered
+            .html
+            .contains(&format!(r#"data-href="{}""#, other.display())));
+        assert_eq!(
+            rendered.includes,
+            vec![shared.join("footer.md").canonicalize().unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_include_snippet() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join("src")).unwrap();
+        fs::write(
+            temp_dir.path().join("src/lib.rs"),
+            "// header\nfn a() {}\nfn b() {}\n",
+        )
+        .unwrap();
+        let markdown = indoc! {"
+            ```rust file=src/lib.rs lines=2-3
+            ```
+
+            ```file=src/lib.rs lines=1
+            ignored
+            ```
+        "};
+
+        let rendered = render_document(
+            markdown,
+            temp_dir.path().join("README.md"),
+            &RenderOptions::default(),
+        )
+        .unwrap();
+
+        assert!(rendered
+            .html
+            .contains("<pre><code class=\"language-rust\">fn a() {}\nfn b() {}\n</code></pre>"));
+        assert!(rendered
+            .html
+            .contains("<pre><code class=\"language-rust\">// header\n</code></pre>"));
+        assert!(!rendered.html.contains("ignored"));
+        assert_eq!(rendered.includes.len(), 1);
+    }
+
+    #[test]
+    fn test_include_table_and_source() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("data.csv"), "name,count\na,1\n").unwrap();
+        fs::write(temp_dir.path().join("script.py"), "print(1 < 2)\n").unwrap();
+
+        let rendered = render_document(
+            "<!-- include: data.csv -->\n\n<!-- include: script.py -->\n",
+            temp_dir.path().join("README.md"),
+            &RenderOptions::default(),
+        )
+        .unwrap();
+
+        assert!(rendered.html.contains(r#"<div class="csv-table">"#));
+        assert!(rendered
+            .html
+            .contains(r#"<code class="language-python">print(1 &lt; 2)"#));
+        assert_eq!(rendered.includes.len(), 2);
+    }
+
+    #[test]
+    fn test_include_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.md"), "<!-- include: b.md -->\n").unwrap();
+        fs::write(temp_dir.path().join("b.md"), "<!-- include: a.md -->\n").unwrap();
+        fs::write(temp_dir.path().join("short.txt"), "one line\n").unwrap();
+        let markdown = indoc! {"
+            <!-- include: missing.md -->
+
+            <!-- include: a.md -->
+
+            ```text file=short.txt lines=5-6
+            ```
+        "};
+
+        let rendered = render_document(
+            markdown,
+            temp_dir.path().join("README.md"),
+            &RenderOptions::default(),
+        )
+        .unwrap();
+        let html = rendered.html;
+
+        assert_eq!(html.matches("Include failed").count(), 3);
+        assert!(html.contains("<code>missing.md</code>: File not found"));
+        assert!(html.contains("<code>a.md</code>: Circular include"));
+        assert!(html.contains("<code>short.txt</code>: Line 5 is past the end"));
+        // Files that were read are watched even if including them failed further down
+        assert_eq!(rendered.includes.len(), 3);
+    }
+
+    #[test]
+    fn test_include_depth_limit() {
+        let temp_dir = TempDir::new().unwrap();
+        for level in 0..=MAX_INCLUDE_DEPTH {
+            fs::write(
+                temp_dir.path().join(format!("{level}.md")),
+                format!("Level {level}\n\n<!-- include: {}.md -->\n", level + 1),
+            )
+            .unwrap();
+        }
+
+        let rendered = render_document(
+            "<!-- include: 0.md -->\n",
+            temp_dir.path().join("README.md"),
+            &RenderOptions::default(),
+        )
+        .unwrap();
+
+        assert!(rendered
+            .html
+            .contains(&format!("Level {}", MAX_INCLUDE_DEPTH - 1)));
+        assert!(!rendered
+            .html
+            .contains(&format!("Level {MAX_INCLUDE_DEPTH}")));
+        assert!(rendered.html.contains("nested more than"));
+    }
+}
@@ -0,0 +1,334 @@
+//! Heading tree of a document and the collapsible sections built from it
+//!
+//! Every top-level heading starts a section that runs until the next heading of the same or a
+//! higher level:
+//!
+//! ```html
+//! <section class="md-section" data-section="install" data-level="2">
+//!   <button class="md-section-toggle" …></button>
+//!   <h2>Install</h2>
+//!   <div class="md-section-body">…nested sections…</div>
+//! </section>
+//! ```
+//!
+//! The renderer folds a section by hiding its body. Headings inside block quotes, lists and
+//! included files don't start sections.
+
+use pulldown_cmark::{Event, HeadingLevel, Tag, TagEnd};
+use std::collections::HashSet;
+
+/// A heading of a document with the headings nested below it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heading {
+    pub level: u8,
+    /// Explicit `{#id}` or GitHub-style slug of the title, unique within the document;
+    /// identifies the heading's section across reloads
+    pub id: String,
+    pub title: String,
+    pub children: Vec<Heading>,
+}
+
+impl Heading {
+    /// Ids of this heading and all headings nested below it, depth first
+    pub fn ids(&self) -> Vec<&str> {
+        std::iter::once(self.id.as_str())
+            .chain(self.children.iter().flat_map(Heading::ids))
+            .collect()
+    }
+}
+
+/// A top-level heading found in the event stream
+struct FlatHeading {
+    /// Index of its `Start(Heading)` event
+    start: usize,
+    level: u8,
+    id: String,
+    title: String,
+}
+
+/// Wrap the sections of top-level headings, rendering those in `folded` folded, and return the
+/// heading tree
+pub(super) fn process_sections<'a>(
+    events: Vec<Event<'a>>,
+    folded: &HashSet<String>,
+) -> (Vec<Event<'a>>, Vec<Heading>) {
+    let headings = collect_headings(&events);
+    if headings.is_empty() {
+        return (events, Vec::new());
+    }
+
+    let mut output = Vec::with_capacity(events.len() + headings.len() * 3);
+    let mut headings_iter = headings.iter().peekable();
+    // Levels of the currently open sections
+    let mut open: Vec<u8> = Vec::new();
+    let mut in_section_heading = false;
+
+    for (index, event) in events.into_iter().enumerate() {
+        if let Some(heading) = headings_iter.next_if(|heading| heading.start == index) {
+            while open.last().is_some_and(|level| *level >= heading.level) {
+                open.pop();
+                output.push(Event::Html("</div></section>\n".into()));
+            }
+            open.push(heading.level);
+            output.push(Event::Html(
+                section_start(heading, folded.contains(&heading.id)).into(),
+            ));
+            in_section_heading = true;
+        }
+        let is_heading_end = matches!(event, Event::End(TagEnd::Heading(_)));
+        output.push(event);
+        if in_section_heading && is_heading_end {
+            in_section_heading = false;
+            output.push(Event::Html("<div class=\"md-section-body\">\n".into()));
+        }
+    }
+    for _ in open {
+        output.push(Event::Html("</div></section>\n".into()));
+    }
+
+    (output, build_tree(headings))
+}
+
+fn section_start(heading: &FlatHeading, folded: bool) -> String {
+    format!(
+        concat!(
+            r#"<section class="md-section{folded_class}" data-section="{id}" data-level="{level}">"#,
+            r#"<button type="button" class="md-section-toggle" aria-expanded="{expanded}" "#,
+            r#"aria-label="Toggle section" title="Fold or unfold this section"></button>"#,
+            "\n",
+        ),
+        folded_class = if folded { " md-section-folded" } else { "" },
+        id = html_escape::encode_double_quoted_attribute(&heading.id),
+        level = heading.level,
+        expanded = !folded,
+    )
+}
+
+/// Find the top-level headings, with unique ids
+fn collect_headings(events: &[Event]) -> Vec<FlatHeading> {
+    let mut headings = Vec::new();
+    let mut used_ids = HashSet::new();
+    // Nesting of block and inline tags; top-level headings start at depth 0
+    let mut depth = 0usize;
+    let mut current: Option<(FlatHeading, Option<String>)> = None;
+
+    for (index, event) in events.iter().enumerate() {
+        match event {
+            Event::Start(Tag::Heading { level, id, .. }) if depth == 0 => {
+                let heading = FlatHeading {
+                    start: index,
+                    level: heading_level(*level),
+                    id: String::new(),
+                    title: String::new(),
+                };
+                current = Some((heading, id.as_ref().map(|id| id.to_string())));
+                depth += 1;
+            }
+            Event::Start(_) => depth += 1,
+            Event::End(TagEnd::Heading(_)) if depth == 1 => {
+                depth -= 1;
+                if let Some((mut heading, explicit_id)) = current.take() {
+                    heading.title = heading.title.trim().to_string();
+                    let id = explicit_id.unwrap_or_else(|| slugify(&heading.title));
+                    heading.id = unique_id(id, &mut used_ids);
+                    headings.push(heading);
+                }
+            }
+            Event::End(_) => depth = depth.saturating_sub(1),
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((heading, _)) = current.as_mut() {
+                    heading.title.push_str(text);
+                }
+            }
+            _ => {}
+        }
+    }
+    headings
+}
+
+fn heading_level(level: HeadingLevel) -> u8 {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+/// Nest flat headings below the closest preceding heading of a higher level
+fn build_tree(headings: Vec<FlatHeading>) -> Vec<Heading> {
+    let mut roots: Vec<Heading> = Vec::new();
+    for flat in headings {
+        let heading = Heading {
+            level: flat.level,
+            id: flat.id,
+            title: flat.title,
+            children: Vec::new(),
+        };
+        let mut siblings = &mut roots;
+        while siblings
+            .last()
+            .is_some_and(|last| last.level < heading.level)
+        {
+            siblings = &mut siblings.last_mut().unwrap().children;
+        }
+        siblings.push(heading);
+    }
+    roots
+}
+
+/// GitHub-style anchor slug: lowercase, punctuation dropped, spaces turned into hyphens
+fn slugify(title: &str) -> String {
+    title
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Suffix repeated ids with `-1`, `-2`, … like GitHub
+fn unique_id(id: String, used: &mut HashSet<String>) -> String {
+    let id = if id.is_empty() {
+        "section".to_string()
+    } else {
+        id
+    };
+    let mut candidate = id.clone();
+    let mut suffix = 0;
+    while used.contains(&candidate) {
+        suffix += 1;
+        candidate = format!("{id}-{suffix}");
+    }
+    used.insert(candidate.clone());
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown::{render_document, RenderOptions};
+    use indoc::indoc;
+    use std::path::Path;
+
+    fn heading(level: u8, id: &str, title: &str, children: Vec<Heading>) -> Heading {
+        Heading {
+            level,
+            id: id.to_string(),
+            title: title.to_string(),
+            children,
+        }
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Getting Started"), "getting-started");
+        assert_eq!(slugify("What's new in v2.0?"), "whats-new-in-v20");
+        assert_eq!(slugify("snake_case & kebab-case"), "snake_case--kebab-case");
+        assert_eq!(slugify("日本語 見出し"), "日本語-見出し");
+    }
+
+    #[test]
+    fn test_outline() {
+        let markdown = indoc! {"
+            # Runbook
+
+            ## Setup `env`
+
+            ### Install
+
+            ## Setup env
+
+            > ## Quoted heading
+
+            ## Deploy {#deploy-steps}
+
+            # Appendix
+        "};
+
+        let rendered =
+            render_document(markdown, Path::new("runbook.md"), &RenderOptions::default()).unwrap();
+
+        assert_eq!(
+            rendered.outline,
+            vec![
+                heading(
+                    1,
+                    "runbook",
+                    "Runbook",
+                    vec![
+                        heading(
+                            2,
+                            "setup-env",
+                            "Setup env",
+                            vec![heading(3, "install", "Install", vec![])]
+                        ),
+                        heading(2, "setup-env-1", "Setup env", vec![]),
+                        heading(2, "deploy-steps", "Deploy", vec![]),
+                    ]
+                ),
+                heading(1, "appendix", "Appendix", vec![]),
+            ]
+        );
+        assert_eq!(
+            rendered.outline[0].ids(),
+            vec![
+                "runbook",
+                "setup-env",
+                "install",
+                "setup-env-1",
+                "deploy-steps"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sections() {
+        let markdown = indoc! {"
+            Intro
+
+            ## One
+
+            Body one
+
+            ### Nested
+
+            ## Two
+
+            Body two
+        "};
+        let options = RenderOptions {
+            folded_sections: HashSet::from(["two".to_string()]),
+            ..RenderOptions::default()
+        };
+
+        let html = render_document(markdown, Path::new("doc.md"), &options)
+            .unwrap()
+            .html;
+
+        let expected = indoc! {r#"
+            <p>Intro</p>
+            <section class="md-section" data-section="one" data-level="2"><button type="button" class="md-section-toggle" aria-expanded="true" aria-label="Toggle section" title="Fold or unfold this section"></button>
+            <h2>One</h2>
+            <div class="md-section-body">
+            <p>Body one</p>
+            <section class="md-section" data-section="nested" data-level="3"><button type="button" class="md-section-toggle" aria-expanded="true" aria-label="Toggle section" title="Fold or unfold this section"></button>
+            <h3>Nested</h3>
+            <div class="md-section-body">
+            </div></section>
+            </div></section>
+            <section class="md-section md-section-folded" data-section="two" data-level="2"><button type="button" class="md-section-toggle" aria-expanded="false" aria-label="Toggle section" title="Fold or unfold this section"></button>
+            <h2>Two</h2>
+            <div class="md-section-body">
+            <p>Body two</p>
+            </div></section>
+        "#};
+        assert_eq!(html, expected);
+    }
+}
@@ -0,0 +1,424 @@
+//! Allowlist sanitization of rendered documents
+//!
+//! Raw HTML in Markdown passes through the parser untouched, and the rendered document is
+//! injected into the webview, which can talk to the app through `dioxus.send`. A `<script>`,
+//! `onerror=` or `<iframe>` in a document from a random repository must not run there, so the
+//! rendered HTML is cleaned with an allowlist modeled on GitHub's. The allowlist also covers
+//! the markup Arto generates itself (alerts, sections, diagram, math and table placeholders,
+//! local links, media players), which carries no inline scripts.
+
+use ammonia::Builder;
+use lol_html::{element, HtmlRewriter, Settings};
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::LazyLock;
+
+use crate::asset_protocol::ASSET_SCHEME;
+
+/// How raw HTML in a document is treated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum HtmlPolicy {
+    /// Keep only the elements Markdown itself produces
+    Strict,
+    /// Keep the elements and attributes GitHub allows
+    #[default]
+    Github,
+    /// Keep everything; for documents the user trusts
+    Trusted,
+}
+
+/// Elements produced by Markdown and by Arto's own processing, with their specific attributes
+const MARKDOWN_ELEMENTS: &[(&str, &[&str])] = &[
+    ("a", &["href", "name"]),
+    ("audio", &["src", "controls", "preload", "loop", "muted"]),
+    ("blockquote", &[]),
+    ("br", &[]),
+    ("button", &["type", "aria-expanded"]),
+    ("code", &[]),
+    ("dd", &[]),
+    ("del", &[]),
+    ("div", &["data-original-content"]),
+    ("dl", &[]),
+    ("dt", &[]),
+    ("em", &[]),
+    ("h1", &[]),
+    ("h2", &[]),
+    ("h3", &[]),
+    ("h4", &[]),
+    ("h5", &[]),
+    ("h6", &[]),
+    ("hr", &[]),
+    ("img", &["src", "alt", "loading", "decoding"]),
+    ("input", &["type", "checked", "disabled"]),
+    ("li", &[]),
+    ("ol", &["start"]),
+    ("p", &[]),
+    ("pre", &["data-original-content", "hidden"]),
+    ("section", &["data-section", "data-level"]),
+    (
+        "span",
+        &["data-original-content", "data-alert-type", "data-href"],
+    ),
+    ("strong", &[]),
+    ("sub", &[]),
+    ("sup", &[]),
+    ("table", &[]),
+    ("tbody", &[]),
+    ("td", &["style"]),
+    ("th", &["style"]),
+    ("thead", &[]),
+    ("tr", &[]),
+    ("ul", &[]),
+    (
+        "video",
+        &[
+            "src",
+            "poster",
+            "controls",
+            "preload",
+            "loop",
+            "muted",
+            "playsinline",
+        ],
+    ),
+];
+
+/// Elements GitHub additionally allows in raw HTML, with their specific attributes
+const GITHUB_ELEMENTS: &[(&str, &[&str])] = &[
+    ("abbr", &[]),
+    ("b", &[]),
+    ("bdo", &[]),
+    ("caption", &[]),
+    ("cite", &[]),
+    ("details", &["open"]),
+    ("dfn", &[]),
+    ("figcaption", &[]),
+    ("figure", &[]),
+    ("i", &[]),
+    ("ins", &["cite", "datetime"]),
+    ("kbd", &[]),
+    ("mark", &[]),
+    ("picture", &[]),
+    ("q", &["cite"]),
+    ("rp", &[]),
+    ("rt", &[]),
+    ("ruby", &[]),
+    ("s", &[]),
+    ("samp", &[]),
+    ("small", &[]),
+    ("source", &["src", "srcset", "media", "type"]),
+    ("strike", &[]),
+    ("summary", &[]),
+    ("tfoot", &[]),
+    ("time", &["datetime"]),
+    ("track", &["src", "kind", "srclang", "label", "default"]),
+    ("tt", &[]),
+    ("var", &[]),
+    ("wbr", &[]),
+];
+
+/// Attributes allowed on every element
+const GENERIC_ATTRIBUTES: &[&str] = &[
+    "class",
+    "id",
+    "title",
+    "dir",
+    "lang",
+    "aria-label",
+    "aria-hidden",
+];
+
+/// Presentational attributes GitHub additionally allows on every element
+const GITHUB_GENERIC_ATTRIBUTES: &[&str] =
+    &["align", "width", "height", "colspan", "rowspan", "valign"];
+
+/// URL schemes allowed in links and image sources; `data:` is limited to images and
+/// `arto-asset:` (local files, see [`crate::asset_protocol`]) to media sources
+const URL_SCHEMES: &[&str] = &["http", "https", "mailto", "data", ASSET_SCHEME];
+
+/// Attributes holding a URL, whose scheme is checked
+const URL_ATTRIBUTES: &[&str] = &["href", "src", "cite", "poster"];
+
+/// Elements and attributes allowed by a policy
+struct Allowlist {
+    elements: HashMap<&'static str, HashSet<&'static str>>,
+    generic_attributes: HashSet<&'static str>,
+}
+
+impl Allowlist {
+    fn new(policy: HtmlPolicy) -> Self {
+        let github = policy == HtmlPolicy::Github;
+        let elements = MARKDOWN_ELEMENTS
+            .iter()
+            .chain(if github { GITHUB_ELEMENTS } else { &[] })
+            .map(|(tag, attributes)| (*tag, attributes.iter().copied().collect()))
+            .collect();
+        let generic_attributes = GENERIC_ATTRIBUTES
+            .iter()
+            .chain(if github {
+                GITHUB_GENERIC_ATTRIBUTES
+            } else {
+                &[]
+            })
+            .copied()
+            .collect();
+        Self {
+            elements,
+            generic_attributes,
+        }
+    }
+
+    fn allows_attribute(&self, tag: &str, attribute: &str) -> bool {
+        self.generic_attributes.contains(attribute)
+            || self
+                .elements
+                .get(tag)
+                .is_some_and(|attributes| attributes.contains(attribute))
+    }
+
+    fn builder(&self) -> Builder<'static> {
+        let mut builder = Builder::default();
+        builder
+            .tags(self.elements.keys().copied().collect())
+            .tag_attributes(self.elements.clone())
+            .generic_attributes(self.generic_attributes.clone())
+            .url_schemes(URL_SCHEMES.iter().copied().collect())
+            .filter_style_properties(HashSet::from(["text-align"]))
+            .link_rel(None)
+            .attribute_filter(filter_attribute);
+        builder
+    }
+}
+
+static STRICT: LazyLock<(Allowlist, Builder<'static>)> = LazyLock::new(|| {
+    let allowlist = Allowlist::new(HtmlPolicy::Strict);
+    let builder = allowlist.builder();
+    (allowlist, builder)
+});
+
+static GITHUB: LazyLock<(Allowlist, Builder<'static>)> = LazyLock::new(|| {
+    let allowlist = Allowlist::new(HtmlPolicy::Github);
+    let builder = allowlist.builder();
+    (allowlist, builder)
+});
+
+/// Value checks the allowlist can't express
+fn filter_attribute<'u>(tag: &str, attribute: &str, value: &'u str) -> Option<Cow<'u, str>> {
+    let is_data_url = value
+        .trim_start()
+        .get(..5)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("data:"));
+    match (tag, attribute) {
+        // Local images are inlined as data URLs, but a data URL document must not be navigable
+        ("img", "src") if is_data_url => value
+            .trim_start()
+            .get(5..)
+            .is_some_and(|rest| rest.starts_with("image/"))
+            .then_some(value.into()),
+        (_, "href" | "cite")
+            if is_data_url || url_scheme(value).as_deref() == Some(ASSET_SCHEME) =>
+        {
+            None
+        }
+        // Task list checkboxes only
+        ("input", "type") => (value == "checkbox").then_some(value.into()),
+        _ => Some(value.into()),
+    }
+}
+
+/// Sanitize rendered HTML, returning it along with descriptions of what was removed
+/// (`<script>`, `onerror attribute`, `javascript: URL`)
+pub fn sanitize(html: &str, policy: HtmlPolicy) -> (String, Vec<String>) {
+    let (allowlist, builder) = match policy {
+        HtmlPolicy::Trusted => return (html.to_string(), Vec::new()),
+        HtmlPolicy::Strict => &*STRICT,
+        HtmlPolicy::Github => &*GITHUB,
+    };
+    // The audit only reports; cleaning never depends on it, as it doesn't parse like a browser
+    let stripped = find_disallowed(html, allowlist);
+    (builder.clean(html).to_string(), stripped)
+}
+
+/// Describe the elements, attributes and URLs the sanitizer will remove
+fn find_disallowed(html: &str, allowlist: &Allowlist) -> Vec<String> {
+    let mut found = BTreeSet::new();
+
+    let mut rewriter = HtmlRewriter::new(
+        Settings {
+            element_content_handlers: vec![element!("*", |el| {
+                let tag = el.tag_name();
+                if !allowlist.elements.contains_key(tag.as_str()) {
+                    found.insert(format!("<{tag}>"));
+                    return Ok(());
+                }
+                for attribute in el.attributes() {
+                    let name = attribute.name();
+                    let value = attribute.value();
+                    if !allowlist.allows_attribute(&tag, &name) {
+                        found.insert(format!("{name} attribute"));
+                    } else if URL_ATTRIBUTES.contains(&name.as_str()) {
+                        match url_scheme(&value) {
+                            Some(scheme) if !URL_SCHEMES.contains(&scheme.as_str()) => {
+                                found.insert(format!("{scheme}: URL"));
+                            }
+                            _ if filter_attribute(&tag, &name, &value).is_none() => {
+                                found.insert(format!("{name} attribute"));
+                            }
+                            _ => {}
+                        }
+                    } else if filter_attribute(&tag, &name, &value).is_none() {
+                        found.insert(format!("{name} attribute"));
+                    }
+                }
+                Ok(())
+            })],
+            ..Settings::default()
+        },
+        |_: &[u8]| {},
+    );
+    let _ = rewriter.write(html.as_bytes());
+    let _ = rewriter.end();
+
+    found.into_iter().collect()
+}
+
+/// Lowercased scheme of an absolute URL
+fn url_scheme(url: &str) -> Option<String> {
+    let url = url.trim_start();
+    let colon = url.find(':')?;
+    let scheme = &url[..colon];
+    let is_scheme = scheme
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    is_scheme.then(|| scheme.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown::{render_document, RenderOptions};
+    use indoc::indoc;
+    use std::path::Path;
+
+    fn render(markdown: &str, policy: HtmlPolicy) -> (String, Vec<String>) {
+        let options = RenderOptions {
+            html_policy: policy,
+            ..RenderOptions::default()
+        };
+        let rendered = render_document(markdown, Path::new("doc.md"), &options).unwrap();
+        (rendered.html, rendered.stripped)
+    }
+
+    #[test]
+    fn test_sanitize_removes_scripts() {
+        let markdown = indoc! {r#"
+            <script>alert(1)</script>
+
+            <img src="https://example.com/a.png" onerror="alert(2)">
+
+            <iframe src="https://example.com"></iframe>
+
+            [click](javascript:alert(3))
+        "#};
+
+        let (html, stripped) = render(markdown, HtmlPolicy::Github);
+
+        assert!(!html.contains("alert"), "{html}");
+        assert!(html.contains(r#"<img src="https://example.com/a.png">"#));
+        assert!(!html.contains("iframe"));
+        assert_eq!(
+            stripped,
+            vec![
+                "<iframe>",
+                "<script>",
+                "javascript: URL",
+                "onerror attribute"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sanitize_policies() {
+        let markdown = indoc! {"
+            <details open><summary>More</summary>
+
+            Press <kbd>Ctrl</kbd>
+
+            </details>
+        "};
+
+        let (html, stripped) = render(markdown, HtmlPolicy::Github);
+        assert!(html.contains("<details open=\"\"><summary>More</summary>"));
+        assert!(html.contains("<kbd>Ctrl</kbd>"));
+        assert!(stripped.is_empty());
+
+        let (html, stripped) = render(markdown, HtmlPolicy::Strict);
+        assert!(!html.contains("<details"));
+        assert!(html.contains("Press Ctrl"));
+        assert_eq!(stripped, vec!["<details>", "<kbd>", "<summary>"]);
+
+        let (html, stripped) = render("<b onclick=\"x()\">bold</b>", HtmlPolicy::Trusted);
+        assert!(html.contains("onclick"));
+        assert!(stripped.is_empty());
+    }
+
+    #[test]
+    fn test_sanitize_keeps_generated_markup() {
+        let markdown = indoc! {"
+            # Title
+
+            > [!NOTE]
+            > Read [the guide](guide.md).
+
+            - [x] done
+
+            | a | b |
+            |:-:|---|
+            | 1 | 2 |
+
+            ```mermaid
+            graph TD; A-->B
+            ```
+
+            ```csv
+            name,age
+            Alice,30
+            ```
+
+            Inline $x^2$ and a footnote[^1].
+
+            [^1]: Note
+        "};
+
+        let (html, stripped) = render(markdown, HtmlPolicy::Strict);
+
+        assert!(stripped.is_empty(), "{stripped:?}");
+        assert!(html.contains(r#"<span class="md-link" data-href="guide.md">"#));
+        assert!(html.contains(r#"data-alert-type="note""#));
+        assert!(html.contains(r#"<button type="button" class="md-section-toggle""#));
+        assert!(html.contains(r#"<input disabled="" type="checkbox" checked="">"#));
+        assert!(html.contains(r#"<th style="text-align:center">"#));
+        assert!(html.contains(r#"<pre class="preprocessed-mermaid" data-original-content="#));
+        assert!(html.contains(r#"<pre class="csv-table-data" hidden="">"#));
+        assert!(html.contains(r#"<span class="preprocessed-math-inline""#));
+    }
+
+    #[test]
+    fn test_filter_attribute() {
+        assert!(filter_attribute("img", "src", "data:image/png;base64,AA==").is_some());
+        assert!(filter_attribute("img", "src", "data:text/html,<b>").is_none());
+        assert!(filter_attribute("a", "href", " DATA:text/html,<b>").is_none());
+        assert!(filter_attribute("video", "src", "arto-asset://localhost/a.mp4").is_some());
+        assert!(filter_attribute("a", "href", "arto-asset://localhost/a.png").is_none());
+        assert!(filter_attribute("input", "type", "text").is_none());
+        assert_eq!(
+            url_scheme(" JavaScript:alert(1)"),
+            Some("javascript".to_string())
+        );
+        assert_eq!(url_scheme("docs/a:b.md"), None);
+    }
+}
@@ -0,0 +1,197 @@
+//! Renderers for lightweight markup languages other than Markdown.
+//!
+//! AsciiDoc and reStructuredText documents are translated to the Markdown dialect understood
+//! by [`crate::markdown`] and rendered through it, so they share its HTML conventions: GitHub
+//! style alerts for admonitions, `language-*` classes on code blocks, `{#id}` heading anchors
+//! and local image/link handling in `post_process_html_tags`.
+
+pub mod asciidoc;
+pub mod rst;
+
+pub use asciidoc::render_asciidoc;
+pub use rst::render_rst;
+
+/// Characters escaped in text so Markdown renders them literally
+const MARKDOWN_SPECIAL_CHARS: &str = "\\`*_[]<>#!|$~^&{}";
+
+/// Append `c` to `out`, escaped if it has a meaning in Markdown
+fn push_escaped(out: &mut String, c: char) {
+    if MARKDOWN_SPECIAL_CHARS.contains(c) {
+        out.push('\\');
+    }
+    out.push(c);
+}
+
+/// Escape text so Markdown renders it literally
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        push_escaped(&mut out, c);
+    }
+    out
+}
+
+/// Markdown code span for `text`, using a backtick fence that doesn't occur in it
+fn code_span(text: &str) -> String {
+    let fence = "`".repeat(longest_run(text, '`') + 1);
+    let padding = if text.starts_with('`') || text.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+    format!("{fence}{padding}{text}{padding}{fence}")
+}
+
+/// Link or image destination, wrapped in `<>` when it contains characters Markdown would split on
+fn link_destination(url: &str) -> String {
+    if url.contains([' ', '(', ')']) {
+        format!("<{url}>")
+    } else {
+        url.to_string()
+    }
+}
+
+fn longest_run(text: &str, target: char) -> usize {
+    text.split(|c| c != target).map(str::len).max().unwrap_or(0)
+}
+
+/// Number of leading spaces of a line
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Remove the common indentation of the non-blank lines
+fn dedent<'a>(lines: &[&'a str]) -> Vec<&'a str> {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| indent_of(line))
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or(""))
+        .collect()
+}
+
+/// Map an admonition name (`note`, `TIP`, `danger`, …) to the GitHub alert it is shown as
+fn alert_kind(name: &str) -> Option<&'static str> {
+    Some(match name.to_ascii_lowercase().as_str() {
+        "note" | "seealso" => "NOTE",
+        "tip" | "hint" => "TIP",
+        "important" => "IMPORTANT",
+        "warning" | "attention" => "WARNING",
+        "caution" | "danger" | "error" => "CAUTION",
+        _ => return None,
+    })
+}
+
+/// Append a heading with an explicit anchor id
+fn push_heading(out: &mut Vec<String>, level: usize, text: &str, id: &str) {
+    out.push(String::new());
+    out.push(format!(
+        "{} {} {{#{}}}",
+        "#".repeat(level.clamp(1, 6)),
+        text,
+        id
+    ));
+    out.push(String::new());
+}
+
+/// Append a fenced code block
+fn push_code_block(out: &mut Vec<String>, language: Option<&str>, lines: &[&str]) {
+    let longest = lines
+        .iter()
+        .map(|line| longest_run(line, '`'))
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest.max(2) + 1);
+    out.push(String::new());
+    out.push(format!("{fence}{}", language.unwrap_or_default()));
+    out.extend(lines.iter().map(|line| line.to_string()));
+    out.push(fence);
+    out.push(String::new());
+}
+
+/// Append Markdown lines as a block quote, or as a GitHub alert when `alert` is given
+fn push_quote(out: &mut Vec<String>, alert: Option<&str>, body: Vec<String>) {
+    out.push(String::new());
+    if let Some(alert) = alert {
+        out.push(format!("> [!{alert}]"));
+    }
+    for line in body {
+        if line.is_empty() {
+            out.push(">".to_string());
+        } else {
+            out.push(format!("> {line}"));
+        }
+    }
+    out.push(String::new());
+}
+
+/// Append a table; cells are Markdown inline content
+fn push_table(out: &mut Vec<String>, header: Option<Vec<String>>, rows: Vec<Vec<String>>) {
+    let columns = rows
+        .iter()
+        .chain(header.iter())
+        .map(Vec::len)
+        .max()
+        .unwrap_or(0);
+    if columns == 0 {
+        return;
+    }
+    let format_row = |cells: &[String]| {
+        let mut line = String::from("|");
+        for column in 0..columns {
+            let cell = cells.get(column).map(String::as_str).unwrap_or_default();
+            line.push(' ');
+            line.push_str(&cell.replace('\n', " "));
+            line.push_str(" |");
+        }
+        line
+    };
+
+    out.push(String::new());
+    // Markdown tables always have a header row; leave it empty when the source has none
+    out.push(format_row(&header.unwrap_or_default()));
+    out.push(format!("|{}", " --- |".repeat(columns)));
+    out.extend(rows.iter().map(|row| format_row(row)));
+    out.push(String::new());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("a_b *c* [d] <e> $f$"),
+            r"a\_b \*c\* \[d\] \<e\> \$f\$"
+        );
+        assert_eq!(escape("plain text."), "plain text.");
+    }
+
+    #[test]
+    fn test_code_span() {
+        assert_eq!(code_span("let x"), "`let x`");
+        assert_eq!(code_span("a`b"), "``a`b``");
+        assert_eq!(code_span("`tick`"), "`` `tick` ``");
+    }
+
+    #[test]
+    fn test_dedent() {
+        assert_eq!(dedent(&["    a", "", "      b"]), vec!["a", "", "  b"]);
+    }
+
+    #[test]
+    fn test_push_table() {
+        let mut out = Vec::new();
+        push_table(
+            &mut out,
+            Some(vec!["a".to_string(), "b".to_string()]),
+            vec![vec!["1".to_string()]],
+        );
+        assert_eq!(out, vec!["", "| a | b |", "| --- | --- |", "| 1 |  |", ""]);
+    }
+}
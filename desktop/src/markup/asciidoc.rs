@@ -0,0 +1,1428 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::{
+    alert_kind, code_span, dedent, escape, link_destination, push_code_block, push_escaped,
+    push_heading, push_quote, push_table,
+};
+use crate::markdown::{render_document, RenderOptions, RenderedDocument};
+
+/// URL schemes recognized as links in text
+const URL_PREFIXES: [&str; 4] = ["https://", "http://", "ftp://", "irc://"];
+
+/// Render an AsciiDoc document to HTML, sanitized like Markdown
+pub fn render_asciidoc(
+    content: &str,
+    base_path: impl AsRef<Path>,
+    render_options: &RenderOptions,
+) -> Result<RenderedDocument> {
+    render_document(to_markdown(content), base_path, render_options)
+}
+
+/// Translate AsciiDoc to Markdown
+fn to_markdown(content: &str) -> String {
+    let content = content.replace("\r\n", "\n");
+    let lines: Vec<&str> = content.lines().map(str::trim_end).collect();
+    let mut converter = Converter {
+        section_titles: collect_section_titles(&lines),
+        ..Converter::default()
+    };
+    let mut out = Vec::new();
+    converter.blocks(&lines, &mut out);
+
+    if !converter.footnotes.is_empty() {
+        out.push(String::new());
+        for (index, footnote) in converter.footnotes.iter().enumerate() {
+            out.push(format!("[^{}]: {footnote}", index + 1));
+        }
+    }
+    out.join("\n")
+}
+
+/// Attributes, anchor and title given on the lines preceding a block
+#[derive(Debug, Default)]
+struct BlockAttributes {
+    /// Positional attributes; the first one is the block style (`source`, `NOTE`, `quote`, …)
+    positional: Vec<String>,
+    named: HashMap<String, String>,
+    /// Options such as `header` from `[%header]` or `options="header"`
+    options: Vec<String>,
+    id: Option<String>,
+    title: Option<String>,
+}
+
+impl BlockAttributes {
+    fn style(&self) -> Option<&str> {
+        self.positional.first().map(String::as_str)
+    }
+
+    fn positional(&self, index: usize) -> Option<&str> {
+        self.positional
+            .get(index)
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    }
+
+    fn has_option(&self, option: &str) -> bool {
+        self.options.iter().any(|o| o == option)
+    }
+
+    /// Parse the contents of a `[…]` block attribute line into `self`
+    fn parse(&mut self, list: &str) {
+        for (index, item) in split_attribute_list(list).into_iter().enumerate() {
+            if let Some((name, value)) = item.split_once('=') {
+                let value = value.trim().trim_matches('"').to_string();
+                match name.trim() {
+                    "options" | "opts" => self
+                        .options
+                        .extend(value.split(',').map(|option| option.trim().to_string())),
+                    "id" => self.id = Some(value),
+                    name => {
+                        self.named.insert(name.to_string(), value);
+                    }
+                }
+            } else if index == 0 {
+                // The first positional attribute may carry `#id`, `.role` and `%option` shorthands
+                let style_end = item.find(['#', '.', '%']).unwrap_or(item.len());
+                self.positional.push(item[..style_end].to_string());
+                let mut shorthand = &item[style_end..];
+                while let Some(marker) = shorthand.chars().next() {
+                    let end = shorthand[1..]
+                        .find(['#', '.', '%'])
+                        .map_or(shorthand.len(), |end| end + 1);
+                    let value = shorthand[1..end].to_string();
+                    match marker {
+                        '#' => self.id = Some(value),
+                        '%' => self.options.push(value),
+                        _ => {}
+                    }
+                    shorthand = &shorthand[end..];
+                }
+            } else {
+                self.positional
+                    .push(item.trim().trim_matches('"').to_string());
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Converter {
+    /// Document attributes set by `:name: value` entries
+    attributes: HashMap<String, String>,
+    /// Section titles by id, for `<<id>>` cross references without text
+    section_titles: HashMap<String, String>,
+    pending: BlockAttributes,
+    /// Markers of the open (nested) lists, outermost first, with their Markdown marker width
+    list_stack: Vec<(String, usize)>,
+    footnotes: Vec<String>,
+}
+
+impl Converter {
+    fn blocks(&mut self, lines: &[&str], out: &mut Vec<String>) {
+        let mut i = 0;
+        while i < lines.len() {
+            let line = lines[i];
+            let trimmed = line.trim();
+
+            if trimmed.is_empty() {
+                out.push(String::new());
+                i += 1;
+                continue;
+            }
+            // Comments and preprocessor conditionals (both branches are shown)
+            if trimmed.starts_with("//") && !trimmed.starts_with("////") {
+                i += 1;
+                continue;
+            }
+            if ["ifdef::", "ifndef::", "ifeval::", "endif::"]
+                .iter()
+                .any(|prefix| trimmed.starts_with(prefix))
+            {
+                i += 1;
+                continue;
+            }
+            if let Some((name, value)) = parse_attribute_entry(line) {
+                match value {
+                    Some(value) => self.attributes.insert(name.to_string(), value.to_string()),
+                    None => self.attributes.remove(name),
+                };
+                i += 1;
+                continue;
+            }
+            if let Some(id) = trimmed
+                .strip_prefix("[[")
+                .and_then(|anchor| anchor.strip_suffix("]]"))
+            {
+                self.pending.id = Some(id.split(',').next().unwrap_or(id).trim().to_string());
+                i += 1;
+                continue;
+            }
+            if let Some(list) = trimmed
+                .strip_prefix('[')
+                .and_then(|list| list.strip_suffix(']'))
+            {
+                self.pending.parse(list);
+                i += 1;
+                continue;
+            }
+            if let Some(title) = trimmed
+                .strip_prefix('.')
+                .filter(|title| title.starts_with(|c: char| !c.is_whitespace() && c != '.'))
+            {
+                self.pending.title = Some(title.to_string());
+                i += 1;
+                continue;
+            }
+
+            if let Some(end) = self.delimited_block(lines, i, out) {
+                self.list_stack.clear();
+                i = end;
+            } else if let Some((level, title)) = parse_heading(line) {
+                self.list_stack.clear();
+                i = self.heading(lines, i, level, title, out);
+            } else if is_thematic_break(trimmed) {
+                self.list_stack.clear();
+                out.extend([String::new(), "***".to_string(), String::new()]);
+                i += 1;
+            } else if trimmed == "<<<" {
+                i += 1;
+            } else if let Some((name, target, attributes)) = parse_block_macro(trimmed) {
+                self.list_stack.clear();
+                self.block_macro(name, target, attributes, out);
+                i += 1;
+            } else if let Some((marker, text)) = parse_list_item(trimmed) {
+                i = self.list_item(lines, i, marker, text, out);
+            } else if let Some((term, definition)) = parse_description_item(trimmed) {
+                self.list_stack.clear();
+                i = self.description_item(lines, i, term, definition, out);
+            } else {
+                self.list_stack.clear();
+                i = self.paragraph(lines, i, out);
+            }
+            self.pending = BlockAttributes::default();
+        }
+    }
+
+    fn heading(
+        &mut self,
+        lines: &[&str],
+        start: usize,
+        level: usize,
+        title: &str,
+        out: &mut Vec<String>,
+    ) -> usize {
+        let id = self
+            .pending
+            .id
+            .take()
+            .unwrap_or_else(|| section_id(title, &self.attributes));
+        let text = self.inline(title);
+        push_heading(out, level + 1, &text, &id);
+
+        // The document title may be followed by author and revision lines
+        let mut end = start + 1;
+        if level == 0 {
+            let mut details = Vec::new();
+            while let Some(line) = lines.get(end).filter(|line| !line.trim().is_empty()) {
+                match parse_attribute_entry(line) {
+                    Some((name, Some(value))) => {
+                        self.attributes.insert(name.to_string(), value.to_string());
+                    }
+                    Some((name, None)) => {
+                        self.attributes.remove(name);
+                    }
+                    None if !line.starts_with("//") => details.push(self.inline(line)),
+                    None => {}
+                }
+                end += 1;
+            }
+            if !details.is_empty() {
+                out.extend([details.join("  \n"), String::new()]);
+            }
+        }
+        end
+    }
+
+    /// Convert a delimited block (listing, example, sidebar, quote, table, …) starting at `start`
+    fn delimited_block(
+        &mut self,
+        lines: &[&str],
+        start: usize,
+        out: &mut Vec<String>,
+    ) -> Option<usize> {
+        let delimiter = lines[start].trim();
+        let kind = delimiter_kind(delimiter)?;
+        let closing = if kind == '`' { "```" } else { delimiter };
+        let close = lines[start + 1..]
+            .iter()
+            .position(|line| line.trim() == closing)
+            .map_or(lines.len(), |offset| start + 1 + offset);
+        let inner = &lines[start + 1..close];
+        let end = (close + 1).min(lines.len());
+        let attributes = std::mem::take(&mut self.pending);
+        let alert = attributes.style().and_then(alert_kind);
+
+        match kind {
+            '-' | '`' => {
+                let language = match kind {
+                    '`' => delimiter.trim_start_matches('`').trim().to_string(),
+                    _ => self.source_language(&attributes).unwrap_or_default(),
+                };
+                self.push_title(&attributes, out);
+                let code: Vec<String> = inner.iter().map(|line| strip_callouts(line)).collect();
+                let code: Vec<&str> = code.iter().map(String::as_str).collect();
+                let language = (!language.is_empty()).then_some(language.as_str());
+                push_code_block(out, language, &code);
+            }
+            '.' => {
+                self.push_title(&attributes, out);
+                push_code_block(out, None, inner);
+            }
+            '+' => {
+                out.push(String::new());
+                out.extend(inner.iter().map(|line| line.to_string()));
+                out.push(String::new());
+            }
+            '/' => {}
+            '|' | ',' | ':' => {
+                self.push_title(&attributes, out);
+                self.table(inner, delimiter, &attributes, out);
+            }
+            _ => {
+                // Example, sidebar, quote and open blocks hold other blocks
+                let mut body = Vec::new();
+                if alert.is_none() {
+                    if let Some(title) = &attributes.title {
+                        body.extend([format!("**{}**", self.inline(title)), String::new()]);
+                    }
+                }
+                let list_stack = std::mem::take(&mut self.list_stack);
+                self.blocks(inner, &mut body);
+                self.list_stack = list_stack;
+                if kind == '_' || attributes.style() == Some("quote") {
+                    body.extend(self.attribution(&attributes));
+                }
+
+                let body = trim_blank(body);
+                // Open blocks only group their content
+                if kind == 'o' && alert.is_none() && attributes.style() != Some("quote") {
+                    out.push(String::new());
+                    out.extend(body);
+                    out.push(String::new());
+                } else {
+                    push_quote(out, alert, body);
+                }
+            }
+        }
+        Some(end)
+    }
+
+    fn paragraph(&mut self, lines: &[&str], start: usize, out: &mut Vec<String>) -> usize {
+        let mut end = start + 1;
+        while end < lines.len()
+            && !lines[end].trim().is_empty()
+            && delimiter_kind(lines[end].trim()).is_none()
+        {
+            end += 1;
+        }
+        let paragraph = &lines[start..end];
+        let attributes = std::mem::take(&mut self.pending);
+
+        // Indented paragraphs are literal
+        if lines[start].starts_with(' ') || attributes.style() == Some("literal") {
+            self.push_title(&attributes, out);
+            push_code_block(out, None, &dedent(paragraph));
+            return end;
+        }
+        if matches!(attributes.style(), Some("source" | "listing")) {
+            let language = self.source_language(&attributes);
+            self.push_title(&attributes, out);
+            push_code_block(out, language.as_deref(), paragraph);
+            return end;
+        }
+
+        let first = paragraph[0].trim_start();
+        let admonition = first
+            .split_once(": ")
+            .filter(|(label, _)| {
+                matches!(*label, "NOTE" | "TIP" | "IMPORTANT" | "WARNING" | "CAUTION")
+            })
+            .and_then(|(label, _)| alert_kind(label));
+        let alert = admonition.or_else(|| attributes.style().and_then(alert_kind));
+        let hard_breaks =
+            attributes.has_option("hardbreaks") || attributes.style() == Some("verse");
+
+        let mut text_lines: Vec<&str> = paragraph.to_vec();
+        if admonition.is_some() {
+            text_lines[0] = first.split_once(": ").map_or(first, |(_, rest)| rest);
+        }
+        let text = self.paragraph_text(&text_lines, hard_breaks);
+
+        match alert {
+            Some(alert) => push_quote(out, Some(alert), vec![text]),
+            None if matches!(attributes.style(), Some("quote" | "verse")) => {
+                let mut body = vec![text];
+                body.extend(self.attribution(&attributes));
+                push_quote(out, None, body);
+            }
+            None => {
+                self.push_title(&attributes, out);
+                out.extend([String::new(), text, String::new()]);
+            }
+        }
+        end
+    }
+
+    /// Inline-converted paragraph lines; a trailing ` +` forces a line break
+    fn paragraph_text(&mut self, lines: &[&str], hard_breaks: bool) -> String {
+        let last = lines.len().saturating_sub(1);
+        lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let line = line.trim();
+                match line.strip_suffix(" +") {
+                    Some(line) => format!("{}<br />", self.inline(line)),
+                    None if hard_breaks && index < last => format!("{}<br />", self.inline(line)),
+                    None => self.inline(line),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn list_item(
+        &mut self,
+        lines: &[&str],
+        start: usize,
+        marker: &str,
+        text: &str,
+        out: &mut Vec<String>,
+    ) -> usize {
+        let ordered = marker.starts_with(|c: char| c == '.' || c.is_ascii_digit());
+        let key = if marker.ends_with('.') && !marker.starts_with('.') {
+            "1."
+        } else {
+            marker
+        };
+        let (markdown_marker, width) = if ordered { ("1. ", 3) } else { ("- ", 2) };
+
+        // Nesting follows the order in which markers appear, like in AsciiDoc
+        let depth = match self.list_stack.iter().position(|(open, _)| open == key) {
+            Some(depth) => {
+                self.list_stack.truncate(depth + 1);
+                depth
+            }
+            None => {
+                self.list_stack.push((key.to_string(), width));
+                self.list_stack.len() - 1
+            }
+        };
+        let indent: usize = self.list_stack[..depth]
+            .iter()
+            .map(|(_, width)| width)
+            .sum();
+        let padding = " ".repeat(indent);
+
+        // Checklist items
+        let (checkbox, text) = match text.split_once("] ") {
+            Some(("[x" | "[*", rest)) => ("[x] ", rest),
+            Some(("[ ", rest)) => ("[ ] ", rest),
+            _ => ("", text),
+        };
+
+        // Following lines continue the item text until a blank line or another item
+        let mut end = start + 1;
+        let mut item_lines = vec![text];
+        while let Some(line) = lines.get(end).map(|line| line.trim()).filter(|line| {
+            !line.is_empty()
+                && *line != "+"
+                && parse_list_item(line).is_none()
+                && parse_description_item(line).is_none()
+                && delimiter_kind(line).is_none()
+                && !line.starts_with("//")
+        }) {
+            item_lines.push(line);
+            end += 1;
+        }
+        let text = self.paragraph_text(&item_lines, false);
+        out.push(format!("{padding}{markdown_marker}{checkbox}{text}"));
+
+        // `+` attaches the following block to the item
+        let content_padding = " ".repeat(indent + width);
+        while lines.get(end).is_some_and(|line| line.trim() == "+") {
+            let block_start = end + 1;
+            let Some(first) = lines.get(block_start) else {
+                break;
+            };
+            let block_end = match delimiter_kind(first.trim()) {
+                Some(_) => {
+                    let closing = first.trim();
+                    lines[block_start + 1..]
+                        .iter()
+                        .position(|line| line.trim() == closing)
+                        .map_or(lines.len(), |offset| block_start + offset + 2)
+                }
+                None => lines[block_start..]
+                    .iter()
+                    .position(|line| line.trim().is_empty() || line.trim() == "+")
+                    .map_or(lines.len(), |offset| block_start + offset),
+            };
+
+            let list_stack = std::mem::take(&mut self.list_stack);
+            let mut attached = Vec::new();
+            self.blocks(&lines[block_start..block_end], &mut attached);
+            self.list_stack = list_stack;
+
+            out.push(String::new());
+            out.extend(trim_blank(attached).into_iter().map(|line| {
+                if line.is_empty() {
+                    line
+                } else {
+                    format!("{content_padding}{line}")
+                }
+            }));
+            end = block_end;
+        }
+        end
+    }
+
+    fn description_item(
+        &mut self,
+        lines: &[&str],
+        start: usize,
+        term: &str,
+        definition: &str,
+        out: &mut Vec<String>,
+    ) -> usize {
+        let mut end = start + 1;
+        let mut definition_lines: Vec<&str> = Vec::new();
+        if !definition.is_empty() {
+            definition_lines.push(definition);
+        }
+        while let Some(line) = lines.get(end).map(|line| line.trim()).filter(|line| {
+            !line.is_empty()
+                && parse_description_item(line).is_none()
+                && parse_list_item(line).is_none()
+        }) {
+            definition_lines.push(line);
+            end += 1;
+        }
+
+        out.push(String::new());
+        out.push(self.inline(term));
+        if !definition_lines.is_empty() {
+            out.push(format!(
+                ": {}",
+                self.paragraph_text(&definition_lines, false)
+            ));
+        }
+        out.push(String::new());
+        end
+    }
+
+    fn block_macro(&mut self, name: &str, target: &str, attributes: &str, out: &mut Vec<String>) {
+        let mut block_attributes = std::mem::take(&mut self.pending);
+        block_attributes.positional.clear();
+        block_attributes.parse(attributes);
+
+        match name {
+            "image" => {
+                let alt = block_attributes
+                    .positional(0)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| default_alt(target));
+                let width = block_attributes
+                    .named
+                    .get("width")
+                    .map(String::as_str)
+                    .or_else(|| block_attributes.positional(1));
+                out.extend([
+                    String::new(),
+                    image_markdown(target, &alt, width),
+                    String::new(),
+                ]);
+                if let Some(title) = &block_attributes.title {
+                    out.extend([format!("*{}*", self.inline(title)), String::new()]);
+                }
+            }
+            _ => tracing::debug!("Skipping unsupported AsciiDoc block macro: {name}"),
+        }
+    }
+
+    fn table(
+        &mut self,
+        lines: &[&str],
+        delimiter: &str,
+        attributes: &BlockAttributes,
+        out: &mut Vec<String>,
+    ) {
+        let format = attributes.named.get("format").map(String::as_str);
+        if delimiter.starts_with(',') || format == Some("csv") {
+            push_code_block(out, Some("csv"), lines);
+            return;
+        }
+        let separator = match delimiter.chars().next() {
+            Some(':') => ':',
+            _ => attributes
+                .named
+                .get("separator")
+                .and_then(|separator| separator.chars().next())
+                .unwrap_or('|'),
+        };
+
+        let first_line = lines.iter().position(|line| !line.trim().is_empty());
+        // An implicit header is a single first line followed by a blank line
+        let implicit_header = first_line.is_some_and(|first| {
+            lines
+                .get(first + 1)
+                .is_some_and(|next| next.trim().is_empty())
+        });
+        let has_header = (attributes.has_option("header") || implicit_header)
+            && !attributes.has_option("noheader");
+
+        let cells = split_table_cells(lines, separator);
+        let columns = attributes
+            .named
+            .get("cols")
+            .map(|cols| column_count(cols))
+            .filter(|count| *count > 0)
+            .or_else(|| {
+                first_line.map(|first| split_table_cells(&lines[first..=first], separator).len())
+            })
+            .unwrap_or(1)
+            .max(1);
+
+        let mut rows: Vec<Vec<String>> = cells
+            .chunks(columns)
+            .map(|row| row.iter().map(|cell| self.inline(cell)).collect())
+            .collect();
+        let header = (has_header && !rows.is_empty()).then(|| rows.remove(0));
+        push_table(out, header, rows);
+    }
+
+    /// Source language of a listing: `[source,rust]`, or the `source-language` attribute
+    fn source_language(&self, attributes: &BlockAttributes) -> Option<String> {
+        if !matches!(attributes.style(), Some("source" | "listing") | None) {
+            return None;
+        }
+        attributes
+            .positional(1)
+            .map(str::to_string)
+            .or_else(|| attributes.named.get("language").cloned())
+            .or_else(|| {
+                (attributes.style() == Some("source"))
+                    .then(|| self.attributes.get("source-language").cloned())
+                    .flatten()
+            })
+    }
+
+    /// Block title (`.Title`) shown above the block
+    fn push_title(&mut self, attributes: &BlockAttributes, out: &mut Vec<String>) {
+        if let Some(title) = &attributes.title {
+            out.extend([String::new(), format!("**{}**", self.inline(title))]);
+        }
+    }
+
+    /// Attribution line of a quote: `[quote, author, source]`
+    fn attribution(&mut self, attributes: &BlockAttributes) -> Vec<String> {
+        let parts: Vec<String> = [attributes.positional(1), attributes.positional(2)]
+            .into_iter()
+            .flatten()
+            .map(|part| self.inline(part))
+            .collect();
+        if parts.is_empty() {
+            Vec::new()
+        } else {
+            vec![String::new(), format!("— {}", parts.join(", "))]
+        }
+    }
+
+    /// Replace `{name}` attribute references; unknown references are kept
+    fn substitute_attributes(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(open) = rest.find('{') {
+            out.push_str(&rest[..open]);
+            let after = &rest[open + 1..];
+            let value = after.find('}').and_then(|close| {
+                let name = &after[..close];
+                let value = self
+                    .attributes
+                    .get(name)
+                    .map(String::as_str)
+                    .or_else(|| builtin_attribute(name))?;
+                Some((value, close))
+            });
+            match value {
+                Some((value, close)) => {
+                    out.push_str(value);
+                    rest = &after[close + 1..];
+                }
+                None => {
+                    out.push('{');
+                    rest = after;
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// Convert inline markup to Markdown, escaping everything else
+    fn inline(&mut self, text: &str) -> String {
+        let text = self.substitute_attributes(text);
+        let mut out = String::with_capacity(text.len());
+        let mut previous: Option<char> = None;
+        let mut rest = text.as_str();
+        while let Some(c) = rest.chars().next() {
+            if c == '\\' {
+                if let Some(escaped) = rest[1..].chars().next() {
+                    push_escaped(&mut out, escaped);
+                    previous = Some(escaped);
+                    rest = &rest[1 + escaped.len_utf8()..];
+                    continue;
+                }
+            }
+            if let Some((markdown, consumed)) = self.inline_markup(rest, previous) {
+                out.push_str(&markdown);
+                previous = rest[..consumed].chars().last();
+                rest = &rest[consumed..];
+                continue;
+            }
+            push_escaped(&mut out, c);
+            previous = Some(c);
+            rest = &rest[c.len_utf8()..];
+        }
+        out
+    }
+
+    /// Inline markup starting at the beginning of `text`, with the number of bytes it spans
+    fn inline_markup(&mut self, text: &str, previous: Option<char>) -> Option<(String, usize)> {
+        let at_word_start = previous.is_none_or(|c| !c.is_alphanumeric());
+
+        // Passthroughs
+        if let Some(inner) = unconstrained(text, "+++") {
+            return Some((inner.to_string(), inner.len() + 6));
+        }
+        if let Some(inner) = unconstrained(text, "++") {
+            return Some((escape(inner), inner.len() + 4));
+        }
+        if let Some(inner) = constrained(text, '+', previous) {
+            return Some((escape(inner), inner.len() + 2));
+        }
+        if let Some((inner, consumed)) = macro_content(text, "pass:") {
+            return Some((inner.to_string(), consumed));
+        }
+
+        // Monospace; `+literal+` inside backticks is shown verbatim
+        let code = unconstrained(text, "``")
+            .map(|inner| (inner, inner.len() + 4))
+            .or_else(|| constrained(text, '`', previous).map(|inner| (inner, inner.len() + 2)));
+        if let Some((inner, consumed)) = code {
+            let inner = inner
+                .strip_prefix('+')
+                .and_then(|inner| inner.strip_suffix('+'))
+                .unwrap_or(inner);
+            return Some((code_span(inner), consumed));
+        }
+
+        // Strong, emphasis and highlight, unconstrained (`**`) before constrained (`*`)
+        for (mark, open, close) in [
+            ('*', "**", "**"),
+            ('_', "*", "*"),
+            ('#', "<mark>", "</mark>"),
+        ] {
+            let double: String = [mark, mark].iter().collect();
+            let quoted = unconstrained(text, &double)
+                .map(|inner| (inner, inner.len() + 4))
+                .or_else(|| {
+                    constrained(text, mark, previous).map(|inner| (inner, inner.len() + 2))
+                });
+            if let Some((inner, consumed)) = quoted {
+                return Some((format!("{open}{}{close}", self.inline(inner)), consumed));
+            }
+        }
+
+        // Roles on highlighted text: `[.underline]#text#`
+        if let Some(role_end) = text.strip_prefix("[.").and_then(|rest| rest.find("]#")) {
+            let role = &text[2..2 + role_end];
+            let quoted = &text[2 + role_end + 1..];
+            if let Some(inner) = unconstrained(quoted, "#").filter(|_| !quoted.starts_with("##")) {
+                let inner_markdown = self.inline(inner);
+                let markdown = match role {
+                    "line-through" => format!("~~{inner_markdown}~~"),
+                    "underline" => format!("<u>{inner_markdown}</u>"),
+                    _ => inner_markdown,
+                };
+                return Some((markdown, 2 + role_end + 1 + inner.len() + 2));
+            }
+        }
+
+        // Superscript and subscript
+        for (mark, tag) in [('^', "sup"), ('~', "sub")] {
+            if let Some(inner) = text
+                .strip_prefix(mark)
+                .and_then(|rest| rest.find(mark).map(|end| &rest[..end]))
+                .filter(|inner| !inner.is_empty() && !inner.contains(char::is_whitespace))
+            {
+                return Some((format!("<{tag}>{}</{tag}>", escape(inner)), inner.len() + 2));
+            }
+        }
+
+        // Cross references: `<<id>>`, `<<id,text>>`, `<<other.adoc#id,text>>`
+        if let Some(inner) = text
+            .strip_prefix("<<")
+            .and_then(|rest| rest.find(">>").map(|end| &rest[..end]))
+        {
+            let (target, label) = match inner.split_once(',') {
+                Some((target, label)) => (target.trim(), Some(label.trim())),
+                None => (inner.trim(), None),
+            };
+            return Some((self.cross_reference(target, label), inner.len() + 4));
+        }
+        if let Some(anchor) = text
+            .strip_prefix("[[")
+            .and_then(|rest| rest.find("]]").map(|end| &rest[..end]))
+        {
+            let id = anchor.split(',').next().unwrap_or(anchor).trim();
+            return Some((format!(r#"<a id="{}"></a>"#, escape(id)), anchor.len() + 4));
+        }
+
+        if !at_word_start {
+            return None;
+        }
+
+        // Inline macros
+        if let Some((target, label, consumed)) = macro_with_target(text, "xref:") {
+            let label = (!label.is_empty()).then_some(label);
+            return Some((self.cross_reference(target, label), consumed));
+        }
+        for prefix in ["link:", "mailto:"] {
+            if let Some((target, label, consumed)) = macro_with_target(text, prefix) {
+                let url = if prefix == "mailto:" {
+                    format!("mailto:{target}")
+                } else {
+                    target.to_string()
+                };
+                let label = link_label(label).unwrap_or(target);
+                return Some((
+                    format!("[{}]({})", self.inline(label), link_destination(&url)),
+                    consumed,
+                ));
+            }
+        }
+        if let Some((target, alt, consumed)) = macro_with_target(text, "image:") {
+            let alt = split_attribute_list(alt)
+                .first()
+                .filter(|alt| !alt.is_empty())
+                .cloned()
+                .unwrap_or_else(|| default_alt(target));
+            return Some((image_markdown(target, &alt, None), consumed));
+        }
+        if let Some((inner, consumed)) = macro_content(text, "kbd:") {
+            let keys: Vec<String> = inner
+                .split('+')
+                .map(|key| format!("<kbd>{}</kbd>", escape(key.trim())))
+                .collect();
+            return Some((keys.join("+"), consumed));
+        }
+        if let Some((inner, consumed)) = macro_content(text, "btn:") {
+            return Some((format!("**\\[{}\\]**", escape(inner)), consumed));
+        }
+        if let Some((menu, items, consumed)) = macro_with_target(text, "menu:") {
+            let path: Vec<String> = std::iter::once(menu)
+                .chain(
+                    items
+                        .split('>')
+                        .map(str::trim)
+                        .filter(|item| !item.is_empty()),
+                )
+                .map(escape)
+                .collect();
+            return Some((format!("**{}**", path.join(" › ")), consumed));
+        }
+        for prefix in ["stem:", "latexmath:"] {
+            if let Some((inner, consumed)) = macro_content(text, prefix) {
+                return Some((format!("${inner}$"), consumed));
+            }
+        }
+        if let Some((_, note, consumed)) = macro_with_target(text, "footnote:") {
+            let note = self.inline(note);
+            self.footnotes.push(note);
+            return Some((format!("[^{}]", self.footnotes.len()), consumed));
+        }
+
+        // URLs, optionally with link text: `https://example.com[Example]`
+        if URL_PREFIXES.iter().any(|prefix| text.starts_with(prefix)) {
+            let end = text
+                .find(|c: char| c.is_whitespace() || matches!(c, '[' | '<' | '>' | '"'))
+                .unwrap_or(text.len());
+            if text[end..].starts_with('[') {
+                if let Some((label, consumed)) = bracketed(&text[end..]) {
+                    let url = &text[..end];
+                    let label = link_label(label).unwrap_or(url);
+                    return Some((
+                        format!("[{}]({})", self.inline(label), link_destination(url)),
+                        end + consumed,
+                    ));
+                }
+            }
+            let url = text[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')', '\'']);
+            return Some((format!("<{url}>"), url.len()));
+        }
+        None
+    }
+
+    fn cross_reference(&mut self, target: &str, label: Option<&str>) -> String {
+        let (url, fallback) = match target.split_once('#') {
+            // Reference into another document
+            Some((document, id)) if !document.is_empty() => {
+                let url = if id.is_empty() {
+                    document.to_string()
+                } else {
+                    target.to_string()
+                };
+                (url, document.to_string())
+            }
+            Some((_, id)) => (format!("#{id}"), self.section_label(id)),
+            None if target.ends_with(".adoc") => (target.to_string(), target.to_string()),
+            None => (format!("#{target}"), self.section_label(target)),
+        };
+        let label = match label {
+            Some(label) => self.inline(label),
+            None => escape(&fallback),
+        };
+        format!("[{label}]({})", link_destination(&url))
+    }
+
+    fn section_label(&self, id: &str) -> String {
+        self.section_titles
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| format!("[{id}]"))
+    }
+}
+
+/// Collect section ids and titles for cross references
+fn collect_section_titles(lines: &[&str]) -> HashMap<String, String> {
+    let mut attributes = HashMap::new();
+    let mut titles = HashMap::new();
+    let mut explicit_id = None;
+    for line in lines {
+        if let Some((name, Some(value))) = parse_attribute_entry(line) {
+            attributes.insert(name.to_string(), value.to_string());
+        } else if let Some(id) = line
+            .strip_prefix("[[")
+            .and_then(|anchor| anchor.strip_suffix("]]"))
+        {
+            explicit_id = Some(id.split(',').next().unwrap_or(id).trim().to_string());
+        } else if let Some((_, title)) = parse_heading(line) {
+            let id = explicit_id
+                .take()
+                .unwrap_or_else(|| section_id(title, &attributes));
+            titles.insert(id, title.to_string());
+        } else if !line.starts_with('[') {
+            explicit_id = None;
+        }
+    }
+    titles
+}
+
+/// Section id the way Asciidoctor derives it (`_section_title` by default)
+fn section_id(title: &str, attributes: &HashMap<String, String>) -> String {
+    let prefix = attributes.get("idprefix").map_or("_", String::as_str);
+    let separator = attributes.get("idseparator").map_or("_", String::as_str);
+    let mut id = String::from(prefix);
+    let mut pending_separator = false;
+    for c in title.to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            if pending_separator && id.len() > prefix.len() {
+                id.push_str(separator);
+            }
+            pending_separator = false;
+            id.push(c);
+        } else if c.is_whitespace() || matches!(c, '-' | '.' | '_') {
+            pending_separator = true;
+        }
+    }
+    id
+}
+
+/// `:name: value` sets an attribute, `:name!:` unsets it
+fn parse_attribute_entry(line: &str) -> Option<(&str, Option<&str>)> {
+    let rest = line.strip_prefix(':')?;
+    let (name, value) = rest.split_once(':')?;
+    if name.is_empty()
+        || name.contains(char::is_whitespace)
+        || !(value.is_empty() || value.starts_with(' '))
+    {
+        return None;
+    }
+    match name.strip_suffix('!') {
+        Some(name) => Some((name, None)),
+        None => Some((name, Some(value.trim()))),
+    }
+}
+
+/// Section title: `== Title` (or Markdown-style `## Title`), with its level (0 for `=`)
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let marker = line.chars().next().filter(|c| *c == '=' || *c == '#')?;
+    let level = line.chars().take_while(|c| *c == marker).count();
+    let title = line[level..].strip_prefix(' ')?.trim();
+    (level <= 6 && !title.is_empty()).then_some((level - 1, title))
+}
+
+/// Kind of a block delimiter line, identified by its character (`o` for open blocks)
+fn delimiter_kind(line: &str) -> Option<char> {
+    if line == "--" {
+        return Some('o');
+    }
+    if line.starts_with("```") {
+        return Some('`');
+    }
+    if matches!(line, "|===" | ",===" | ":===") {
+        return line.chars().next();
+    }
+    let first = line.chars().next()?;
+    (line.len() >= 4 && "-.=*_+/".contains(first) && line.chars().all(|c| c == first))
+        .then_some(first)
+}
+
+fn is_thematic_break(line: &str) -> bool {
+    matches!(line, "'''" | "---" | "- - -" | "***" | "* * *")
+}
+
+/// Block macro line: `name::target[attributes]`
+fn parse_block_macro(line: &str) -> Option<(&str, &str, &str)> {
+    let (name, rest) = line.split_once("::")?;
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return None;
+    }
+    let (target, attributes) = rest.strip_suffix(']')?.split_once('[')?;
+    (!target.contains(char::is_whitespace)).then_some((name, target, attributes))
+}
+
+/// List item marker (`*`, `**`, `-`, `.`, `1.`, …) and text
+fn parse_list_item(line: &str) -> Option<(&str, &str)> {
+    let (marker, text) = line.split_once(' ')?;
+    let text = text.trim_start();
+    if text.is_empty() {
+        return None;
+    }
+    let is_marker = marker == "-"
+        || (marker.len() <= 5 && marker.chars().all(|c| c == '*'))
+        || (marker.len() <= 5 && marker.chars().all(|c| c == '.'))
+        || marker
+            .strip_suffix('.')
+            .is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()));
+    is_marker.then_some((marker, text))
+}
+
+/// Description list item: `term:: definition` (or `:::`, `::::`, `;;`)
+fn parse_description_item(line: &str) -> Option<(&str, &str)> {
+    for separator in ["::::", ":::", "::", ";;"] {
+        let Some(position) = line.find(separator) else {
+            continue;
+        };
+        let term = line[..position].trim();
+        let rest = &line[position + separator.len()..];
+        if !term.is_empty() && (rest.is_empty() || rest.starts_with(' ')) {
+            return Some((term, rest.trim()));
+        }
+    }
+    None
+}
+
+/// Split a block attribute list on commas outside double quotes
+fn split_attribute_list(list: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in list.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            ',' if !quoted => items.push(std::mem::take(&mut current).trim().to_string()),
+            _ => current.push(c),
+        }
+    }
+    if !current.trim().is_empty() || !items.is_empty() {
+        items.push(current.trim().to_string());
+    }
+    items
+}
+
+/// Number of columns in a `cols` attribute (`"1,2,3"`, `3*`, `2*,1`)
+fn column_count(cols: &str) -> usize {
+    cols.split([',', ';'])
+        .map(|spec| {
+            spec.split_once('*')
+                .and_then(|(count, _)| count.trim().parse().ok())
+                .unwrap_or(1)
+        })
+        .sum()
+}
+
+/// Split table lines into cells; cell specifiers such as `2+` or `a` before a separator
+/// are dropped
+fn split_table_cells(lines: &[&str], separator: char) -> Vec<String> {
+    let mut cells: Vec<String> = Vec::new();
+    for line in lines.iter().map(|line| line.trim()) {
+        if line.is_empty() {
+            continue;
+        }
+        let mut parts = line.split(separator);
+        let leading = parts.next().unwrap_or_default().trim();
+        let is_specifier = leading
+            .chars()
+            .all(|c| c.is_ascii_digit() || ".+*^<>aehlmsdv".contains(c));
+        if !leading.is_empty() && (!is_specifier || !line.contains(separator)) {
+            // Text before the first separator continues the previous cell
+            match cells.last_mut() {
+                Some(cell) => {
+                    cell.push(' ');
+                    cell.push_str(leading);
+                }
+                None => cells.push(leading.to_string()),
+            }
+        }
+        for part in parts {
+            cells.push(part.trim().to_string());
+        }
+    }
+    cells
+}
+
+/// Text in the brackets at the start of `text`, and the number of bytes including brackets
+fn bracketed(text: &str) -> Option<(&str, usize)> {
+    let rest = text.strip_prefix('[')?;
+    let mut depth = 0;
+    for (index, c) in rest.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' if depth == 0 => return Some((&rest[..index], index + 2)),
+            ']' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `name:[content]` inline macro
+fn macro_content<'a>(text: &'a str, prefix: &str) -> Option<(&'a str, usize)> {
+    let (content, consumed) = bracketed(text.strip_prefix(prefix)?)?;
+    Some((content, prefix.len() + consumed))
+}
+
+/// `name:target[content]` inline macro
+fn macro_with_target<'a>(text: &'a str, prefix: &str) -> Option<(&'a str, &'a str, usize)> {
+    let rest = text.strip_prefix(prefix)?;
+    let target_end = rest.find(['[', ' '])?;
+    let (content, consumed) = bracketed(&rest[target_end..])?;
+    Some((
+        &rest[..target_end],
+        content,
+        prefix.len() + target_end + consumed,
+    ))
+}
+
+/// Link text without attributes such as `window=_blank` or the trailing `^`
+fn link_label(label: &str) -> Option<&str> {
+    let label = label
+        .split(",window=")
+        .next()
+        .unwrap_or(label)
+        .trim_end_matches('^')
+        .trim()
+        .trim_matches('"');
+    (!label.is_empty()).then_some(label)
+}
+
+/// Quoted text that may start and end inside words: `**text**`
+fn unconstrained<'a>(text: &'a str, mark: &str) -> Option<&'a str> {
+    let rest = text.strip_prefix(mark)?;
+    let inner = &rest[..rest.find(mark)?];
+    let padded = inner.starts_with(char::is_whitespace) || inner.ends_with(char::is_whitespace);
+    (!inner.is_empty() && !padded).then_some(inner)
+}
+
+/// Quoted text bounded by non-word characters: `*text*`
+fn constrained(text: &str, mark: char, previous: Option<char>) -> Option<&str> {
+    if previous.is_some_and(|c| c.is_alphanumeric() || c == mark || c == '\\') {
+        return None;
+    }
+    let rest = text.strip_prefix(mark)?;
+    if rest.starts_with(char::is_whitespace) || rest.starts_with(mark) {
+        return None;
+    }
+    let mut search = 0;
+    while let Some(position) = rest[search..].find(mark) {
+        let end = search + position;
+        let followed = rest[end + mark.len_utf8()..].chars().next();
+        if end > 0
+            && !rest[..end].ends_with(char::is_whitespace)
+            && followed.is_none_or(|c| !c.is_alphanumeric() && c != mark)
+        {
+            return Some(&rest[..end]);
+        }
+        search = end + mark.len_utf8();
+    }
+    None
+}
+
+/// Remove callout markers (`<1>`, `// <2>`) from the end of a listing line
+fn strip_callouts(line: &str) -> String {
+    fn strip_marker(line: &str) -> Option<&str> {
+        let rest = line.strip_suffix('>')?;
+        let open = rest.rfind('<')?;
+        let marker = &rest[open + 1..];
+        (marker == "." || (!marker.is_empty() && marker.chars().all(|c| c.is_ascii_digit())))
+            .then(|| rest[..open].trim_end())
+    }
+    let mut line = line.trim_end();
+    while let Some(stripped) = strip_marker(line) {
+        line = stripped;
+    }
+    let line = line.strip_suffix("//").map_or(line, str::trim_end);
+    line.to_string()
+}
+
+fn image_markdown(target: &str, alt: &str, width: Option<&str>) -> String {
+    match width {
+        Some(width) => format!(
+            r#"<img src="{}" alt="{}" width="{}" />"#,
+            html_escape::encode_double_quoted_attribute(target),
+            html_escape::encode_double_quoted_attribute(alt),
+            html_escape::encode_double_quoted_attribute(width),
+        ),
+        None => format!("![{}]({})", escape(alt), link_destination(target)),
+    }
+}
+
+/// Asciidoctor's default alt text: the file name without extension, dashes as spaces
+fn default_alt(target: &str) -> String {
+    Path::new(target)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(target)
+        .replace(['-', '_'], " ")
+}
+
+/// Built-in character replacement attributes
+fn builtin_attribute(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "nbsp" => "\u{a0}",
+        "sp" | "space" => " ",
+        "empty" | "blank" => "",
+        "zwsp" => "\u{200b}",
+        "startsb" => "[",
+        "endsb" => "]",
+        "vbar" => "|",
+        "caret" => "^",
+        "asterisk" => "*",
+        "tilde" => "~",
+        "plus" => "+",
+        "backslash" => "\\",
+        "backtick" => "`",
+        "amp" => "&",
+        "lt" => "<",
+        "gt" => ">",
+        "apos" => "'",
+        "quot" => "\"",
+        "deg" => "°",
+        "brvbar" => "¦",
+        _ => return None,
+    })
+}
+
+fn trim_blank(mut lines: Vec<String>) -> Vec<String> {
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    let start = lines
+        .iter()
+        .position(|line| !line.is_empty())
+        .unwrap_or(lines.len());
+    lines.split_off(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn render(adoc: &str) -> String {
+        render_asciidoc(adoc, "/docs/guide.adoc", &RenderOptions::default())
+            .unwrap()
+            .html
+    }
+
+    #[test]
+    fn test_document_title_and_sections() {
+        let html = render(indoc! {"
+            = User Guide
+            Jane Doe <jane@example.com>
+            :toc:
+
+            == Getting Started
+
+            [[custom-id]]
+            === Install it
+        "});
+
+        assert!(html.contains(r#"<h1 id="_user_guide">User Guide</h1>"#));
+        assert!(html.contains("Jane Doe"));
+        assert!(html.contains(r#"<h2 id="_getting_started">Getting Started</h2>"#));
+        assert!(html.contains(r#"<h3 id="custom-id">Install it</h3>"#));
+        assert!(!html.contains(":toc:"));
+    }
+
+    #[test]
+    fn test_inline_formatting() {
+        let html = render(
+            "Use *bold*, _italic_, `code_here`, **un**constrained, #marked# and H~2~O in snake_case_name.",
+        );
+
+        assert!(html.contains("<strong>bold</strong>"));
+        assert!(html.contains("<em>italic</em>"));
+        assert!(html.contains("<code>code_here</code>"));
+        assert!(html.contains("<strong>un</strong>constrained"));
+        assert!(html.contains("<mark>marked</mark>"));
+        assert!(html.contains("H<sub>2</sub>O"));
+        assert!(html.contains("snake_case_name"));
+    }
+
+    #[test]
+    fn test_links_and_cross_references() {
+        let html = render(indoc! {"
+            :project: Arto
+
+            See https://example.com[the site^], link:other.adoc[another page],
+            <<_details>> and {project}.
+
+            == Details
+        "});
+
+        assert!(html.contains(r#"<a href="https://example.com">the site</a>"#));
+        assert!(html.contains("another page"));
+        assert!(html.contains(r##"<a href="#_details">Details</a>"##));
+        assert!(html.contains("and Arto."));
+    }
+
+    #[test]
+    fn test_source_blocks_and_callouts() {
+        let html = render(indoc! {r#"
+            [source,rust]
+            ----
+            fn main() { // <1>
+                println!("<hi>");
+            }
+            ----
+            <1> Entry point
+        "#});
+
+        assert!(html.contains(
+            r#"<code class="language-rust">fn main() {
+    println!("#
+        ));
+        assert!(html.contains("&lt;hi&gt;"));
+        assert!(!html.contains("{ //"));
+    }
+
+    #[test]
+    fn test_admonitions() {
+        let html = render(indoc! {"
+            NOTE: A short note.
+
+            [WARNING]
+            ====
+            A *longer* warning.
+            ====
+        "});
+
+        assert!(html.contains("markdown-alert-note"));
+        assert!(html.contains("A short note."));
+        assert!(html.contains("markdown-alert-warning"));
+        assert!(html.contains("<strong>longer</strong>"));
+    }
+
+    #[test]
+    fn test_nested_lists() {
+        let html = render(indoc! {"
+            * one
+            ** nested
+            * two
+            . first
+            . second
+            * [x] done
+        "});
+
+        assert!(html.contains("<li>nested</li>"));
+        assert!(html.contains("<li>second</li>"));
+        assert!(html.contains("checkbox"));
+    }
+
+    #[test]
+    fn test_tables() {
+        let html = render(indoc! {"
+            [cols=\"1,2\"]
+            |===
+            |Name |Description
+
+            |arto
+            |Markdown viewer
+            |===
+        "});
+
+        assert!(html.contains("<th>Name</th>"));
+        assert!(html.contains("<td>Markdown viewer</td>"));
+    }
+
+    #[test]
+    fn test_images() {
+        let html =
+            render("image::images/diagram-overview.png[]\n\nInline image:icon.svg[Icon] here.");
+
+        assert!(html.contains(r#"<img src="images/diagram-overview.png" alt="diagram overview">"#));
+        assert!(html.contains(r#"<img src="icon.svg" alt="Icon">"#));
+    }
+
+    #[test]
+    fn test_comments_are_dropped() {
+        let html = render(indoc! {"
+            // single line comment
+            ////
+            block comment
+            ////
+            Visible
+        "});
+
+        assert!(!html.contains("comment"));
+        assert!(html.contains("<p>Visible</p>"));
+    }
+
+    #[test]
+    fn test_passthrough_html_is_sanitized() {
+        let rendered = render_asciidoc(
+            "Text +++<script>alert(1)</script>+++",
+            "/docs/guide.adoc",
+            &RenderOptions::default(),
+        )
+        .unwrap();
+
+        assert!(!rendered.html.contains("<script>"));
+        assert_eq!(rendered.stripped, vec!["<script>"]);
+    }
+
+    #[test]
+    fn test_strip_callouts() {
+        assert_eq!(strip_callouts("let x = 1; // <1>"), "let x = 1;");
+        assert_eq!(strip_callouts("foo <1> <2>"), "foo");
+        assert_eq!(strip_callouts("a < b > c"), "a < b > c");
+    }
+}
@@ -0,0 +1,1103 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::{
+    alert_kind, code_span, dedent, escape, indent_of, link_destination, push_code_block,
+    push_escaped, push_heading, push_quote, push_table,
+};
+use crate::markdown::{render_document, RenderOptions, RenderedDocument};
+
+/// Roles rendered as inline code (Sphinx domain roles such as `:py:func:` included)
+const CODE_ROLES: [&str; 23] = [
+    "code", "literal", "file", "samp", "command", "program", "envvar", "option", "class", "func",
+    "meth", "attr", "mod", "obj", "exc", "data", "const", "type", "member", "var", "macro",
+    "struct", "enum",
+];
+
+/// Render a reStructuredText document to HTML, sanitized like Markdown
+pub fn render_rst(
+    content: &str,
+    base_path: impl AsRef<Path>,
+    render_options: &RenderOptions,
+) -> Result<RenderedDocument> {
+    render_document(to_markdown(content), base_path, render_options)
+}
+
+/// Translate reStructuredText to Markdown
+fn to_markdown(content: &str) -> String {
+    let content = content.replace("\r\n", "\n").replace('\t', "        ");
+    let lines: Vec<&str> = content.lines().map(str::trim_end).collect();
+    let mut converter = Converter {
+        title_styles: Vec::new(),
+        targets: collect_targets(&lines),
+        pending_id: None,
+    };
+    let mut out = Vec::new();
+    converter.blocks(&lines, &mut out);
+    out.join("\n")
+}
+
+struct Converter {
+    /// Section adornments (character, with overline) in order of first use; the index is
+    /// the section level
+    title_styles: Vec<(char, bool)>,
+    /// Named hyperlink targets (`.. _name: https://…`), keyed by normalized name
+    targets: HashMap<String, String>,
+    /// Id set by a `.. _label:` target for the section that follows it
+    pending_id: Option<String>,
+}
+
+impl Converter {
+    fn blocks(&mut self, lines: &[&str], out: &mut Vec<String>) {
+        let mut i = 0;
+        while i < lines.len() {
+            let line = lines[i];
+            if line.trim().is_empty() {
+                out.push(String::new());
+                i += 1;
+            } else if indent_of(line) > 0 {
+                // Indented text without a preceding construct is a block quote
+                let end = block_end(lines, i, 1);
+                let mut body = Vec::new();
+                self.blocks(&dedent(&lines[i..end]), &mut body);
+                push_quote(out, None, trim_blank(body));
+                i = end;
+            } else if line == ".." || line.starts_with(".. ") {
+                i = self.explicit_markup(lines, i, out);
+            } else if let Some(end) = self.section_title(lines, i, out) {
+                i = end;
+            } else if is_adornment(line)
+                && line.len() >= 4
+                && lines.get(i + 1).is_none_or(|next| next.trim().is_empty())
+            {
+                out.extend([String::new(), "***".to_string(), String::new()]);
+                i += 1;
+            } else if let Some(end) = self.grid_table(lines, i, out) {
+                i = end;
+            } else if let Some(end) = self.simple_table(lines, i, out) {
+                i = end;
+            } else if let Some((marker, width)) = list_marker(line) {
+                i = self.list_item(lines, i, &marker, width, out);
+            } else if let Some((name, value)) = parse_field(line) {
+                // Field lists (`:Author: …`) are shown as one line per field
+                let end = block_end(lines, i + 1, 1);
+                let text = std::iter::once(value)
+                    .chain(lines[i + 1..end].iter().map(|line| line.trim()))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                out.push(format!(
+                    "**{}:** {}  ",
+                    escape(name),
+                    self.inline(text.trim())
+                ));
+                i = end;
+            } else {
+                i = self.paragraph(lines, i, out);
+            }
+        }
+    }
+
+    fn paragraph(&mut self, lines: &[&str], start: usize, out: &mut Vec<String>) -> usize {
+        // A single line directly followed by indented text is a definition list item
+        if lines
+            .get(start + 1)
+            .is_some_and(|next| indent_of(next) > 0 && !next.trim().is_empty())
+        {
+            return self.definition(lines, start, out);
+        }
+
+        let mut end = start + 1;
+        while end < lines.len() && !lines[end].trim().is_empty() && indent_of(lines[end]) == 0 {
+            end += 1;
+        }
+        let text = lines[start..end].join(" ");
+
+        // `text::` introduces a literal block; the marker is shown as a single colon
+        let (text, literal) = match text.strip_suffix("::") {
+            Some(before) if before.is_empty() || before.ends_with(' ') => {
+                (before.trim_end().to_string(), true)
+            }
+            Some(before) => (format!("{before}:"), true),
+            None => (text, false),
+        };
+        if !text.is_empty() {
+            self.pending_id = None;
+            out.extend([String::new(), self.inline(&text), String::new()]);
+        }
+
+        if literal {
+            let mut next = end;
+            while next < lines.len() && lines[next].trim().is_empty() {
+                next += 1;
+            }
+            if next < lines.len() && indent_of(lines[next]) > 0 {
+                let block_end = block_end(lines, next, 1);
+                push_code_block(out, None, &dedent(&lines[next..block_end]));
+                return block_end;
+            }
+        }
+        end
+    }
+
+    fn definition(&mut self, lines: &[&str], start: usize, out: &mut Vec<String>) -> usize {
+        let end = block_end(lines, start + 1, 1);
+        let mut body = Vec::new();
+        self.blocks(&dedent(&lines[start + 1..end]), &mut body);
+
+        out.push(String::new());
+        out.push(self.inline(lines[start]));
+        for (index, line) in trim_blank(body).into_iter().enumerate() {
+            out.push(match index {
+                0 => format!(": {line}"),
+                _ if line.is_empty() => line,
+                _ => format!("  {line}"),
+            });
+        }
+        out.push(String::new());
+        end
+    }
+
+    fn list_item(
+        &mut self,
+        lines: &[&str],
+        start: usize,
+        marker: &str,
+        width: usize,
+        out: &mut Vec<String>,
+    ) -> usize {
+        let end = block_end(lines, start + 1, width);
+        let item: Vec<&str> = std::iter::once(&lines[start][width..])
+            .chain(
+                lines[start + 1..end]
+                    .iter()
+                    .map(|line| line.get(width..).unwrap_or("")),
+            )
+            .collect();
+        let mut body = Vec::new();
+        self.blocks(&item, &mut body);
+
+        let padding = " ".repeat(marker.len());
+        for (index, line) in trim_blank(body).into_iter().enumerate() {
+            out.push(match index {
+                0 => format!("{marker}{line}"),
+                _ if line.is_empty() => line,
+                _ => format!("{padding}{line}"),
+            });
+        }
+        end
+    }
+
+    /// Convert a section title (underlined, optionally overlined) starting at `start`
+    fn section_title(
+        &mut self,
+        lines: &[&str],
+        start: usize,
+        out: &mut Vec<String>,
+    ) -> Option<usize> {
+        let line = lines[start];
+        let (title, adornment, overline, end) = if is_adornment(line) {
+            let title = lines.get(start + 1)?.trim();
+            let underline = *lines.get(start + 2)?;
+            if title.is_empty() || underline != line {
+                return None;
+            }
+            (title, underline, true, start + 3)
+        } else {
+            let underline = *lines.get(start + 1)?;
+            let title_width = line.chars().count();
+            if !is_adornment(underline) || underline.len() < title_width.min(3) {
+                return None;
+            }
+            (line, underline, false, start + 2)
+        };
+
+        let style = (adornment.chars().next()?, overline);
+        let level = match self.title_styles.iter().position(|s| *s == style) {
+            Some(index) => index + 1,
+            None => {
+                self.title_styles.push(style);
+                self.title_styles.len()
+            }
+        };
+        let id = self.pending_id.take().unwrap_or_else(|| slug(title));
+        push_heading(out, level, &self.inline(title), &id);
+        Some(end)
+    }
+
+    /// Handle `..` blocks: directives, hyperlink targets, footnotes and comments
+    fn explicit_markup(&mut self, lines: &[&str], start: usize, out: &mut Vec<String>) -> usize {
+        let rest = lines[start].trim_start_matches('.').trim();
+        let end = block_end(lines, start + 1, 1);
+        let block = dedent(&lines[start + 1..end]);
+
+        if let Some(target) = rest.strip_prefix('_') {
+            // `.. _label:` names the following section; targets with a URL were collected up front
+            if let Some(label) = target.strip_suffix(':') {
+                self.pending_id = Some(slug(label));
+            }
+            return end;
+        }
+        if let Some(footnote) = rest.strip_prefix('[') {
+            if let Some((label, text)) = footnote.split_once(']') {
+                let text = std::iter::once(text.trim())
+                    .chain(block.iter().map(|line| line.trim()))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                out.extend([
+                    String::new(),
+                    format!("<sup>{}</sup> {}", escape(label), self.inline(text.trim())),
+                    String::new(),
+                ]);
+            }
+            return end;
+        }
+        // Anything else that isn't a directive (including substitution definitions) is a comment
+        let Some((name, args)) = rest.split_once("::").filter(|_| !rest.starts_with('|')) else {
+            return end;
+        };
+
+        // Directive options directly follow the directive line
+        let mut options = HashMap::new();
+        let mut body_start = 0;
+        while let Some((key, value)) = block.get(body_start).and_then(|line| parse_field(line)) {
+            options.insert(key, value);
+            body_start += 1;
+        }
+        self.directive(
+            &name.trim().to_ascii_lowercase(),
+            args.trim(),
+            &options,
+            &block[body_start..],
+            out,
+        );
+        end
+    }
+
+    fn directive(
+        &mut self,
+        name: &str,
+        args: &str,
+        options: &HashMap<&str, &str>,
+        body: &[&str],
+        out: &mut Vec<String>,
+    ) {
+        // Directive arguments on the first line continue into the body for text directives
+        let content: Vec<&str> = std::iter::once(args)
+            .filter(|args| !args.is_empty())
+            .chain(body.iter().copied())
+            .collect();
+
+        match name {
+            "code-block" | "code" | "sourcecode" => {
+                push_code_block(out, args.split_whitespace().next(), &trim_blank_lines(body));
+            }
+            "math" => push_code_block(out, Some("math"), &trim_blank_lines(&content)),
+            "image" | "figure" => {
+                let alt = options.get("alt").copied().unwrap_or_default();
+                let image = match options.get("width") {
+                    Some(width) => format!(
+                        r#"<img src="{}" alt="{}" width="{}" />"#,
+                        html_escape::encode_double_quoted_attribute(args),
+                        html_escape::encode_double_quoted_attribute(alt),
+                        html_escape::encode_double_quoted_attribute(width),
+                    ),
+                    None => format!("![{}]({})", escape(alt), link_destination(args)),
+                };
+                let image = match options.get("target") {
+                    Some(target) => format!("[{image}]({})", link_destination(target)),
+                    None => image,
+                };
+                out.extend([String::new(), image, String::new()]);
+                // Figure captions and legends
+                self.blocks(body, out);
+            }
+            "raw" => {
+                if args.split_whitespace().any(|format| format == "html") {
+                    out.push(String::new());
+                    out.extend(body.iter().map(|line| line.to_string()));
+                    out.push(String::new());
+                }
+            }
+            "toctree" => {
+                out.push(String::new());
+                for entry in body.iter().map(|line| line.trim()) {
+                    if entry.is_empty() || entry == "self" || entry.contains('*') {
+                        continue;
+                    }
+                    let (title, target) = split_reference(entry);
+                    out.push(format!(
+                        "- [{}]({})",
+                        escape(title),
+                        link_destination(&document_url(target))
+                    ));
+                }
+                out.push(String::new());
+            }
+            "csv-table" => {
+                if !args.is_empty() {
+                    out.extend([String::new(), format!("**{}**", self.inline(args))]);
+                }
+                let header = options
+                    .get("header")
+                    .map(|header| normalize_csv_line(header));
+                let rows = body
+                    .iter()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(|line| normalize_csv_line(line));
+                let csv: Vec<String> = header.into_iter().chain(rows).collect();
+                let csv: Vec<&str> = csv.iter().map(String::as_str).collect();
+                push_code_block(out, Some("csv"), &csv);
+            }
+            "list-table" => {
+                if !args.is_empty() {
+                    out.extend([String::new(), format!("**{}**", self.inline(args))]);
+                }
+                self.list_table(body, options.contains_key("header-rows"), out);
+            }
+            "rubric" => out.extend([
+                String::new(),
+                format!("**{}**", self.inline(args)),
+                String::new(),
+            ]),
+            "topic" | "sidebar" => {
+                let mut quote = vec![format!("**{}**", self.inline(args)), String::new()];
+                self.blocks(body, &mut quote);
+                push_quote(out, None, trim_blank(quote));
+            }
+            "epigraph" | "pull-quote" | "highlights" => {
+                let mut quote = Vec::new();
+                self.blocks(&content, &mut quote);
+                push_quote(out, None, trim_blank(quote));
+            }
+            "admonition" => {
+                let mut alert = vec![format!("**{}**", self.inline(args)), String::new()];
+                self.blocks(body, &mut alert);
+                push_quote(out, Some("NOTE"), trim_blank(alert));
+            }
+            "versionadded" | "versionchanged" | "deprecated" => {
+                let label = match name {
+                    "versionadded" => "New in version",
+                    "versionchanged" => "Changed in version",
+                    _ => "Deprecated since version",
+                };
+                let (version, text) = args.split_once(' ').unwrap_or((args, ""));
+                let text = std::iter::once(text)
+                    .chain(body.iter().map(|line| line.trim()))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                out.extend([
+                    String::new(),
+                    format!(
+                        "*{label} {}:* {}",
+                        escape(version),
+                        self.inline(text.trim())
+                    ),
+                    String::new(),
+                ]);
+            }
+            // Containers whose content is shown as is
+            "container" | "only" | "class" | "compound" | "table" => self.blocks(body, out),
+            _ => match alert_kind(name) {
+                Some(alert) => {
+                    let mut alert_body = Vec::new();
+                    self.blocks(&content, &mut alert_body);
+                    push_quote(out, Some(alert), trim_blank(alert_body));
+                }
+                None => tracing::debug!("Skipping unsupported reStructuredText directive: {name}"),
+            },
+        }
+    }
+
+    /// Convert a `list-table` body (a bullet list of rows, each a bullet list of cells)
+    fn list_table(&self, body: &[&str], has_header: bool, out: &mut Vec<String>) {
+        let mut rows: Vec<Vec<String>> = bullet_items(body)
+            .iter()
+            .map(|row| {
+                bullet_items(row)
+                    .iter()
+                    .map(|cell| self.inline(&join_trimmed(cell)))
+                    .collect()
+            })
+            .collect();
+        let header = (has_header && !rows.is_empty()).then(|| rows.remove(0));
+        push_table(out, header, rows);
+    }
+
+    /// Convert a grid table (`+---+---+` borders) starting at `start`
+    fn grid_table(&self, lines: &[&str], start: usize, out: &mut Vec<String>) -> Option<usize> {
+        let border = lines[start];
+        if !(border.starts_with("+-") || border.starts_with("+=")) || !border.ends_with('+') {
+            return None;
+        }
+        let border: Vec<char> = border.chars().collect();
+        let columns: Vec<usize> = (0..border.len()).filter(|i| border[*i] == '+').collect();
+
+        let mut end = start + 1;
+        let mut header = None;
+        let mut rows = Vec::new();
+        let mut row: Vec<Vec<String>> = Vec::new();
+        while let Some(line) = lines.get(end).filter(|line| line.starts_with(['+', '|'])) {
+            end += 1;
+            if line.starts_with('+') {
+                let cells: Vec<String> = row
+                    .drain(..)
+                    .map(|fragments| self.inline(&fragments.join(" ")))
+                    .collect();
+                if line.contains('=') && header.is_none() && rows.is_empty() {
+                    header = Some(cells);
+                } else if !cells.is_empty() {
+                    rows.push(cells);
+                }
+                continue;
+            }
+            let chars: Vec<char> = line.chars().collect();
+            for (column, bounds) in columns.windows(2).enumerate() {
+                let text: String = chars
+                    .get(bounds[0] + 1..bounds[1].min(chars.len()))
+                    .unwrap_or_default()
+                    .iter()
+                    .collect();
+                if row.len() <= column {
+                    row.push(Vec::new());
+                }
+                let text = text.trim();
+                if !text.is_empty() {
+                    row[column].push(text.to_string());
+                }
+            }
+        }
+        push_table(out, header, rows);
+        Some(end)
+    }
+
+    /// Convert a simple table (`=====  =====` borders) starting at `start`
+    fn simple_table(&self, lines: &[&str], start: usize, out: &mut Vec<String>) -> Option<usize> {
+        let is_border = |line: &str| {
+            line.starts_with('=')
+                && line.contains(' ')
+                && line.chars().all(|c| c == '=' || c == ' ')
+        };
+        if !is_border(lines[start]) {
+            return None;
+        }
+        // Column start offsets, from the runs of `=` in the top border
+        let border: Vec<char> = lines[start].chars().collect();
+        let starts: Vec<usize> = (0..border.len())
+            .filter(|i| border[*i] == '=' && (*i == 0 || border[i - 1] == ' '))
+            .collect();
+
+        let mut header = None;
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        for (index, line) in lines.iter().enumerate().skip(start + 1) {
+            if is_border(line) {
+                if lines
+                    .get(index + 1)
+                    .is_none_or(|next| next.trim().is_empty())
+                {
+                    let rows = rows
+                        .into_iter()
+                        .map(|row| row.iter().map(|cell| self.inline(cell)).collect())
+                        .collect();
+                    let header = header.map(|header: Vec<String>| {
+                        header.iter().map(|cell| self.inline(cell)).collect()
+                    });
+                    push_table(out, header, rows);
+                    return Some(index + 1);
+                }
+                header = Some(rows.drain(..).flatten().take(starts.len()).collect());
+                continue;
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            let chars: Vec<char> = line.chars().collect();
+            let cells: Vec<String> = starts
+                .iter()
+                .enumerate()
+                .map(|(column, from)| {
+                    let to = starts.get(column + 1).copied().unwrap_or(chars.len());
+                    chars
+                        .get(*from..to.min(chars.len()))
+                        .unwrap_or_default()
+                        .iter()
+                        .collect::<String>()
+                        .trim()
+                        .to_string()
+                })
+                .collect();
+            // A blank first column continues the previous row
+            match rows.last_mut() {
+                Some(row) if cells[0].is_empty() => {
+                    for (cell, text) in row.iter_mut().zip(cells).filter(|(_, t)| !t.is_empty()) {
+                        cell.push(' ');
+                        cell.push_str(&text);
+                    }
+                }
+                _ => rows.push(cells),
+            }
+        }
+        None
+    }
+
+    /// Convert inline markup to Markdown, escaping everything else
+    fn inline(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut previous: Option<char> = None;
+        let mut rest = text;
+        while let Some(c) = rest.chars().next() {
+            if c == '\\' {
+                // Backslash escapes; an escaped space disappears
+                let mut chars = rest[1..].chars();
+                match chars.next() {
+                    Some(' ') | None => {}
+                    Some(escaped) => push_escaped(&mut out, escaped),
+                }
+                previous = Some(c);
+                rest = chars.as_str();
+                continue;
+            }
+            if can_start_markup(previous) {
+                if let Some((markdown, consumed)) = self.inline_markup(rest) {
+                    out.push_str(&markdown);
+                    previous = rest[..consumed].chars().last();
+                    rest = &rest[consumed..];
+                    continue;
+                }
+            }
+            push_escaped(&mut out, c);
+            previous = Some(c);
+            rest = &rest[c.len_utf8()..];
+        }
+        out
+    }
+
+    /// Inline markup starting at the beginning of `text`, with the number of bytes it spans
+    fn inline_markup(&self, text: &str) -> Option<(String, usize)> {
+        if let Some(inner) = delimited(text, "``", "``") {
+            return Some((code_span(inner), inner.len() + 4));
+        }
+        if text.starts_with(':') {
+            if let Some(role) = self.role(text) {
+                return Some(role);
+            }
+        }
+        if let Some(inner) = delimited(text, "**", "**") {
+            return Some((format!("**{}**", escape(inner)), inner.len() + 4));
+        }
+        if let Some(inner) = delimited(text, "*", "*") {
+            return Some((format!("*{}*", escape(inner)), inner.len() + 2));
+        }
+        if text.starts_with('`') {
+            return self.interpreted_text(text);
+        }
+        if text.starts_with("http://") || text.starts_with("https://") {
+            let end = text
+                .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '`'))
+                .unwrap_or(text.len());
+            let url = text[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')', '\'', '"']);
+            return Some((format!("<{url}>"), url.len()));
+        }
+        // Reference to a named target: `word_`
+        let word_end = text
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '.')))
+            .unwrap_or(text.len());
+        let word = &text[..word_end];
+        let after = &text[word_end..];
+        if !word.is_empty() && after.starts_with('_') {
+            let underscores = if after.starts_with("__") { 2 } else { 1 };
+            let followed = after[underscores..].chars().next();
+            if followed.is_none_or(|c| !c.is_alphanumeric() && c != '_') {
+                if let Some(url) = self.targets.get(&normalize_name(word)) {
+                    return Some((
+                        format!("[{}]({})", escape(word), link_destination(url)),
+                        word_end + underscores,
+                    ));
+                }
+            }
+        }
+        None
+    }
+
+    /// `` `text` `` (default role), `` `text <url>`_ `` and `` `name`_ `` references
+    fn interpreted_text(&self, text: &str) -> Option<(String, usize)> {
+        let inner = delimited(text, "`", "`")?;
+        let after = &text[inner.len() + 2..];
+        let underscores = after.chars().take(2).take_while(|c| *c == '_').count();
+        let consumed = inner.len() + 2 + underscores;
+        if underscores == 0 {
+            return Some((format!("*{}*", escape(inner)), consumed));
+        }
+
+        let (title, target) = split_reference(inner);
+        let url = if title != target {
+            match target.strip_suffix('_') {
+                Some(name) => self.targets.get(&normalize_name(name)).cloned(),
+                None => Some(target.to_string()),
+            }
+        } else {
+            // Named target, or implicitly a section title in this document
+            self.targets
+                .get(&normalize_name(inner))
+                .cloned()
+                .or_else(|| Some(format!("#{}", slug(inner))))
+        };
+        let markdown = match url {
+            Some(url) => format!("[{}]({})", escape(title), link_destination(&url)),
+            None => escape(title),
+        };
+        Some((markdown, consumed))
+    }
+
+    /// `:role:`content`` interpreted text
+    fn role(&self, text: &str) -> Option<(String, usize)> {
+        let tick = text.find('`')?;
+        let name = text[1..tick].strip_suffix(':')?;
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':' | '+'))
+        {
+            return None;
+        }
+        let close = text[tick + 1..].find('`')?;
+        let content = &text[tick + 1..tick + 1 + close];
+        let consumed = tick + close + 2;
+        // Domain roles (`py:func`) are treated like their plain counterparts
+        let role = name.rsplit(':').next().unwrap_or(name);
+
+        let markdown = match role {
+            "math" => format!("${content}$"),
+            "doc" => {
+                let (title, target) = split_reference(content);
+                format!(
+                    "[{}]({})",
+                    escape(title),
+                    link_destination(&document_url(target))
+                )
+            }
+            "ref" => {
+                let (title, label) = split_reference(content);
+                format!("[{}](#{})", escape(title), slug(label))
+            }
+            "sub" => format!("<sub>{}</sub>", escape(content)),
+            "sup" => format!("<sup>{}</sup>", escape(content)),
+            "kbd" => format!("<kbd>{}</kbd>", escape(content)),
+            "emphasis" | "title-reference" | "title" | "t" => format!("*{}*", escape(content)),
+            "strong" => format!("**{}**", escape(content)),
+            "abbr" => escape(content.split(" (").next().unwrap_or(content)),
+            role if CODE_ROLES.contains(&role) => {
+                let (title, target) = split_reference(content);
+                let shown = if title != target {
+                    title
+                } else if let Some(target) = target.strip_prefix('~') {
+                    // `~module.Class.method` shows the last component only
+                    target.rsplit('.').next().unwrap_or(target)
+                } else {
+                    target.trim_start_matches('!')
+                };
+                code_span(shown)
+            }
+            _ => escape(content),
+        };
+        Some((markdown, consumed))
+    }
+}
+
+/// Collect `.. _name: url` hyperlink targets
+fn collect_targets(lines: &[&str]) -> HashMap<String, String> {
+    lines
+        .iter()
+        .filter_map(|line| {
+            let target = line.trim_start().strip_prefix(".. _")?;
+            let (name, url) = target.split_once(": ")?;
+            let url = url.trim();
+            (!url.is_empty()).then(|| (normalize_name(name), url.to_string()))
+        })
+        .collect()
+}
+
+/// Reference names are case- and whitespace-insensitive
+fn normalize_name(name: &str) -> String {
+    name.trim_matches('`')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Section id the way docutils derives it from the title
+fn slug(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Split `title <target>` into its parts; a plain reference is both title and target
+fn split_reference(text: &str) -> (&str, &str) {
+    text.strip_suffix('>')
+        .and_then(|text| text.rsplit_once('<'))
+        .map(|(title, target)| (title.trim(), target.trim()))
+        .filter(|(title, _)| !title.is_empty())
+        .unwrap_or((text, text))
+}
+
+/// Link to another document of the project (`:doc:` and `toctree` entries omit `.rst`)
+fn document_url(target: &str) -> String {
+    if target.contains("://") || Path::new(target).extension().is_some() {
+        target.to_string()
+    } else {
+        format!("{target}.rst")
+    }
+}
+
+/// Section adornment or transition: a line repeating one punctuation character
+fn is_adornment(line: &str) -> bool {
+    let mut chars = line.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    line.len() >= 2 && first.is_ascii_punctuation() && chars.all(|c| c == first)
+}
+
+/// End (exclusive) of the block starting at `start` whose lines are indented by at least
+/// `min_indent`, without trailing blank lines
+fn block_end(lines: &[&str], start: usize, min_indent: usize) -> usize {
+    let mut end = start;
+    for (index, line) in lines.iter().enumerate().skip(start) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if indent_of(line) < min_indent {
+            break;
+        }
+        end = index + 1;
+    }
+    end
+}
+
+/// List item marker of a line: the Markdown marker to use and the width of the source marker
+fn list_marker(line: &str) -> Option<(String, usize)> {
+    let marker_end = line.find(' ')?;
+    let marker = &line[..marker_end];
+    let markdown = match marker {
+        "-" | "*" | "+" => "- ".to_string(),
+        "#." => "1. ".to_string(),
+        _ => {
+            let number = marker
+                .strip_suffix(['.', ')'])
+                .map(|number| number.strip_prefix('(').unwrap_or(number))?;
+            if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            format!("{number}. ")
+        }
+    };
+    let width = marker_end + indent_of(&line[marker_end..]);
+    (width < line.len()).then_some((markdown, width))
+}
+
+/// Split a bullet list into its items, with markers and item indentation removed
+fn bullet_items<'a>(lines: &[&'a str]) -> Vec<Vec<&'a str>> {
+    let mut items: Vec<(usize, Vec<&str>)> = Vec::new();
+    for line in lines {
+        match list_marker(line).filter(|(marker, _)| marker == "- ") {
+            Some((_, width)) if indent_of(line) == 0 => items.push((width, vec![&line[width..]])),
+            _ => {
+                if let Some((width, item)) = items.last_mut() {
+                    item.push(line.get(indent_of(line).min(*width)..).unwrap_or(""));
+                }
+            }
+        }
+    }
+    items.into_iter().map(|(_, item)| item).collect()
+}
+
+/// Field list item or directive option: `:name: value`
+fn parse_field(line: &str) -> Option<(&str, &str)> {
+    let rest = line.strip_prefix(':')?;
+    let (name, value) = rest
+        .split_once(": ")
+        .or_else(|| rest.strip_suffix(':').map(|name| (name, "")))?;
+    if name.is_empty() || name.starts_with(' ') || name.contains('`') {
+        return None;
+    }
+    Some((name, value.trim()))
+}
+
+/// Whether inline markup may start after `previous`
+fn can_start_markup(previous: Option<char>) -> bool {
+    previous.is_none_or(|c| {
+        c.is_whitespace() || matches!(c, '-' | ':' | '/' | '\'' | '"' | '<' | '(' | '[' | '{')
+    })
+}
+
+/// Text between `open` and `close` at the start of `text`, following the inline markup
+/// recognition rules: no whitespace just inside the delimiters, and the end delimiter must
+/// be followed by whitespace, punctuation or the end of the text
+fn delimited<'a>(text: &'a str, open: &str, close: &str) -> Option<&'a str> {
+    let after = text.strip_prefix(open)?;
+    if after.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let mut search = 0;
+    while let Some(position) = after[search..].find(close) {
+        let end = search + position;
+        let followed = after[end + close.len()..].chars().next();
+        if end > 0
+            && !after[..end].ends_with(char::is_whitespace)
+            && followed.is_none_or(|c| {
+                c.is_whitespace()
+                    || matches!(
+                        c,
+                        '-' | '.'
+                            | ','
+                            | ':'
+                            | ';'
+                            | '!'
+                            | '?'
+                            | '\\'
+                            | '/'
+                            | '\''
+                            | '"'
+                            | ')'
+                            | ']'
+                            | '}'
+                            | '>'
+                            | '_'
+                    )
+            })
+        {
+            return Some(&after[..end]);
+        }
+        search = end + 1;
+    }
+    None
+}
+
+/// CSV rows in `csv-table` directives put spaces after the commas; drop them so quoted
+/// fields are recognized
+fn normalize_csv_line(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut quoted = false;
+    let mut after_comma = false;
+    for c in line.trim().chars() {
+        if after_comma && c == ' ' {
+            continue;
+        }
+        after_comma = !quoted && c == ',';
+        if c == '"' {
+            quoted = !quoted;
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn join_trimmed(lines: &[&str]) -> String {
+    lines
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn trim_blank_lines<'a>(lines: &[&'a str]) -> Vec<&'a str> {
+    let start = lines.iter().position(|line| !line.trim().is_empty());
+    let end = lines.iter().rposition(|line| !line.trim().is_empty());
+    match (start, end) {
+        (Some(start), Some(end)) => lines[start..=end].to_vec(),
+        _ => Vec::new(),
+    }
+}
+
+fn trim_blank(mut lines: Vec<String>) -> Vec<String> {
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    let start = lines
+        .iter()
+        .position(|line| !line.is_empty())
+        .unwrap_or(lines.len());
+    lines.split_off(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn render(rst: &str) -> String {
+        render_rst(rst, "/docs/index.rst", &RenderOptions::default())
+            .unwrap()
+            .html
+    }
+
+    #[test]
+    fn test_section_levels_follow_adornment_order() {
+        let html = render(indoc! {"
+            ==========
+             Overview
+            ==========
+
+            Install
+            -------
+
+            Details
+            ~~~~~~~
+
+            Usage
+            -----
+        "});
+
+        assert!(html.contains(r#"<h1 id="overview">Overview</h1>"#));
+        assert!(html.contains(r#"<h2 id="install">Install</h2>"#));
+        assert!(html.contains(r#"<h3 id="details">Details</h3>"#));
+        assert!(html.contains(r#"<h2 id="usage">Usage</h2>"#));
+    }
+
+    #[test]
+    fn test_inline_markup() {
+        let html = render(
+            "Some *emphasis*, **strong**, ``code_here``, :func:`~pkg.mod.run`, `Example <https://example.com>`_ and a_b_c.",
+        );
+
+        assert!(html.contains("<em>emphasis</em>"));
+        assert!(html.contains("<strong>strong</strong>"));
+        assert!(html.contains("<code>code_here</code>"));
+        assert!(html.contains("<code>run</code>"));
+        assert!(html.contains(r#"<a href="https://example.com">Example</a>"#));
+        assert!(html.contains("a_b_c."));
+    }
+
+    #[test]
+    fn test_named_targets_and_section_references() {
+        let html = render(indoc! {"
+            See Python_ and `Install`_.
+
+            .. _Python: https://www.python.org/
+
+            Install
+            =======
+        "});
+
+        assert!(html.contains(r#"<a href="https://www.python.org/">Python</a>"#));
+        assert!(html.contains(r##"<a href="#install">Install</a>"##));
+    }
+
+    #[test]
+    fn test_code_blocks() {
+        let html = render(indoc! {"
+            .. code-block:: python
+               :linenos:
+
+               def main():
+                   return 1
+
+            Example::
+
+                $ make <target>
+        "});
+
+        assert!(html.contains(
+            r#"<code class="language-python">def main():
+    return 1
+</code>"#
+        ));
+        assert!(html.contains("<p>Example:</p>"));
+        assert!(html.contains("<pre><code>$ make &lt;target&gt;\n</code></pre>"));
+    }
+
+    #[test]
+    fn test_admonitions_become_alerts() {
+        let html = render(indoc! {"
+            .. warning:: Do not
+               run this twice.
+
+            .. danger::
+
+               Really.
+        "});
+
+        assert!(html.contains("markdown-alert-warning"));
+        assert!(html.contains("Do not run this twice."));
+        assert!(html.contains("markdown-alert-caution"));
+        assert!(html.contains("Really."));
+    }
+
+    #[test]
+    fn test_lists_and_nested_lists() {
+        let html = render(indoc! {"
+            - first
+            - second
+
+              #. nested
+              #. items
+
+            3. three
+        "});
+
+        assert!(html.contains("first"));
+        assert!(html.contains("<li>nested</li>\n<li>items</li>"));
+        assert!(html.contains(r#"<ol start="3">"#));
+    }
+
+    #[test]
+    fn test_tables() {
+        let html = render(indoc! {"
+            =====  =====
+            Name   Value
+            =====  =====
+            a      1
+            b      2
+            =====  =====
+
+            +------+-------+
+            | Key  | Notes |
+            +======+=======+
+            | x    | long  |
+            |      | text  |
+            +------+-------+
+        "});
+
+        assert!(html.contains("<th>Name</th>"));
+        assert!(html.contains("<td>b</td>"));
+        assert!(html.contains("<th>Notes</th>"));
+        assert!(html.contains("<td>long text</td>"));
+    }
+
+    #[test]
+    fn test_images_and_document_links() {
+        let html = render(indoc! {"
+            .. image:: img/logo.png
+               :alt: Logo
+
+            Read :doc:`the guide <guide/start>`.
+        "});
+
+        assert!(html.contains(r#"<img src="img/logo.png" alt="Logo">"#));
+        assert!(html.contains("guide/start.rst"));
+        assert!(html.contains(r#"class="md-link""#));
+    }
+
+    #[test]
+    fn test_comments_and_transitions() {
+        let html = render(indoc! {"
+            Before
+
+            .. This is a comment
+               spanning lines
+
+            ----
+
+            After
+        "});
+
+        assert!(!html.contains("comment"));
+        assert!(html.contains("<hr>"));
+    }
+}
@@ -1,21 +1,34 @@
+use dioxus::document;
 use dioxus::prelude::{spawn, ReadableExt, WritableExt};
 use dioxus_desktop::muda::accelerator::{Accelerator, Code, Modifiers};
 use dioxus_desktop::muda::{Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu};
 use dioxus_desktop::window;
 use std::path::PathBuf;
 
-use crate::components::content::set_preferences_tab_to_about;
+mod keybindings;
+pub use keybindings::{keybinding_entries, keybinding_issues};
+
+use crate::assets::MAIN_SCRIPT;
+use crate::components::content::{go_to_change, set_preferences_tab_to_about};
+use crate::presentation::start_presentation;
+use crate::print;
 use crate::state::AppState;
 use crate::window::{self, CreateMainWindowConfigParams};
+use crate::workspace::{is_workspace_file, WORKSPACE_EXTENSION};
 
 /// Menu identifier enum
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum MenuId {
     About,
     NewWindow,
     NewTab,
     Open,
     OpenDirectory,
+    OpenWorkspace,
+    AddFolderToWorkspace,
+    SaveWorkspaceAs,
+    Print,
+    ExportPdf,
     CloseTab,
     CloseAllTabs,
     CloseWindow,
@@ -26,12 +39,51 @@ enum MenuId {
     ActualSize,
     ZoomIn,
     ZoomOut,
+    CollapseAllSections,
+    ExpandAllSections,
+    StartPresentation,
+    ShowChanges,
+    NextChange,
+    PreviousChange,
     GoBack,
     GoForward,
     GoToHomepage,
 }
 
 impl MenuId {
+    /// All menu items that can carry a keyboard shortcut, in menu order
+    const ALL: [MenuId; 29] = [
+        Self::About,
+        Self::Preferences,
+        Self::NewWindow,
+        Self::NewTab,
+        Self::Open,
+        Self::OpenDirectory,
+        Self::OpenWorkspace,
+        Self::AddFolderToWorkspace,
+        Self::SaveWorkspaceAs,
+        Self::Print,
+        Self::ExportPdf,
+        Self::CloseTab,
+        Self::CloseAllTabs,
+        Self::CloseWindow,
+        Self::ToggleSidebar,
+        Self::ActualSize,
+        Self::ZoomIn,
+        Self::ZoomOut,
+        Self::CollapseAllSections,
+        Self::ExpandAllSections,
+        Self::StartPresentation,
+        Self::ShowChanges,
+        Self::NextChange,
+        Self::PreviousChange,
+        Self::GoBack,
+        Self::GoForward,
+        Self::CloseAllChildWindows,
+        Self::CloseAllWindows,
+        Self::GoToHomepage,
+    ];
+
     /// Convert menu ID string to enum variant
     fn from_str(s: &str) -> Option<Self> {
         match s {
@@ -40,6 +92,11 @@ impl MenuId {
             "file.new_tab" => Some(Self::NewTab),
             "file.open" => Some(Self::Open),
             "file.open_directory" => Some(Self::OpenDirectory),
+            "file.open_workspace" => Some(Self::OpenWorkspace),
+            "file.add_folder_to_workspace" => Some(Self::AddFolderToWorkspace),
+            "file.save_workspace_as" => Some(Self::SaveWorkspaceAs),
+            "file.print" => Some(Self::Print),
+            "file.export_pdf" => Some(Self::ExportPdf),
             "file.close_tab" => Some(Self::CloseTab),
             "file.close_all_tabs" => Some(Self::CloseAllTabs),
             "file.close_window" => Some(Self::CloseWindow),
@@ -50,6 +107,12 @@ impl MenuId {
             "view.actual_size" => Some(Self::ActualSize),
             "view.zoom_in" => Some(Self::ZoomIn),
             "view.zoom_out" => Some(Self::ZoomOut),
+            "view.collapse_all_sections" => Some(Self::CollapseAllSections),
+            "view.expand_all_sections" => Some(Self::ExpandAllSections),
+            "view.start_presentation" => Some(Self::StartPresentation),
+            "view.show_changes" => Some(Self::ShowChanges),
+            "view.next_change" => Some(Self::NextChange),
+            "view.previous_change" => Some(Self::PreviousChange),
             "history.back" => Some(Self::GoBack),
             "history.forward" => Some(Self::GoForward),
             "help.homepage" => Some(Self::GoToHomepage),
@@ -65,6 +128,11 @@ impl MenuId {
             Self::NewTab => "file.new_tab",
             Self::Open => "file.open",
             Self::OpenDirectory => "file.open_directory",
+            Self::OpenWorkspace => "file.open_workspace",
+            Self::AddFolderToWorkspace => "file.add_folder_to_workspace",
+            Self::SaveWorkspaceAs => "file.save_workspace_as",
+            Self::Print => "file.print",
+            Self::ExportPdf => "file.export_pdf",
             Self::CloseTab => "file.close_tab",
             Self::CloseAllTabs => "file.close_all_tabs",
             Self::CloseWindow => "file.close_window",
@@ -75,22 +143,94 @@ impl MenuId {
             Self::ActualSize => "view.actual_size",
             Self::ZoomIn => "view.zoom_in",
             Self::ZoomOut => "view.zoom_out",
+            Self::CollapseAllSections => "view.collapse_all_sections",
+            Self::ExpandAllSections => "view.expand_all_sections",
+            Self::StartPresentation => "view.start_presentation",
+            Self::ShowChanges => "view.show_changes",
+            Self::NextChange => "view.next_change",
+            Self::PreviousChange => "view.previous_change",
             Self::GoBack => "history.back",
             Self::GoForward => "history.forward",
             Self::GoToHomepage => "help.homepage",
         }
     }
+
+    /// Get the label shown in the menu bar
+    fn label(self) -> &'static str {
+        match self {
+            Self::About => "About Arto",
+            Self::NewWindow => "New Window",
+            Self::NewTab => "New Tab",
+            Self::Open => "Open File...",
+            Self::OpenDirectory => "Open Directory...",
+            Self::OpenWorkspace => "Open Workspace...",
+            Self::AddFolderToWorkspace => "Add Folder to Workspace...",
+            Self::SaveWorkspaceAs => "Save Workspace As...",
+            Self::Print => "Print...",
+            Self::ExportPdf => "Export as PDF...",
+            Self::CloseTab => "Close Tab",
+            Self::CloseAllTabs => "Close All Tabs",
+            Self::CloseWindow => "Close Window",
+            Self::CloseAllChildWindows => "Close All Child Windows",
+            Self::CloseAllWindows => "Close All Windows",
+            Self::Preferences => "Preferences...",
+            Self::ToggleSidebar => "Toggle Sidebar",
+            Self::ActualSize => "Actual Size",
+            Self::ZoomIn => "Zoom In",
+            Self::ZoomOut => "Zoom Out",
+            Self::CollapseAllSections => "Collapse All Sections",
+            Self::ExpandAllSections => "Expand All Sections",
+            Self::StartPresentation => "Start Presentation",
+            Self::ShowChanges => "Show Changes Since Last Commit",
+            Self::NextChange => "Next Change",
+            Self::PreviousChange => "Previous Change",
+            Self::GoBack => "Go Back",
+            Self::GoForward => "Go Forward",
+            Self::GoToHomepage => "Go to Homepage",
+        }
+    }
+
+    /// Get the built-in keyboard shortcut (used unless overridden in config)
+    fn default_accelerator(self) -> Option<Accelerator> {
+        let (code, additional_modifiers) = match self {
+            Self::Preferences => (Code::Comma, None),
+            Self::NewWindow => (Code::KeyN, None),
+            Self::NewTab => (Code::KeyT, None),
+            Self::Open => (Code::KeyO, None),
+            Self::OpenDirectory => (Code::KeyO, Some(Modifiers::SHIFT)),
+            Self::Print => (Code::KeyP, None),
+            Self::ExportPdf => (Code::KeyE, Some(Modifiers::SHIFT)),
+            Self::CloseTab => (Code::KeyW, None),
+            Self::CloseWindow => (Code::KeyW, Some(Modifiers::SHIFT)),
+            Self::ToggleSidebar => (Code::KeyB, None),
+            Self::ActualSize => (Code::Digit0, None),
+            Self::ZoomIn => (Code::Equal, None),
+            Self::ZoomOut => (Code::Minus, None),
+            Self::CollapseAllSections => (Code::ArrowLeft, Some(Modifiers::ALT | Modifiers::SHIFT)),
+            Self::ExpandAllSections => (Code::ArrowRight, Some(Modifiers::ALT | Modifiers::SHIFT)),
+            Self::StartPresentation => (Code::Enter, Some(Modifiers::ALT)),
+            Self::ShowChanges => (Code::KeyG, Some(Modifiers::ALT)),
+            Self::NextChange => (Code::ArrowDown, Some(Modifiers::ALT)),
+            Self::PreviousChange => (Code::ArrowUp, Some(Modifiers::ALT)),
+            Self::GoBack => (Code::BracketLeft, None),
+            Self::GoForward => (Code::BracketRight, None),
+            Self::About
+            | Self::OpenWorkspace
+            | Self::AddFolderToWorkspace
+            | Self::SaveWorkspaceAs
+            | Self::CloseAllTabs
+            | Self::CloseAllChildWindows
+            | Self::CloseAllWindows
+            | Self::GoToHomepage => return None,
+        };
+        Some(get_cmd_or_ctrl(code, additional_modifiers))
+    }
 }
 
-/// Helper to create a menu item with optional keyboard shortcut
-fn create_menu_item(
-    id: MenuId,
-    label: &str,
-    code: Option<Code>,
-    additional_modifiers: Option<Modifiers>,
-) -> MenuItem {
-    let accelerator = code.map(|c| get_cmd_or_ctrl(c, additional_modifiers));
-    MenuItem::with_id(id.as_str(), label, true, accelerator)
+/// Helper to create a menu item with its resolved keyboard shortcut
+fn create_menu_item(id: MenuId) -> MenuItem {
+    let accelerator = keybindings::accelerator_for(id);
+    MenuItem::with_id(id.as_str(), id.label(), true, accelerator)
 }
 
 /// Build the application menu bar
@@ -115,14 +255,9 @@ fn add_app_menu(menu: &Menu) {
 
     arto_menu
         .append_items(&[
-            &create_menu_item(MenuId::About, "About Arto", None, None),
+            &create_menu_item(MenuId::About),
             &PredefinedMenuItem::separator(),
-            &create_menu_item(
-                MenuId::Preferences,
-                "Preferences...",
-                Some(Code::Comma),
-                None,
-            ),
+            &create_menu_item(MenuId::Preferences),
             &PredefinedMenuItem::separator(),
             &PredefinedMenuItem::quit(Some("Quit")),
         ])
@@ -136,25 +271,22 @@ fn add_file_menu(menu: &Menu) {
 
     file_menu
         .append_items(&[
-            &create_menu_item(MenuId::NewWindow, "New Window", Some(Code::KeyN), None),
-            &create_menu_item(MenuId::NewTab, "New Tab", Some(Code::KeyT), None),
+            &create_menu_item(MenuId::NewWindow),
+            &create_menu_item(MenuId::NewTab),
+            &PredefinedMenuItem::separator(),
+            &create_menu_item(MenuId::Open),
+            &create_menu_item(MenuId::OpenDirectory),
             &PredefinedMenuItem::separator(),
-            &create_menu_item(MenuId::Open, "Open File...", Some(Code::KeyO), None),
-            &create_menu_item(
-                MenuId::OpenDirectory,
-                "Open Directory...",
-                Some(Code::KeyO),
-                Some(Modifiers::SHIFT),
-            ),
+            &create_menu_item(MenuId::OpenWorkspace),
+            &create_menu_item(MenuId::AddFolderToWorkspace),
+            &create_menu_item(MenuId::SaveWorkspaceAs),
             &PredefinedMenuItem::separator(),
-            &create_menu_item(MenuId::CloseTab, "Close Tab", Some(Code::KeyW), None),
-            &create_menu_item(MenuId::CloseAllTabs, "Close All Tabs", None, None),
-            &create_menu_item(
-                MenuId::CloseWindow,
-                "Close Window",
-                Some(Code::KeyW),
-                Some(Modifiers::SHIFT),
-            ),
+            &create_menu_item(MenuId::Print),
+            &create_menu_item(MenuId::ExportPdf),
+            &PredefinedMenuItem::separator(),
+            &create_menu_item(MenuId::CloseTab),
+            &create_menu_item(MenuId::CloseAllTabs),
+            &create_menu_item(MenuId::CloseWindow),
         ])
         .unwrap();
 
@@ -182,16 +314,20 @@ fn add_view_menu(menu: &Menu) {
 
     view_menu
         .append_items(&[
-            &create_menu_item(
-                MenuId::ToggleSidebar,
-                "Toggle Sidebar",
-                Some(Code::KeyB),
-                None,
-            ),
+            &create_menu_item(MenuId::ToggleSidebar),
+            &PredefinedMenuItem::separator(),
+            &create_menu_item(MenuId::ActualSize),
+            &create_menu_item(MenuId::ZoomIn),
+            &create_menu_item(MenuId::ZoomOut),
+            &PredefinedMenuItem::separator(),
+            &create_menu_item(MenuId::CollapseAllSections),
+            &create_menu_item(MenuId::ExpandAllSections),
             &PredefinedMenuItem::separator(),
-            &create_menu_item(MenuId::ActualSize, "Actual Size", Some(Code::Digit0), None),
-            &create_menu_item(MenuId::ZoomIn, "Zoom In", Some(Code::Equal), None),
-            &create_menu_item(MenuId::ZoomOut, "Zoom Out", Some(Code::Minus), None),
+            &create_menu_item(MenuId::StartPresentation),
+            &PredefinedMenuItem::separator(),
+            &create_menu_item(MenuId::ShowChanges),
+            &create_menu_item(MenuId::NextChange),
+            &create_menu_item(MenuId::PreviousChange),
         ])
         .unwrap();
 
@@ -203,13 +339,8 @@ fn add_history_menu(menu: &Menu) {
 
     history_menu
         .append_items(&[
-            &create_menu_item(MenuId::GoBack, "Go Back", Some(Code::BracketLeft), None),
-            &create_menu_item(
-                MenuId::GoForward,
-                "Go Forward",
-                Some(Code::BracketRight),
-                None,
-            ),
+            &create_menu_item(MenuId::GoBack),
+            &create_menu_item(MenuId::GoForward),
         ])
         .unwrap();
 
@@ -221,13 +352,8 @@ fn add_window_menu(menu: &Menu) {
 
     window_menu
         .append_items(&[
-            &create_menu_item(
-                MenuId::CloseAllChildWindows,
-                "Close All Child Windows",
-                None,
-                None,
-            ),
-            &create_menu_item(MenuId::CloseAllWindows, "Close All Windows", None, None),
+            &create_menu_item(MenuId::CloseAllChildWindows),
+            &create_menu_item(MenuId::CloseAllWindows),
         ])
         .unwrap();
 
@@ -238,12 +364,7 @@ fn add_help_menu(menu: &Menu) {
     let help_menu = Submenu::new("Help", true);
 
     help_menu
-        .append(&create_menu_item(
-            MenuId::GoToHomepage,
-            "Go to Homepage",
-            None,
-            None,
-        ))
+        .append(&create_menu_item(MenuId::GoToHomepage))
         .unwrap();
 
     menu.append(&help_menu).unwrap();
@@ -251,7 +372,11 @@ fn add_help_menu(menu: &Menu) {
 
 /// Get Cmd modifier with optional additional modifiers
 fn get_cmd_or_ctrl(code: Code, additional: Option<Modifiers>) -> Accelerator {
-    let base_modifier = Modifiers::SUPER;
+    let base_modifier = if cfg!(target_os = "macos") {
+        Modifiers::SUPER
+    } else {
+        Modifiers::CONTROL
+    };
 
     let modifiers = if let Some(additional_mods) = additional {
         base_modifier | additional_mods
@@ -345,9 +470,36 @@ pub fn handle_menu_event_with_state(event: &MenuEvent, state: &mut AppState) ->
         }
         MenuId::OpenDirectory => {
             if let Some(dir) = pick_directory() {
-                state.set_root_directory(dir);
+                state.open_directory(dir);
+            }
+        }
+        MenuId::OpenWorkspace => {
+            if let Some(file) = pick_workspace_file() {
+                if let Err(e) = state.open_workspace_file(&file) {
+                    tracing::error!("Failed to open workspace {:?}: {:?}", file, e);
+                }
             }
         }
+        MenuId::AddFolderToWorkspace => {
+            if let Some(dir) = pick_directory() {
+                state.add_workspace_folder(dir);
+            }
+        }
+        MenuId::SaveWorkspaceAs => {
+            if state.directory.read().is_none() {
+                tracing::debug!("No folders open, nothing to save as a workspace");
+            } else if let Some(file) = pick_workspace_save_path(state) {
+                if let Err(e) = state.save_workspace(&file) {
+                    tracing::error!("Failed to save workspace {:?}: {:?}", file, e);
+                }
+            }
+        }
+        MenuId::Print => {
+            print::print_document(state);
+        }
+        MenuId::ExportPdf => {
+            print::export_pdf(state);
+        }
         MenuId::CloseTab => {
             let active_tab = *state.active_tab.read();
             state.close_tab(active_tab);
@@ -378,6 +530,24 @@ pub fn handle_menu_event_with_state(event: &MenuEvent, state: &mut AppState) ->
             // Min zoom: 0.1
             state.zoom_level.set((current - 0.1).max(0.1));
         }
+        MenuId::CollapseAllSections => {
+            set_all_sections_folded(true);
+        }
+        MenuId::ExpandAllSections => {
+            set_all_sections_folded(false);
+        }
+        MenuId::StartPresentation => {
+            start_presentation(*state);
+        }
+        MenuId::ShowChanges => {
+            state.toggle_changes();
+        }
+        MenuId::NextChange => {
+            go_to_change(true);
+        }
+        MenuId::PreviousChange => {
+            go_to_change(false);
+        }
         MenuId::GoBack => {
             state.update_current_tab(|tab| {
                 if let Some(path) = tab.history.go_back() {
@@ -398,6 +568,17 @@ pub fn handle_menu_event_with_state(event: &MenuEvent, state: &mut AppState) ->
     true
 }
 
+/// Fold or unfold every heading section of the displayed document
+fn set_all_sections_folded(folded: bool) {
+    spawn(async move {
+        let _ = document::eval(&indoc::formatdoc! {r#"
+            const {{ setAllSectionsFolded }} = await import("{MAIN_SCRIPT}");
+            setAllSectionsFolded({folded});
+        "#})
+        .await;
+    });
+}
+
 /// Show file picker dialog and return selected file
 fn pick_markdown_file() -> Option<PathBuf> {
     use rfd::FileDialog;
@@ -407,6 +588,9 @@ fn pick_markdown_file() -> Option<PathBuf> {
 
     let file = FileDialog::new()
         .add_filter("Markdown", &["md", "markdown"])
+        .add_filter("AsciiDoc", &["adoc", "asciidoc"])
+        .add_filter("reStructuredText", &["rst"])
+        .add_filter("Jupyter Notebook", &["ipynb"])
         .set_directory(std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")))
         .pick_file();
 
@@ -415,8 +599,48 @@ fn pick_markdown_file() -> Option<PathBuf> {
     file
 }
 
+/// Show file picker dialog for `.arto-workspace` files
+fn pick_workspace_file() -> Option<PathBuf> {
+    use rfd::FileDialog;
+
+    FileDialog::new()
+        .add_filter("Arto Workspace", &[WORKSPACE_EXTENSION])
+        .set_directory(std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")))
+        .pick_file()
+}
+
+/// Show save dialog for a workspace file, starting next to the current one (or first root)
+fn pick_workspace_save_path(state: &AppState) -> Option<PathBuf> {
+    use rfd::FileDialog;
+
+    let workspace = state.workspace.read().clone();
+    let file = workspace.as_ref().and_then(|w| w.file.clone());
+    let directory = file
+        .as_ref()
+        .and_then(|f| f.parent().map(PathBuf::from))
+        .or_else(|| state.directory.read().clone())
+        .unwrap_or_else(|| PathBuf::from("/"));
+    let file_name = file
+        .as_ref()
+        .and_then(|f| f.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| format!("workspace.{WORKSPACE_EXTENSION}"));
+
+    let path = FileDialog::new()
+        .add_filter("Arto Workspace", &[WORKSPACE_EXTENSION])
+        .set_directory(directory)
+        .set_file_name(file_name)
+        .save_file()?;
+    // Some platforms don't append the filter extension on their own
+    Some(if is_workspace_file(&path) {
+        path
+    } else {
+        path.with_extension(WORKSPACE_EXTENSION)
+    })
+}
+
 /// Show directory picker dialog and return selected directory
-fn pick_directory() -> Option<PathBuf> {
+pub(crate) fn pick_directory() -> Option<PathBuf> {
     use rfd::FileDialog;
 
     tracing::debug!("Opening directory picker dialog...");
@@ -431,9 +655,13 @@ fn pick_directory() -> Option<PathBuf> {
     dir
 }
 
+#[cfg(target_os = "macos")]
 fn disable_automatic_window_tabbing() {
     use objc2::MainThreadMarker;
     use objc2_app_kit::NSWindow;
     let marker = MainThreadMarker::new().expect("Failed to get main thread marker");
     NSWindow::setAllowsAutomaticWindowTabbing(false, marker);
 }
+
+#[cfg(not(target_os = "macos"))]
+fn disable_automatic_window_tabbing() {}
@@ -0,0 +1,366 @@
+use dioxus_desktop::muda::accelerator::{Accelerator, Modifiers};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use super::MenuId;
+use crate::config::CONFIG;
+
+/// Keyboard shortcuts resolved from the built-in defaults and the user's config.
+///
+/// Resolved once on first access; the native menu is built at startup so changes to
+/// `keybindings.menu` in config.json take effect after a restart.
+static RESOLVED: LazyLock<ResolvedKeybindings> = LazyLock::new(|| {
+    let overrides = CONFIG.read().keybindings.menu.clone();
+    let resolved = ResolvedKeybindings::resolve(&overrides);
+    for issue in &resolved.issues {
+        tracing::warn!("Keybinding issue: {issue}");
+    }
+    resolved
+});
+
+/// A menu item and its effective keyboard shortcut, for display in Preferences
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeybindingEntry {
+    pub id: &'static str,
+    pub label: &'static str,
+    /// Human readable shortcut (e.g. "Cmd+Shift+O"), `None` if the item has no shortcut
+    pub shortcut: Option<String>,
+    /// Whether the shortcut comes from the user's config rather than the default
+    pub customized: bool,
+}
+
+/// A problem found while applying keybinding overrides from config
+#[derive(Debug, Clone, PartialEq)]
+enum KeybindingIssue {
+    UnknownMenuId(String),
+    InvalidAccelerator {
+        id: String,
+        value: String,
+    },
+    Conflict {
+        id: &'static str,
+        shortcut: String,
+        existing: &'static str,
+    },
+    /// A customized shortcut took over the default shortcut of another item
+    DefaultUnbound {
+        id: &'static str,
+        shortcut: String,
+        by: &'static str,
+    },
+}
+
+impl fmt::Display for KeybindingIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownMenuId(id) => write!(f, "Unknown menu item \"{id}\""),
+            Self::InvalidAccelerator { id, value } => {
+                write!(f, "Invalid shortcut \"{value}\" for \"{id}\"")
+            }
+            Self::Conflict {
+                id,
+                shortcut,
+                existing,
+            } => write!(
+                f,
+                "Shortcut {shortcut} for \"{id}\" is already used by \"{existing}\" and was ignored"
+            ),
+            Self::DefaultUnbound { id, shortcut, by } => write!(
+                f,
+                "Default shortcut {shortcut} of \"{id}\" was removed because \"{by}\" uses it"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ResolvedKeybindings {
+    accelerators: HashMap<MenuId, Accelerator>,
+    customized: Vec<MenuId>,
+    issues: Vec<KeybindingIssue>,
+}
+
+impl ResolvedKeybindings {
+    /// Apply user overrides on top of the default shortcuts.
+    ///
+    /// An empty override removes the shortcut. Customized shortcuts take precedence: a
+    /// default shortcut one of them reuses is unbound. When two customized (or two default)
+    /// shortcuts are the same, the item that comes first in menu order keeps it.
+    fn resolve(overrides: &BTreeMap<String, String>) -> Self {
+        let mut result = Self::default();
+        let mut requested: HashMap<MenuId, Option<Accelerator>> = HashMap::new();
+
+        for (id, value) in overrides {
+            let Some(menu_id) = MenuId::from_str(id) else {
+                result
+                    .issues
+                    .push(KeybindingIssue::UnknownMenuId(id.clone()));
+                continue;
+            };
+            let value = value.trim();
+            if value.is_empty() {
+                requested.insert(menu_id, None);
+                continue;
+            }
+            match Accelerator::from_str(value) {
+                Ok(accelerator) => {
+                    requested.insert(menu_id, Some(accelerator));
+                }
+                Err(_) => result.issues.push(KeybindingIssue::InvalidAccelerator {
+                    id: id.clone(),
+                    value: value.to_string(),
+                }),
+            }
+        }
+
+        let mut owners: HashMap<Accelerator, MenuId> = HashMap::new();
+        // Customized shortcuts first, so that they win over the defaults
+        for menu_id in MenuId::ALL {
+            let Some(accelerator) = requested.get(&menu_id) else {
+                continue;
+            };
+            result.customized.push(menu_id);
+            let Some(accelerator) = *accelerator else {
+                continue;
+            };
+            if let Some(existing) = owners.get(&accelerator) {
+                result.issues.push(KeybindingIssue::Conflict {
+                    id: menu_id.as_str(),
+                    shortcut: format_accelerator(&accelerator),
+                    existing: existing.as_str(),
+                });
+                continue;
+            }
+            owners.insert(accelerator, menu_id);
+            result.accelerators.insert(menu_id, accelerator);
+        }
+        for menu_id in MenuId::ALL {
+            if requested.contains_key(&menu_id) {
+                continue;
+            }
+            let Some(accelerator) = menu_id.default_accelerator() else {
+                continue;
+            };
+            if let Some(existing) = owners.get(&accelerator) {
+                let shortcut = format_accelerator(&accelerator);
+                result.issues.push(if result.customized.contains(existing) {
+                    KeybindingIssue::DefaultUnbound {
+                        id: menu_id.as_str(),
+                        shortcut,
+                        by: existing.as_str(),
+                    }
+                } else {
+                    KeybindingIssue::Conflict {
+                        id: menu_id.as_str(),
+                        shortcut,
+                        existing: existing.as_str(),
+                    }
+                });
+                continue;
+            }
+            owners.insert(accelerator, menu_id);
+            result.accelerators.insert(menu_id, accelerator);
+        }
+
+        result
+    }
+}
+
+/// Get the effective keyboard shortcut for a menu item
+pub(super) fn accelerator_for(id: MenuId) -> Option<Accelerator> {
+    RESOLVED.accelerators.get(&id).copied()
+}
+
+/// List all menu items with their effective keyboard shortcuts, in menu order
+pub fn keybinding_entries() -> Vec<KeybindingEntry> {
+    MenuId::ALL
+        .into_iter()
+        .map(|id| KeybindingEntry {
+            id: id.as_str(),
+            label: id.label(),
+            shortcut: RESOLVED.accelerators.get(&id).map(format_accelerator),
+            customized: RESOLVED.customized.contains(&id),
+        })
+        .collect()
+}
+
+/// List problems found in the keybinding overrides as human readable messages
+pub fn keybinding_issues() -> Vec<String> {
+    RESOLVED.issues.iter().map(ToString::to_string).collect()
+}
+
+/// Format an accelerator for display (e.g. "Cmd+Shift+O")
+fn format_accelerator(accelerator: &Accelerator) -> String {
+    let modifiers = accelerator.modifiers();
+    let mut parts = Vec::new();
+    if modifiers.contains(Modifiers::SUPER) {
+        parts.push(if cfg!(target_os = "macos") {
+            "Cmd"
+        } else {
+            "Super"
+        });
+    }
+    if modifiers.contains(Modifiers::CONTROL) {
+        parts.push("Ctrl");
+    }
+    if modifiers.contains(Modifiers::ALT) {
+        parts.push(if cfg!(target_os = "macos") {
+            "Option"
+        } else {
+            "Alt"
+        });
+    }
+    if modifiers.contains(Modifiers::SHIFT) {
+        parts.push("Shift");
+    }
+
+    let code = format!("{:?}", accelerator.key());
+    let key = match code.as_str() {
+        "Comma" => ",",
+        "Period" => ".",
+        "Equal" => "=",
+        "Minus" => "-",
+        "BracketLeft" => "[",
+        "BracketRight" => "]",
+        "Semicolon" => ";",
+        "Quote" => "'",
+        "Slash" => "/",
+        "Backslash" => "\\",
+        "Backquote" => "`",
+        other => other
+            .strip_prefix("Key")
+            .or_else(|| other.strip_prefix("Digit"))
+            .unwrap_or(other),
+    };
+
+    let mut result = parts.join("+");
+    if !result.is_empty() {
+        result.push('+');
+    }
+    result.push_str(key);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dioxus_desktop::muda::accelerator::Code;
+
+    fn overrides(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_resolve_defaults() {
+        let resolved = ResolvedKeybindings::resolve(&BTreeMap::new());
+        assert!(resolved.issues.is_empty());
+        assert!(resolved.customized.is_empty());
+        assert_eq!(
+            resolved.accelerators.get(&MenuId::ToggleSidebar),
+            MenuId::ToggleSidebar.default_accelerator().as_ref()
+        );
+        assert!(!resolved.accelerators.contains_key(&MenuId::CloseAllTabs));
+    }
+
+    #[test]
+    fn test_resolve_override_and_removal() {
+        let resolved = ResolvedKeybindings::resolve(&overrides(&[
+            ("view.toggle_sidebar", "Super+Shift+KeyB"),
+            ("file.close_tab", ""),
+        ]));
+        assert!(resolved.issues.is_empty());
+        assert_eq!(
+            resolved.accelerators.get(&MenuId::ToggleSidebar),
+            Some(&Accelerator::new(
+                Some(Modifiers::SUPER | Modifiers::SHIFT),
+                Code::KeyB
+            ))
+        );
+        assert!(!resolved.accelerators.contains_key(&MenuId::CloseTab));
+        assert!(resolved.customized.contains(&MenuId::ToggleSidebar));
+        assert!(resolved.customized.contains(&MenuId::CloseTab));
+    }
+
+    #[test]
+    fn test_resolve_reports_unknown_and_invalid() {
+        let resolved = ResolvedKeybindings::resolve(&overrides(&[
+            ("view.nonexistent", "Super+KeyX"),
+            ("view.zoom_in", "Super+NotAKey"),
+        ]));
+        assert_eq!(
+            resolved.issues,
+            vec![
+                KeybindingIssue::UnknownMenuId("view.nonexistent".to_string()),
+                KeybindingIssue::InvalidAccelerator {
+                    id: "view.zoom_in".to_string(),
+                    value: "Super+NotAKey".to_string(),
+                },
+            ]
+        );
+        // Invalid overrides fall back to the default
+        assert_eq!(
+            resolved.accelerators.get(&MenuId::ZoomIn),
+            MenuId::ZoomIn.default_accelerator().as_ref()
+        );
+    }
+
+    #[test]
+    fn test_resolve_customized_wins_over_default() {
+        // Cmd+T (Ctrl+T off macOS) is New Tab's default
+        let resolved =
+            ResolvedKeybindings::resolve(&overrides(&[("view.toggle_sidebar", "CmdOrCtrl+KeyT")]));
+        assert_eq!(resolved.issues.len(), 1);
+        assert!(matches!(
+            resolved.issues[0],
+            KeybindingIssue::DefaultUnbound {
+                id: "file.new_tab",
+                by: "view.toggle_sidebar",
+                ..
+            }
+        ));
+        assert_eq!(
+            resolved.accelerators.get(&MenuId::ToggleSidebar),
+            MenuId::NewTab.default_accelerator().as_ref()
+        );
+        assert!(!resolved.accelerators.contains_key(&MenuId::NewTab));
+        assert!(!resolved.customized.contains(&MenuId::NewTab));
+    }
+
+    #[test]
+    fn test_resolve_conflict_between_customized_keeps_first_in_menu_order() {
+        let resolved = ResolvedKeybindings::resolve(&overrides(&[
+            ("view.toggle_sidebar", "Super+Shift+KeyK"),
+            ("file.new_tab", "Super+Shift+KeyK"),
+        ]));
+        assert_eq!(resolved.issues.len(), 1);
+        assert!(matches!(
+            resolved.issues[0],
+            KeybindingIssue::Conflict {
+                id: "view.toggle_sidebar",
+                existing: "file.new_tab",
+                ..
+            }
+        ));
+        assert!(resolved.accelerators.contains_key(&MenuId::NewTab));
+        assert!(!resolved.accelerators.contains_key(&MenuId::ToggleSidebar));
+    }
+
+    #[test]
+    fn test_format_accelerator() {
+        let accelerator = Accelerator::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::KeyO);
+        let expected = if cfg!(target_os = "macos") {
+            "Cmd+Shift+O"
+        } else {
+            "Super+Shift+O"
+        };
+        assert_eq!(format_accelerator(&accelerator), expected);
+
+        let accelerator = Accelerator::new(Some(Modifiers::CONTROL), Code::Equal);
+        assert_eq!(format_accelerator(&accelerator), "Ctrl+=");
+    }
+}
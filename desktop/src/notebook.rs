@@ -0,0 +1,400 @@
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+use crate::markdown::{render_document, sanitize, HtmlPolicy, RenderOptions, RenderedDocument};
+
+/// Image output types, in order of preference
+const IMAGE_MIME_TYPES: [&str; 4] = ["image/png", "image/jpeg", "image/gif", "image/svg+xml"];
+
+/// Jupyter notebook (nbformat 4), reduced to the parts we render
+#[derive(Debug, Deserialize)]
+struct Notebook {
+    #[serde(default)]
+    cells: Vec<Cell>,
+    #[serde(default)]
+    metadata: NotebookMetadata,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct NotebookMetadata {
+    kernelspec: Option<LanguageMetadata>,
+    language_info: Option<LanguageMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LanguageMetadata {
+    #[serde(alias = "language")]
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "cell_type", rename_all = "snake_case")]
+enum Cell {
+    Markdown {
+        source: MultilineText,
+    },
+    Code {
+        source: MultilineText,
+        execution_count: Option<u64>,
+        #[serde(default)]
+        outputs: Vec<Output>,
+    },
+    Raw {
+        source: MultilineText,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "output_type", rename_all = "snake_case")]
+enum Output {
+    Stream {
+        name: String,
+        text: MultilineText,
+    },
+    DisplayData {
+        data: HashMap<String, MultilineText>,
+    },
+    ExecuteResult {
+        data: HashMap<String, MultilineText>,
+        execution_count: Option<u64>,
+    },
+    Error {
+        ename: String,
+        evalue: String,
+        #[serde(default)]
+        traceback: Vec<String>,
+    },
+}
+
+/// Notebook text fields are either a string or a list of lines
+#[derive(Debug, Deserialize)]
+#[serde(from = "Value")]
+struct MultilineText(String);
+
+impl From<Value> for MultilineText {
+    fn from(value: Value) -> Self {
+        Self(match value {
+            Value::String(text) => text,
+            Value::Array(lines) => lines
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .concat(),
+            // JSON outputs (application/json, widgets) are not rendered
+            _ => String::new(),
+        })
+    }
+}
+
+/// Render a Jupyter notebook (`.ipynb` JSON) to sanitized HTML
+///
+/// Markdown cells are rendered like Markdown files; code cells become highlighted code blocks
+/// with their execution count, followed by their outputs. HTML outputs are sanitized like raw
+/// HTML in Markdown, with the same policy.
+pub fn render_notebook(
+    json: &str,
+    base_path: impl AsRef<Path>,
+    render_options: &RenderOptions,
+) -> Result<RenderedDocument> {
+    let base_path = base_path.as_ref();
+    let notebook: Notebook = serde_json::from_str(json).context("Invalid notebook JSON")?;
+    let language = notebook
+        .metadata
+        .language_info
+        .and_then(|info| info.name)
+        .or_else(|| notebook.metadata.kernelspec.and_then(|spec| spec.name))
+        .unwrap_or_else(|| "python".to_string());
+
+    let mut html = String::from(r#"<div class="notebook">"#);
+    let mut stripped = BTreeSet::new();
+    for cell in notebook.cells {
+        match cell {
+            Cell::Markdown { source } => {
+                html.push_str(r#"<div class="notebook-cell notebook-markdown-cell">"#);
+                let rendered = render_document(&source.0, base_path, render_options)?;
+                html.push_str(&rendered.html);
+                stripped.extend(rendered.stripped);
+                html.push_str("</div>");
+            }
+            Cell::Code {
+                source,
+                execution_count,
+                outputs,
+            } => {
+                html.push_str(r#"<div class="notebook-cell notebook-code-cell">"#);
+                html.push_str(&format!(
+                    r#"<div class="notebook-input">{}<pre><code class="language-{}">{}</code></pre></div>"#,
+                    prompt("In", execution_count),
+                    html_escape::encode_double_quoted_attribute(&language),
+                    html_escape::encode_text(source.0.trim_end()),
+                ));
+                for output in outputs {
+                    html.push_str(&render_output(
+                        output,
+                        render_options.html_policy,
+                        &mut stripped,
+                    ));
+                }
+                html.push_str("</div>");
+            }
+            Cell::Raw { source } => {
+                html.push_str(&format!(
+                    r#"<div class="notebook-cell notebook-raw-cell"><pre class="notebook-text-output">{}</pre></div>"#,
+                    html_escape::encode_text(&source.0),
+                ));
+            }
+        }
+    }
+    html.push_str("</div>");
+    Ok(RenderedDocument {
+        stripped: stripped.into_iter().collect(),
+        ..RenderedDocument::from(html)
+    })
+}
+
+fn prompt(label: &str, execution_count: Option<u64>) -> String {
+    let count = execution_count.map(|n| n.to_string()).unwrap_or_default();
+    format!(r#"<div class="notebook-prompt">{label} [{count}]:</div>"#)
+}
+
+/// Render a cell output, adding what the sanitizer removed from HTML outputs to `stripped`
+fn render_output(output: Output, policy: HtmlPolicy, stripped: &mut BTreeSet<String>) -> String {
+    let (prompt_html, body) = match output {
+        Output::Stream { name, text } => (
+            prompt_placeholder(),
+            format!(
+                r#"<pre class="notebook-text-output notebook-stream-{}">{}</pre>"#,
+                html_escape::encode_double_quoted_attribute(&name),
+                html_escape::encode_text(&strip_ansi_escapes::strip_str(&text.0)),
+            ),
+        ),
+        Output::DisplayData { data } => (
+            prompt_placeholder(),
+            render_mime_bundle(&data, policy, stripped),
+        ),
+        Output::ExecuteResult {
+            data,
+            execution_count,
+        } => (
+            prompt("Out", execution_count),
+            render_mime_bundle(&data, policy, stripped),
+        ),
+        Output::Error {
+            ename,
+            evalue,
+            traceback,
+        } => {
+            let text = if traceback.is_empty() {
+                format!("{ename}: {evalue}")
+            } else {
+                traceback.join("\n")
+            };
+            (
+                prompt_placeholder(),
+                format!(
+                    r#"<pre class="notebook-text-output notebook-error">{}</pre>"#,
+                    html_escape::encode_text(&strip_ansi_escapes::strip_str(&text)),
+                ),
+            )
+        }
+    };
+    format!(r#"<div class="notebook-output">{prompt_html}{body}</div>"#)
+}
+
+/// Base64 data of an image output, re-encoded so that it can't break out of the `src` attribute
+///
+/// SVG is stored as markup, raster images as base64; invalid base64 is `None`.
+fn image_data(mime_type: &str, content: &str) -> Option<String> {
+    if mime_type == "image/svg+xml" {
+        return Some(general_purpose::STANDARD.encode(content));
+    }
+    let base64_data: String = content.split_whitespace().collect();
+    let bytes = general_purpose::STANDARD.decode(base64_data).ok()?;
+    Some(general_purpose::STANDARD.encode(bytes))
+}
+
+fn prompt_placeholder() -> String {
+    r#"<div class="notebook-prompt"></div>"#.to_string()
+}
+
+// Render the richest output type we support from a MIME bundle
+fn render_mime_bundle(
+    data: &HashMap<String, MultilineText>,
+    policy: HtmlPolicy,
+    stripped: &mut BTreeSet<String>,
+) -> String {
+    if let Some((mime_type, base64_data)) = IMAGE_MIME_TYPES.iter().find_map(|mime_type| {
+        let content = data.get(*mime_type)?;
+        Some((*mime_type, image_data(mime_type, &content.0)?))
+    }) {
+        return format!(
+            r#"<div class="notebook-image-output"><img src="data:{mime_type};base64,{base64_data}" alt="" /></div>"#
+        );
+    }
+    if let Some(content) = data.get("text/html") {
+        let (html, removed) = sanitize(&content.0, policy);
+        stripped.extend(removed);
+        return format!(r#"<div class="notebook-html-output">{html}</div>"#);
+    }
+    if let Some(content) = data.get("text/plain") {
+        return format!(
+            r#"<pre class="notebook-text-output">{}</pre>"#,
+            html_escape::encode_text(&strip_ansi_escapes::strip_str(&content.0)),
+        );
+    }
+    String::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(json: &str) -> String {
+        render_notebook(json, "/notebooks/analysis.ipynb", &RenderOptions::default())
+            .unwrap()
+            .html
+    }
+
+    #[test]
+    fn test_render_markdown_cell() {
+        let html = render(
+            r##"{"cells": [{"cell_type": "markdown", "metadata": {}, "source": ["# Title\n", "Some *text*"]}]}"##,
+        );
+
+        assert!(html.contains(r#"<div class="notebook-cell notebook-markdown-cell">"#));
+        assert!(html.contains("<h1>Title</h1>"));
+        assert!(html.contains("<em>text</em>"));
+    }
+
+    #[test]
+    fn test_render_code_cell_with_language_and_count() {
+        let html = render(indoc::indoc! {r#"
+            {
+              "metadata": {"language_info": {"name": "julia"}},
+              "cells": [{
+                "cell_type": "code",
+                "execution_count": 3,
+                "metadata": {},
+                "source": "x = 1 < 2\n",
+                "outputs": [
+                  {"output_type": "execute_result", "execution_count": 3, "metadata": {},
+                   "data": {"text/plain": ["true"]}}
+                ]
+              }]
+            }
+        "#});
+
+        assert!(html.contains(r#"<div class="notebook-prompt">In [3]:</div>"#));
+        assert!(html.contains(r#"<code class="language-julia">x = 1 &lt; 2</code>"#));
+        assert!(html.contains(r#"<div class="notebook-prompt">Out [3]:</div>"#));
+        assert!(html.contains(r#"<pre class="notebook-text-output">true</pre>"#));
+    }
+
+    #[test]
+    fn test_render_unexecuted_code_cell_defaults_to_python() {
+        let html = render(
+            r#"{"cells": [{"cell_type": "code", "execution_count": null, "metadata": {}, "source": [], "outputs": []}]}"#,
+        );
+
+        assert!(html.contains("In []:"));
+        assert!(html.contains(r#"class="language-python""#));
+    }
+
+    #[test]
+    fn test_render_stream_and_error_outputs() {
+        let html = render(indoc::indoc! {r#"
+            {"cells": [{
+              "cell_type": "code", "execution_count": 1, "metadata": {}, "source": "print(1/0)",
+              "outputs": [
+                {"output_type": "stream", "name": "stdout", "text": ["hello\n"]},
+                {"output_type": "error", "ename": "ZeroDivisionError", "evalue": "division by zero",
+                 "traceback": ["\u001b[0;31mZeroDivisionError\u001b[0m: division by zero"]}
+              ]
+            }]}
+        "#});
+
+        assert!(html.contains(r#"<pre class="notebook-text-output notebook-stream-stdout">hello"#));
+        assert!(html.contains(
+            r#"<pre class="notebook-text-output notebook-error">ZeroDivisionError: division by zero</pre>"#
+        ));
+    }
+
+    #[test]
+    fn test_render_image_outputs() {
+        let html = render(indoc::indoc! {r#"
+            {"cells": [{
+              "cell_type": "code", "execution_count": 2, "metadata": {}, "source": "plot()",
+              "outputs": [
+                {"output_type": "display_data", "metadata": {},
+                 "data": {"image/png": "iVBORw0KGgo=\n", "text/plain": ["<Figure>"]}},
+                {"output_type": "display_data", "metadata": {},
+                 "data": {"image/svg+xml": ["<svg></svg>"]}}
+              ]
+            }]}
+        "#});
+
+        assert!(html.contains(r#"<img src="data:image/png;base64,iVBORw0KGgo=" alt="" />"#));
+        assert!(html.contains(&format!(
+            "data:image/svg+xml;base64,{}",
+            general_purpose::STANDARD.encode("<svg></svg>")
+        )));
+        assert!(!html.contains("&lt;Figure&gt;"));
+    }
+
+    #[test]
+    fn test_render_invalid_image_output_is_not_injected() {
+        let html = render(indoc::indoc! {r#"
+            {"cells": [{
+              "cell_type": "code", "execution_count": 1, "metadata": {}, "source": "plot()",
+              "outputs": [
+                {"output_type": "display_data", "metadata": {},
+                 "data": {"image/png": "x\" onerror=\"alert(1)", "text/plain": ["<Figure>"]}}
+              ]
+            }]}
+        "#});
+
+        assert!(!html.contains("onerror"));
+        assert!(!html.contains("<img"));
+        assert!(html.contains(r#"<pre class="notebook-text-output">&lt;Figure&gt;</pre>"#));
+    }
+
+    #[test]
+    fn test_render_html_output_is_sanitized() {
+        let json = indoc::indoc! {r#"
+            {"cells": [{
+              "cell_type": "code", "execution_count": 1, "metadata": {}, "source": "df",
+              "outputs": [
+                {"output_type": "execute_result", "execution_count": 1, "metadata": {},
+                 "data": {"text/html": ["<table onclick=\"alert(1)\"><tr><td>1</td></tr></table>",
+                                        "<script>alert(2)</script>"]}}
+              ]
+            }]}
+        "#};
+
+        let rendered = render_notebook(json, "analysis.ipynb", &RenderOptions::default()).unwrap();
+        assert!(rendered
+            .html
+            .contains("<table><tbody><tr><td>1</td></tr></tbody></table>"));
+        assert!(!rendered.html.contains("onclick"));
+        assert!(!rendered.html.contains("<script>"));
+        assert_eq!(rendered.stripped, vec!["<script>", "onclick attribute"]);
+
+        // The configured policy applies to outputs too
+        let options = RenderOptions {
+            html_policy: HtmlPolicy::Trusted,
+            ..RenderOptions::default()
+        };
+        let rendered = render_notebook(json, "analysis.ipynb", &options).unwrap();
+        assert!(rendered.html.contains("<script>"));
+        assert!(rendered.stripped.is_empty());
+    }
+
+    #[test]
+    fn test_render_invalid_notebook() {
+        assert!(render_notebook("not json", "a.ipynb", &RenderOptions::default()).is_err());
+    }
+}
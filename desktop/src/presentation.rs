@@ -0,0 +1,229 @@
+//! Presenting a Markdown document as slides
+//!
+//! A presentation is shown full screen over the window that started it, and optionally in a
+//! presenter view (a child window with the speaker notes, the next slide and a timer). Both
+//! follow the same [`Presentation`], so navigating in either moves the other along.
+
+mod slides;
+
+pub use slides::{render_slides, Slide};
+
+use anyhow::{Context, Result};
+use dioxus::prelude::*;
+use encoding_rs::Encoding;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::watch;
+
+use crate::config::CONFIG;
+use crate::markdown::RenderOptions;
+use crate::remote_images::{rewrite_remote_images, RemoteImageOptions};
+use crate::state::AppState;
+use crate::utils::encoding::decode_text;
+use crate::utils::file::is_markdown_file;
+
+/// Slides of a document along with the one shown
+#[derive(Debug, Clone)]
+pub struct Presentation {
+    pub file: PathBuf,
+    pub slides: Arc<Vec<Slide>>,
+    /// Directories the slides load images and media from, granted to the windows showing them
+    pub asset_directories: Arc<Vec<PathBuf>>,
+    current: Arc<watch::Sender<usize>>,
+}
+
+impl PartialEq for Presentation {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.slides, &other.slides)
+    }
+}
+
+impl Presentation {
+    pub fn new(file: PathBuf, slides: Vec<Slide>, asset_directories: Vec<PathBuf>) -> Self {
+        Self {
+            file,
+            slides: Arc::new(slides),
+            asset_directories: Arc::new(asset_directories),
+            current: Arc::new(watch::Sender::new(0)),
+        }
+    }
+
+    /// Index of the slide shown
+    pub fn current(&self) -> usize {
+        *self.current.borrow()
+    }
+
+    /// Receive the index of the slide shown whenever it changes
+    pub fn subscribe(&self) -> watch::Receiver<usize> {
+        self.current.subscribe()
+    }
+
+    /// Show the slide at `index`, clamped to the existing ones
+    pub fn go_to(&self, index: usize) {
+        let index = index.min(self.slides.len().saturating_sub(1));
+        self.current.send_if_modified(|current| {
+            let changed = *current != index;
+            *current = index;
+            changed
+        });
+    }
+
+    /// Apply a navigation command, returning whether it was one
+    pub fn navigate(&self, command: SlideCommand) -> bool {
+        let current = self.current();
+        match command {
+            SlideCommand::Next => self.go_to(current + 1),
+            SlideCommand::Previous => self.go_to(current.saturating_sub(1)),
+            SlideCommand::First => self.go_to(0),
+            SlideCommand::Last => self.go_to(usize::MAX),
+            SlideCommand::Exit | SlideCommand::PresenterView => return false,
+        }
+        true
+    }
+}
+
+/// What a key press does during a presentation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlideCommand {
+    Next,
+    Previous,
+    First,
+    Last,
+    Exit,
+    PresenterView,
+}
+
+impl SlideCommand {
+    /// Command bound to `key`, like in other presentation tools (and presentation remotes)
+    pub fn from_key(key: &Key) -> Option<Self> {
+        match key {
+            Key::ArrowRight | Key::ArrowDown | Key::PageDown | Key::Enter => Some(Self::Next),
+            Key::ArrowLeft | Key::ArrowUp | Key::PageUp | Key::Backspace => Some(Self::Previous),
+            Key::Home => Some(Self::First),
+            Key::End => Some(Self::Last),
+            Key::Escape => Some(Self::Exit),
+            Key::Character(c) => match c.as_str() {
+                " " | "n" | "l" | "j" => Some(Self::Next),
+                "p" | "h" | "k" => Some(Self::Previous),
+                "s" | "S" => Some(Self::PresenterView),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Present the Markdown document of the current tab
+pub fn start_presentation(mut state: AppState) {
+    let Some((file, encoding)) = state
+        .current_tab()
+        .and_then(|tab| Some((tab.file()?.to_path_buf(), tab.encoding_override)))
+        .filter(|(file, _)| is_markdown_file(file))
+    else {
+        tracing::debug!("Only Markdown documents can be presented");
+        return;
+    };
+    let render_options = state.render_options_for(&file);
+    let image_options = CONFIG.read().remote_content.image_options();
+    let asset_directories = render_options
+        .asset_directories(&file)
+        .map(Path::to_path_buf)
+        .collect();
+
+    spawn(async move {
+        let task_file = file.clone();
+        let slides = tokio::task::spawn_blocking(move || {
+            load_slides(&task_file, encoding, &render_options, &image_options)
+        })
+        .await;
+        match slides {
+            Ok(Ok(slides)) if !slides.is_empty() => {
+                state
+                    .presentation
+                    .set(Some(Presentation::new(file, slides, asset_directories)));
+            }
+            Ok(Ok(_)) => tracing::info!("{:?} has no slides to present", file),
+            Ok(Err(e)) => tracing::error!("Failed to present {:?}: {:#}", file, e),
+            Err(e) => tracing::error!("Failed to present {:?}: {}", file, e),
+        }
+    });
+}
+
+/// Read and render the slides of `file`, decoded with `encoding` when one was chosen
+fn load_slides(
+    file: &Path,
+    encoding: Option<&'static Encoding>,
+    render_options: &RenderOptions,
+    image_options: &RemoteImageOptions,
+) -> Result<Vec<Slide>> {
+    let bytes = std::fs::read(file)?;
+    let content = decode_text(&bytes, encoding).context("The file appears to be binary")?;
+    let mut slides = render_slides(&content.text, file, render_options)?;
+    for slide in &mut slides {
+        slide.html = rewrite_remote_images(&slide.html, image_options).0;
+        slide.notes = rewrite_remote_images(&slide.notes, image_options).0;
+    }
+    Ok(slides)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn presentation(len: usize) -> Presentation {
+        let slide = Slide {
+            html: String::new(),
+            notes: String::new(),
+        };
+        Presentation::new(PathBuf::from("deck.md"), vec![slide; len], Vec::new())
+    }
+
+    #[test]
+    fn test_navigate() {
+        let presentation = presentation(3);
+        let mut rx = presentation.subscribe();
+
+        assert!(presentation.navigate(SlideCommand::Next));
+        assert_eq!(presentation.current(), 1);
+        assert!(rx.has_changed().unwrap());
+        rx.mark_unchanged();
+
+        presentation.navigate(SlideCommand::Last);
+        presentation.navigate(SlideCommand::Next);
+        assert_eq!(presentation.current(), 2);
+
+        presentation.navigate(SlideCommand::First);
+        presentation.navigate(SlideCommand::Previous);
+        assert_eq!(presentation.current(), 0);
+
+        assert!(!presentation.navigate(SlideCommand::Exit));
+    }
+
+    #[test]
+    fn test_slide_command_from_key() {
+        assert_eq!(
+            SlideCommand::from_key(&Key::ArrowRight),
+            Some(SlideCommand::Next)
+        );
+        assert_eq!(
+            SlideCommand::from_key(&Key::Character(" ".to_string())),
+            Some(SlideCommand::Next)
+        );
+        assert_eq!(
+            SlideCommand::from_key(&Key::PageUp),
+            Some(SlideCommand::Previous)
+        );
+        assert_eq!(
+            SlideCommand::from_key(&Key::Character("s".to_string())),
+            Some(SlideCommand::PresenterView)
+        );
+        assert_eq!(
+            SlideCommand::from_key(&Key::Escape),
+            Some(SlideCommand::Exit)
+        );
+        assert_eq!(
+            SlideCommand::from_key(&Key::Character("x".to_string())),
+            None
+        );
+    }
+}
@@ -0,0 +1,277 @@
+//! Splitting a Markdown document into slides
+//!
+//! Slides are separated by top-level thematic breaks (`---`), or start at each H1/H2 heading.
+//! The `slides` field of the front matter chooses one (`---`, `h1` or `h2`); without it,
+//! documents with no thematic break are split on headings. A top-level paragraph starting with
+//! `Note:` starts the speaker notes, which run to the end of the slide.
+
+use anyhow::Result;
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use std::path::Path;
+
+use crate::markdown::{render_document, RenderOptions};
+
+/// Prefixes of the paragraph starting the speaker notes of a slide
+const NOTES_PREFIXES: [&str; 2] = ["Notes:", "Note:"];
+
+/// Where a document is split into slides
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlideSplit {
+    /// On top-level thematic breaks, which aren't shown
+    Rule,
+    /// Before every heading up to the given level
+    Headings(HeadingLevel),
+}
+
+impl SlideSplit {
+    /// Parse the `slides` field of the front matter
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "---" | "rule" | "hr" => Some(Self::Rule),
+            "h1" | "#" => Some(Self::Headings(HeadingLevel::H1)),
+            "h2" | "##" => Some(Self::Headings(HeadingLevel::H2)),
+            _ => None,
+        }
+    }
+}
+
+/// Markdown source of a slide
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlideSource<'a> {
+    pub content: &'a str,
+    /// Speaker notes, without their `Note:` prefix
+    pub notes: Option<&'a str>,
+}
+
+/// Rendered slide
+#[derive(Debug, Clone, PartialEq)]
+pub struct Slide {
+    pub html: String,
+    /// Rendered speaker notes, empty when the slide has none
+    pub notes: String,
+}
+
+/// Render each slide of `markdown` (and its notes) like a document at `base_path`
+pub fn render_slides(
+    markdown: &str,
+    base_path: &Path,
+    render_options: &RenderOptions,
+) -> Result<Vec<Slide>> {
+    split_slides(markdown)
+        .into_iter()
+        .map(|source| {
+            let render = |markdown| -> Result<String> {
+                Ok(render_document(markdown, base_path, render_options)?.html)
+            };
+            Ok(Slide {
+                html: render(source.content)?,
+                notes: source.notes.map(render).transpose()?.unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Split `markdown` into slides as chosen by its front matter
+pub fn split_slides(markdown: &str) -> Vec<SlideSource<'_>> {
+    if let Some(split) = front_matter_field(markdown, "slides").and_then(SlideSplit::parse) {
+        return split_slides_on(markdown, split);
+    }
+    let slides = split_slides_on(markdown, SlideSplit::Rule);
+    if slides.len() > 1 {
+        slides
+    } else {
+        split_slides_on(markdown, SlideSplit::Headings(HeadingLevel::H2))
+    }
+}
+
+/// Split `markdown` into slides at `split`, leaving out the front matter and empty slides
+pub fn split_slides_on(markdown: &str, split: SlideSplit) -> Vec<SlideSource<'_>> {
+    let mut slides = Vec::new();
+    let mut finish = |start: usize, end: usize, notes: Option<usize>| {
+        let content = &markdown[start..notes.unwrap_or(end)];
+        let notes = notes.map(|notes| {
+            let notes = &markdown[notes..end];
+            let prefix = NOTES_PREFIXES
+                .iter()
+                .find(|prefix| notes.starts_with(*prefix))
+                .map_or(0, |prefix| prefix.len());
+            notes[prefix..].trim()
+        });
+        if !content.trim().is_empty() || notes.is_some_and(|notes| !notes.is_empty()) {
+            slides.push(SlideSource { content, notes });
+        }
+    };
+
+    let mut depth = 0_usize;
+    let mut start = 0;
+    let mut notes = None;
+    for (event, range) in Parser::new_ext(markdown, Options::all()).into_offset_iter() {
+        match event {
+            Event::Start(tag) => {
+                if depth == 0 {
+                    match tag {
+                        Tag::Heading { level, .. } if matches!(split, SlideSplit::Headings(max) if level <= max) =>
+                        {
+                            finish(start, range.start, notes.take());
+                            start = range.start;
+                        }
+                        Tag::Paragraph
+                            if notes.is_none()
+                                && NOTES_PREFIXES
+                                    .iter()
+                                    .any(|prefix| markdown[range.clone()].starts_with(prefix)) =>
+                        {
+                            notes = Some(range.start);
+                        }
+                        _ => {}
+                    }
+                }
+                depth += 1;
+            }
+            Event::End(tag) => {
+                depth -= 1;
+                // The front matter isn't part of the first slide
+                if depth == 0 && matches!(tag, TagEnd::MetadataBlock(_)) {
+                    start = range.end;
+                }
+            }
+            Event::Rule if depth == 0 && split == SlideSplit::Rule => {
+                finish(start, range.start, notes.take());
+                start = range.end;
+            }
+            _ => {}
+        }
+    }
+    finish(start, markdown.len(), notes);
+
+    slides
+}
+
+/// Value of a top-level field of the YAML front matter of `markdown`
+fn front_matter_field<'a>(markdown: &'a str, key: &str) -> Option<&'a str> {
+    let mut lines = markdown.lines();
+    if lines.next()?.trim_end() != "---" {
+        return None;
+    }
+    lines
+        .take_while(|line| !matches!(line.trim_end(), "---" | "..."))
+        .find_map(|line| {
+            let (field, value) = line.split_once(':')?;
+            (field == key).then(|| value.trim().trim_matches(['"', '\'']))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn contents<'a>(slides: &[SlideSource<'a>]) -> Vec<&'a str> {
+        slides.iter().map(|slide| slide.content.trim()).collect()
+    }
+
+    #[test]
+    fn test_split_slides_on_rules() {
+        let markdown = indoc! {"
+            # Title
+
+            Intro
+
+            ---
+
+            ## Agenda
+
+            - one
+            - two
+
+            > ---
+            > quoted rules don't split
+
+            ***
+
+            Setext headings aren't rules
+            ---
+        "};
+
+        let slides = split_slides(markdown);
+
+        assert_eq!(
+            contents(&slides),
+            vec![
+                "# Title\n\nIntro",
+                "## Agenda\n\n- one\n- two\n\n> ---\n> quoted rules don't split",
+                "Setext headings aren't rules\n---",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_slides_on_headings() {
+        let markdown = indoc! {"
+            ---
+            title: Demo
+            slides: h1
+            ---
+            # One
+
+            ## Still one
+
+            # Two
+        "};
+
+        assert_eq!(
+            contents(&split_slides(markdown)),
+            vec!["# One\n\n## Still one", "# Two"]
+        );
+
+        // Without separators, slides start at H1 and H2 headings
+        let markdown = "Preamble\n\n# One\n\n## Two\n\n### Still two\n";
+        assert_eq!(
+            contents(&split_slides(markdown)),
+            vec!["Preamble", "# One", "## Two\n\n### Still two"]
+        );
+    }
+
+    #[test]
+    fn test_split_slides_with_speaker_notes() {
+        let markdown = indoc! {"
+            # Welcome
+
+            Note: Introduce yourself.
+
+            Mention the **agenda**.
+
+            ---
+
+            # No notes
+
+            A Note: in the middle isn't one
+        "};
+
+        let slides = split_slides(markdown);
+
+        assert_eq!(slides.len(), 2);
+        assert_eq!(slides[0].content.trim(), "# Welcome");
+        assert_eq!(
+            slides[0].notes,
+            Some("Introduce yourself.\n\nMention the **agenda**.")
+        );
+        assert_eq!(slides[1].notes, None);
+    }
+
+    #[test]
+    fn test_render_slides() {
+        let slides = render_slides(
+            "# Hello\n\nNote: Smile\n\n---\n\nBye\n",
+            Path::new("/tmp/deck.md"),
+            &RenderOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(slides.len(), 2);
+        assert!(slides[0].html.contains("Hello</h1>"));
+        assert!(!slides[0].html.contains("Smile"));
+        assert_eq!(slides[0].notes.trim(), "<p>Smile</p>");
+        assert!(slides[1].notes.is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+//! Printing and PDF export
+//!
+//! Before printing, the renderer (`print.ts`) switches the document to the light theme, waits
+//! for diagrams, formulas and images to be rendered, and adds the print-only parts configured
+//! in [`PrintConfig`]. Printing goes through the webview's print dialog; PDF export prints to a
+//! file without one, from the menu or from the command line (`arto export-pdf`).
+
+pub mod headless;
+mod pdf;
+
+use anyhow::Result;
+use dioxus::desktop::{window, DesktopContext};
+use dioxus::document;
+use dioxus::prelude::spawn;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::assets::MAIN_SCRIPT;
+use crate::config::{PrintConfig, CONFIG};
+use crate::state::AppState;
+
+/// Options of `preparePrint` in the renderer
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrintOptions {
+    /// File name shown in the page header
+    pub title: String,
+    pub table_of_contents: bool,
+    pub page_header: bool,
+    pub link_footnotes: bool,
+}
+
+impl PrintOptions {
+    pub fn new(file: &Path, config: &PrintConfig) -> Self {
+        Self {
+            title: file
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            table_of_contents: config.table_of_contents,
+            page_header: config.page_header,
+            link_footnotes: config.link_footnotes,
+        }
+    }
+}
+
+/// Open the print dialog for the document of the current tab
+pub fn print_document(state: &AppState) {
+    let Some(file) = current_file(state) else {
+        tracing::debug!("No document to print");
+        return;
+    };
+    let options = PrintOptions::new(&file, &CONFIG.read().print);
+    spawn(async move {
+        if let Err(e) = prepare_print(&options).await {
+            tracing::error!("Failed to prepare {:?} for printing: {:#}", file, e);
+            return;
+        }
+        // The renderer restores the document once the dialog closes (afterprint)
+        if let Err(e) = window().webview.print() {
+            tracing::error!("Failed to print {:?}: {}", file, e);
+        }
+    });
+}
+
+/// Ask where to save the document of the current tab as PDF, and export it there
+pub fn export_pdf(state: &AppState) {
+    let Some(file) = current_file(state) else {
+        tracing::debug!("No document to export");
+        return;
+    };
+    let Some(output) = pick_pdf_save_path(&file) else {
+        return;
+    };
+    let options = PrintOptions::new(&file, &CONFIG.read().print);
+    spawn(async move {
+        match export_document(&window(), &options, &output).await {
+            Ok(()) => tracing::info!("Exported {:?} to {:?}", file, output),
+            Err(e) => tracing::error!("Failed to export {:?} as PDF: {:#}", file, e),
+        }
+    });
+}
+
+/// Print the displayed document to a PDF file at `output`
+pub async fn export_document(
+    context: &DesktopContext,
+    options: &PrintOptions,
+    output: &Path,
+) -> Result<()> {
+    prepare_print(options).await?;
+    let result = pdf::print_to_pdf(context, output).await;
+    finish_print().await;
+    result
+}
+
+/// Prepare the displayed document for printing, returning once it is fully rendered
+async fn prepare_print(options: &PrintOptions) -> Result<()> {
+    let mut eval = document::eval(&indoc::formatdoc! {r#"
+        const {{ preparePrint }} = await import("{MAIN_SCRIPT}");
+        await preparePrint(await dioxus.recv());
+        dioxus.send(true);
+    "#});
+    eval.send(options)?;
+    eval.recv::<bool>().await?;
+    Ok(())
+}
+
+/// Remove the print-only parts of the document and restore its theme
+async fn finish_print() {
+    let _ = document::eval(&indoc::formatdoc! {r#"
+        const {{ finishPrint }} = await import("{MAIN_SCRIPT}");
+        finishPrint();
+    "#})
+    .await;
+}
+
+fn current_file(state: &AppState) -> Option<PathBuf> {
+    state
+        .current_tab()
+        .and_then(|tab| tab.file().map(Path::to_path_buf))
+}
+
+/// Show save dialog for the PDF, starting next to the document
+fn pick_pdf_save_path(file: &Path) -> Option<PathBuf> {
+    use rfd::FileDialog;
+
+    let file_name = file
+        .file_stem()
+        .map(|stem| format!("{}.pdf", stem.to_string_lossy()))
+        .unwrap_or_else(|| "document.pdf".to_string());
+    let path = FileDialog::new()
+        .add_filter("PDF", &["pdf"])
+        .set_directory(file.parent().unwrap_or(Path::new("/")))
+        .set_file_name(file_name)
+        .save_file()?;
+    // Some platforms don't append the filter extension on their own
+    Some(if path.extension().is_some_and(|ext| ext == "pdf") {
+        path
+    } else {
+        path.with_extension("pdf")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_print_options() {
+        let options = PrintOptions::new(
+            Path::new("/notes/weekly report.md"),
+            &PrintConfig {
+                table_of_contents: true,
+                ..PrintConfig::default()
+            },
+        );
+
+        assert_eq!(
+            serde_json::to_value(&options).unwrap(),
+            serde_json::json!({
+                "title": "weekly report.md",
+                "tableOfContents": true,
+                "pageHeader": true,
+                "linkFootnotes": true,
+            })
+        );
+    }
+}
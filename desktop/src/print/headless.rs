@@ -0,0 +1,243 @@
+//! `arto export-pdf`: export a document to PDF without showing a window
+//!
+//! The document is rendered like in a tab, in a hidden window, then printed to the output file.
+//! The process exits with status 0 once the PDF is written, 1 when the export fails and 2 on
+//! invalid arguments.
+
+use anyhow::{Context, Result};
+use dioxus::desktop::{window, Config, LogicalSize, WindowBuilder};
+use dioxus::document;
+use dioxus::prelude::*;
+use parking_lot::Mutex;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use super::{export_document, PrintOptions};
+use crate::asset_protocol::{handle_request as handle_asset_request, AssetGrant, ASSET_SCHEME};
+use crate::assets::{MAIN_SCRIPT, MAIN_STYLE};
+use crate::components::content::{allow_included_assets, patch_document, render_page};
+use crate::config::{PrintConfig, Project, CONFIG};
+use crate::markdown::{BlockCache, RenderOptions};
+use crate::theme::Theme;
+use crate::utils::encoding::decode_text;
+use crate::window::index::build_custom_index;
+
+/// Subcommand exporting a document to PDF
+pub const SUBCOMMAND: &str = "export-pdf";
+
+const USAGE: &str = "\
+Usage: arto export-pdf <INPUT> [-o <OUTPUT>] [--toc] [--no-page-header] [--no-link-footnotes]
+
+Options:
+  -o, --output <OUTPUT>   PDF file to write (default: INPUT with a .pdf extension)
+  --toc                   Add a table of contents
+  --no-toc                Don't add a table of contents
+  --page-header           Print the file name at the top of every page
+  --no-page-header        Don't print the file name
+  --link-footnotes        List link URLs as footnotes
+  --no-link-footnotes     Don't list link URLs";
+
+/// Arguments of `arto export-pdf`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportArgs {
+    pub input: PathBuf,
+    pub output: PathBuf,
+    pub print: PrintConfig,
+}
+
+impl ExportArgs {
+    /// Parse the arguments following the subcommand, with `config` providing the defaults
+    pub fn parse(args: &[OsString], config: &PrintConfig) -> Result<Self, String> {
+        let mut input = None;
+        let mut output = None;
+        let mut print = config.clone();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.to_str() {
+                Some("-o" | "--output") => {
+                    let value = args.next().ok_or("--output requires a file name")?;
+                    output = Some(PathBuf::from(value));
+                }
+                Some("--toc") => print.table_of_contents = true,
+                Some("--no-toc") => print.table_of_contents = false,
+                Some("--page-header") => print.page_header = true,
+                Some("--no-page-header") => print.page_header = false,
+                Some("--link-footnotes") => print.link_footnotes = true,
+                Some("--no-link-footnotes") => print.link_footnotes = false,
+                Some(option) if option.starts_with('-') => {
+                    return Err(format!("Unknown option: {option}"));
+                }
+                _ if input.is_some() => {
+                    return Err(format!("Unexpected argument: {}", arg.to_string_lossy()));
+                }
+                _ => input = Some(PathBuf::from(arg)),
+            }
+        }
+        let input = input.ok_or("No input file given")?;
+        let output = output.unwrap_or_else(|| input.with_extension("pdf"));
+        Ok(Self {
+            input,
+            output,
+            print,
+        })
+    }
+}
+
+/// Run `arto export-pdf` with the arguments following the subcommand, returning the exit status
+pub fn run(args: &[OsString]) -> i32 {
+    let args = match ExportArgs::parse(args, &CONFIG.read().print) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("arto: {e}\n\n{USAGE}");
+            return 2;
+        }
+    };
+    if !args.input.is_file() {
+        eprintln!("arto: {} is not a file", args.input.display());
+        return 1;
+    }
+
+    let config = Config::new()
+        .with_window(
+            WindowBuilder::new()
+                .with_title("Arto")
+                .with_visible(false)
+                .with_inner_size(LogicalSize::new(1000, 800)),
+        )
+        .with_menu(None)
+        .with_custom_head(indoc::formatdoc! {r#"<link rel="stylesheet" href="{MAIN_STYLE}">"#})
+        // Documents are printed in the light theme
+        .with_custom_index(build_custom_index(Theme::Light))
+        .with_asynchronous_custom_protocol(ASSET_SCHEME, handle_asset_request);
+
+    dioxus::LaunchBuilder::desktop()
+        .with_cfg(config)
+        .with_context(args)
+        .launch(HeadlessExport);
+    0
+}
+
+/// Hidden window rendering the document and exporting it, then exiting
+#[component]
+fn HeadlessExport() -> Element {
+    let args = use_context::<ExportArgs>();
+
+    use_hook(|| {
+        spawn(async move {
+            let status = match export(&args).await {
+                Ok(()) => {
+                    println!("Exported {}", args.output.display());
+                    0
+                }
+                Err(e) => {
+                    eprintln!("arto: Failed to export {}: {e:#}", args.input.display());
+                    1
+                }
+            };
+            std::process::exit(status);
+        });
+    });
+
+    rsx! {
+        div {
+            class: "markdown-viewer",
+            article { class: "markdown-body", "data-document": "true" }
+        }
+    }
+}
+
+async fn export(args: &ExportArgs) -> Result<()> {
+    let input = std::path::absolute(&args.input)?;
+    let bytes = tokio::fs::read(&input)
+        .await
+        .with_context(|| format!("Failed to read {}", input.display()))?;
+    let content = decode_text(&bytes, None).context("The file appears to be binary")?;
+
+    let dir = input.parent().map(PathBuf::from).unwrap_or_default();
+    let project = Project::discover(&dir);
+    // Rendered like in a tab of the project, without a workspace
+    let render_options = RenderOptions::for_file(&input, project.as_ref(), &[]);
+    let image_options = CONFIG.read().remote_content.image_options();
+    // Served until the export is done
+    let grant = AssetGrant::for_window(render_options.asset_directories(&input));
+
+    let rendered = tokio::task::spawn_blocking({
+        let input = input.clone();
+        move || {
+            let cache = Mutex::new((PathBuf::new(), BlockCache::default()));
+            render_page(
+                &input,
+                &content.text,
+                &render_options,
+                &image_options,
+                &cache,
+            )
+        }
+    })
+    .await?;
+
+    let (mermaid, math) = project
+        .map(|project| (project.config.mermaid, project.config.math))
+        .unwrap_or_default();
+    let render_config = serde_json::json!({ "mermaid": mermaid, "math": math });
+    document::eval(&indoc::formatdoc! {r#"
+        // Wait for the document element to be mounted
+        while (!document.querySelector("article[data-document]")) {{
+            await new Promise((resolve) => requestAnimationFrame(resolve));
+        }}
+        const {{ init, setRenderConfig }} = await import("{MAIN_SCRIPT}");
+        init();
+        setRenderConfig({render_config});
+    "#})
+    .await?;
+    allow_included_assets(&grant, &rendered);
+    patch_document(&rendered.blocks).await;
+
+    let options = PrintOptions::new(&input, &args.print);
+    export_document(&window(), &options, &args.output).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<ExportArgs, String> {
+        let args: Vec<OsString> = args.iter().map(OsString::from).collect();
+        ExportArgs::parse(&args, &PrintConfig::default())
+    }
+
+    #[test]
+    fn test_parse_export_args() {
+        assert_eq!(
+            parse(&["notes/report.md"]),
+            Ok(ExportArgs {
+                input: PathBuf::from("notes/report.md"),
+                output: PathBuf::from("notes/report.pdf"),
+                print: PrintConfig::default(),
+            })
+        );
+
+        let args = parse(&["--toc", "report.md", "-o", "out.pdf", "--no-page-header"]).unwrap();
+        assert_eq!(args.output, PathBuf::from("out.pdf"));
+        assert!(args.print.table_of_contents);
+        assert!(!args.print.page_header);
+        assert!(args.print.link_footnotes);
+    }
+
+    #[test]
+    fn test_parse_export_args_errors() {
+        assert_eq!(parse(&[]), Err("No input file given".to_string()));
+        assert_eq!(
+            parse(&["report.md", "--output"]),
+            Err("--output requires a file name".to_string())
+        );
+        assert_eq!(
+            parse(&["report.md", "--landscape"]),
+            Err("Unknown option: --landscape".to_string())
+        );
+        assert_eq!(
+            parse(&["a.md", "b.md"]),
+            Err("Unexpected argument: b.md".to_string())
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! Printing the webview to a PDF file, without a print dialog
+
+use anyhow::Result;
+use dioxus::desktop::DesktopContext;
+use std::path::Path;
+
+/// Print the webview of `context` to `output` with WebKitGTK, without a print dialog
+///
+/// No printer is selected: printers are named in the user's language, and the output URI alone
+/// makes WebKitGTK write the PDF file.
+#[cfg(target_os = "linux")]
+pub async fn print_to_pdf(context: &DesktopContext, output: &Path) -> Result<()> {
+    use dioxus::desktop::wry::WebViewExtUnix;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use webkit2gtk::{PrintOperation, PrintOperationExt};
+
+    let settings = gtk::PrintSettings::new();
+    settings.set(gtk::PRINT_SETTINGS_OUTPUT_FILE_FORMAT, Some("pdf"));
+    let uri = gtk::glib::filename_to_uri(std::path::absolute(output)?, None)?;
+    settings.set(gtk::PRINT_SETTINGS_OUTPUT_URI, Some(&uri));
+
+    let operation = PrintOperation::new(&context.webview.webview());
+    operation.set_print_settings(&settings);
+
+    // "failed" is emitted before "finished" when printing fails
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let tx = Rc::new(RefCell::new(Some(tx)));
+    operation.connect_failed({
+        let tx = tx.clone();
+        move |_, error| {
+            if let Some(tx) = tx.borrow_mut().take() {
+                let _ = tx.send(Err(anyhow::anyhow!("{error}")));
+            }
+        }
+    });
+    operation.connect_finished(move |_| {
+        if let Some(tx) = tx.borrow_mut().take() {
+            let _ = tx.send(Ok(()));
+        }
+    });
+    operation.print();
+
+    rx.await?
+}
+
+/// Print the webview of `context` to `output` with a save job of AppKit's print system
+#[cfg(target_os = "macos")]
+pub async fn print_to_pdf(context: &DesktopContext, output: &Path) -> Result<()> {
+    use dioxus::desktop::wry::WebViewExtMacOS;
+    use objc2::runtime::ProtocolObject;
+    use objc2_app_kit::{NSPrintInfo, NSPrintJobSavingURL, NSPrintSaveJob};
+    use objc2_foundation::{NSString, NSURL};
+
+    let webview = context.webview.webview();
+    let url = NSURL::fileURLWithPath(&NSString::from_str(&output.to_string_lossy()));
+    let succeeded = unsafe {
+        let print_info = NSPrintInfo::new();
+        print_info.setJobDisposition(NSPrintSaveJob);
+        print_info
+            .dictionary()
+            .setObject_forKey(&url, ProtocolObject::from_ref(NSPrintJobSavingURL));
+
+        let operation = webview.printOperationWithPrintInfo(&print_info);
+        operation.setShowsPrintPanel(false);
+        operation.setShowsProgressPanel(false);
+        // WebKit paginates from the frame of the print view; without one the pages are blank
+        if let Some(view) = operation.view() {
+            view.setFrame(webview.frame());
+        }
+        operation.runOperation()
+    };
+    anyhow::ensure!(succeeded, "Printing to {} failed", output.display());
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub async fn print_to_pdf(_context: &DesktopContext, _output: &Path) -> Result<()> {
+    anyhow::bail!("PDF export isn't supported on this platform; use File → Print… instead")
+}
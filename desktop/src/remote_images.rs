@@ -0,0 +1,473 @@
+//! Remote images in rendered documents
+//!
+//! A remote `<img>` left in a document makes the webview contact a third-party host as soon as
+//! the document is opened, revealing the reader's address and activity, and breaks offline.
+//! Depending on [`RemoteImagePolicy`], remote images are replaced with a placeholder the
+//! reader can click to load, or left to load. Images loaded once are kept in an
+//! [`ImageCache`] and inlined from there afterwards, without contacting the host. Other
+//! remote media URLs (video and audio sources, posters, `srcset` candidates) are dropped
+//! unless the policy lets remote images load.
+
+use anyhow::{bail, Context, Result};
+use lol_html::{element, HtmlRewriter, Settings};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::asset_protocol::is_asset_url;
+
+mod cache;
+
+pub use cache::{CachedImage, ImageCache};
+
+/// Images larger than this are not downloaded
+const MAX_IMAGE_BYTES: u64 = 20 * 1024 * 1024;
+
+/// Time allowed for downloading an image
+const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Media elements other than `<img src>` that make the webview load their URL attributes
+const MEDIA_SELECTOR: &str =
+    "video[src], video[poster], audio[src], source[src], track[src], img[srcset], source[srcset]";
+
+/// What to do with remote images that aren't cached
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoteImagePolicy {
+    /// Never contact remote hosts; show a placeholder
+    Block,
+    /// Show a placeholder that loads the image when clicked
+    #[default]
+    ClickToLoad,
+    /// Let the webview load remote images
+    Always,
+}
+
+impl RemoteImagePolicy {
+    pub const ALL: [RemoteImagePolicy; 3] = [
+        RemoteImagePolicy::Block,
+        RemoteImagePolicy::ClickToLoad,
+        RemoteImagePolicy::Always,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RemoteImagePolicy::Block => "Block",
+            RemoteImagePolicy::ClickToLoad => "Click to Load",
+            RemoteImagePolicy::Always => "Always Load",
+        }
+    }
+}
+
+/// How remote images are handled when rendering a document
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RemoteImageOptions {
+    pub policy: RemoteImagePolicy,
+    /// Cache images loaded before are inlined from, whatever the policy
+    pub cache: Option<ImageCache>,
+}
+
+/// Inline cached remote images and apply the policy to the others
+///
+/// Returns the HTML along with the URLs of uncached images left for the webview to load
+/// (`Always` policy), which can be [fetched](fetch_into_cache) into the cache in the
+/// background.
+pub fn rewrite_remote_images(html: &str, options: &RemoteImageOptions) -> (String, Vec<String>) {
+    let mut uncached = Vec::new();
+    let mut output = Vec::new();
+
+    let mut rewriter = HtmlRewriter::new(
+        Settings {
+            element_content_handlers: vec![
+                element!(MEDIA_SELECTOR, |el| {
+                    let tag = el.tag_name();
+                    for attribute in ["src", "poster"] {
+                        if tag == "img" && attribute == "src" {
+                            // Handled below, with a placeholder
+                            continue;
+                        }
+                        let Some(url) = el.get_attribute(attribute) else {
+                            continue;
+                        };
+                        if !is_remote_url(&url) {
+                            continue;
+                        }
+                        let cached = (attribute == "poster")
+                            .then(|| options.cache.as_ref().and_then(|cache| cache.get(&url)))
+                            .flatten();
+                        if let Some(image) = cached {
+                            el.set_attribute(attribute, &image.to_data_url())?;
+                        } else if options.policy != RemoteImagePolicy::Always {
+                            el.remove_attribute(attribute);
+                        }
+                    }
+                    if options.policy != RemoteImagePolicy::Always {
+                        if let Some(srcset) = el.get_attribute("srcset") {
+                            match local_srcset(&srcset) {
+                                Some(local) => el.set_attribute("srcset", &local)?,
+                                None => el.remove_attribute("srcset"),
+                            }
+                        }
+                    }
+                    Ok(())
+                }),
+                element!("img[src]", |el| {
+                    let Some(src) = el.get_attribute("src") else {
+                        return Ok(());
+                    };
+                    if !is_remote_url(&src) {
+                        return Ok(());
+                    }
+                    if let Some(image) = options.cache.as_ref().and_then(|cache| cache.get(&src)) {
+                        el.set_attribute("src", &image.to_data_url())?;
+                        return Ok(());
+                    }
+                    if options.policy == RemoteImagePolicy::Always {
+                        uncached.push(src);
+                        return Ok(());
+                    }
+                    let alt = el.get_attribute("alt").unwrap_or_default();
+                    el.replace(
+                        &placeholder(&src, &alt, options.policy),
+                        lol_html::html_content::ContentType::Html,
+                    );
+                    Ok(())
+                }),
+            ],
+            ..Settings::default()
+        },
+        |chunk: &[u8]| output.extend_from_slice(chunk),
+    );
+
+    let _ = rewriter.write(html.as_bytes());
+    let _ = rewriter.end();
+    let html = String::from_utf8(output).unwrap_or_else(|_| html.to_string());
+    (html, uncached)
+}
+
+fn is_remote_url(src: &str) -> bool {
+    let src = src.trim_start();
+    // Local files are served from `http://arto-asset.localhost` on Windows
+    !is_asset_url(src)
+        && ["http://", "https://"].iter().any(|scheme| {
+            src.get(..scheme.len())
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme))
+        })
+}
+
+/// CSS without the remote URLs it would make the webview load
+///
+/// Remote `url(…)` values become `none` and remote quoted URLs (`@import "…"`,
+/// `image-set("…")`, quoted `url("…")`) become `""`; comments are kept as they are.
+pub fn strip_remote_css_urls(css: &str) -> String {
+    let mut output = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(c) = rest.chars().next() {
+        let token_len = if rest.starts_with("/*") {
+            rest.find("*/").map_or(rest.len(), |end| end + 2)
+        } else if c == '"' || c == '\'' {
+            let end = css_token_len(rest, 1, |next| next == c || next == '\n');
+            let len = (end + 1).min(rest.len());
+            if is_remote_css_url(&rest[1..end]) {
+                output.push_str("\"\"");
+                rest = &rest[len..];
+                continue;
+            }
+            len
+        } else if rest.len() >= 4 && rest[..4].eq_ignore_ascii_case("url(") {
+            let value = rest[4..].trim_start();
+            if value.starts_with(['"', '\'']) {
+                // The quoted URL is handled as a string
+                rest.len() - value.len()
+            } else {
+                let len = css_token_len(rest, 4, |next| next == ')');
+                if is_remote_css_url(&rest[4..len]) {
+                    output.push_str("none");
+                    rest = &rest[(len + 1).min(rest.len())..];
+                    continue;
+                }
+                len
+            }
+        } else {
+            c.len_utf8()
+        };
+        output.push_str(&rest[..token_len]);
+        rest = &rest[token_len..];
+    }
+    output
+}
+
+/// Length of the CSS token starting at `start` in `css` up to the first unescaped character
+/// matching `end` (excluded), or the end of `css`
+fn css_token_len(css: &str, start: usize, end: impl Fn(char) -> bool) -> usize {
+    let mut chars = css[start..].char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if end(c) {
+            return start + i;
+        }
+    }
+    css.len()
+}
+
+/// Whether a CSS URL loads from a remote host once its escapes (`\68ttps:`) are decoded
+fn is_remote_css_url(value: &str) -> bool {
+    let mut url = String::with_capacity(value.len());
+    let mut chars = value.trim().chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            url.push(c);
+            continue;
+        }
+        let mut hex = String::new();
+        while hex.len() < 6 && chars.peek().is_some_and(char::is_ascii_hexdigit) {
+            hex.extend(chars.next());
+        }
+        if hex.is_empty() {
+            url.extend(chars.next());
+        } else {
+            let code = u32::from_str_radix(&hex, 16).unwrap_or_default();
+            url.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+            chars.next_if(|c| c.is_ascii_whitespace());
+        }
+    }
+    // Protocol-relative URLs are remote where the page is served over http (Windows)
+    is_remote_url(&url) || url.starts_with("//")
+}
+
+/// `srcset` without its remote candidates, `None` when none is left
+fn local_srcset(srcset: &str) -> Option<String> {
+    let local: Vec<_> = srcset
+        .split(',')
+        .map(str::trim)
+        .filter(|candidate| !candidate.is_empty() && !is_remote_url(candidate))
+        .collect();
+    (!local.is_empty()).then(|| local.join(", "))
+}
+
+/// Placeholder for a remote image; clicks are handled by the renderer (`remote-images.ts`)
+fn placeholder(src: &str, alt: &str, policy: RemoteImagePolicy) -> String {
+    let host = src
+        .split_once("://")
+        .map(|(_, rest)| rest.split(['/', '?', '#']).next().unwrap_or(rest))
+        .unwrap_or(src);
+    let action = match policy {
+        RemoteImagePolicy::ClickToLoad => format!("Click to load image from {host}"),
+        _ => format!("Image from {host} blocked"),
+    };
+    let label = if alt.trim().is_empty() { &action } else { alt };
+    format!(
+        r#"<span class="remote-image{clickable}" data-remote-src="{src}" title="{action}">{label}</span>"#,
+        clickable = if policy == RemoteImagePolicy::ClickToLoad {
+            " remote-image-clickable"
+        } else {
+            ""
+        },
+        src = html_escape::encode_double_quoted_attribute(src),
+        action = html_escape::encode_double_quoted_attribute(&action),
+        label = html_escape::encode_text(label),
+    )
+}
+
+/// Download the image at `url`
+pub fn fetch_image(url: &str) -> Result<CachedImage> {
+    if !is_remote_url(url) {
+        bail!("Not a remote URL: {url}");
+    }
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(FETCH_TIMEOUT))
+        .build()
+        .into();
+    let mut response = agent
+        .get(url)
+        .call()
+        .with_context(|| format!("Failed to download {url}"))?;
+    let mime_type = response
+        .headers()
+        .get("content-type")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    if !mime_type.starts_with("image/") {
+        bail!("Not an image ({mime_type:?}): {url}");
+    }
+    let data = response
+        .body_mut()
+        .with_config()
+        .limit(MAX_IMAGE_BYTES)
+        .read_to_vec()
+        .with_context(|| format!("Failed to download {url}"))?;
+    Ok(CachedImage { mime_type, data })
+}
+
+/// Download the image at `url` into `cache`
+pub fn fetch_into_cache(url: &str, cache: &ImageCache) -> Result<()> {
+    let image = fetch_image(url)?;
+    cache
+        .insert(url, &image)
+        .with_context(|| format!("Failed to cache {url}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\nfake";
+
+    /// Local stand-in for an image host, counting the requests it serves
+    struct ImageServer {
+        base_url: String,
+        requests: Arc<AtomicUsize>,
+    }
+
+    impl ImageServer {
+        fn start() -> Self {
+            let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+            let base_url = format!("http://{}", server.server_addr().to_ip().unwrap());
+            let requests = Arc::new(AtomicUsize::new(0));
+            let counter = requests.clone();
+            std::thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    let (content_type, body): (&str, &[u8]) = match request.url() {
+                        "/logo.png" => ("image/png", PNG),
+                        _ => ("text/html", b"<script>alert(1)</script>"),
+                    };
+                    let header =
+                        tiny_http::Header::from_bytes("Content-Type", content_type).unwrap();
+                    let _ =
+                        request.respond(tiny_http::Response::from_data(body).with_header(header));
+                }
+            });
+            Self { base_url, requests }
+        }
+
+        fn requests(&self) -> usize {
+            self.requests.load(Ordering::SeqCst)
+        }
+    }
+
+    fn options(policy: RemoteImagePolicy, cache: &ImageCache) -> RemoteImageOptions {
+        RemoteImageOptions {
+            policy,
+            cache: Some(cache.clone()),
+        }
+    }
+
+    #[test]
+    fn test_placeholders_do_not_contact_host() {
+        let server = ImageServer::start();
+        let temp = TempDir::new().unwrap();
+        let cache = ImageCache::new(temp.path(), 1024 * 1024);
+        let html = format!(
+            r#"<p><img src="{}/logo.png" alt="Logo"></p>"#,
+            server.base_url
+        );
+
+        let (blocked, uncached) =
+            rewrite_remote_images(&html, &options(RemoteImagePolicy::Block, &cache));
+        assert!(blocked.contains(r#"<span class="remote-image" data-remote-src=""#));
+        assert!(blocked.contains(">Logo</span>"));
+        assert!(uncached.is_empty());
+
+        let (clickable, _) =
+            rewrite_remote_images(&html, &options(RemoteImagePolicy::ClickToLoad, &cache));
+        assert!(clickable.contains(r#"class="remote-image remote-image-clickable""#));
+        assert!(clickable.contains("Click to load image from 127.0.0.1:"));
+
+        let (always, uncached) =
+            rewrite_remote_images(&html, &options(RemoteImagePolicy::Always, &cache));
+        assert_eq!(always, html);
+        assert_eq!(uncached, vec![format!("{}/logo.png", server.base_url)]);
+
+        assert_eq!(server.requests(), 0);
+    }
+
+    #[test]
+    fn test_other_remote_media_is_dropped() {
+        let temp = TempDir::new().unwrap();
+        let cache = ImageCache::new(temp.path(), 1024 * 1024);
+        let html = concat!(
+            r#"<video src="https://example.com/a.mp4" poster="https://example.com/a.png">"#,
+            r#"<source src="https://example.com/a.webm">"#,
+            r#"<track src="https://example.com/a.vtt"></video>"#,
+            r#"<img srcset="https://example.com/b.png 2x, arto-asset://localhost/b.png 1x">"#,
+        );
+
+        let (blocked, _) = rewrite_remote_images(html, &options(RemoteImagePolicy::Block, &cache));
+        assert!(!blocked.contains("example.com"), "{blocked}");
+        assert!(blocked.contains(r#"<img srcset="arto-asset://localhost/b.png 1x">"#));
+
+        let (always, uncached) =
+            rewrite_remote_images(html, &options(RemoteImagePolicy::Always, &cache));
+        assert_eq!(always, html);
+        assert!(uncached.is_empty());
+    }
+
+    #[test]
+    fn test_strip_remote_css_urls() {
+        let css = indoc::indoc! {r#"
+            @import "https://example.com/theme.css";
+            @import url(//example.com/fonts.css);
+            /* don't load url(https://example.com/comment.png) */
+            h1 { background: url(https://example.com/a.png) no-repeat; }
+            h2 { background: URL( "HTTP://example.com/b.png" ), url('images/local.png'); }
+            h3 { background: url(\68ttps://example.com/c.png); content: "Hi"; }
+            h4 { background-image: image-set('https://example.com/d.png' 1x); }
+        "#};
+
+        assert_eq!(
+            strip_remote_css_urls(css),
+            indoc::indoc! {r#"
+                @import "";
+                @import none;
+                /* don't load url(https://example.com/comment.png) */
+                h1 { background: none no-repeat; }
+                h2 { background: URL( "" ), url('images/local.png'); }
+                h3 { background: none; content: "Hi"; }
+                h4 { background-image: image-set("" 1x); }
+            "#}
+        );
+    }
+
+    #[test]
+    fn test_cached_images_render_offline() {
+        let server = ImageServer::start();
+        let temp = TempDir::new().unwrap();
+        let cache = ImageCache::new(temp.path(), 1024 * 1024);
+        let url = format!("{}/logo.png", server.base_url);
+
+        fetch_into_cache(&url, &cache).unwrap();
+        assert_eq!(server.requests(), 1);
+
+        let html = format!(r#"<img src="{url}" alt="Logo">"#);
+        for policy in RemoteImagePolicy::ALL {
+            let (rendered, uncached) = rewrite_remote_images(&html, &options(policy, &cache));
+            assert_eq!(
+                rendered,
+                format!(
+                    r#"<img src="{}" alt="Logo">"#,
+                    cache.get(&url).unwrap().to_data_url()
+                )
+            );
+            assert!(uncached.is_empty());
+        }
+        assert_eq!(server.requests(), 1);
+    }
+
+    #[test]
+    fn test_fetch_rejects_non_images() {
+        let server = ImageServer::start();
+        let temp = TempDir::new().unwrap();
+        let cache = ImageCache::new(temp.path(), 1024 * 1024);
+        let url = format!("{}/page.html", server.base_url);
+
+        assert!(fetch_into_cache(&url, &cache).is_err());
+        assert!(cache.get(&url).is_none());
+        assert!(fetch_image("file:///etc/passwd").is_err());
+    }
+}
@@ -0,0 +1,196 @@
+use base64::{engine::general_purpose, Engine as _};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Extension of cache entries; each holds the MIME type on its first line, then the image
+const ENTRY_EXTENSION: &str = "img";
+
+/// A downloaded image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedImage {
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+impl CachedImage {
+    /// The image as a `data:` URL
+    pub fn to_data_url(&self) -> String {
+        format!(
+            "data:{};base64,{}",
+            self.mime_type,
+            general_purpose::STANDARD.encode(&self.data)
+        )
+    }
+}
+
+/// On-disk cache of remote images keyed by URL, so documents seen before render offline
+///
+/// The least recently used entries are evicted once the cache grows past `max_bytes`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl ImageCache {
+    pub fn new(dir: impl Into<PathBuf>, max_bytes: u64) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes,
+        }
+    }
+
+    /// Default cache directory (`images` in the platform cache directory)
+    pub fn default_dir() -> PathBuf {
+        dirs::cache_dir()
+            .or_else(|| dirs::home_dir().map(|home| home.join(".arto")))
+            .unwrap_or_default()
+            .join("arto")
+            .join("images")
+    }
+
+    /// Look up the image downloaded from `url`, marking it as recently used
+    pub fn get(&self, url: &str) -> Option<CachedImage> {
+        let path = self.entry_path(url);
+        let content = fs::read(&path).ok()?;
+        let newline = content.iter().position(|b| *b == b'\n')?;
+        let mime_type = String::from_utf8(content[..newline].to_vec()).ok()?;
+        if let Ok(file) = fs::File::options().append(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(CachedImage {
+            mime_type,
+            data: content[newline + 1..].to_vec(),
+        })
+    }
+
+    /// Store the image downloaded from `url`, then evict old entries beyond the size limit
+    pub fn insert(&self, url: &str, image: &CachedImage) -> io::Result<()> {
+        // An image larger than the whole cache would only evict everything else
+        if (image.mime_type.len() + 1 + image.data.len()) as u64 > self.max_bytes {
+            return Ok(());
+        }
+        fs::create_dir_all(&self.dir)?;
+        let path = self.entry_path(url);
+        // Write to a temporary file first so readers never see a partial entry
+        let temp_path = path.with_extension("tmp");
+        {
+            let mut file = fs::File::create(&temp_path)?;
+            file.write_all(image.mime_type.as_bytes())?;
+            file.write_all(b"\n")?;
+            file.write_all(&image.data)?;
+        }
+        fs::rename(&temp_path, &path)?;
+        self.evict(&path)
+    }
+
+    /// Remove the least recently used entries until the cache fits its size limit, keeping
+    /// `keep` (the entry just written)
+    fn evict(&self, keep: &Path) -> io::Result<()> {
+        let mut entries: Vec<(PathBuf, u64, SystemTime)> = fs::read_dir(&self.dir)?
+            .filter_map(Result::ok)
+            .filter(|entry| {
+                entry
+                    .path()
+                    .extension()
+                    .is_some_and(|ext| ext == ENTRY_EXTENSION)
+            })
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                Some((entry.path(), metadata.len(), modified))
+            })
+            .collect();
+        let mut total: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        if total <= self.max_bytes {
+            return Ok(());
+        }
+
+        // Oldest first, with the new entry last
+        entries.sort_by_key(|(path, _, modified)| (path == keep, *modified));
+        for (path, size, _) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            fs::remove_file(&path)?;
+            total -= size;
+        }
+        Ok(())
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        let digest = Sha256::digest(url.as_bytes());
+        let name: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+        self.dir.join(name).with_extension(ENTRY_EXTENSION)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn image(data: &[u8]) -> CachedImage {
+        CachedImage {
+            mime_type: "image/png".to_string(),
+            data: data.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_insert_and_get() {
+        let temp = TempDir::new().unwrap();
+        let cache = ImageCache::new(temp.path(), 1024);
+
+        assert_eq!(cache.get("https://example.com/a.png"), None);
+        cache
+            .insert("https://example.com/a.png", &image(b"\x89PNG\n\x00"))
+            .unwrap();
+
+        assert_eq!(
+            cache.get("https://example.com/a.png"),
+            Some(image(b"\x89PNG\n\x00"))
+        );
+        assert_eq!(cache.get("https://example.com/b.png"), None);
+        assert_eq!(
+            image(b"abc").to_data_url(),
+            "data:image/png;base64,YWJj".to_string()
+        );
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let temp = TempDir::new().unwrap();
+        // Room for two 100-byte images with their headers
+        let cache = ImageCache::new(temp.path(), 250);
+        let age = |url: &str, seconds: u64| {
+            let file = fs::File::options()
+                .append(true)
+                .open(cache.entry_path(url))
+                .unwrap();
+            file.set_modified(SystemTime::now() - Duration::from_secs(seconds))
+                .unwrap();
+        };
+
+        cache.insert("a", &image(&[0; 100])).unwrap();
+        age("a", 30);
+        cache.insert("b", &image(&[0; 100])).unwrap();
+        age("b", 20);
+        // Reading marks "a" as recently used, leaving "b" the oldest
+        assert!(cache.get("a").is_some());
+        cache.insert("c", &image(&[0; 100])).unwrap();
+
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("c").is_some());
+
+        // An image larger than the whole cache isn't kept
+        cache.insert("huge", &image(&[0; 300])).unwrap();
+        assert!(cache.get("huge").is_none());
+        assert!(cache.get("c").is_some());
+    }
+}
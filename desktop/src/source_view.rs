@@ -0,0 +1,216 @@
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Files larger than this are shown with line numbers but without highlighting
+const MAX_HIGHLIGHT_BYTES: usize = 1024 * 1024;
+
+/// highlight.js language for well-known file names without a telling extension
+fn language_for_file_name(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "Dockerfile" | "Containerfile" => "dockerfile",
+        "Makefile" | "GNUmakefile" | "makefile" => "makefile",
+        "CMakeLists.txt" => "cmake",
+        "Gemfile" | "Rakefile" | "Podfile" | "Vagrantfile" => "ruby",
+        "Cargo.lock" | "Pipfile" => "toml",
+        ".bashrc" | ".bash_profile" | ".zshrc" | ".profile" => "bash",
+        ".gitignore" | ".gitattributes" | ".dockerignore" | ".editorconfig" => "ini",
+        _ => return None,
+    })
+}
+
+/// highlight.js language for a file extension
+fn language_for_extension(ext: &str) -> Option<&'static str> {
+    Some(match ext {
+        "rs" => "rust",
+        "py" | "pyi" | "pyw" => "python",
+        "js" | "mjs" | "cjs" | "jsx" => "javascript",
+        "ts" | "mts" | "cts" | "tsx" => "typescript",
+        "go" => "go",
+        "java" => "java",
+        "kt" | "kts" => "kotlin",
+        "scala" | "sc" => "scala",
+        "swift" => "swift",
+        "c" | "h" => "c",
+        "cc" | "cpp" | "cxx" | "hpp" | "hh" | "hxx" => "cpp",
+        "m" | "mm" => "objectivec",
+        "cs" => "csharp",
+        "fs" | "fsx" => "fsharp",
+        "rb" => "ruby",
+        "php" => "php",
+        "pl" | "pm" => "perl",
+        "lua" => "lua",
+        "r" => "r",
+        "dart" => "dart",
+        "ex" | "exs" => "elixir",
+        "erl" | "hrl" => "erlang",
+        "hs" => "haskell",
+        "ml" | "mli" => "ocaml",
+        "clj" | "cljs" | "edn" => "clojure",
+        "el" | "lisp" | "scm" => "lisp",
+        "sh" | "bash" | "zsh" | "ksh" => "bash",
+        "ps1" | "psm1" => "powershell",
+        "bat" | "cmd" => "dos",
+        "sql" => "sql",
+        "yaml" | "yml" => "yaml",
+        "toml" => "toml",
+        "ini" | "cfg" | "conf" | "properties" => "ini",
+        "json" | "jsonc" | "json5" | "geojson" => "json",
+        "xml" | "svg" | "plist" | "xsd" | "xsl" => "xml",
+        "html" | "htm" | "xhtml" | "vue" | "svelte" => "xml",
+        "css" => "css",
+        "scss" => "scss",
+        "less" => "less",
+        "graphql" | "gql" => "graphql",
+        "proto" => "protobuf",
+        "nix" => "nix",
+        "tex" | "sty" | "cls" => "latex",
+        "diff" | "patch" => "diff",
+        "dockerfile" => "dockerfile",
+        "mk" | "mak" => "makefile",
+        "cmake" => "cmake",
+        "gradle" | "groovy" => "groovy",
+        "vim" => "vim",
+        _ => return None,
+    })
+}
+
+/// highlight.js language for an interpreter named in a shebang line
+fn language_for_interpreter(interpreter: &str) -> Option<&'static str> {
+    let name = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    Some(match name {
+        "python" | "pypy" => "python",
+        "sh" | "bash" | "zsh" | "ksh" | "dash" => "bash",
+        "node" | "deno" | "bun" => "javascript",
+        "ruby" => "ruby",
+        "perl" => "perl",
+        "php" => "php",
+        "lua" => "lua",
+        "Rscript" => "r",
+        "pwsh" => "powershell",
+        _ => return None,
+    })
+}
+
+/// Detect the language of a file from its name or extension alone
+pub fn language_for_path(path: impl AsRef<Path>) -> Option<&'static str> {
+    let path = path.as_ref();
+    let name = path.file_name()?.to_str()?;
+    language_for_file_name(name).or_else(|| {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        language_for_extension(&ext)
+    })
+}
+
+/// Detect the language of a file from its name, extension or shebang line
+pub fn detect_language(path: impl AsRef<Path>, content: &str) -> Option<&'static str> {
+    language_for_path(path).or_else(|| {
+        let shebang = content.lines().next()?.strip_prefix("#!")?;
+        let mut words = shebang.split_whitespace();
+        let program = words.next()?.rsplit('/').next()?;
+        let interpreter = if program == "env" {
+            // Skip env options such as `-S`
+            words.find(|word| !word.starts_with('-'))?
+        } else {
+            program
+        };
+        language_for_interpreter(interpreter)
+    })
+}
+
+/// Render a text file as a highlighted source listing with line numbers
+///
+/// Every line number carries an `L<n>` id so `#L10` and `#L10-L20` anchors can be revealed;
+/// highlighting itself is done by the renderer like for fenced code blocks.
+pub fn render_source_to_html(content: &str, path: impl AsRef<Path>) -> String {
+    let language = detect_language(path, content).unwrap_or("plaintext");
+    let language = if content.len() > MAX_HIGHLIGHT_BYTES {
+        "plaintext"
+    } else {
+        language
+    };
+    let content = content.strip_suffix('\n').unwrap_or(content);
+    let line_count = content.split('\n').count();
+
+    let mut gutter = String::new();
+    for line in 1..=line_count {
+        let _ = write!(
+            gutter,
+            r#"<span id="L{line}" data-line="{line}">{line}</span>"#
+        );
+    }
+
+    format!(
+        concat!(
+            r#"<div class="source-view" data-language="{language}">"#,
+            r#"<div class="source-header"><span>{language}</span><span>{line_count} {lines}</span></div>"#,
+            r#"<div class="source-body">"#,
+            r#"<div class="source-gutter" aria-hidden="true">{gutter}</div>"#,
+            r#"<pre class="source-code"><code class="language-{language}">{code}</code></pre>"#,
+            r#"</div></div>"#,
+        ),
+        language = language,
+        line_count = line_count,
+        lines = if line_count == 1 { "line" } else { "lines" },
+        gutter = gutter,
+        code = html_escape::encode_text(content),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_for_path() {
+        assert_eq!(language_for_path("src/main.rs"), Some("rust"));
+        assert_eq!(language_for_path("config.YAML"), Some("yaml"));
+        assert_eq!(language_for_path("docker/Dockerfile"), Some("dockerfile"));
+        assert_eq!(language_for_path("notes.txt"), None);
+        assert_eq!(language_for_path("image.png"), None);
+        assert_eq!(language_for_path("README"), None);
+    }
+
+    #[test]
+    fn test_detect_language_from_shebang() {
+        assert_eq!(
+            detect_language("bin/tool", "#!/usr/bin/env python3\nprint(1)\n"),
+            Some("python")
+        );
+        assert_eq!(
+            detect_language("bin/run", "#!/usr/bin/env -S deno run\n"),
+            Some("javascript")
+        );
+        assert_eq!(detect_language("bin/setup", "#!/bin/bash\n"), Some("bash"));
+        assert_eq!(detect_language("bin/other", "#!/usr/bin/awk -f\n"), None);
+        assert_eq!(detect_language("bin/plain", "no shebang"), None);
+        // The extension wins over the shebang
+        assert_eq!(
+            detect_language("script.rb", "#!/usr/bin/env python\n"),
+            Some("ruby")
+        );
+    }
+
+    #[test]
+    fn test_render_source_to_html() {
+        let html = render_source_to_html("fn main() {\n    a < b;\n}\n", "main.rs");
+
+        assert!(html.contains(r#"<div class="source-view" data-language="rust">"#));
+        assert!(html.contains("<span>3 lines</span>"));
+        assert!(html.contains(r#"<span id="L1" data-line="1">1</span>"#));
+        assert!(html.contains(r#"<span id="L3" data-line="3">3</span>"#));
+        assert!(!html.contains(r#"id="L4""#));
+        assert!(html.contains(
+            r#"<code class="language-rust">fn main() {
+    a &lt; b;
+}</code>"#
+        ));
+    }
+
+    #[test]
+    fn test_render_source_unknown_language() {
+        let html = render_source_to_html("", "LICENSE");
+
+        assert!(html.contains(r#"<code class="language-plaintext">"#));
+        assert!(html.contains("<span>1 line</span>"));
+    }
+}
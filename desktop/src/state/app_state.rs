@@ -1,12 +1,18 @@
 use dioxus::desktop::tao::dpi::{LogicalPosition, LogicalSize};
 use dioxus::prelude::*;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 use super::persistence::LAST_FOCUSED_STATE;
+use crate::config::{Project, CONFIG};
+use crate::git::{RepositoryStatus, DEFAULT_BASE};
+use crate::presentation::Presentation;
 use crate::theme::Theme;
+use crate::workspace::Workspace;
 
 mod sidebar;
 mod tabs;
+mod workspace;
 
 pub use sidebar::Sidebar;
 pub use tabs::{Tab, TabContent};
@@ -39,7 +45,19 @@ pub struct AppState {
     pub current_theme: Signal<Theme>,
     pub zoom_level: Signal<f64>,
     pub directory: Signal<Option<PathBuf>>,
+    /// Project configuration (.arto.toml) found from the current directory
+    pub project: Signal<Option<Project>>,
+    /// Multi-root workspace; `directory` is kept pointing at its first root
+    pub workspace: Signal<Option<Workspace>>,
     pub sidebar: Signal<Sidebar>,
+    /// Ids of the folded heading sections of each file, kept across reloads
+    pub folded_sections: Signal<HashMap<PathBuf, HashSet<String>>>,
+    /// Slides presented full screen over the window
+    pub presentation: Signal<Option<Presentation>>,
+    /// Git repository of the displayed file, refreshed whenever the file is rendered
+    pub repository: Signal<Option<RepositoryStatus>>,
+    /// Revision the changes of displayed documents are highlighted against, if highlighted
+    pub change_base: Signal<Option<String>>,
     pub position: Signal<LogicalPosition<i32>>,
     pub size: Signal<LogicalSize<u32>>,
 }
@@ -52,7 +70,13 @@ impl Default for AppState {
             current_theme: Signal::new(LAST_FOCUSED_STATE.read().theme),
             zoom_level: Signal::new(1.0),
             directory: Signal::new(None),
+            project: Signal::new(None),
+            workspace: Signal::new(None),
             sidebar: Signal::new(Sidebar::default()),
+            folded_sections: Signal::new(HashMap::new()),
+            presentation: Signal::new(None),
+            repository: Signal::new(None),
+            change_base: Signal::new(None),
             position: Signal::new(Default::default()),
             size: Signal::new(Default::default()),
         }
@@ -60,12 +84,71 @@ impl Default for AppState {
 }
 
 impl AppState {
-    /// Set the root directory
+    /// Set the root directory, leaving any multi-root workspace
     /// Note: The directory is persisted to state file when window closes
     pub fn set_root_directory(&mut self, path: impl Into<PathBuf>) {
         let path = path.into();
+        self.workspace.set(None);
         *self.directory.write() = Some(path.clone());
         self.sidebar.write().expanded_dirs.clear();
         LAST_FOCUSED_STATE.write().directory = Some(path);
     }
+
+    /// Open a directory chosen by the user (menu, drag and drop, CLI)
+    ///
+    /// Applies the project configuration found for the directory: its theme, and when the
+    /// project directory itself is opened, its root directory and start page.
+    pub fn open_directory(&mut self, path: impl Into<PathBuf>) {
+        let path = path.into();
+        let Some(project) = Project::discover(&path) else {
+            self.set_root_directory(path);
+            return;
+        };
+
+        let is_project_dir = project.dir == path;
+        let config = project.merged_config(&CONFIG.read());
+        let root = config
+            .directory
+            .default_directory
+            .filter(|root| is_project_dir && project.config.root.is_some() && root.is_dir())
+            .unwrap_or(path);
+        self.set_root_directory(root);
+
+        if project.config.theme.is_some() {
+            self.current_theme.set(config.theme.default_theme);
+        }
+        if is_project_dir && self.is_current_tab_no_file() {
+            if let Some(start_page) = project.start_page() {
+                self.open_file(start_page);
+            }
+        }
+    }
+
+    /// Ids of the folded sections of `file`, without subscribing to changes
+    pub fn folded_sections_of(&self, file: &Path) -> HashSet<String> {
+        self.folded_sections
+            .peek()
+            .get(file)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Remember the folded sections of `file`
+    pub fn set_folded_sections(&mut self, file: PathBuf, ids: HashSet<String>) {
+        let mut folded_sections = self.folded_sections.write();
+        if ids.is_empty() {
+            folded_sections.remove(&file);
+        } else {
+            folded_sections.insert(file, ids);
+        }
+    }
+
+    /// Highlight the changes of documents since the last commit, or stop highlighting them
+    pub fn toggle_changes(&mut self) {
+        let base = match *self.change_base.peek() {
+            Some(_) => None,
+            None => Some(DEFAULT_BASE.to_string()),
+        };
+        self.change_base.set(base);
+    }
 }
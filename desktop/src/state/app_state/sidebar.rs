@@ -1,5 +1,7 @@
 use super::super::persistence::LAST_FOCUSED_STATE;
 use super::AppState;
+use crate::config::{SortMode, CONFIG};
+use crate::events::CONFIG_CHANGED_BROADCAST;
 use dioxus::prelude::*;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
@@ -9,8 +11,12 @@ use std::path::{Path, PathBuf};
 pub struct Sidebar {
     pub open: bool,
     pub expanded_dirs: HashSet<PathBuf>,
+    /// Workspace root sections the user has collapsed
+    pub collapsed_roots: HashSet<PathBuf>,
     pub width: f64,
     pub show_all_files: bool,
+    pub show_hidden_files: bool,
+    pub sort_mode: SortMode,
 }
 
 impl Default for Sidebar {
@@ -18,8 +24,11 @@ impl Default for Sidebar {
         Self {
             open: false,
             expanded_dirs: HashSet::new(),
+            collapsed_roots: HashSet::new(),
             width: 280.0,
             show_all_files: false,
+            show_hidden_files: false,
+            sort_mode: SortMode::default(),
         }
     }
 }
@@ -34,6 +43,14 @@ impl Sidebar {
             self.expanded_dirs.insert(path.to_owned());
         }
     }
+
+    /// Toggle whether a workspace root section is collapsed
+    pub fn toggle_root_collapsed(&mut self, root: impl AsRef<Path>) {
+        let root = root.as_ref();
+        if !self.collapsed_roots.remove(root) {
+            self.collapsed_roots.insert(root.to_owned());
+        }
+    }
 }
 
 impl AppState {
@@ -49,6 +66,23 @@ impl AppState {
         let mut sidebar = self.sidebar.write();
         sidebar.toggle_expansion(path);
     }
+
+    /// Change the file explorer sort mode and persist it to config for all windows
+    pub fn set_sort_mode(&mut self, mode: SortMode) {
+        self.sidebar.write().sort_mode = mode;
+        let config = {
+            let mut config = CONFIG.write();
+            if config.sidebar.sort_mode == mode {
+                return;
+            }
+            config.sidebar.sort_mode = mode;
+            config.clone()
+        };
+        if let Err(e) = config.save() {
+            tracing::error!("Failed to save configuration: {:?}", e);
+        }
+        let _ = CONFIG_CHANGED_BROADCAST.send(Ok(config));
+    }
 }
 
 #[cfg(test)]
@@ -62,7 +96,22 @@ mod tests {
         assert!(!sidebar.open);
         assert_eq!(sidebar.width, 280.0);
         assert!(!sidebar.show_all_files);
+        assert!(!sidebar.show_hidden_files);
+        assert_eq!(sidebar.sort_mode, SortMode::Name);
         assert!(sidebar.expanded_dirs.is_empty());
+        assert!(sidebar.collapsed_roots.is_empty());
+    }
+
+    #[test]
+    fn test_sidebar_toggle_root_collapsed() {
+        let mut sidebar = Sidebar::default();
+        let root = PathBuf::from("/workspace/handbook");
+
+        sidebar.toggle_root_collapsed(&root);
+        assert!(sidebar.collapsed_roots.contains(&root));
+
+        sidebar.toggle_root_collapsed(&root);
+        assert!(!sidebar.collapsed_roots.contains(&root));
     }
 
     #[test]
@@ -1,6 +1,9 @@
 use super::AppState;
 use crate::history::HistoryManager;
+use crate::utils::file::rebase_path;
+use crate::workspace::is_workspace_file;
 use dioxus::prelude::*;
+use encoding_rs::Encoding;
 use std::path::{Path, PathBuf};
 
 /// Content source for a tab
@@ -24,6 +27,12 @@ pub enum TabContent {
 pub struct Tab {
     pub content: TabContent,
     pub history: HistoryManager,
+    /// Fragment to reveal once the file is shown: an `L10`/`L10-L20` line range or an element id
+    pub anchor: Option<String>,
+    /// Encoding the file was reopened with, used instead of detecting it
+    pub encoding_override: Option<&'static Encoding>,
+    /// Encoding the displayed file was decoded with
+    pub encoding: Option<&'static Encoding>,
 }
 
 impl Tab {
@@ -32,14 +41,20 @@ impl Tab {
         let mut history = HistoryManager::new();
         history.push(file.clone());
         let content = TabContent::File(file);
-        Self { content, history }
+        Self {
+            content,
+            history,
+            anchor: None,
+            encoding_override: None,
+            encoding: None,
+        }
     }
 
     pub fn with_inline_content(content: impl Into<String>) -> Self {
         let content = content.into();
         Self {
             content: TabContent::Inline(content),
-            history: HistoryManager::new(),
+            ..Default::default()
         }
     }
 
@@ -64,6 +79,28 @@ impl Tab {
         let file = file.into();
         self.history.push(file.clone());
         self.content = TabContent::File(file);
+        self.anchor = None;
+        self.encoding_override = None;
+        self.encoding = None;
+    }
+
+    /// Reopen the file with `encoding`, or with the detected encoding when `None`
+    pub fn reopen_with_encoding(&mut self, encoding: Option<&'static Encoding>) {
+        // Files taken for binary can be forced open as text
+        if let TabContent::FileError(path, _) = &self.content {
+            self.content = TabContent::File(path.clone());
+        }
+        self.encoding_override = encoding;
+    }
+
+    /// Follow a rename of `from` (a file or directory) to `to`
+    pub fn rename(&mut self, from: &Path, to: &Path) {
+        if let TabContent::File(path) | TabContent::FileError(path, _) = &mut self.content {
+            if let Some(renamed) = rebase_path(path, from, to) {
+                *path = renamed;
+            }
+        }
+        self.history.rename(from, to);
     }
 }
 
@@ -184,10 +221,24 @@ impl AppState {
             .position(|tab| tab.file().map(|f| f == file).unwrap_or(false))
     }
 
+    /// Update tabs pointing at `from` (or anything inside it) after a rename to `to`
+    pub fn rename_in_tabs(&mut self, from: &Path, to: &Path) {
+        for tab in self.tabs.write().iter_mut() {
+            tab.rename(from, to);
+        }
+    }
+
     /// Open a file, reusing NoFile tab or existing tab with the same file if possible
     /// Used when opening from sidebar or external sources
     pub fn open_file(&mut self, file: impl AsRef<Path>) {
         let file = file.as_ref();
+        // Workspace files replace the window's roots instead of opening in a tab
+        if is_workspace_file(file) {
+            if let Err(e) = self.open_workspace_file(file) {
+                tracing::error!("Failed to open workspace {:?}: {:?}", file, e);
+            }
+            return;
+        }
         // Check if the file is already open in another tab
         if let Some(tab_index) = self.find_tab_with_file(file) {
             // Switch to the existing tab instead of creating a new one
@@ -235,7 +286,7 @@ impl AppState {
             let mut tabs = self.tabs.write();
             tabs.push(Tab {
                 content: TabContent::Preferences,
-                history: HistoryManager::new(),
+                ..Default::default()
             });
             let new_index = tabs.len() - 1;
             drop(tabs);
@@ -335,6 +386,21 @@ mod tests {
         assert_eq!(tab.file(), Some(path.as_path()));
     }
 
+    #[test]
+    fn test_tab_reopen_with_encoding() {
+        let path = PathBuf::from("/test/legacy.md");
+        let mut tab = Tab::new(path.clone());
+        tab.content = TabContent::FileError(path.clone(), "binary".to_string());
+
+        tab.reopen_with_encoding(Some(encoding_rs::SHIFT_JIS));
+
+        assert_eq!(tab.content, TabContent::File(path));
+        assert_eq!(tab.encoding_override, Some(encoding_rs::SHIFT_JIS));
+
+        tab.navigate_to("/test/other.md");
+        assert_eq!(tab.encoding_override, None);
+    }
+
     #[test]
     fn test_tab_file() {
         let path = PathBuf::from("/test/file.md");
@@ -0,0 +1,127 @@
+use super::super::persistence::LAST_FOCUSED_STATE;
+use super::AppState;
+use crate::markdown::RenderOptions;
+use crate::workspace::Workspace;
+use anyhow::Result;
+use dioxus::prelude::*;
+use std::path::{Path, PathBuf};
+
+impl AppState {
+    /// Root folders shown in the file explorer: the workspace roots, or the single directory
+    pub fn roots(&self) -> Vec<PathBuf> {
+        match self.workspace.read().as_ref() {
+            Some(workspace) => workspace.roots.clone(),
+            None => self.directory.read().iter().cloned().collect(),
+        }
+    }
+
+    /// The root folder a path belongs to (falls back to the first root)
+    pub fn root_for(&self, path: &Path) -> Option<PathBuf> {
+        if let Some(workspace) = self.workspace.read().as_ref() {
+            if let Some(root) = workspace.root_for(path) {
+                return Some(root.to_path_buf());
+            }
+        }
+        self.directory.read().clone()
+    }
+
+    /// Directories local links are resolved against besides the document's own directory:
+    /// the project's site root, then every workspace root
+    pub fn link_roots(&self) -> Vec<PathBuf> {
+        let site_root = self
+            .project
+            .read()
+            .as_ref()
+            .and_then(|project| project.site_root());
+        let workspace_roots = self
+            .workspace
+            .read()
+            .as_ref()
+            .map(|workspace| workspace.roots.clone())
+            .unwrap_or_default();
+        site_root.into_iter().chain(workspace_roots).collect()
+    }
+
+    /// Options to render `file` with in this window, without folded sections
+    pub fn render_options_for(&self, file: &Path) -> RenderOptions {
+        let workspace_roots = self
+            .workspace
+            .read()
+            .as_ref()
+            .map(|workspace| workspace.roots.clone())
+            .unwrap_or_default();
+        RenderOptions::for_file(file, self.project.read().as_ref(), &workspace_roots)
+    }
+
+    /// Replace the window's roots with the given workspace and show the sidebar
+    pub fn open_workspace(&mut self, workspace: Workspace) {
+        let Some(first_root) = workspace.roots.first().cloned() else {
+            tracing::warn!("Ignoring workspace without folders: {:?}", workspace.file);
+            return;
+        };
+        *self.directory.write() = Some(first_root.clone());
+        LAST_FOCUSED_STATE.write().directory = Some(first_root);
+        {
+            let mut sidebar = self.sidebar.write();
+            sidebar.expanded_dirs.clear();
+            sidebar.collapsed_roots.clear();
+        }
+        self.workspace.set(Some(workspace));
+        if !self.sidebar.read().open {
+            self.toggle_sidebar();
+        }
+    }
+
+    /// Load and open a `.arto-workspace` file
+    pub fn open_workspace_file(&mut self, path: &Path) -> Result<()> {
+        let workspace = Workspace::load(path)?;
+        self.open_workspace(workspace);
+        Ok(())
+    }
+
+    /// Add a root folder, turning the current directory into a workspace if needed
+    pub fn add_workspace_folder(&mut self, path: impl Into<PathBuf>) {
+        let mut workspace = self.current_workspace();
+        workspace.add_root(path.into());
+        self.open_workspace_keeping_layout(workspace);
+    }
+
+    /// Remove a root folder from the workspace; removing the last one closes the directory
+    pub fn remove_workspace_folder(&mut self, root: &Path) {
+        let mut workspace = self.current_workspace();
+        workspace.remove_root(root);
+        if workspace.roots.is_empty() {
+            self.workspace.set(None);
+            self.directory.set(None);
+            LAST_FOCUSED_STATE.write().directory = None;
+            return;
+        }
+        self.open_workspace_keeping_layout(workspace);
+    }
+
+    /// Save the current roots as a workspace file
+    pub fn save_workspace(&mut self, path: &Path) -> Result<()> {
+        let mut workspace = self.current_workspace();
+        workspace.save(path)?;
+        self.workspace.set(Some(workspace));
+        Ok(())
+    }
+
+    /// The current workspace, or a single-root one built from the open directory
+    fn current_workspace(&self) -> Workspace {
+        self.workspace
+            .read()
+            .clone()
+            .unwrap_or_else(|| Workspace::new(self.directory.read().iter().cloned().collect()))
+    }
+
+    // Like open_workspace, but keeps expanded and collapsed folders as they are
+    fn open_workspace_keeping_layout(&mut self, workspace: Workspace) {
+        let first_root = workspace.roots.first().cloned();
+        if *self.directory.read() != first_root {
+            self.directory.set(first_root.clone());
+            LAST_FOCUSED_STATE.write().directory = first_root;
+        }
+        self.workspace.set(Some(workspace));
+    }
+}
@@ -53,6 +53,7 @@ pub struct PersistedState {
     pub sidebar_open: bool,
     pub sidebar_width: f64,
     pub sidebar_show_all_files: bool,
+    pub sidebar_show_hidden_files: bool,
     pub window_position: Position,
     pub window_size: Size,
 }
@@ -65,6 +66,7 @@ impl Default for PersistedState {
             sidebar_open: false,
             sidebar_width: 280.0,
             sidebar_show_all_files: false,
+            sidebar_show_hidden_files: false,
             window_position: Position::default(),
             window_size: Size::default(),
         }
@@ -80,6 +82,7 @@ impl From<&AppState> for PersistedState {
             sidebar_open: sidebar.open,
             sidebar_width: sidebar.width,
             sidebar_show_all_files: sidebar.show_all_files,
+            sidebar_show_hidden_files: sidebar.show_hidden_files,
             window_position: (*state.position.read()).into(),
             window_size: (*state.size.read()).into(),
         }
@@ -87,10 +90,11 @@ impl From<&AppState> for PersistedState {
 }
 
 impl PersistedState {
-    /// Get the state file path (state.json in local data directory)
+    /// Get the state file path (state.json in `$XDG_STATE_HOME` on Linux, the local data
+    /// directory elsewhere)
     pub fn path() -> PathBuf {
         const FILENAME: &str = "state.json";
-        if let Some(mut path) = dirs::data_local_dir() {
+        if let Some(mut path) = dirs::state_dir().or_else(dirs::data_local_dir) {
             path.push("arto");
             path.push(FILENAME);
             return path;
@@ -132,6 +136,7 @@ impl PersistedState {
             sidebar_open = self.sidebar_open,
             sidebar_width = self.sidebar_width,
             sidebar_show_all_files = self.sidebar_show_all_files,
+            sidebar_show_hidden_files = self.sidebar_show_hidden_files,
             "Saving persisted state"
         );
 
@@ -1,5 +1,11 @@
 pub use dioxus_sdk_window::theme::Theme as DioxusTheme;
 
+mod custom_theme;
+pub use custom_theme::{
+    custom_style_paths, list_custom_themes, select_custom_theme, themes_dir, user_stylesheet_path,
+    CustomStyles, CustomTheme,
+};
+
 #[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Theme {
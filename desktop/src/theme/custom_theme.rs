@@ -0,0 +1,297 @@
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::Theme;
+use crate::config::{Config, CONFIG};
+use crate::events::CONFIG_CHANGED_BROADCAST;
+use crate::remote_images::{strip_remote_css_urls, RemoteImagePolicy};
+
+const THEMES_DIRNAME: &str = "themes";
+const USER_STYLESHEET_FILENAME: &str = "user.css";
+
+/// A user-supplied theme: a CSS file in the themes directory layered over the built-in styles
+///
+/// Metadata is read from an optional JSON file next to the CSS file (e.g. `solarized.css` +
+/// `solarized.json`) with `name`, `description` and `base` ("light" or "dark") fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomTheme {
+    /// File stem of the CSS file, used as the identifier in config
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// Built-in theme the CSS is designed for, applied when the theme is selected
+    pub base: Option<Theme>,
+    pub css_path: PathBuf,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct CustomThemeMetadata {
+    name: Option<String>,
+    description: Option<String>,
+    base: Option<Theme>,
+}
+
+impl CustomTheme {
+    fn from_css_path(css_path: &Path) -> Option<Self> {
+        if css_path.extension().and_then(|ext| ext.to_str()) != Some("css") {
+            return None;
+        }
+        let id = css_path.file_stem()?.to_str()?.to_string();
+
+        let metadata_path = css_path.with_extension("json");
+        let metadata = match fs::read_to_string(&metadata_path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                tracing::warn!(path = %metadata_path.display(), "Invalid theme metadata: {e}");
+                CustomThemeMetadata::default()
+            }),
+            Err(_) => CustomThemeMetadata::default(),
+        };
+
+        Some(Self {
+            name: metadata.name.unwrap_or_else(|| id.clone()),
+            description: metadata.description,
+            base: metadata.base.filter(|base| *base != Theme::Auto),
+            css_path: css_path.to_path_buf(),
+            id,
+        })
+    }
+}
+
+/// CSS layered on top of the bundled stylesheet, in application order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CustomStyles {
+    pub theme_css: String,
+    /// Stylesheet from the project configuration (.arto.toml)
+    pub project_css: String,
+    pub user_css: String,
+}
+
+impl CustomStyles {
+    /// Load the selected custom theme, the project stylesheet and the user stylesheet;
+    /// missing files yield empty CSS
+    ///
+    /// The project stylesheet comes with the repository rather than from the user, so its
+    /// remote URLs are only kept when `remote_images` lets remote images load.
+    pub fn load(
+        theme_id: Option<&str>,
+        project_stylesheet: Option<&Path>,
+        remote_images: RemoteImagePolicy,
+    ) -> Self {
+        let mut styles = Self::load_from(
+            &themes_dir(),
+            &user_stylesheet_path(),
+            theme_id,
+            project_stylesheet,
+        );
+        if remote_images != RemoteImagePolicy::Always {
+            styles.project_css = strip_remote_css_urls(&styles.project_css);
+        }
+        styles
+    }
+
+    fn load_from(
+        themes_dir: &Path,
+        user_stylesheet: &Path,
+        theme_id: Option<&str>,
+        project_stylesheet: Option<&Path>,
+    ) -> Self {
+        let theme_css = theme_id
+            .and_then(|id| find_custom_theme_in(themes_dir, id))
+            .and_then(|theme| fs::read_to_string(&theme.css_path).ok())
+            .unwrap_or_default();
+        let project_css = project_stylesheet
+            .and_then(|path| fs::read_to_string(path).ok())
+            .unwrap_or_default();
+        let user_css = fs::read_to_string(user_stylesheet).unwrap_or_default();
+        Self {
+            theme_css,
+            project_css,
+            user_css,
+        }
+    }
+}
+
+/// Directory containing user-supplied theme CSS files (next to config.json)
+pub fn themes_dir() -> PathBuf {
+    config_dir().join(THEMES_DIRNAME)
+}
+
+/// Path to the per-user extra stylesheet (next to config.json)
+pub fn user_stylesheet_path() -> PathBuf {
+    config_dir().join(USER_STYLESHEET_FILENAME)
+}
+
+fn config_dir() -> PathBuf {
+    Config::path()
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default()
+}
+
+/// List available custom themes sorted by name
+pub fn list_custom_themes() -> Vec<CustomTheme> {
+    list_custom_themes_in(&themes_dir())
+}
+
+fn list_custom_themes_in(dir: &Path) -> Vec<CustomTheme> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut themes: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| CustomTheme::from_css_path(&path))
+        .collect();
+    themes.sort_by_cached_key(|theme| theme.name.to_lowercase());
+    themes
+}
+
+/// Find a custom theme by its identifier
+fn find_custom_theme(id: &str) -> Option<CustomTheme> {
+    find_custom_theme_in(&themes_dir(), id)
+}
+
+fn find_custom_theme_in(dir: &Path, id: &str) -> Option<CustomTheme> {
+    // Reject anything that could escape the themes directory
+    if id.is_empty() || id.contains(['/', '\\']) || id.starts_with('.') {
+        return None;
+    }
+    let css_path = dir.join(format!("{id}.css"));
+    css_path
+        .is_file()
+        .then(|| CustomTheme::from_css_path(&css_path))
+        .flatten()
+}
+
+/// Files whose changes should re-apply the custom styles
+///
+/// The project and user stylesheets are included even when they don't exist yet, so that
+/// creating them applies them right away.
+pub fn custom_style_paths(
+    theme_id: Option<&str>,
+    project_stylesheet: Option<&Path>,
+) -> Vec<PathBuf> {
+    let mut paths: Vec<_> = theme_id
+        .and_then(find_custom_theme)
+        .map(|theme| theme.css_path)
+        .into_iter()
+        .collect();
+    paths.extend(project_stylesheet.map(Path::to_path_buf));
+    paths.push(user_stylesheet_path());
+    paths
+}
+
+/// Select the custom theme for all windows and persist it to config
+pub fn select_custom_theme(theme_id: Option<String>) {
+    let config = {
+        let mut config = CONFIG.write();
+        if config.theme.custom_theme == theme_id {
+            return;
+        }
+        config.theme.custom_theme = theme_id;
+        config.clone()
+    };
+    if let Err(e) = config.save() {
+        tracing::error!("Failed to save configuration: {:?}", e);
+    }
+    let _ = CONFIG_CHANGED_BROADCAST.send(Ok(config));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, name: &str, content: &str) {
+        fs::write(dir.join(name), content).unwrap();
+    }
+
+    #[test]
+    fn test_list_custom_themes() {
+        let temp = TempDir::new().unwrap();
+        write(temp.path(), "solarized.css", "body { color: #839496; }");
+        write(
+            temp.path(),
+            "solarized.json",
+            r#"{"name": "Solarized Dark", "description": "Low contrast", "base": "dark"}"#,
+        );
+        write(temp.path(), "Minimal.css", "body {}");
+        write(temp.path(), "notes.txt", "not a theme");
+
+        let themes = list_custom_themes_in(temp.path());
+
+        assert_eq!(themes.len(), 2);
+        assert_eq!(themes[0].id, "Minimal");
+        assert_eq!(themes[0].name, "Minimal");
+        assert_eq!(themes[0].base, None);
+        assert_eq!(themes[1].id, "solarized");
+        assert_eq!(themes[1].name, "Solarized Dark");
+        assert_eq!(themes[1].description.as_deref(), Some("Low contrast"));
+        assert_eq!(themes[1].base, Some(Theme::Dark));
+    }
+
+    #[test]
+    fn test_list_custom_themes_invalid_metadata() {
+        let temp = TempDir::new().unwrap();
+        write(temp.path(), "broken.css", "body {}");
+        write(temp.path(), "broken.json", "{ not json");
+
+        let themes = list_custom_themes_in(temp.path());
+
+        assert_eq!(themes.len(), 1);
+        assert_eq!(themes[0].name, "broken");
+    }
+
+    #[test]
+    fn test_list_custom_themes_missing_dir() {
+        let temp = TempDir::new().unwrap();
+        assert!(list_custom_themes_in(&temp.path().join("missing")).is_empty());
+    }
+
+    #[test]
+    fn test_find_custom_theme_rejects_path_traversal() {
+        let temp = TempDir::new().unwrap();
+        let themes = temp.path().join("themes");
+        fs::create_dir(&themes).unwrap();
+        write(temp.path(), "outside.css", "body {}");
+
+        assert!(find_custom_theme_in(&themes, "../outside").is_none());
+        assert!(find_custom_theme_in(&themes, "").is_none());
+    }
+
+    #[test]
+    fn test_load_custom_styles() {
+        let temp = TempDir::new().unwrap();
+        write(temp.path(), "paper.css", ".markdown-body { color: black; }");
+        let user_css = temp.path().join("user.css");
+        write(
+            temp.path(),
+            "user.css",
+            ".markdown-body { font-size: 18px; }",
+        );
+
+        write(temp.path(), "project.css", "h1 { color: red; }");
+        let project_css = temp.path().join("project.css");
+
+        let styles =
+            CustomStyles::load_from(temp.path(), &user_css, Some("paper"), Some(&project_css));
+        assert_eq!(styles.theme_css, ".markdown-body { color: black; }");
+        assert_eq!(styles.project_css, "h1 { color: red; }");
+        assert_eq!(styles.user_css, ".markdown-body { font-size: 18px; }");
+
+        let styles = CustomStyles::load_from(temp.path(), &user_css, None, None);
+        assert_eq!(styles.theme_css, "");
+        assert_eq!(styles.project_css, "");
+
+        let styles = CustomStyles::load_from(
+            temp.path(),
+            &temp.path().join("none.css"),
+            Some("missing"),
+            Some(&temp.path().join("missing.css")),
+        );
+        assert_eq!(styles, CustomStyles::default());
+    }
+}
@@ -1,3 +1,7 @@
+pub mod encoding;
 pub mod file;
+pub mod file_filter;
+pub mod file_operations;
+pub mod file_sort;
 pub mod screen;
 pub mod window_title;
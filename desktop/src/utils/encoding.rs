@@ -0,0 +1,148 @@
+//! Text encoding detection for files that aren't UTF-8
+//!
+//! Files starting with a byte order mark are decoded accordingly, valid UTF-8 is taken as is,
+//! and anything else is sniffed: content with NUL bytes or a known binary signature is binary,
+//! and the encoding of the rest is guessed from its bytes (Shift_JIS, EUC-KR, Windows-1252, …).
+
+use encoding_rs::Encoding;
+
+/// Bytes looked at when deciding whether a file is binary
+const SNIFF_LEN: usize = 8192;
+
+/// Encodings a file can be reopened with, in the order they are offered
+pub const ENCODINGS: &[&Encoding] = &[
+    encoding_rs::UTF_8,
+    encoding_rs::UTF_16LE,
+    encoding_rs::UTF_16BE,
+    encoding_rs::WINDOWS_1252,
+    encoding_rs::ISO_8859_15,
+    encoding_rs::WINDOWS_1250,
+    encoding_rs::ISO_8859_2,
+    encoding_rs::WINDOWS_1251,
+    encoding_rs::KOI8_R,
+    encoding_rs::WINDOWS_1253,
+    encoding_rs::WINDOWS_1254,
+    encoding_rs::WINDOWS_1255,
+    encoding_rs::WINDOWS_1256,
+    encoding_rs::WINDOWS_874,
+    encoding_rs::WINDOWS_1258,
+    encoding_rs::SHIFT_JIS,
+    encoding_rs::EUC_JP,
+    encoding_rs::ISO_2022_JP,
+    encoding_rs::EUC_KR,
+    encoding_rs::GBK,
+    encoding_rs::GB18030,
+    encoding_rs::BIG5,
+];
+
+/// Text of a file along with the encoding it was decoded with
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedText {
+    pub text: String,
+    pub encoding: &'static Encoding,
+}
+
+/// Decode `bytes` with `encoding`, or with the encoding detected from them
+///
+/// Returns `None` when no encoding is given and the content is binary.
+pub fn decode_text(bytes: &[u8], encoding: Option<&'static Encoding>) -> Option<DecodedText> {
+    let encoding = match encoding {
+        Some(encoding) => encoding,
+        None => detect_encoding(bytes)?,
+    };
+    // Malformed sequences are replaced with U+FFFD
+    let (text, _) = encoding.decode_with_bom_removal(bytes);
+    Some(DecodedText {
+        text: text.into_owned(),
+        encoding,
+    })
+}
+
+/// Detect the encoding of `bytes`, or `None` if they are binary
+pub fn detect_encoding(bytes: &[u8]) -> Option<&'static Encoding> {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return Some(encoding);
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return Some(encoding_rs::UTF_8);
+    }
+    if is_binary(bytes) {
+        return None;
+    }
+    let mut detector = chardetng::EncodingDetector::new();
+    detector.feed(bytes, true);
+    Some(detector.guess(None, true))
+}
+
+/// Whether content that is neither UTF-8 nor starts with a byte order mark is binary
+fn is_binary(bytes: &[u8]) -> bool {
+    let head = &bytes[..bytes.len().min(SNIFF_LEN)];
+    // Legacy text encodings never use NUL; images, archives and executables do
+    head.contains(&0)
+        || infer::get(head).is_some_and(|kind| kind.matcher_type() != infer::MatcherType::Text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_text_detects_legacy_encodings() {
+        let cases: [(&str, &'static Encoding); 3] = [
+            (
+                "# 議事録\n\n今日の会議では、来年度の予算について話し合いました。",
+                encoding_rs::SHIFT_JIS,
+            ),
+            (
+                "# 회의록\n\n오늘 회의에서는 내년 예산에 대해 논의했습니다.",
+                encoding_rs::EUC_KR,
+            ),
+            (
+                "# Café menu\n\nCrème brûlée, façade, naïve — déjà vu for €5.",
+                encoding_rs::WINDOWS_1252,
+            ),
+        ];
+        for (text, encoding) in cases {
+            let (bytes, _, _) = encoding.encode(text);
+
+            let decoded = decode_text(&bytes, None).unwrap();
+
+            assert_eq!(decoded.encoding, encoding);
+            assert_eq!(decoded.text, text);
+        }
+    }
+
+    #[test]
+    fn test_decode_text_honors_byte_order_marks() {
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend("# Title\n".encode_utf16().flat_map(u16::to_le_bytes));
+
+        let decoded = decode_text(&bytes, None).unwrap();
+
+        assert_eq!(decoded.encoding, encoding_rs::UTF_16LE);
+        assert_eq!(decoded.text, "# Title\n");
+
+        let decoded = decode_text("\u{FEFF}# Title\n".as_bytes(), None).unwrap();
+        assert_eq!(decoded.encoding, encoding_rs::UTF_8);
+        assert_eq!(decoded.text, "# Title\n");
+    }
+
+    #[test]
+    fn test_decode_text_with_chosen_encoding() {
+        let (bytes, _, _) = encoding_rs::EUC_JP.encode("日本語");
+
+        let decoded = decode_text(&bytes, Some(encoding_rs::EUC_JP)).unwrap();
+
+        assert_eq!(decoded.text, "日本語");
+        assert_eq!(decoded.encoding, encoding_rs::EUC_JP);
+    }
+
+    #[test]
+    fn test_binary_content_is_not_decoded() {
+        let png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR\x00\x00\x00\x01";
+        assert_eq!(decode_text(png, None), None);
+        assert_eq!(decode_text(b"%PDF-1.7\n%\xe2\xe3\xcf\xd3\n", None), None);
+        // Valid UTF-8 is always text
+        assert!(decode_text(b"plain\x00text", None).is_some());
+    }
+}
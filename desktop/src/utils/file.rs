@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Check if a file path has a markdown extension (.md or .markdown)
 pub fn is_markdown_file(path: impl AsRef<Path>) -> bool {
@@ -8,3 +8,53 @@ pub fn is_markdown_file(path: impl AsRef<Path>) -> bool {
         .map(|ext| ext == "md" || ext == "markdown")
         .unwrap_or(false)
 }
+
+/// Check if a file path is a Jupyter notebook (.ipynb)
+pub fn is_notebook_file(path: impl AsRef<Path>) -> bool {
+    path.as_ref()
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ipynb"))
+}
+
+/// Check if a file path is an AsciiDoc document (.adoc or .asciidoc)
+pub fn is_asciidoc_file(path: impl AsRef<Path>) -> bool {
+    path.as_ref()
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("adoc") || ext.eq_ignore_ascii_case("asciidoc"))
+}
+
+/// Check if a file path is a reStructuredText document (.rst)
+pub fn is_rst_file(path: impl AsRef<Path>) -> bool {
+    path.as_ref()
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("rst"))
+}
+
+/// Check if a file path is delimited data rendered as a table (.csv or .tsv)
+pub fn is_table_file(path: impl AsRef<Path>) -> bool {
+    path.as_ref()
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv") || ext.eq_ignore_ascii_case("tsv"))
+}
+
+/// Map `path` to its new location after `from` was renamed to `to`
+///
+/// Returns `None` when `path` is neither `from` nor inside it.
+pub fn rebase_path(path: &Path, from: &Path, to: &Path) -> Option<PathBuf> {
+    let relative = path.strip_prefix(from).ok()?;
+    Some(if relative.as_os_str().is_empty() {
+        to.to_path_buf()
+    } else {
+        to.join(relative)
+    })
+}
+
+/// Check if a file path has one of the given extensions (case-insensitive, without the dot)
+pub fn has_extension(path: impl AsRef<Path>, extensions: &[String]) -> bool {
+    path.as_ref()
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+}
@@ -0,0 +1,243 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::WalkBuilder;
+use std::path::{Path, PathBuf};
+
+use crate::config::{Project, SidebarConfig};
+
+/// Decides which entries the file explorer lists, both in its tree and when filtering the tree
+/// by file name
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileFilter {
+    /// Hide entries matched by `.gitignore`, `.ignore` and git exclude files
+    pub respect_ignore_files: bool,
+    /// List dotfiles and dot-directories (`.git` is always hidden)
+    pub show_hidden_files: bool,
+    /// Additional gitignore-style patterns, anchored at the listing root
+    pub ignore_globs: Vec<String>,
+    /// Directory of the project configuration (`.arto.toml`) and its `ignore` patterns,
+    /// anchored at that directory
+    pub project_ignore: Option<(PathBuf, Vec<String>)>,
+}
+
+impl FileFilter {
+    pub fn new(config: &SidebarConfig, show_hidden_files: bool) -> Self {
+        Self {
+            respect_ignore_files: config.respect_ignore_files,
+            show_hidden_files,
+            ignore_globs: config.ignore_globs.clone(),
+            project_ignore: None,
+        }
+    }
+
+    /// Also hide the entries matching the `ignore` patterns of `project`
+    pub fn with_project(mut self, project: Option<&Project>) -> Self {
+        self.project_ignore = project
+            .filter(|project| !project.config.ignore.is_empty())
+            .map(|project| (project.dir.clone(), project.config.ignore.clone()));
+        self
+    }
+
+    /// List the immediate children of `dir` that pass the filter, unsorted
+    ///
+    /// `root` is the directory `ignore_globs` containing a `/` are relative to.
+    pub fn read_dir(&self, root: &Path, dir: &Path) -> Vec<PathBuf> {
+        self.walker(root, dir)
+            .max_depth(Some(1))
+            .build()
+            .filter_map(|entry| match entry {
+                Ok(entry) => Some(entry),
+                Err(err) => {
+                    tracing::error!("Failed to read directory {:?}: {}", dir, err);
+                    None
+                }
+            })
+            .filter(|entry| entry.depth() == 1)
+            .map(|entry| entry.into_path())
+            .collect()
+    }
+
+    /// Recursively list every entry below `root` that passes the filter; ignored
+    /// directories are not descended into
+    pub fn walk(&self, root: &Path) -> impl Iterator<Item = PathBuf> {
+        self.walker(root, root)
+            .build()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.depth() > 0)
+            .map(|entry| entry.into_path())
+    }
+
+    fn walker(&self, root: &Path, dir: &Path) -> WalkBuilder {
+        let globs = build_globs(root, &self.ignore_globs);
+        let project_globs = self.project_ignore.as_ref().map(|(project_dir, patterns)| {
+            (project_dir.clone(), build_globs(project_dir, patterns))
+        });
+        let mut builder = WalkBuilder::new(dir);
+        builder
+            .standard_filters(self.respect_ignore_files)
+            .hidden(!self.show_hidden_files)
+            .require_git(false)
+            .filter_entry(move |entry| {
+                let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+                let path = entry.path();
+                let project_ignored = project_globs.as_ref().is_some_and(|(project_dir, globs)| {
+                    path.starts_with(project_dir) && globs.matched(path, is_dir).is_ignore()
+                });
+                entry.file_name() != ".git"
+                    && !globs.matched(path, is_dir).is_ignore()
+                    && !project_ignored
+            });
+        builder
+    }
+}
+
+/// Gitignore-style `globs` anchored at `root`
+fn build_globs(root: &Path, globs: &[String]) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(root);
+    for glob in globs {
+        if let Err(err) = builder.add_line(None, glob) {
+            tracing::warn!("Invalid ignore pattern {:?}: {}", glob, err);
+        }
+    }
+    builder.build().unwrap_or_else(|err| {
+        tracing::warn!("Failed to build ignore patterns: {}", err);
+        Gitignore::empty()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn filter() -> FileFilter {
+        FileFilter {
+            respect_ignore_files: true,
+            show_hidden_files: false,
+            ignore_globs: Vec::new(),
+            project_ignore: None,
+        }
+    }
+
+    fn names(mut paths: Vec<PathBuf>) -> Vec<String> {
+        paths.sort();
+        paths
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    fn setup() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::create_dir_all(root.join("target/debug")).unwrap();
+        fs::create_dir_all(root.join("docs/drafts")).unwrap();
+        fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        fs::write(root.join(".ignore"), "*.log\n").unwrap();
+        fs::write(root.join("README.md"), "").unwrap();
+        fs::write(root.join("build.log"), "").unwrap();
+        fs::write(root.join("docs/guide.md"), "").unwrap();
+        fs::write(root.join("docs/drafts/wip.md"), "").unwrap();
+        temp
+    }
+
+    #[test]
+    fn test_read_dir_respects_ignore_files() {
+        let temp = setup();
+        let entries = filter().read_dir(temp.path(), temp.path());
+        assert_eq!(names(entries), vec!["README.md", "docs"]);
+    }
+
+    #[test]
+    fn test_read_dir_without_ignore_files() {
+        let temp = setup();
+        let filter = FileFilter {
+            respect_ignore_files: false,
+            ..filter()
+        };
+        let entries = filter.read_dir(temp.path(), temp.path());
+        assert_eq!(
+            names(entries),
+            vec!["README.md", "build.log", "docs", "target"]
+        );
+    }
+
+    #[test]
+    fn test_read_dir_show_hidden_files() {
+        let temp = setup();
+        let filter = FileFilter {
+            show_hidden_files: true,
+            ..filter()
+        };
+        let entries = filter.read_dir(temp.path(), temp.path());
+        assert_eq!(
+            names(entries),
+            vec![".gitignore", ".ignore", "README.md", "docs"]
+        );
+    }
+
+    #[test]
+    fn test_read_dir_ignore_globs() {
+        let temp = setup();
+        let filter = FileFilter {
+            ignore_globs: vec!["README.md".to_string(), "docs/drafts".to_string()],
+            ..filter()
+        };
+
+        let entries = filter.read_dir(temp.path(), temp.path());
+        assert_eq!(names(entries), vec!["docs"]);
+
+        let entries = filter.read_dir(temp.path(), &temp.path().join("docs"));
+        assert_eq!(names(entries), vec!["guide.md"]);
+    }
+
+    #[test]
+    fn test_walk_skips_ignored_directories() {
+        let temp = setup();
+        let filter = FileFilter {
+            ignore_globs: vec!["docs/drafts".to_string()],
+            ..filter()
+        };
+
+        let mut entries: Vec<_> = filter
+            .walk(temp.path())
+            .map(|path| path.strip_prefix(temp.path()).unwrap().to_path_buf())
+            .collect();
+        entries.sort();
+
+        assert_eq!(
+            entries,
+            vec![
+                PathBuf::from("README.md"),
+                PathBuf::from("docs"),
+                PathBuf::from("docs/guide.md"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_walk_respects_project_ignore() {
+        let temp = setup();
+        let docs = temp.path().join("docs");
+        fs::write(docs.join(".arto.toml"), r#"ignore = ["drafts/**", "*.md"]"#).unwrap();
+        let project = Project::discover(&docs).unwrap();
+        let filter = filter().with_project(Some(&project));
+
+        let mut entries: Vec<_> = filter
+            .walk(temp.path())
+            .map(|path| path.strip_prefix(temp.path()).unwrap().to_path_buf())
+            .collect();
+        entries.sort();
+
+        // Patterns only apply within the project directory
+        assert_eq!(
+            entries,
+            vec![
+                PathBuf::from("README.md"),
+                PathBuf::from("docs"),
+                PathBuf::from("docs/drafts"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Create an empty Markdown file named `name` in `dir`, appending `.md` when the name
+/// has no extension. Fails if the file already exists.
+pub fn create_markdown_file(dir: &Path, name: &str) -> io::Result<PathBuf> {
+    let name = validate_file_name(name)?;
+    let mut path = dir.join(name);
+    if path.extension().is_none() {
+        path.set_extension("md");
+    }
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    Ok(path)
+}
+
+/// Rename a file or directory within its parent directory, returning the new path.
+/// Fails if an entry with the new name already exists.
+pub fn rename_entry(path: &Path, new_name: &str) -> io::Result<PathBuf> {
+    let new_name = validate_file_name(new_name)?;
+    let parent = path
+        .parent()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Cannot rename a root"))?;
+    let target = parent.join(new_name);
+    if target == path {
+        return Ok(target);
+    }
+    // On case-insensitive file systems, a case-only rename finds the entry itself
+    if target.exists() && !is_same_entry(path, &target) {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("\"{new_name}\" already exists"),
+        ));
+    }
+    fs::rename(path, &target)?;
+    Ok(target)
+}
+
+/// Move a file or directory to the system trash
+pub fn move_to_trash(path: &Path) -> io::Result<()> {
+    trash::delete(path).map_err(io::Error::other)
+}
+
+/// Show the entry in the system file manager (selected where supported)
+pub fn reveal_in_file_manager(path: &Path) -> io::Result<()> {
+    if cfg!(target_os = "macos") {
+        Command::new("open").arg("-R").arg(path).spawn()?;
+    } else if cfg!(target_os = "windows") {
+        Command::new("explorer")
+            .arg(format!("/select,{}", path.display()))
+            .spawn()?;
+    } else {
+        let dir = if path.is_dir() {
+            path
+        } else {
+            path.parent().unwrap_or(path)
+        };
+        open::that_detached(dir)?;
+    }
+    Ok(())
+}
+
+/// Whether both paths lead to the same file or directory
+fn is_same_entry(a: &Path, b: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        match (fs::symlink_metadata(a), fs::symlink_metadata(b)) {
+            (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+            _ => false,
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        match (a.canonicalize(), b.canonicalize()) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+fn validate_file_name(name: &str) -> io::Result<&str> {
+    let name = name.trim();
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("\"{name}\" is not a valid file name"),
+        ));
+    }
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_create_markdown_file() {
+        let temp = TempDir::new().unwrap();
+
+        let path = create_markdown_file(temp.path(), "notes").unwrap();
+        assert_eq!(path, temp.path().join("notes.md"));
+        assert!(path.is_file());
+
+        let path = create_markdown_file(temp.path(), " guide.markdown ").unwrap();
+        assert_eq!(path, temp.path().join("guide.markdown"));
+
+        let err = create_markdown_file(temp.path(), "notes.md").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+    }
+
+    #[test]
+    fn test_create_markdown_file_rejects_invalid_names() {
+        let temp = TempDir::new().unwrap();
+        for name in ["", "  ", "..", "../escape", "a/b"] {
+            let err = create_markdown_file(temp.path(), name).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{name:?}");
+        }
+    }
+
+    #[test]
+    fn test_rename_entry() {
+        let temp = TempDir::new().unwrap();
+        let original = temp.path().join("old.md");
+        fs::write(&original, "content").unwrap();
+        fs::write(temp.path().join("taken.md"), "").unwrap();
+
+        let renamed = rename_entry(&original, "new.md").unwrap();
+        assert_eq!(renamed, temp.path().join("new.md"));
+        assert!(!original.exists());
+        assert_eq!(fs::read_to_string(&renamed).unwrap(), "content");
+
+        let err = rename_entry(&renamed, "taken.md").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert!(renamed.exists());
+    }
+
+    #[test]
+    fn test_rename_entry_case_change() {
+        let temp = TempDir::new().unwrap();
+        let lower = temp.path().join("readme.md");
+        fs::write(&lower, "lower").unwrap();
+        fs::write(temp.path().join("README.md"), "upper").unwrap();
+
+        if fs::read_dir(temp.path()).unwrap().count() == 2 {
+            // Case-sensitive file system: the other file must not be overwritten
+            let err = rename_entry(&lower, "README.md").unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+            assert_eq!(fs::read_to_string(&lower).unwrap(), "lower");
+            assert_eq!(
+                fs::read_to_string(temp.path().join("README.md")).unwrap(),
+                "upper"
+            );
+        } else {
+            // Case-insensitive file system: both names are the same file
+            let renamed = rename_entry(&lower, "README.md").unwrap();
+            assert_eq!(renamed, temp.path().join("README.md"));
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::config::SortMode;
+
+/// Maximum number of bytes read when looking for front matter
+const FRONT_MATTER_READ_LIMIT: u64 = 8 * 1024;
+
+/// Files in a directory whose front matter orders the directory itself
+const DIRECTORY_INDEX_FILES: &[&str] = &["_index.md", "index.md", "README.md"];
+
+/// Sort entries with directories first, then by `mode`, falling back to natural name order
+pub fn sort_entries(items: &mut Vec<PathBuf>, mode: SortMode) {
+    let mut keyed: Vec<_> = items
+        .drain(..)
+        .map(|path| (SortKey::new(&path, mode), path))
+        .collect();
+    keyed.sort_by(|(a, _), (b, _)| a.compare(b, mode));
+    items.extend(keyed.into_iter().map(|(_, path)| path));
+}
+
+/// Compare strings in natural order: digit runs compare numerically, other characters
+/// case-insensitively (`2.md` < `10.md`, `a.md` < `B.md`)
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x_digits = take_digits(&mut a_chars);
+                let y_digits = take_digits(&mut b_chars);
+                let x_num = x_digits.trim_start_matches('0');
+                let y_num = y_digits.trim_start_matches('0');
+                let ordering = x_num.len().cmp(&y_num.len()).then_with(|| x_num.cmp(y_num));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        digits.push(c);
+    }
+    digits
+}
+
+/// Metadata an entry is sorted by; only the fields needed by the sort mode are read
+struct SortKey {
+    is_dir: bool,
+    name: String,
+    modified: Option<SystemTime>,
+    size: u64,
+    order: Option<f64>,
+}
+
+impl SortKey {
+    fn new(path: &Path, mode: SortMode) -> Self {
+        let metadata = path.metadata().ok();
+        let is_dir = metadata.as_ref().is_some_and(|m| m.is_dir());
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let mut key = Self {
+            is_dir,
+            name,
+            modified: None,
+            size: 0,
+            order: None,
+        };
+        match mode {
+            SortMode::Name => {}
+            SortMode::Modified => {
+                key.modified = metadata.and_then(|m| m.modified().ok());
+            }
+            SortMode::Size => {
+                key.size = metadata.map(|m| m.len()).unwrap_or(0);
+            }
+            SortMode::FrontMatter => {
+                key.order = if is_dir {
+                    DIRECTORY_INDEX_FILES
+                        .iter()
+                        .find_map(|index| read_front_matter_order(&path.join(index)))
+                } else {
+                    read_front_matter_order(path)
+                };
+            }
+        }
+        key
+    }
+
+    fn compare(&self, other: &Self, mode: SortMode) -> Ordering {
+        other
+            .is_dir
+            .cmp(&self.is_dir)
+            .then_with(|| match mode {
+                SortMode::Name => Ordering::Equal,
+                SortMode::Modified => other.modified.cmp(&self.modified),
+                SortMode::Size if self.is_dir || other.is_dir => Ordering::Equal,
+                SortMode::Size => other.size.cmp(&self.size),
+                SortMode::FrontMatter => match (self.order, other.order) {
+                    (Some(a), Some(b)) => a.total_cmp(&b),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                },
+            })
+            .then_with(|| natural_cmp(&self.name, &other.name))
+    }
+}
+
+fn read_front_matter_order(path: &Path) -> Option<f64> {
+    let mut content = String::new();
+    File::open(path)
+        .ok()?
+        .take(FRONT_MATTER_READ_LIMIT)
+        .read_to_string(&mut content)
+        .ok()?;
+    parse_front_matter_order(&content)
+}
+
+/// Read a top-level `order` or `weight` field from YAML front matter
+fn parse_front_matter_order(content: &str) -> Option<f64> {
+    let mut lines = content.lines();
+    if lines.next()?.trim_end() != "---" {
+        return None;
+    }
+    lines
+        .take_while(|line| !matches!(line.trim_end(), "---" | "..."))
+        .find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            if key != "order" && key != "weight" {
+                return None;
+            }
+            value.trim().trim_matches(['"', '\'']).parse().ok()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn names(items: &[PathBuf]) -> Vec<String> {
+        items
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_natural_cmp() {
+        let mut names = vec![
+            "10.md",
+            "2.md",
+            "B.md",
+            "a.md",
+            "chapter-1.md",
+            "chapter-01b.md",
+        ];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(
+            names,
+            vec![
+                "2.md",
+                "10.md",
+                "a.md",
+                "B.md",
+                "chapter-1.md",
+                "chapter-01b.md"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_front_matter_order() {
+        assert_eq!(
+            parse_front_matter_order("---\ntitle: Intro\norder: 2\n---\n# Intro"),
+            Some(2.0)
+        );
+        assert_eq!(
+            parse_front_matter_order("---\nweight: \"1.5\"\n---\n"),
+            Some(1.5)
+        );
+        assert_eq!(
+            parse_front_matter_order("# No front matter\norder: 1"),
+            None
+        );
+        assert_eq!(
+            parse_front_matter_order("---\ntitle: x\n---\norder: 1"),
+            None
+        );
+        assert_eq!(
+            parse_front_matter_order("---\nmeta:\n  order: 1\n---\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sort_entries_by_name() {
+        let temp = TempDir::new().unwrap();
+        for name in ["10.md", "2.md", "notes.md"] {
+            fs::write(temp.path().join(name), "").unwrap();
+        }
+        fs::create_dir(temp.path().join("zeta")).unwrap();
+
+        let mut items: Vec<_> = ["notes.md", "10.md", "zeta", "2.md"]
+            .iter()
+            .map(|name| temp.path().join(name))
+            .collect();
+        sort_entries(&mut items, SortMode::Name);
+
+        assert_eq!(names(&items), vec!["zeta", "2.md", "10.md", "notes.md"]);
+    }
+
+    #[test]
+    fn test_sort_entries_by_size() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("small.md"), "a").unwrap();
+        fs::write(temp.path().join("large.md"), "a".repeat(100)).unwrap();
+
+        let mut items = vec![temp.path().join("small.md"), temp.path().join("large.md")];
+        sort_entries(&mut items, SortMode::Size);
+
+        assert_eq!(names(&items), vec!["large.md", "small.md"]);
+    }
+
+    #[test]
+    fn test_sort_entries_by_front_matter() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("a.md"), "# No order").unwrap();
+        fs::write(temp.path().join("b.md"), "---\norder: 2\n---\n").unwrap();
+        fs::write(temp.path().join("c.md"), "---\nweight: 1\n---\n").unwrap();
+        fs::create_dir(temp.path().join("guide")).unwrap();
+        fs::create_dir(temp.path().join("api")).unwrap();
+        fs::write(temp.path().join("guide/_index.md"), "---\nweight: 1\n---\n").unwrap();
+
+        let mut items: Vec<_> = ["a.md", "api", "b.md", "c.md", "guide"]
+            .iter()
+            .map(|name| temp.path().join(name))
+            .collect();
+        sort_entries(&mut items, SortMode::FrontMatter);
+
+        assert_eq!(names(&items), vec!["guide", "api", "c.md", "b.md", "a.md"]);
+    }
+}
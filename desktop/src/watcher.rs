@@ -2,7 +2,7 @@ use notify_debouncer_full::{
     new_debouncer, notify::RecursiveMode, DebounceEventResult, Debouncer, RecommendedCache,
 };
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, LazyLock, Mutex};
 use std::time::Duration;
 use thiserror::Error;
@@ -17,6 +17,10 @@ pub enum WatcherError {
 type WatcherResult<T> = Result<T, WatcherError>;
 
 /// Global file watcher that manages file change notifications
+///
+/// Files are watched through their parent directory, so that a file created after it's
+/// registered, or replaced by an editor saving to a temporary file and renaming it, is still
+/// reported.
 pub struct FileWatcher {
     command_tx: Sender<FileWatcherCommand>,
 }
@@ -36,6 +40,8 @@ impl FileWatcher {
             let watchers: Arc<Mutex<HashMap<PathBuf, Vec<Sender<()>>>>> =
                 Arc::new(Mutex::new(HashMap::new()));
             let watchers_clone = watchers.clone();
+            // Number of watched files by watched directory
+            let mut directories: HashMap<PathBuf, usize> = HashMap::new();
 
             // Create a debouncer with 500ms delay
             let mut debouncer: Debouncer<
@@ -92,7 +98,15 @@ impl FileWatcher {
 
                         // Only start watching if this is the first watcher for this file
                         if is_first {
-                            if let Err(e) = debouncer.watch(&path, RecursiveMode::NonRecursive) {
+                            let directory = watched_directory(&path);
+                            let count = directories.entry(directory.clone()).or_default();
+                            *count += 1;
+                            let result = if *count == 1 {
+                                debouncer.watch(&directory, RecursiveMode::NonRecursive)
+                            } else {
+                                Ok(())
+                            };
+                            if let Err(e) = result {
                                 tracing::error!("Failed to watch file {:?}: {:?}", path, e);
                             } else {
                                 tracing::info!("Started watching file: {:?}", path);
@@ -102,14 +116,26 @@ impl FileWatcher {
                     Some(FileWatcherCommand::Unwatch(path)) => {
                         let mut watchers = watchers.lock().unwrap();
                         if let Some(senders) = watchers.get_mut(&path) {
-                            senders.pop();
+                            // Only drop the registrations whose receiver is gone, not those of
+                            // other subscribers watching the same file
+                            senders.retain(|sender| !sender.is_closed());
                             // If no more watchers for this file, stop watching
                             if senders.is_empty() {
                                 watchers.remove(&path);
-                                if let Err(e) = debouncer.unwatch(&path) {
-                                    tracing::error!("Failed to unwatch file {:?}: {:?}", path, e);
-                                } else {
-                                    tracing::info!("Stopped watching file: {:?}", path);
+                                tracing::info!("Stopped watching file: {:?}", path);
+                                let directory = watched_directory(&path);
+                                if let Some(count) = directories.get_mut(&directory) {
+                                    *count -= 1;
+                                    if *count == 0 {
+                                        directories.remove(&directory);
+                                        if let Err(e) = debouncer.unwatch(&directory) {
+                                            tracing::error!(
+                                                "Failed to unwatch directory {:?}: {:?}",
+                                                directory,
+                                                e
+                                            );
+                                        }
+                                    }
                                 }
                             }
                         }
@@ -125,7 +151,8 @@ impl FileWatcher {
         Self { command_tx }
     }
 
-    /// Watch a file and receive notifications when it changes
+    /// Watch a file and receive notifications when it changes, including when it's created
+    /// (its directory must exist)
     pub async fn watch(&self, path: impl Into<PathBuf>) -> WatcherResult<Receiver<()>> {
         let path = path.into();
         let (tx, rx) = mpsc::channel(100);
@@ -136,7 +163,7 @@ impl FileWatcher {
         Ok(rx)
     }
 
-    /// Stop watching a file
+    /// Stop watching a file for the receivers that have been dropped
     pub async fn unwatch(&self, path: impl Into<PathBuf>) -> WatcherResult<()> {
         let path = path.into();
         self.command_tx
@@ -146,4 +173,12 @@ impl FileWatcher {
     }
 }
 
+/// Directory watched to be notified about changes of `path`
+fn watched_directory(path: &Path) -> PathBuf {
+    path.parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(path)
+        .to_path_buf()
+}
+
 pub static FILE_WATCHER: LazyLock<FileWatcher> = LazyLock::new(FileWatcher::new);
@@ -6,8 +6,8 @@ pub mod settings;
 mod types;
 
 pub use child::{
-    close_child_windows_for_last_focused, close_child_windows_for_parent,
-    open_or_focus_mermaid_window,
+    close_child_windows_for_last_focused, close_child_windows_for_parent, close_presenter_window,
+    open_or_focus_mermaid_window, open_or_focus_presenter_window,
 };
 pub use main::{
     close_all_main_windows, create_main_window_config, create_new_main_window_with_empty,
@@ -1,15 +1,18 @@
 use dioxus::desktop::tao::window::WindowId;
-use dioxus::desktop::{window, Config, WeakDesktopContext, WindowBuilder};
+use dioxus::desktop::{window, Config, DesktopContext, WeakDesktopContext, WindowBuilder};
 use dioxus::prelude::*;
 
 use std::cell::RefCell;
 use std::collections::HashMap;
 
+use crate::asset_protocol::{handle_request as handle_asset_request, ASSET_SCHEME};
 use crate::assets::MAIN_STYLE;
 use crate::components::mermaid_window::{generate_diagram_id, MermaidWindow, MermaidWindowProps};
+use crate::components::presenter_window::{PresenterWindow, PresenterWindowProps};
+use crate::presentation::Presentation;
 use crate::theme::Theme;
 
-use super::index::build_mermaid_window_index;
+use super::index::{build_mermaid_window_index, build_presenter_window_index};
 use super::main::get_last_focused_window;
 
 struct ChildWindowEntry {
@@ -94,29 +97,51 @@ pub fn close_child_windows_for_last_focused() {
     }
 }
 
-pub fn open_or_focus_mermaid_window(source: String, theme: Theme) {
-    let diagram_id = generate_diagram_id(&source);
-    let parent_id = window().id();
-
-    // Check if window already exists and can be focused
-    let needs_creation = CHILD_WINDOWS.with(|windows| {
+/// Focus the child window registered as `key`, or reserve the key for a new one
+///
+/// Returns whether the window needs to be created.
+fn focus_or_reserve_child_window(key: &str, parent_id: WindowId) -> bool {
+    CHILD_WINDOWS.with(|windows| {
         let mut windows = windows.borrow_mut();
         windows.retain(|_, state| match state {
             ChildWindowState::Pending { .. } => true,
             ChildWindowState::Created(entry) => entry.is_alive(),
         });
 
-        match windows.get(&diagram_id) {
+        match windows.get(key) {
             Some(ChildWindowState::Created(entry)) => !entry.focus(),
             Some(ChildWindowState::Pending { .. }) => false,
             None => {
-                windows.insert(diagram_id.clone(), ChildWindowState::Pending { parent_id });
+                windows.insert(key.to_string(), ChildWindowState::Pending { parent_id });
                 true
             }
         }
+    })
+}
+
+/// Register a created child window as `key`
+fn register_child_window(key: String, ctx: &DesktopContext, parent_id: WindowId) {
+    let weak_handle = std::rc::Rc::downgrade(ctx);
+    let window_id = ctx.window.id();
+
+    CHILD_WINDOWS.with(|windows| {
+        windows.borrow_mut().insert(
+            key,
+            ChildWindowState::Created(ChildWindowEntry {
+                handle: weak_handle,
+                window_id,
+                parent_id,
+            }),
+        );
     });
+}
+
+pub fn open_or_focus_mermaid_window(source: String, theme: Theme) {
+    let diagram_id = generate_diagram_id(&source);
+    let parent_id = window().id();
 
-    if needs_creation {
+    // Check if window already exists and can be focused
+    if focus_or_reserve_child_window(&diagram_id, parent_id) {
         dioxus_core::spawn(create_and_register_mermaid_window(
             source, diagram_id, theme, parent_id,
         ));
@@ -146,17 +171,49 @@ async fn create_and_register_mermaid_window(
 
     let pending = window().new_window(dom, config);
     let ctx = pending.await;
-    let weak_handle = std::rc::Rc::downgrade(&ctx);
-    let window_id = ctx.window.id();
+    register_child_window(diagram_id, &ctx, parent_id);
+}
 
+/// Key of the presenter view of the presentation shown in `parent_id`
+fn presenter_window_key(parent_id: WindowId) -> String {
+    format!("presenter:{parent_id:?}")
+}
+
+/// Open the presenter view of the presentation shown in the current window
+pub fn open_or_focus_presenter_window(presentation: Presentation, theme: Theme) {
+    let parent_id = window().id();
+    let key = presenter_window_key(parent_id);
+
+    if focus_or_reserve_child_window(&key, parent_id) {
+        dioxus_core::spawn(async move {
+            let dom = VirtualDom::new_with_props(
+                PresenterWindow,
+                PresenterWindowProps {
+                    presentation,
+                    theme,
+                },
+            );
+            let config = Config::new()
+                .with_menu(None)
+                .with_window(WindowBuilder::new().with_title("Presenter View"))
+                .with_custom_head(
+                    indoc::formatdoc! {r#"<link rel="stylesheet" href="{MAIN_STYLE}">"#},
+                )
+                .with_custom_index(build_presenter_window_index(theme))
+                .with_asynchronous_custom_protocol(ASSET_SCHEME, handle_asset_request);
+
+            let ctx = window().new_window(dom, config).await;
+            register_child_window(key, &ctx, parent_id);
+        });
+    }
+}
+
+/// Close the presenter view of the presentation shown in the current window
+pub fn close_presenter_window() {
+    let key = presenter_window_key(window().id());
     CHILD_WINDOWS.with(|windows| {
-        windows.borrow_mut().insert(
-            diagram_id,
-            ChildWindowState::Created(ChildWindowEntry {
-                handle: weak_handle,
-                window_id,
-                parent_id,
-            }),
-        );
+        if let Some(ChildWindowState::Created(entry)) = windows.borrow_mut().remove(&key) {
+            entry.close();
+        }
     });
 }
@@ -6,6 +6,7 @@ use dioxus::prelude::*;
 use std::cell::RefCell;
 use std::path::PathBuf;
 
+use crate::asset_protocol::{handle_request as handle_asset_request, ASSET_SCHEME};
 use crate::assets::MAIN_STYLE;
 use crate::components::app::{App, AppProps};
 use crate::config::{WindowPositionOffset, CONFIG};
@@ -36,6 +37,8 @@ pub fn create_main_window_config(params: &CreateMainWindowConfigParams) -> Confi
         .with_custom_head(indoc::formatdoc! {r#"<link rel="stylesheet" href="{MAIN_STYLE}">"#})
         // Use a custom index to set the initial theme correctly
         .with_custom_index(build_custom_index(params.theme))
+        // Local images and media referenced by documents
+        .with_asynchronous_custom_protocol(ASSET_SCHEME, handle_asset_request)
 }
 
 /// Parameters for creating a new main window
@@ -45,6 +48,7 @@ pub struct CreateMainWindowConfigParams {
     pub sidebar_open: bool,
     pub sidebar_width: f64,
     pub sidebar_show_all_files: bool,
+    pub sidebar_show_hidden_files: bool,
     pub size: LogicalSize<u32>,
     pub position: LogicalPosition<i32>,
 }
@@ -65,6 +69,7 @@ impl CreateMainWindowConfigParams {
             sidebar_open: sidebar_pref.open,
             sidebar_width: sidebar_pref.width,
             sidebar_show_all_files: sidebar_pref.show_all_files,
+            sidebar_show_hidden_files: sidebar_pref.show_hidden_files,
             size: size_pref.size,
             position: position_pref.position,
         }
@@ -217,6 +222,7 @@ pub(crate) async fn create_new_main_window(
             sidebar_open: params.sidebar_open,
             sidebar_width: params.sidebar_width,
             sidebar_show_all_files: params.sidebar_show_all_files,
+            sidebar_show_hidden_files: params.sidebar_show_hidden_files,
         },
     );
 
@@ -226,7 +232,10 @@ pub(crate) async fn create_new_main_window(
         ..params
     };
 
-    let config = create_main_window_config(&params_with_shift).with_menu(None); // To avoid child window taking over the main window's menu
+    // macOS has a single menu bar that a new window must not take over; elsewhere the menu
+    // lives inside each window, so every window gets its own
+    let menu = (!cfg!(target_os = "macos")).then(crate::menu::build_menu);
+    let config = create_main_window_config(&params_with_shift).with_menu(menu);
 
     let pending = window().new_window(dom, config);
     let handle = pending.await;
@@ -28,6 +28,7 @@ pub struct SidebarPreference {
     pub open: bool,
     pub width: f64,
     pub show_all_files: bool,
+    pub show_hidden_files: bool,
 }
 
 pub struct WindowSizePreference {
@@ -232,6 +233,7 @@ pub fn get_sidebar_preference(is_first_window: bool) -> SidebarPreference {
             open: cfg.sidebar.default_open,
             width: cfg.sidebar.default_width,
             show_all_files: cfg.sidebar.default_show_all_files,
+            show_hidden_files: cfg.sidebar.default_show_hidden_files,
         },
         || {
             let state = LAST_FOCUSED_STATE.read();
@@ -239,6 +241,7 @@ pub fn get_sidebar_preference(is_first_window: bool) -> SidebarPreference {
                 open: state.sidebar_open,
                 width: state.sidebar_width,
                 show_all_files: state.sidebar_show_all_files,
+                show_hidden_files: state.sidebar_show_hidden_files,
             }
         },
     )
@@ -0,0 +1,212 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File extension of saved workspaces
+pub const WORKSPACE_EXTENSION: &str = "arto-workspace";
+
+/// A set of root folders shown side by side in one window's file explorer
+///
+/// Saved as JSON; folder paths are stored relative to the workspace file when possible so
+/// a workspace can live in (and move with) a repository.
+///
+/// ```json
+/// {
+///   "name": "Docs",
+///   "folders": [
+///     { "path": "docs" },
+///     { "path": "../handbook" }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Workspace {
+    pub name: Option<String>,
+    pub roots: Vec<PathBuf>,
+    /// Workspace file this was opened from or last saved to
+    pub file: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct WorkspaceFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default)]
+    folders: Vec<WorkspaceFolder>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct WorkspaceFolder {
+    path: PathBuf,
+}
+
+/// Check whether a path is a saved workspace (`*.arto-workspace`)
+pub fn is_workspace_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(WORKSPACE_EXTENSION))
+}
+
+impl Workspace {
+    pub fn new(roots: Vec<PathBuf>) -> Self {
+        Self {
+            roots,
+            ..Default::default()
+        }
+    }
+
+    /// Load a workspace file, dropping folders that no longer exist
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read workspace file {path:?}"))?;
+        let file: WorkspaceFile = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse workspace file {path:?}"))?;
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+
+        let mut workspace = Self {
+            name: file.name,
+            roots: Vec::new(),
+            file: Some(path.to_path_buf()),
+        };
+        for folder in file.folders {
+            let root = base_dir.join(&folder.path);
+            match root.canonicalize() {
+                Ok(root) if root.is_dir() => workspace.add_root(root),
+                _ => tracing::warn!("Skipping missing workspace folder {:?}", root),
+            }
+        }
+        Ok(workspace)
+    }
+
+    /// Save the workspace to `path`, remembering it as the workspace file
+    pub fn save(&mut self, path: &Path) -> Result<()> {
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+        let file = WorkspaceFile {
+            name: self.name.clone(),
+            folders: self
+                .roots
+                .iter()
+                .map(|root| WorkspaceFolder {
+                    path: pathdiff::diff_paths(root, base_dir).unwrap_or_else(|| root.clone()),
+                })
+                .collect(),
+        };
+        let json = serde_json::to_string_pretty(&file)?;
+        fs::write(path, json).with_context(|| format!("Failed to write workspace {path:?}"))?;
+        self.file = Some(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Name shown in the file explorer: the explicit name, else the workspace file stem
+    pub fn display_name(&self) -> String {
+        self.name
+            .clone()
+            .or_else(|| {
+                self.file
+                    .as_ref()
+                    .and_then(|file| file.file_stem())
+                    .map(|stem| stem.to_string_lossy().into_owned())
+            })
+            .unwrap_or_else(|| "Workspace".to_string())
+    }
+
+    /// Add a root folder; folders already present are ignored
+    pub fn add_root(&mut self, root: PathBuf) {
+        if !self.roots.contains(&root) {
+            self.roots.push(root);
+        }
+    }
+
+    pub fn remove_root(&mut self, root: &Path) {
+        self.roots.retain(|r| r != root);
+    }
+
+    /// The (innermost) root folder containing `path`
+    pub fn root_for(&self, path: &Path) -> Option<&Path> {
+        self.roots
+            .iter()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+            .map(PathBuf::as_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_is_workspace_file() {
+        assert!(is_workspace_file(Path::new("/a/docs.arto-workspace")));
+        assert!(!is_workspace_file(Path::new("/a/docs.md")));
+        assert!(!is_workspace_file(Path::new("/a/arto-workspace")));
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path().canonicalize().unwrap();
+        fs::create_dir_all(base.join("repo/docs")).unwrap();
+        fs::create_dir_all(base.join("handbook")).unwrap();
+
+        let mut workspace = Workspace {
+            name: Some("Docs".to_string()),
+            ..Workspace::new(vec![base.join("repo/docs"), base.join("handbook")])
+        };
+        let file = base.join("repo/docs.arto-workspace");
+        workspace.save(&file).unwrap();
+        assert_eq!(workspace.file.as_deref(), Some(file.as_path()));
+
+        let saved = fs::read_to_string(&file).unwrap();
+        assert!(saved.contains(r#""path": "docs""#));
+        assert!(saved.contains(r#""path": "../handbook""#));
+
+        let loaded = Workspace::load(&file).unwrap();
+        assert_eq!(loaded, workspace);
+    }
+
+    #[test]
+    fn test_load_skips_missing_folders() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path().canonicalize().unwrap();
+        fs::create_dir_all(base.join("docs")).unwrap();
+        let file = base.join("team.arto-workspace");
+        fs::write(
+            &file,
+            r#"{"folders": [{"path": "docs"}, {"path": "gone"}, {"path": "docs"}]}"#,
+        )
+        .unwrap();
+
+        let workspace = Workspace::load(&file).unwrap();
+        assert_eq!(workspace.roots, vec![base.join("docs")]);
+        assert_eq!(workspace.display_name(), "team");
+    }
+
+    #[test]
+    fn test_load_invalid_json() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("broken.arto-workspace");
+        fs::write(&file, "{ folders: ").unwrap();
+
+        assert!(Workspace::load(&file).is_err());
+    }
+
+    #[test]
+    fn test_root_for_prefers_innermost_root() {
+        let workspace = Workspace::new(vec![
+            PathBuf::from("/repo"),
+            PathBuf::from("/repo/vendor/handbook"),
+        ]);
+
+        assert_eq!(
+            workspace.root_for(Path::new("/repo/vendor/handbook/a.md")),
+            Some(Path::new("/repo/vendor/handbook"))
+        );
+        assert_eq!(
+            workspace.root_for(Path::new("/repo/README.md")),
+            Some(Path::new("/repo"))
+        );
+        assert_eq!(workspace.root_for(Path::new("/other/a.md")), None);
+    }
+}
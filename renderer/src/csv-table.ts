@@ -0,0 +1,183 @@
+const PAGE_SIZE = 200;
+
+type TableData = {
+  header: string[] | null;
+  rows: string[][];
+  totalRows: number;
+};
+
+type SortState = {
+  column: number;
+  ascending: boolean;
+} | null;
+
+const collator = new Intl.Collator(undefined, { numeric: true, sensitivity: "base" });
+
+/**
+ * Turn `.csv-table` placeholders (rows embedded as JSON) into sortable, paginated tables
+ */
+export function renderCsvTables(container: Element): void {
+  const tables = container.querySelectorAll<HTMLElement>(".csv-table:not([data-rendered])");
+
+  tables.forEach((element) => {
+    element.dataset.rendered = "yes";
+    const data = element.querySelector(".csv-table-data");
+    if (!data?.textContent) {
+      return;
+    }
+    try {
+      setupTable(element, JSON.parse(data.textContent) as TableData);
+    } catch (error) {
+      console.error("Failed to render CSV table:", error);
+    }
+  });
+}
+
+function setupTable(element: HTMLElement, data: TableData): void {
+  // Avoid spreading rows into Math.max: large files exceed the argument limit
+  const columnCount = data.rows.reduce(
+    (max, row) => Math.max(max, row.length),
+    data.header?.length ?? 0,
+  );
+  const header =
+    data.header ?? Array.from({ length: columnCount }, (_, i) => `Column ${i + 1}`);
+
+  let sort: SortState = null;
+  let page = 0;
+  let order = data.rows.map((_, i) => i);
+  const pageCount = Math.max(1, Math.ceil(data.rows.length / PAGE_SIZE));
+
+  // Toolbar: row count and pagination
+  const toolbar = document.createElement("div");
+  toolbar.className = "csv-table-toolbar";
+  const count = document.createElement("span");
+  count.className = "csv-table-count";
+  toolbar.appendChild(count);
+
+  const pager = document.createElement("div");
+  pager.className = "csv-table-pager";
+  const prevButton = createButton("‹ Prev", () => showPage(page - 1));
+  const pageLabel = document.createElement("span");
+  const nextButton = createButton("Next ›", () => showPage(page + 1));
+  pager.append(prevButton, pageLabel, nextButton);
+  if (pageCount > 1) {
+    toolbar.appendChild(pager);
+  }
+
+  // Table with sticky header
+  const scroll = document.createElement("div");
+  scroll.className = "csv-table-scroll";
+  const table = document.createElement("table");
+  const thead = document.createElement("thead");
+  const headerRow = document.createElement("tr");
+  const tbody = document.createElement("tbody");
+
+  const headerCells = header.map((label, column) => {
+    const th = document.createElement("th");
+    th.textContent = label;
+    th.title = "Sort by this column";
+    th.addEventListener("click", () => sortBy(column));
+    headerRow.appendChild(th);
+    return th;
+  });
+  // Pad the header when some rows have more cells than the header
+  for (let column = header.length; column < columnCount; column++) {
+    headerRow.appendChild(document.createElement("th"));
+  }
+  thead.appendChild(headerRow);
+  table.append(thead, tbody);
+  scroll.appendChild(table);
+
+  element.replaceChildren(toolbar, scroll);
+
+  function sortBy(column: number): void {
+    sort =
+      sort?.column === column
+        ? sort.ascending
+          ? { column, ascending: false }
+          : null
+        : { column, ascending: true };
+
+    order = data.rows.map((_, i) => i);
+    if (sort) {
+      const { ascending } = sort;
+      order.sort((a, b) => {
+        const result = compareCells(data.rows[a][column] ?? "", data.rows[b][column] ?? "");
+        return ascending ? result : -result;
+      });
+    }
+
+    headerCells.forEach((th, i) => {
+      th.classList.toggle("sorted-ascending", sort?.column === i && sort.ascending);
+      th.classList.toggle("sorted-descending", sort?.column === i && !sort.ascending);
+    });
+    showPage(0);
+  }
+
+  function showPage(target: number): void {
+    page = Math.min(Math.max(target, 0), pageCount - 1);
+    const start = page * PAGE_SIZE;
+    const end = Math.min(start + PAGE_SIZE, data.rows.length);
+
+    const fragment = document.createDocumentFragment();
+    for (const index of order.slice(start, end)) {
+      const tr = document.createElement("tr");
+      const row = data.rows[index];
+      for (let column = 0; column < columnCount; column++) {
+        const td = document.createElement("td");
+        td.textContent = row[column] ?? "";
+        tr.appendChild(td);
+      }
+      fragment.appendChild(tr);
+    }
+    tbody.replaceChildren(fragment);
+
+    count.textContent = formatCount(data, columnCount, start, end);
+    pageLabel.textContent = `Page ${page + 1} of ${pageCount}`;
+    prevButton.disabled = page === 0;
+    nextButton.disabled = page === pageCount - 1;
+  }
+
+  showPage(0);
+}
+
+function createButton(label: string, onClick: () => void): HTMLButtonElement {
+  const button = document.createElement("button");
+  button.className = "csv-table-button";
+  button.textContent = label;
+  button.addEventListener("click", onClick);
+  return button;
+}
+
+// Compare numerically when both cells are numbers, otherwise with a natural-order collator
+function compareCells(a: string, b: string): number {
+  const numberA = parseNumber(a);
+  const numberB = parseNumber(b);
+  if (numberA !== null && numberB !== null) {
+    return numberA - numberB;
+  }
+  return collator.compare(a, b);
+}
+
+function parseNumber(value: string): number | null {
+  const trimmed = value.trim();
+  if (trimmed === "") {
+    return null;
+  }
+  const number = Number(trimmed);
+  return Number.isNaN(number) ? null : number;
+}
+
+function formatCount(data: TableData, columnCount: number, start: number, end: number): string {
+  const total = data.totalRows.toLocaleString();
+  const columns = `${columnCount} ${columnCount === 1 ? "column" : "columns"}`;
+  const rows = `${total} ${data.totalRows === 1 ? "row" : "rows"}`;
+  const truncated =
+    data.totalRows > data.rows.length
+      ? ` (first ${data.rows.length.toLocaleString()} shown)`
+      : "";
+  if (data.rows.length <= PAGE_SIZE) {
+    return `${rows} × ${columns}${truncated}`;
+  }
+  return `${(start + 1).toLocaleString()}–${end.toLocaleString()} of ${rows} × ${columns}${truncated}`;
+}
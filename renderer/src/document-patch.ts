@@ -0,0 +1,86 @@
+/**
+ * Block-by-block updates of the displayed document
+ *
+ * The file viewer sends the document as its top-level blocks (`markdown/blocks.rs`): content
+ * blocks with a key derived from their HTML, and the markup between them (section wrappers).
+ * Content blocks are bracketed with `<!--arto-block:KEY-->` comments, so on reload the blocks
+ * whose key is still in the document are moved into the new document as they are. Their
+ * highlighting, diagrams and math aren't rendered again, and only the changed blocks are parsed.
+ */
+
+export interface DocumentBlock {
+  /** Key of a content block, `null` for the markup between blocks */
+  key: string | null;
+  html: string;
+}
+
+const BLOCK_START = "arto-block:";
+const BLOCK_END = "/arto-block";
+const REUSE = "arto-reuse:";
+
+/**
+ * Replace the content of the document with `blocks`, keeping the nodes of unchanged blocks
+ */
+export function patchDocument(blocks: DocumentBlock[]): void {
+  const article = document.querySelector<HTMLElement>("article[data-document]");
+  if (!article) {
+    return;
+  }
+
+  const previous = collectBlocks(article);
+  const html = blocks
+    .map(({ key, html }) => {
+      if (key === null) {
+        return html;
+      }
+      if (previous.has(key)) {
+        return `<!--${REUSE}${key}-->`;
+      }
+      return `<!--${BLOCK_START}${key}-->${html}<!--${BLOCK_END}-->`;
+    })
+    .join("");
+
+  const template = document.createElement("template");
+  template.innerHTML = html;
+  const fragment = document.importNode(template.content, true);
+  for (const placeholder of commentsOf(fragment)) {
+    if (!placeholder.data.startsWith(REUSE)) {
+      continue;
+    }
+    const nodes = previous.get(placeholder.data.slice(REUSE.length)) ?? [];
+    placeholder.replaceWith(...nodes);
+  }
+  article.replaceChildren(fragment);
+}
+
+/**
+ * Nodes of the blocks displayed, including their bracketing comments, by key
+ */
+function collectBlocks(root: Node): Map<string, Node[]> {
+  const blocks = new Map<string, Node[]>();
+  for (const start of commentsOf(root)) {
+    if (!start.data.startsWith(BLOCK_START)) {
+      continue;
+    }
+    const nodes: Node[] = [start];
+    let node = start.nextSibling;
+    while (node) {
+      nodes.push(node);
+      if (node.nodeType === Node.COMMENT_NODE && (node as Comment).data === BLOCK_END) {
+        break;
+      }
+      node = node.nextSibling;
+    }
+    blocks.set(start.data.slice(BLOCK_START.length), nodes);
+  }
+  return blocks;
+}
+
+function commentsOf(root: Node): Comment[] {
+  const walker = document.createTreeWalker(root, NodeFilter.SHOW_COMMENT);
+  const comments: Comment[] = [];
+  while (walker.nextNode()) {
+    comments.push(walker.currentNode as Comment);
+  }
+  return comments;
+}
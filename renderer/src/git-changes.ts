@@ -0,0 +1,217 @@
+/**
+ * Highlights of the blocks changed since a git revision
+ *
+ * The file viewer compares the blocks of the document with those of its version at the chosen
+ * revision (`git/changes.rs`), and sends the keys of the added and modified blocks along with
+ * the HTML of the removed ones. Changed blocks are marked with `data-change`, removed blocks
+ * are shown where they were, and a gutter next to the document marks every change.
+ */
+
+import { unfoldAncestors } from "./section-fold";
+
+export interface BlockChanges {
+  changed: { key: string; kind: "added" | "modified" }[];
+  /** Removed blocks, with the key of the block they stood before (`null` at the end) */
+  removed: { before: string | null; html: string }[];
+  hunks: number;
+}
+
+const CHANGES_CLASS = "markdown-body--changes";
+const BLOCK_START = "arto-block:";
+const BLOCK_END = "/arto-block";
+const CURRENT_CLASS = "change-current";
+/** Changed blocks, but not the gutter markers for them */
+const CHANGED_SELECTOR = "[data-change]:not(.change-marker)";
+
+let gutterObserver: ResizeObserver | null = null;
+
+/**
+ * Highlight `changes` in the displayed document, replacing any previous highlights
+ */
+export function showChanges(changes: BlockChanges | null): void {
+  const article = document.querySelector<HTMLElement>("article[data-document]");
+  if (!article) {
+    return;
+  }
+  clearChanges(article);
+  if (!changes) {
+    return;
+  }
+
+  const starts = blockStarts(article);
+  for (const { key, kind } of changes.changed) {
+    const start = starts.get(key);
+    if (start) {
+      blockElements(start).forEach((element) => element.setAttribute("data-change", kind));
+    }
+  }
+  for (const { before, html } of changes.removed) {
+    const removed = document.createElement("div");
+    removed.className = "change-removed";
+    removed.setAttribute("data-change", "removed");
+    removed.innerHTML = html;
+    const anchor = before === null ? undefined : starts.get(before);
+    if (anchor) {
+      anchor.before(removed);
+    } else {
+      article.append(removed);
+    }
+  }
+
+  article.classList.add(CHANGES_CLASS);
+  const gutter = document.createElement("div");
+  gutter.className = "change-gutter";
+  gutter.setAttribute("aria-hidden", "true");
+  gutter.addEventListener("click", handleMarkerClick);
+  article.prepend(gutter);
+  // Folding sections and rendering diagrams move the blocks around
+  gutterObserver = new ResizeObserver(() => drawGutter(article, gutter));
+  gutterObserver.observe(article);
+}
+
+/**
+ * Scroll to the next (`1`) or previous (`-1`) change, wrapping around the document
+ */
+export function goToChange(direction: 1 | -1): void {
+  const article = document.querySelector<HTMLElement>(`article.${CHANGES_CLASS}`);
+  if (!article) {
+    return;
+  }
+  const hunks = hunkStarts(article);
+  if (hunks.length === 0) {
+    return;
+  }
+
+  const viewport = scrollContainer(article).getBoundingClientRect();
+  // Changes starting at the top of the viewport count as the current one
+  const threshold = viewport.top + 8;
+  const target =
+    direction === 1
+      ? (hunks.find((element) => visibleTop(element) > threshold) ?? hunks[0])
+      : ([...hunks].reverse().find((element) => visibleTop(element) < threshold - 16) ??
+        hunks[hunks.length - 1]);
+  reveal(target);
+}
+
+function clearChanges(article: HTMLElement): void {
+  gutterObserver?.disconnect();
+  gutterObserver = null;
+  article.classList.remove(CHANGES_CLASS);
+  article.querySelectorAll(".change-gutter, .change-removed").forEach((node) => node.remove());
+  article.querySelectorAll(CHANGED_SELECTOR).forEach((element) => {
+    element.removeAttribute("data-change");
+  });
+  article.querySelectorAll(`.${CURRENT_CLASS}`).forEach((element) => {
+    element.classList.remove(CURRENT_CLASS);
+  });
+}
+
+/**
+ * Comments starting the content blocks of the document (`document-patch.ts`), by key
+ */
+function blockStarts(article: HTMLElement): Map<string, Comment> {
+  const starts = new Map<string, Comment>();
+  const walker = document.createTreeWalker(article, NodeFilter.SHOW_COMMENT);
+  while (walker.nextNode()) {
+    const comment = walker.currentNode as Comment;
+    if (comment.data.startsWith(BLOCK_START)) {
+      starts.set(comment.data.slice(BLOCK_START.length), comment);
+    }
+  }
+  return starts;
+}
+
+/**
+ * Elements of the block started by `start`
+ */
+function blockElements(start: Comment): Element[] {
+  const elements: Element[] = [];
+  let node = start.nextSibling;
+  while (node && !(node.nodeType === Node.COMMENT_NODE && (node as Comment).data === BLOCK_END)) {
+    if (node instanceof Element) {
+      elements.push(node);
+    }
+    node = node.nextSibling;
+  }
+  return elements;
+}
+
+/**
+ * First element of every run of changed blocks, in document order
+ */
+function hunkStarts(article: HTMLElement): HTMLElement[] {
+  const changed = Array.from(article.querySelectorAll<HTMLElement>(CHANGED_SELECTOR));
+  return changed.filter((element) => {
+    let previous = element.previousElementSibling;
+    while (previous?.classList.contains("change-gutter")) {
+      previous = previous.previousElementSibling;
+    }
+    return !previous?.hasAttribute("data-change");
+  });
+}
+
+function drawGutter(article: HTMLElement, gutter: HTMLElement): void {
+  const markers = Array.from(article.querySelectorAll<HTMLElement>(CHANGED_SELECTOR))
+    // Blocks in folded sections aren't laid out
+    .filter((element) => element.getClientRects().length > 0)
+    .map((element, index) => {
+      const marker = document.createElement("div");
+      marker.className = "change-marker";
+      marker.dataset.change = element.dataset.change;
+      marker.dataset.index = String(index);
+      marker.style.top = `${offsetTopWithin(element, article)}px`;
+      marker.style.height = `${element.offsetHeight}px`;
+      return marker;
+    });
+  gutter.replaceChildren(...markers);
+}
+
+function handleMarkerClick(event: MouseEvent): void {
+  const marker = (event.target as HTMLElement | null)?.closest<HTMLElement>(".change-marker");
+  const article = marker?.closest<HTMLElement>("article");
+  if (!marker || !article) {
+    return;
+  }
+  const visible = Array.from(article.querySelectorAll<HTMLElement>(CHANGED_SELECTOR)).filter(
+    (element) => element.getClientRects().length > 0,
+  );
+  const target = visible[Number(marker.dataset.index)];
+  if (target) {
+    reveal(target);
+  }
+}
+
+function reveal(element: HTMLElement): void {
+  unfoldAncestors(element);
+  element.scrollIntoView({ block: "center" });
+  element.classList.add(CURRENT_CLASS);
+  setTimeout(() => element.classList.remove(CURRENT_CLASS), 1200);
+}
+
+/**
+ * Offset of `element` from the top of `ancestor`, in the layout's (unzoomed) pixels
+ */
+function offsetTopWithin(element: HTMLElement, ancestor: HTMLElement): number {
+  let top = 0;
+  let node: HTMLElement | null = element;
+  while (node && node !== ancestor) {
+    top += node.offsetTop;
+    node = node.offsetParent as HTMLElement | null;
+  }
+  return top;
+}
+
+/**
+ * Top of `element` on screen, or of the folded section hiding it
+ */
+function visibleTop(element: HTMLElement): number {
+  const visible =
+    element.getClientRects().length > 0
+      ? element
+      : (element.closest<HTMLElement>(".md-section-folded") ?? element);
+  return visible.getBoundingClientRect().top;
+}
+
+function scrollContainer(element: HTMLElement): HTMLElement {
+  return element.closest<HTMLElement>(".content") ?? document.documentElement;
+}
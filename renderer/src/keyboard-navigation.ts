@@ -0,0 +1,149 @@
+/**
+ * Vim-style keyboard navigation for the document view
+ *
+ * - j / k: scroll down / up
+ * - gg / G: jump to top / bottom
+ * - n / N: find next / previous occurrence of the selection (or last search)
+ * - ] / [: jump to next / previous heading
+ */
+
+const SCROLL_STEP = 60;
+const SEQUENCE_TIMEOUT_MS = 500;
+const HEADING_SELECTOR = ".markdown-body :is(h1, h2, h3, h4, h5, h6)";
+
+let enabled = false;
+let mounted = false;
+let pendingG = false;
+let pendingGTimer: number | undefined;
+let lastSearch = "";
+
+export function setEnabled(value: boolean): void {
+  enabled = value;
+}
+
+export function mount(): void {
+  if (mounted) {
+    return;
+  }
+  mounted = true;
+  document.addEventListener("keydown", handleKeyDown);
+}
+
+function handleKeyDown(event: KeyboardEvent): void {
+  if (!enabled || event.defaultPrevented || event.isComposing) {
+    return;
+  }
+  if (event.metaKey || event.ctrlKey || event.altKey) {
+    return;
+  }
+  if (isEditableTarget(event.target)) {
+    return;
+  }
+
+  const scroller = getScrollContainer();
+  if (!scroller) {
+    return;
+  }
+
+  const handled = dispatch(event.key, scroller);
+  if (handled) {
+    event.preventDefault();
+  }
+}
+
+function dispatch(key: string, scroller: HTMLElement): boolean {
+  if (key !== "g") {
+    clearPendingG();
+  }
+
+  switch (key) {
+    case "j":
+      scroller.scrollBy({ top: SCROLL_STEP });
+      return true;
+    case "k":
+      scroller.scrollBy({ top: -SCROLL_STEP });
+      return true;
+    case "g":
+      if (pendingG) {
+        clearPendingG();
+        scroller.scrollTo({ top: 0 });
+      } else {
+        pendingG = true;
+        pendingGTimer = window.setTimeout(clearPendingG, SEQUENCE_TIMEOUT_MS);
+      }
+      return true;
+    case "G":
+      scroller.scrollTo({ top: scroller.scrollHeight });
+      return true;
+    case "n":
+      findNext(false);
+      return true;
+    case "N":
+      findNext(true);
+      return true;
+    case "]":
+      jumpToHeading(scroller, 1);
+      return true;
+    case "[":
+      jumpToHeading(scroller, -1);
+      return true;
+    default:
+      return false;
+  }
+}
+
+function clearPendingG(): void {
+  pendingG = false;
+  if (pendingGTimer !== undefined) {
+    window.clearTimeout(pendingGTimer);
+    pendingGTimer = undefined;
+  }
+}
+
+function findNext(backwards: boolean): void {
+  const selected = window.getSelection()?.toString().trim() ?? "";
+  if (selected) {
+    lastSearch = selected;
+  }
+  if (!lastSearch) {
+    return;
+  }
+
+  // window.find() is non-standard but supported by WebKit
+  const find = (window as unknown as { find?: (...args: unknown[]) => boolean }).find;
+  find?.call(window, lastSearch, false, backwards, true, false, false, false);
+}
+
+function jumpToHeading(scroller: HTMLElement, direction: 1 | -1): void {
+  const headings = Array.from(document.querySelectorAll<HTMLElement>(HEADING_SELECTOR));
+  if (headings.length === 0) {
+    return;
+  }
+
+  // Small threshold so a heading already at the top is treated as "current"
+  const top = scroller.getBoundingClientRect().top + 1;
+  const offsets = headings.map((heading) => heading.getBoundingClientRect().top - top);
+
+  const target =
+    direction > 0
+      ? headings.find((_, i) => offsets[i] > 1)
+      : [...headings].reverse().find((_, i) => offsets[headings.length - 1 - i] < -1);
+
+  target?.scrollIntoView({ block: "start" });
+}
+
+function getScrollContainer(): HTMLElement | null {
+  return document.querySelector<HTMLElement>(".content");
+}
+
+function isEditableTarget(target: EventTarget | null): boolean {
+  if (!(target instanceof HTMLElement)) {
+    return false;
+  }
+  return (
+    target.isContentEditable ||
+    target instanceof HTMLInputElement ||
+    target instanceof HTMLTextAreaElement ||
+    target instanceof HTMLSelectElement
+  );
+}
@@ -4,6 +4,12 @@ import { type Theme, getSystemTheme } from "./theme";
 import * as markdownViewer from "./markdown-viewer";
 import * as syntaxHighlighter from "./syntax-highlighter";
 import * as mermaidRenderer from "./mermaid-renderer";
+import * as mathRenderer from "./math-renderer";
+import * as keyboardNavigation from "./keyboard-navigation";
+import * as sourceView from "./source-view";
+import * as sectionFold from "./section-fold";
+import * as markdownLinks from "./markdown-links";
+import * as remoteImages from "./remote-images";
 import { renderCoordinator } from "./render-coordinator";
 
 function getCurrentTheme(): Theme {
@@ -25,20 +31,56 @@ export function setCurrentTheme(theme: Theme) {
   renderCoordinator.forceRenderMermaid();
 }
 
-export function init(): void {
+export interface RenderConfig {
+  mermaid?: Parameters<typeof mermaidRenderer.setProjectConfig>[0];
+  math?: Parameters<typeof mathRenderer.setProjectOptions>[0];
+}
+
+/**
+ * Apply project-specific rendering options (from .arto.toml)
+ */
+export function setRenderConfig(config: RenderConfig): void {
+  mathRenderer.setProjectOptions(config.math ?? {});
+  mermaidRenderer.setProjectConfig(config.mermaid ?? {});
+  renderCoordinator.forceRenderMermaid();
+}
+
+export interface InitOptions {
+  vimNavigation?: boolean;
+}
+
+export function init(options: InitOptions = {}): void {
   markdownViewer.mount();
   syntaxHighlighter.mount();
   mermaidRenderer.init();
   renderCoordinator.init();
+  keyboardNavigation.setEnabled(options.vimNavigation ?? false);
+  keyboardNavigation.mount();
+  sourceView.mount();
+  sectionFold.mount();
+  markdownLinks.mount();
+  remoteImages.mount();
 
   // Listen for theme changes from Rust
   document.addEventListener("arto:theme-changed", ((event: CustomEvent) => {
     setCurrentTheme(event.detail);
   }) as EventListener);
 
+  // Listen for keybinding preference changes from Rust
+  document.addEventListener("arto:vim-navigation-changed", ((event: CustomEvent) => {
+    keyboardNavigation.setEnabled(event.detail);
+  }) as EventListener);
+
   // Set initial theme
   setCurrentTheme(getCurrentTheme());
 }
 
+export { revealAnchor } from "./source-view";
+export { setAllSectionsFolded } from "./section-fold";
+export { loadRemoteImage, failRemoteImage } from "./remote-images";
+export { patchDocument } from "./document-patch";
+export { preparePrint, finishPrint } from "./print";
+export { showChanges, goToChange } from "./git-changes";
+
 // Re-export mermaid window functions
 export { initMermaidWindow } from "./mermaid-window-controller";
@@ -0,0 +1,37 @@
+/**
+ * Clicks on local links
+ *
+ * Links to local files are rendered by Rust as `<span class="md-link" data-href="…">`
+ * (`post_process_html_tags` in `markdown.rs`) without inline handlers, which the HTML
+ * sanitizer would strip; clicks are delegated from here to the file viewer.
+ */
+
+declare global {
+  interface Window {
+    handleMarkdownLinkClick?: (path: string, button: number) => void;
+  }
+}
+
+let mounted = false;
+
+export function mount(): void {
+  if (mounted) {
+    return;
+  }
+  mounted = true;
+  document.addEventListener("mousedown", handleLinkMouseDown);
+}
+
+function handleLinkMouseDown(event: MouseEvent): void {
+  // Left click opens the file, middle click opens it in a new tab
+  if (event.button !== 0 && event.button !== 1) {
+    return;
+  }
+  const link = (event.target as Element | null)?.closest<HTMLElement>(".md-link[data-href]");
+  const href = link?.dataset.href;
+  if (!href) {
+    return;
+  }
+  event.preventDefault();
+  window.handleMarkdownLinkClick?.(href, event.button);
+}
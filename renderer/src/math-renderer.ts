@@ -1,5 +1,19 @@
 import katex from "katex";
 
+type KatexOptions = NonNullable<Parameters<typeof katex.renderToString>[1]>;
+
+let projectOptions: KatexOptions = {};
+
+/**
+ * Set project-specific KaTeX options (from .arto.toml)
+ *
+ * The options are limited to the safe ones when the project configuration is read
+ * (`project_config.rs`); `trust` is turned off after them regardless.
+ */
+export function setProjectOptions(options: KatexOptions): void {
+  projectOptions = options;
+}
+
 export function renderMath(container: Element): void {
   renderInlineMath(container);
   renderDisplayMath(container);
@@ -30,6 +44,8 @@ function renderInlineMath(container: Element): void {
     try {
       // Use renderToString to avoid intermediate DOM access
       const html = katex.renderToString(content, {
+        ...projectOptions,
+        trust: false,
         throwOnError: false,
         displayMode: false,
       });
@@ -70,6 +86,8 @@ function renderDisplayMath(container: Element): void {
     try {
       // Use renderToString to avoid intermediate DOM access
       const html = katex.renderToString(content, {
+        ...projectOptions,
+        trust: false,
         throwOnError: false,
         displayMode: true,
       });
@@ -111,6 +129,8 @@ function renderBlockMath(container: Element): void {
     try {
       // Use renderToString to avoid intermediate DOM access
       const html = katex.renderToString(content, {
+        ...projectOptions,
+        trust: false,
         throwOnError: false,
         displayMode: true,
       });
@@ -2,23 +2,39 @@ import mermaid from "mermaid";
 import type { Theme } from "./theme";
 import { openMermaidWindow } from "./mermaid-window-controller";
 
+type MermaidConfig = Parameters<typeof mermaid.initialize>[0];
+
+let currentTheme: Theme = "light";
+let projectConfig: MermaidConfig = {};
+
 export function init(): void {
-  mermaid.initialize({
-    startOnLoad: false, // We'll manually trigger rendering
-    theme: "default", // Will be updated based on app theme
-    securityLevel: "loose", // Allow more flexibility in diagrams
-    fontFamily: "inherit",
-  });
+  initialize();
 }
 
 export function setTheme(theme: Theme): void {
-  // Update mermaid theme configuration
-  const mermaidTheme = theme === "dark" ? "dark" : "default";
+  currentTheme = theme;
+  initialize();
+}
+
+/**
+ * Set project-specific mermaid options (from .arto.toml), layered over the defaults
+ *
+ * The options are limited to the safe ones when the project configuration is read
+ * (`project_config.rs`); the security level is set after them regardless.
+ */
+export function setProjectConfig(config: MermaidConfig): void {
+  projectConfig = config;
+  initialize();
+}
+
+function initialize(): void {
   mermaid.initialize({
-    startOnLoad: false,
-    theme: mermaidTheme,
-    securityLevel: "loose",
+    startOnLoad: false, // We'll manually trigger rendering
+    theme: currentTheme === "dark" ? "dark" : "default",
     fontFamily: "inherit",
+    ...projectConfig,
+    securityLevel: "loose", // Allow more flexibility in diagrams
+    themeCSS: undefined,
   });
 }
 
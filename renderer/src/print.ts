@@ -0,0 +1,162 @@
+/**
+ * Print preparation
+ *
+ * Before printing or exporting to PDF, the document is switched to the light theme, every
+ * image, diagram and formula is rendered, and print-only elements are added: footnotes with
+ * the URLs of links, a table of contents and a page header with the file name. Everything is
+ * undone by `finishPrint`, which runs after the print dialog closes.
+ */
+
+import { renderCoordinator } from "./render-coordinator";
+
+export interface PrintOptions {
+  /** File name shown in the page header */
+  title: string;
+  tableOfContents: boolean;
+  pageHeader: boolean;
+  linkFootnotes: boolean;
+}
+
+const ADDED_ATTR = "data-print-added";
+
+let restoreTheme: string | null = null;
+let eagerImages: HTMLImageElement[] = [];
+
+/**
+ * Prepare the displayed document for printing, resolving once it is fully rendered
+ */
+export async function preparePrint(options: PrintOptions): Promise<void> {
+  finishPrint();
+
+  const markdownBody = document.querySelector<HTMLElement>(".markdown-body");
+  if (!markdownBody) {
+    return;
+  }
+
+  // Paper is white; diagrams and highlighting are re-rendered for it
+  const theme = document.body.getAttribute("data-theme");
+  if (theme === "dark") {
+    restoreTheme = theme;
+    document.dispatchEvent(new CustomEvent("arto:theme-changed", { detail: "light" }));
+  }
+
+  // Lazy images below the fold would be missing from the printout
+  eagerImages = Array.from(markdownBody.querySelectorAll<HTMLImageElement>('img[loading="lazy"]'));
+  eagerImages.forEach((img) => (img.loading = "eager"));
+  await Promise.all(
+    Array.from(markdownBody.querySelectorAll("img"), (img) => img.decode().catch(() => {})),
+  );
+
+  await renderCoordinator.renderNow();
+
+  if (options.linkFootnotes) {
+    addLinkFootnotes(markdownBody);
+  }
+  if (options.tableOfContents) {
+    addTableOfContents(markdownBody);
+  }
+  if (options.pageHeader) {
+    addPageHeader(markdownBody, options.title);
+  }
+
+  window.addEventListener("afterprint", finishPrint, { once: true });
+}
+
+/**
+ * Remove the print-only elements and restore the theme
+ */
+export function finishPrint(): void {
+  document.querySelectorAll(`[${ADDED_ATTR}]`).forEach((el) => el.remove());
+  eagerImages.forEach((img) => (img.loading = "lazy"));
+  eagerImages = [];
+  if (restoreTheme) {
+    document.dispatchEvent(new CustomEvent("arto:theme-changed", { detail: restoreTheme }));
+    restoreTheme = null;
+  }
+}
+
+function printOnly<K extends keyof HTMLElementTagNameMap>(
+  tagName: K,
+  className: string,
+): HTMLElementTagNameMap[K] {
+  const element = document.createElement(tagName);
+  element.className = `print-only ${className}`;
+  element.setAttribute(ADDED_ATTR, "");
+  return element;
+}
+
+/**
+ * Number external links and list their URLs at the end of the document
+ */
+function addLinkFootnotes(markdownBody: HTMLElement): void {
+  const urls: string[] = [];
+  markdownBody.querySelectorAll<HTMLAnchorElement>("a[href]").forEach((link) => {
+    const url = link.href;
+    // The URL is already visible when it is the link text
+    if (!/^(https?|mailto):/.test(url) || link.textContent?.trim() === url) {
+      return;
+    }
+    let index = urls.indexOf(url);
+    if (index === -1) {
+      index = urls.push(url) - 1;
+    }
+    const ref = printOnly("sup", "print-link-ref");
+    ref.textContent = `[${index + 1}]`;
+    link.after(ref);
+  });
+  if (urls.length === 0) {
+    return;
+  }
+
+  const section = printOnly("section", "print-link-footnotes");
+  const heading = document.createElement("h2");
+  heading.textContent = "Links";
+  const list = document.createElement("ol");
+  urls.forEach((url) => {
+    const item = document.createElement("li");
+    item.textContent = url;
+    list.append(item);
+  });
+  section.append(heading, list);
+  markdownBody.append(section);
+}
+
+/**
+ * Table of contents of the top three heading levels, at the start of the document
+ */
+function addTableOfContents(markdownBody: HTMLElement): void {
+  const headings = markdownBody.querySelectorAll<HTMLHeadingElement>("h1, h2, h3");
+  if (headings.length === 0) {
+    return;
+  }
+
+  const nav = printOnly("nav", "print-toc");
+  const heading = document.createElement("h2");
+  heading.textContent = "Contents";
+  const list = document.createElement("ol");
+  headings.forEach((h) => {
+    const item = document.createElement("li");
+    item.dataset.level = h.tagName.substring(1);
+    const text = h.textContent?.trim() ?? "";
+    if (h.id) {
+      const link = document.createElement("a");
+      link.href = `#${h.id}`;
+      link.textContent = text;
+      item.append(link);
+    } else {
+      item.textContent = text;
+    }
+    list.append(item);
+  });
+  nav.append(heading, list);
+  markdownBody.prepend(nav);
+}
+
+/**
+ * File name repeated at the top of every page
+ */
+function addPageHeader(markdownBody: HTMLElement, title: string): void {
+  const header = printOnly("div", "print-page-header");
+  header.textContent = title;
+  markdownBody.prepend(header);
+}
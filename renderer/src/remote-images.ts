@@ -0,0 +1,69 @@
+/**
+ * Placeholders of remote images
+ *
+ * Remote images that aren't cached are rendered by Rust as
+ * `<span class="remote-image" data-remote-src="…">` (`remote_images.rs`) so the webview doesn't
+ * contact their host. Clicking a `remote-image-clickable` placeholder asks the file viewer to
+ * download the image, which is then swapped in with `loadRemoteImage`.
+ */
+
+declare global {
+  interface Window {
+    handleRemoteImageLoad?: (url: string) => void;
+  }
+}
+
+let mounted = false;
+
+export function mount(): void {
+  if (mounted) {
+    return;
+  }
+  mounted = true;
+  document.addEventListener("click", handlePlaceholderClick);
+}
+
+function handlePlaceholderClick(event: MouseEvent): void {
+  const placeholder = (event.target as Element | null)?.closest<HTMLElement>(
+    ".remote-image-clickable[data-remote-src]",
+  );
+  const url = placeholder?.dataset.remoteSrc;
+  if (!placeholder || !url || placeholder.classList.contains("remote-image-loading")) {
+    return;
+  }
+  event.preventDefault();
+  for (const each of placeholdersOf(url)) {
+    each.classList.remove("remote-image-failed");
+    each.classList.add("remote-image-loading");
+  }
+  window.handleRemoteImageLoad?.(url);
+}
+
+function placeholdersOf(url: string): HTMLElement[] {
+  const placeholders = document.querySelectorAll<HTMLElement>(".remote-image[data-remote-src]");
+  return Array.from(placeholders).filter((placeholder) => placeholder.dataset.remoteSrc === url);
+}
+
+/**
+ * Replace the placeholders of `url` with the downloaded image
+ */
+export function loadRemoteImage(url: string, dataUrl: string): void {
+  for (const placeholder of placeholdersOf(url)) {
+    const image = document.createElement("img");
+    image.src = dataUrl;
+    image.alt = placeholder.textContent ?? "";
+    image.title = url;
+    placeholder.replaceWith(image);
+  }
+}
+
+/**
+ * Mark the placeholders of `url` as failed, so they can be clicked again
+ */
+export function failRemoteImage(url: string, message: string): void {
+  for (const placeholder of placeholdersOf(url)) {
+    placeholder.classList.remove("remote-image-loading");
+    placeholder.classList.add("remote-image-failed");
+    placeholder.title = message;
+  }
+}
@@ -2,10 +2,14 @@ import * as mathRenderer from "./math-renderer";
 import * as mermaidRenderer from "./mermaid-renderer";
 import * as syntaxHighlighter from "./syntax-highlighter";
 import * as codeCopy from "./code-copy";
+import * as csvTable from "./csv-table";
 
 class RenderCoordinator {
   #rafId: number | null = null;
   #isRendering = false;
+  #inFlight: Promise<void> = Promise.resolve();
+  // Diagrams are to be re-rendered (new theme or config) and no render has started since
+  #mermaidStale = false;
 
   init(): void {
     const observer = new MutationObserver((mutations) => {
@@ -43,17 +47,41 @@ class RenderCoordinator {
     }
     this.#rafId = requestAnimationFrame(() => {
       this.#rafId = null;
-      this.#executeBatchRender();
+      this.#inFlight = this.#executeBatchRender();
     });
   }
 
+  /**
+   * Render pending content right away, resolving once math, diagrams and highlighting are done
+   */
+  async renderNow(): Promise<void> {
+    if (this.#rafId !== null) {
+      cancelAnimationFrame(this.#rafId);
+      this.#rafId = null;
+    }
+    await this.#inFlight;
+    // The cancelled frame may have been a Mermaid re-render; diagrams drawn meanwhile are stale
+    if (this.#mermaidStale) {
+      this.#clearRenderedMermaid();
+    }
+    this.#inFlight = this.#executeBatchRender();
+    await this.#inFlight;
+  }
+
   forceRenderMermaid(): void {
-    const markdownBodies = document.querySelectorAll(".markdown-body");
-    if (markdownBodies.length === 0) {
+    if (document.querySelectorAll(".markdown-body").length === 0) {
       return;
     }
 
-    markdownBodies.forEach((markdownBody) => {
+    this.#clearRenderedMermaid();
+    this.#mermaidStale = true;
+
+    // Schedule only Mermaid rendering
+    this.#scheduleMermaidRender();
+  }
+
+  #clearRenderedMermaid(): void {
+    document.querySelectorAll(".markdown-body").forEach((markdownBody) => {
       markdownBody.querySelectorAll("pre.preprocessed-mermaid[data-rendered]").forEach((el) => {
         const element = el as HTMLElement;
 
@@ -63,9 +91,6 @@ class RenderCoordinator {
         element.removeAttribute("data-copy-button-added");
       });
     });
-
-    // Schedule only Mermaid rendering
-    this.#scheduleMermaidRender();
   }
 
   #scheduleMermaidRender(): void {
@@ -73,33 +98,38 @@ class RenderCoordinator {
       return; // Already scheduled
     }
 
-    this.#rafId = requestAnimationFrame(async () => {
+    this.#rafId = requestAnimationFrame(() => {
       this.#rafId = null;
+      this.#inFlight = this.#renderMermaid();
+    });
+  }
 
-      const markdownBodies = document.querySelectorAll(".markdown-body");
-      if (markdownBodies.length === 0) {
-        return;
-      }
+  async #renderMermaid(): Promise<void> {
+    const markdownBodies = document.querySelectorAll(".markdown-body");
+    if (markdownBodies.length === 0) {
+      return;
+    }
 
-      this.#isRendering = true;
-      try {
-        await Promise.all(
-          Array.from(markdownBodies).map(async (markdownBody) => {
-            await mermaidRenderer.renderDiagrams(markdownBody);
-            // Re-add copy buttons after Mermaid re-render
-            codeCopy.addCopyButtons(markdownBody);
-          }),
-        );
-        console.debug("RenderCoordinator: Mermaid re-render completed");
-      } catch (error) {
-        console.error("RenderCoordinator: Error during Mermaid re-render:", error);
-      } finally {
-        this.#isRendering = false;
-      }
-    });
+    this.#mermaidStale = false;
+    this.#isRendering = true;
+    try {
+      await Promise.all(
+        Array.from(markdownBodies).map(async (markdownBody) => {
+          await mermaidRenderer.renderDiagrams(markdownBody);
+          // Re-add copy buttons after Mermaid re-render
+          codeCopy.addCopyButtons(markdownBody);
+        }),
+      );
+      console.debug("RenderCoordinator: Mermaid re-render completed");
+    } catch (error) {
+      console.error("RenderCoordinator: Error during Mermaid re-render:", error);
+    } finally {
+      this.#isRendering = false;
+    }
   }
 
   async #executeBatchRender(): Promise<void> {
+    this.#mermaidStale = false;
     this.#isRendering = true;
 
     const markdownBodies = document.querySelectorAll(".markdown-body");
@@ -112,6 +142,7 @@ class RenderCoordinator {
       await Promise.all(
         Array.from(markdownBodies).map(async (markdownBody) => {
           mathRenderer.renderMath(markdownBody);
+          csvTable.renderCsvTables(markdownBody);
           syntaxHighlighter.highlightCodeBlocks(markdownBody);
           await mermaidRenderer.renderDiagrams(markdownBody);
           codeCopy.addCopyButtons(markdownBody);
@@ -0,0 +1,82 @@
+/**
+ * Collapsible heading sections
+ *
+ * Sections are rendered by Rust (`markdown/outline.rs`) with the reader's folds applied;
+ * toggles are handled here and the folded section ids reported back, so the next reload of
+ * the file renders them folded again.
+ */
+
+const FOLDED_CLASS = "md-section-folded";
+
+declare global {
+  interface Window {
+    handleSectionFoldChange?: (folded: string[]) => void;
+  }
+}
+
+let mounted = false;
+
+export function mount(): void {
+  if (mounted) {
+    return;
+  }
+  mounted = true;
+  document.addEventListener("click", handleToggleClick);
+}
+
+/**
+ * Fold or unfold every section of the displayed document
+ */
+export function setAllSectionsFolded(folded: boolean): void {
+  document.querySelectorAll<HTMLElement>(".markdown-body .md-section").forEach((section) => {
+    setFolded(section, folded);
+  });
+  reportFolds();
+}
+
+/**
+ * Unfold the sections containing `element`, so it can be scrolled to
+ */
+export function unfoldAncestors(element: Element): void {
+  let section = element.closest<HTMLElement>(`.${FOLDED_CLASS}`);
+  if (!section) {
+    return;
+  }
+  while (section) {
+    setFolded(section, false);
+    section = section.parentElement?.closest<HTMLElement>(`.${FOLDED_CLASS}`) ?? null;
+  }
+  reportFolds();
+}
+
+function handleToggleClick(event: MouseEvent): void {
+  const target = event.target as HTMLElement | null;
+  const toggle = target?.closest<HTMLElement>(".md-section-toggle");
+  const section = toggle?.parentElement;
+  if (!toggle || !section?.classList.contains("md-section")) {
+    return;
+  }
+  event.preventDefault();
+  // Alt-click folds or unfolds the nested sections too, like in outliners
+  const folded = !section.classList.contains(FOLDED_CLASS);
+  const sections = event.altKey
+    ? [section, ...section.querySelectorAll<HTMLElement>(".md-section")]
+    : [section];
+  sections.forEach((s) => setFolded(s, folded));
+  reportFolds();
+}
+
+function setFolded(section: HTMLElement, folded: boolean): void {
+  section.classList.toggle(FOLDED_CLASS, folded);
+  section
+    .querySelector(":scope > .md-section-toggle")
+    ?.setAttribute("aria-expanded", String(!folded));
+}
+
+function reportFolds(): void {
+  const folded = Array.from(
+    document.querySelectorAll<HTMLElement>(`.markdown-body .md-section.${FOLDED_CLASS}`),
+    (section) => section.dataset.section ?? "",
+  ).filter((id) => id !== "");
+  window.handleSectionFoldChange?.(folded);
+}
@@ -0,0 +1,83 @@
+import { unfoldAncestors } from "./section-fold";
+
+// Matches `L10` and `L10-L20` (the trailing `L` is optional)
+const LINE_ANCHOR_PATTERN = /^L(\d+)(?:-L?(\d+))?$/;
+
+let mounted = false;
+let selectionStart: number | null = null;
+
+/**
+ * Scroll to a fragment of the current document
+ *
+ * Line anchors (`#L10`, `#L10-L20`) in a source view highlight the lines as well.
+ */
+export function revealAnchor(anchor: string): void {
+  const match = LINE_ANCHOR_PATTERN.exec(anchor);
+  const sourceView = document.querySelector<HTMLElement>(".source-view");
+  if (match && sourceView) {
+    const start = Number(match[1]);
+    const end = match[2] ? Number(match[2]) : start;
+    highlightLines(sourceView, Math.min(start, end), Math.max(start, end));
+    return;
+  }
+  const target = document.getElementById(anchor) ?? document.getElementsByName(anchor)[0];
+  if (target) {
+    unfoldAncestors(target);
+    target.scrollIntoView({ block: "start" });
+  }
+}
+
+/**
+ * Highlight lines clicked in a source view gutter; shift-click extends the selection
+ */
+export function mount(): void {
+  if (mounted) {
+    return;
+  }
+  mounted = true;
+  document.addEventListener("click", handleGutterClick);
+}
+
+function handleGutterClick(event: MouseEvent): void {
+  const target = event.target as HTMLElement | null;
+  const line = target?.closest<HTMLElement>(".source-gutter [data-line]");
+  const sourceView = line?.closest<HTMLElement>(".source-view");
+  if (!line || !sourceView) {
+    return;
+  }
+  const number = Number(line.dataset.line);
+  if (event.shiftKey && selectionStart !== null) {
+    highlightLines(
+      sourceView,
+      Math.min(selectionStart, number),
+      Math.max(selectionStart, number),
+      false,
+    );
+  } else {
+    selectionStart = number;
+    highlightLines(sourceView, number, number, false);
+  }
+}
+
+function highlightLines(sourceView: HTMLElement, start: number, end: number, scroll = true): void {
+  const body = sourceView.querySelector<HTMLElement>(".source-body");
+  const first = sourceView.querySelector<HTMLElement>(`#L${start}`);
+  const last = sourceView.querySelector<HTMLElement>(`#L${end}`) ?? first;
+  if (!body || !first || !last) {
+    return;
+  }
+
+  let overlay = body.querySelector<HTMLElement>(".source-line-highlight");
+  if (!overlay) {
+    overlay = document.createElement("div");
+    overlay.className = "source-line-highlight";
+    body.appendChild(overlay);
+  }
+  overlay.style.top = `${first.offsetTop}px`;
+  overlay.style.height = `${last.offsetTop + last.offsetHeight - first.offsetTop}px`;
+
+  if (scroll) {
+    selectionStart = start;
+    overlay.scrollIntoView({ block: "center" });
+  }
+}